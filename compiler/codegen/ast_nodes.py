@@ -9,6 +9,13 @@ import json
 from pathlib import Path
 from enum import Enum
 
+# Versão do formato .ast.json que este módulo entende (ver src/ast_schema.rs
+# e ast.schema.json). As versões 1 e 2 também são aceitas.
+FORMAT_VERSION = 3
+
+# decimais não finitos, gravados como strings desde a versão 3
+NON_FINITE = {"inf": float("inf"), "-inf": float("-inf"), "nan": float("nan")}
+
 class SerializableType(Enum):
     Inteiro = "Inteiro"
     Decimal = "Decimal" 
@@ -42,10 +49,12 @@ class ProgramMetadata:
     timestamp: str
     version: str
     entry_point: Optional[str] = None
+    format_version: int = 1
 
     @classmethod
     def from_dict(cls, data: Dict[str, Any]) -> 'ProgramMetadata':
         return cls(
+            format_version=data.get("format_version", 1),
             source_file=data.get("source_file", "unknown"),
             timestamp=data.get("timestamp", ""),
             version=data.get("version", "0.1.0"),
@@ -62,7 +71,8 @@ class SerializableLiteral:
         if "Inteiro" in data:
             return cls(value=data["Inteiro"], literal_type="Inteiro")
         elif "Decimal" in data:
-            return cls(value=data["Decimal"], literal_type="Decimal")
+            value = data["Decimal"]
+            return cls(value=NON_FINITE[value] if isinstance(value, str) else value, literal_type="Decimal")
         elif "Texto" in data:
             return cls(value=data["Texto"], literal_type="Texto")
         elif "Logico" in data:
@@ -373,6 +383,12 @@ class ASTDeserializer:
     @staticmethod
     def _deserialize_program(data: Dict[str, Any]) -> SerializableProgram:
         """Desserializa o programa principal"""
+        version = data.get("metadata", {}).get("format_version", 1)
+        if not isinstance(version, int) or not 1 <= version <= FORMAT_VERSION:
+            raise ValueError(
+                f"Formato da AST {version} não suportado (este codegen entende até o {FORMAT_VERSION}); "
+                "atualize o codegen ou gere o JSON com um compilador compatível"
+            )
         return SerializableProgram.from_dict(data)
 
 # ==================== UTILITÁRIO ====================
@@ -424,7 +440,8 @@ if __name__ == "__main__":
             "source_file": "test.sym",
             "timestamp": "2024-01-01T00:00:00Z",
             "version": "0.1.0",
-            "entry_point": "main"
+            "entry_point": "main",
+            "format_version": FORMAT_VERSION
         }
     }
     
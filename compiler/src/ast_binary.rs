@@ -0,0 +1,570 @@
+use crate::ast_schema::AST_FORMAT_VERSION;
+use crate::parser::ast::Program;
+use crate::semantic::semantic::SemanticAnalysisResult;
+use crate::serialization::*;
+use std::collections::HashMap;
+use std::fs;
+
+// Codificação binária compacta da AST tipada, com a mesma estrutura do
+// `.ast.json` (`SerializableProgram`) e a mesma versão de formato. Serve para
+// programas grandes, em que o JSON pesa; `compiler dump` converte de volta.
+//
+//     "SYMA"  versão (varint)
+//     tabela de strings: quantidade, e cada uma como tamanho + bytes UTF-8
+//     metadata, imports, funções, statements globais
+//
+// Inteiros sem sinal são varints LEB128; `Inteiro` usa zigzag antes do
+// varint; `Decimal` são 8 bytes little-endian. Nomes e textos aparecem uma
+// vez na tabela e são referenciados pelo índice. Cada nó começa com um byte
+// de tag (a posição da variante no enum); Option é 0 (None) ou 1 + valor.
+
+pub const MAGIC: &[u8; 4] = b"SYMA";
+
+// Aninhamento máximo de statements e expressões na leitura. Cada nível daqui é
+// pelo menos um nível no .ast.json, então tudo que o JSON aceita cabe; sem
+// limite, um arquivo montado à mão estouraria a pilha
+const MAX_DEPTH: usize = MAX_JSON_DEPTH;
+
+// ==================== ESCRITA ====================
+
+pub fn encode(program: &SerializableProgram) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.program(program);
+
+    let mut bytes = MAGIC.to_vec();
+    write_varint(&mut bytes, AST_FORMAT_VERSION);
+    write_varint(&mut bytes, writer.strings.len() as u64);
+    for string in &writer.strings {
+        write_varint(&mut bytes, string.len() as u64);
+        bytes.extend_from_slice(string.as_bytes());
+    }
+    bytes.extend(writer.body);
+    bytes
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+#[derive(Default)]
+struct Writer {
+    strings: Vec<String>,
+    indices: HashMap<String, u64>,
+    body: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, value: u64) {
+        write_varint(&mut self.body, value);
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.body.push(tag);
+    }
+
+    fn string(&mut self, string: &str) {
+        let index = match self.indices.get(string) {
+            Some(index) => *index,
+            None => {
+                let index = self.strings.len() as u64;
+                self.strings.push(string.to_string());
+                self.indices.insert(string.to_string(), index);
+                index
+            }
+        };
+        self.varint(index);
+    }
+
+    fn option<T>(&mut self, value: &Option<T>, write: impl FnOnce(&mut Self, &T)) {
+        match value {
+            None => self.tag(0),
+            Some(value) => {
+                self.tag(1);
+                write(self, value);
+            }
+        }
+    }
+
+    fn list<T>(&mut self, items: &[T], mut write: impl FnMut(&mut Self, &T)) {
+        self.varint(items.len() as u64);
+        for item in items {
+            write(self, item);
+        }
+    }
+
+    fn program(&mut self, program: &SerializableProgram) {
+        let metadata = &program.metadata;
+        self.string(&metadata.source_file);
+        self.string(&metadata.timestamp);
+        self.string(&metadata.version);
+        self.option(&metadata.entry_point, |w, entry| w.string(entry));
+        self.list(&program.imports, |w, import| w.string(import));
+        self.list(&program.functions, Self::function);
+        self.list(&program.global_statements, Self::statement);
+    }
+
+    fn function(&mut self, function: &SerializableFunction) {
+        self.string(&function.name);
+        self.option(&function.return_type, Self::ty);
+        self.list(&function.parameters, |w, param| {
+            w.ty(&param.param_type);
+            w.string(&param.name);
+        });
+        self.block(&function.body);
+        self.varint(function.line as u64);
+    }
+
+    fn block(&mut self, block: &SerializableBlock) {
+        self.list(&block.statements, Self::statement);
+    }
+
+    fn statement(&mut self, statement: &SerializableStatement) {
+        match statement {
+            SerializableStatement::VariableDecl(decl) => {
+                self.tag(0);
+                self.ty(&decl.var_type);
+                self.string(&decl.name);
+                self.option(&decl.initializer, Self::expr);
+                self.varint(decl.line as u64);
+            }
+            SerializableStatement::ExprStmt(stmt) => {
+                self.tag(1);
+                self.expr(&stmt.expr);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::IfStmt(stmt) => {
+                self.tag(2);
+                self.expr(&stmt.condition);
+                self.block(&stmt.then_branch);
+                self.option(&stmt.else_branch, Self::block);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::WhileStmt(stmt) => {
+                self.tag(3);
+                self.expr(&stmt.condition);
+                self.block(&stmt.body);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::ForStmt(stmt) => {
+                self.tag(4);
+                self.string(&stmt.variable);
+                self.expr(&stmt.start);
+                self.expr(&stmt.end);
+                self.block(&stmt.body);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::ReturnStmt(stmt) => {
+                self.tag(5);
+                self.option(&stmt.value, Self::expr);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::WriteStmt(stmt) => {
+                self.tag(6);
+                self.list(&stmt.arguments, Self::expr);
+                self.varint(stmt.line as u64);
+            }
+            SerializableStatement::ReadStmt(stmt) => {
+                self.tag(7);
+                self.expr(&stmt.target);
+                self.varint(stmt.line as u64);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &SerializableExpr) {
+        match expr {
+            SerializableExpr::Literal { value, expr_type } => {
+                self.tag(0);
+                match value {
+                    SerializableLiteral::Inteiro(n) => {
+                        self.tag(0);
+                        self.varint(((*n << 1) ^ (*n >> 63)) as u64);
+                    }
+                    SerializableLiteral::Decimal(n) => {
+                        self.tag(1);
+                        self.body.extend_from_slice(&n.to_le_bytes());
+                    }
+                    SerializableLiteral::Texto(s) => {
+                        self.tag(2);
+                        self.string(s);
+                    }
+                    SerializableLiteral::Logico(b) => {
+                        self.tag(3);
+                        self.tag(*b as u8);
+                    }
+                }
+                self.ty(expr_type);
+            }
+            SerializableExpr::Variable { name, expr_type } => {
+                self.tag(1);
+                self.string(name);
+                self.ty(expr_type);
+            }
+            SerializableExpr::Call { call, expr_type } => {
+                self.tag(2);
+                self.string(&call.function);
+                self.list(&call.arguments, Self::expr);
+                self.ty(expr_type);
+            }
+            SerializableExpr::BinaryOp { op, left, right, expr_type } => {
+                self.tag(3);
+                self.tag(BINARY_OPERATORS.iter().position(|o| o == op).unwrap_or_default() as u8);
+                self.expr(left);
+                self.expr(right);
+                self.ty(expr_type);
+            }
+            SerializableExpr::UnaryOp { op, operand, expr_type } => {
+                self.tag(4);
+                self.tag(UNARY_OPERATORS.iter().position(|o| o == op).unwrap_or_default() as u8);
+                self.expr(operand);
+                self.ty(expr_type);
+            }
+        }
+    }
+
+    fn ty(&mut self, ty: &SerializableType) {
+        self.tag(TYPES.iter().position(|t| t == ty).unwrap_or_default() as u8);
+    }
+}
+
+// a tag de cada variante é a posição dela na tabela
+const TYPES: [SerializableType; 5] = [
+    SerializableType::Inteiro,
+    SerializableType::Decimal,
+    SerializableType::Texto,
+    SerializableType::Logico,
+    SerializableType::Void,
+];
+
+const BINARY_OPERATORS: [SerializableBinaryOperator; 13] = [
+    SerializableBinaryOperator::Add,
+    SerializableBinaryOperator::Subtract,
+    SerializableBinaryOperator::Multiply,
+    SerializableBinaryOperator::Divide,
+    SerializableBinaryOperator::Modulo,
+    SerializableBinaryOperator::Equal,
+    SerializableBinaryOperator::NotEqual,
+    SerializableBinaryOperator::Less,
+    SerializableBinaryOperator::LessEqual,
+    SerializableBinaryOperator::Greater,
+    SerializableBinaryOperator::GreaterEqual,
+    SerializableBinaryOperator::And,
+    SerializableBinaryOperator::Or,
+];
+
+const UNARY_OPERATORS: [SerializableUnaryOperator; 3] =
+    [SerializableUnaryOperator::Negate, SerializableUnaryOperator::Plus, SerializableUnaryOperator::Not];
+
+// ==================== LEITURA ====================
+
+pub fn decode(bytes: &[u8]) -> Result<SerializableProgram, SerializationError> {
+    let body = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| error("não é um arquivo de AST binária (cabeçalho 'SYMA' ausente)".to_string()))?;
+    let mut reader = Reader { bytes: body, position: 0, strings: Vec::new(), depth: 0 };
+
+    // a versão 3 só mudou o JSON; o binário da 2 é o mesmo
+    let version = reader.varint()?;
+    if !(2..=AST_FORMAT_VERSION).contains(&version) {
+        return Err(error(format!(
+            "AST binária no formato {}, mas este compilador lê do 2 ao {}; gere o arquivo de novo",
+            version, AST_FORMAT_VERSION
+        )));
+    }
+    for _ in 0..reader.count()? {
+        let length = reader.count()?;
+        let string = std::str::from_utf8(reader.take(length)?).map_err(|_| reader.invalid("string que não é UTF-8"))?;
+        reader.strings.push(string.to_string());
+    }
+
+    let program = reader.program()?;
+    if reader.position != reader.bytes.len() {
+        return Err(reader.invalid("bytes sobrando depois do programa"));
+    }
+    Ok(program)
+}
+
+fn error(message: String) -> SerializationError {
+    SerializationError { message }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    strings: Vec<String>,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn invalid(&self, what: &str) -> SerializationError {
+        // posição relativa ao início do arquivo, contando o cabeçalho
+        error(format!("AST binária inválida no byte {}: {}", self.position + MAGIC.len(), what))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SerializationError> {
+        let end = self.position.checked_add(length).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(|| self.invalid("arquivo truncado"))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    // conta um nível de aninhamento; quem chama desconta ao terminar (num erro
+    // a leitura inteira é abandonada, então não precisa)
+    fn enter(&mut self) -> Result<(), SerializationError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.invalid(&format!("mais de {} níveis de aninhamento", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn tag(&mut self) -> Result<u8, SerializationError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, SerializationError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.tag()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.invalid("varint longo demais"))
+    }
+
+    // quantidades e tamanhos; nunca passam do que resta no arquivo, então
+    // um arquivo corrompido não faz reservar memória à toa
+    fn count(&mut self) -> Result<usize, SerializationError> {
+        let count = self.varint()?;
+        if count > (self.bytes.len() - self.position) as u64 {
+            return Err(self.invalid("quantidade maior que o arquivo"));
+        }
+        Ok(count as usize)
+    }
+
+    fn line(&mut self) -> Result<usize, SerializationError> {
+        usize::try_from(self.varint()?).map_err(|_| self.invalid("linha grande demais"))
+    }
+
+    fn string(&mut self) -> Result<String, SerializationError> {
+        let index = self.varint()?;
+        let string = usize::try_from(index).ok().and_then(|i| self.strings.get(i));
+        string.cloned().ok_or_else(|| self.invalid(&format!("string {} fora da tabela", index)))
+    }
+
+    fn option<T>(&mut self, read: impl FnOnce(&mut Self) -> Result<T, SerializationError>) -> Result<Option<T>, SerializationError> {
+        match self.tag()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            tag => Err(self.invalid(&format!("tag de Option inválida {}", tag))),
+        }
+    }
+
+    fn list<T>(&mut self, mut read: impl FnMut(&mut Self) -> Result<T, SerializationError>) -> Result<Vec<T>, SerializationError> {
+        let count = self.count()?;
+        (0..count).map(|_| read(self)).collect()
+    }
+
+    fn program(&mut self) -> Result<SerializableProgram, SerializationError> {
+        let metadata = ProgramMetadata {
+            format_version: AST_FORMAT_VERSION,
+            source_file: self.string()?,
+            timestamp: self.string()?,
+            version: self.string()?,
+            entry_point: self.option(Self::string)?,
+        };
+        Ok(SerializableProgram {
+            imports: self.list(Self::string)?,
+            functions: self.list(Self::function)?,
+            global_statements: self.list(Self::statement)?,
+            metadata,
+        })
+    }
+
+    fn function(&mut self) -> Result<SerializableFunction, SerializationError> {
+        Ok(SerializableFunction {
+            name: self.string()?,
+            return_type: self.option(Self::ty)?,
+            parameters: self.list(|r| Ok(SerializableParameter { param_type: r.ty()?, name: r.string()? }))?,
+            body: self.block()?,
+            line: self.line()?,
+        })
+    }
+
+    fn block(&mut self) -> Result<SerializableBlock, SerializationError> {
+        Ok(SerializableBlock { statements: self.list(Self::statement)? })
+    }
+
+    fn statement(&mut self) -> Result<SerializableStatement, SerializationError> {
+        self.enter()?;
+        let statement = match self.tag()? {
+            0 => SerializableStatement::VariableDecl(SerializableVariableDecl {
+                var_type: self.ty()?,
+                name: self.string()?,
+                initializer: self.option(Self::expr)?,
+                line: self.line()?,
+            }),
+            1 => SerializableStatement::ExprStmt(SerializableExprStmt { expr: self.expr()?, line: self.line()? }),
+            2 => SerializableStatement::IfStmt(SerializableIfStmt {
+                condition: self.expr()?,
+                then_branch: self.block()?,
+                else_branch: self.option(Self::block)?,
+                line: self.line()?,
+            }),
+            3 => SerializableStatement::WhileStmt(SerializableWhileStmt {
+                condition: self.expr()?,
+                body: self.block()?,
+                line: self.line()?,
+            }),
+            4 => SerializableStatement::ForStmt(SerializableForStmt {
+                variable: self.string()?,
+                start: self.expr()?,
+                end: self.expr()?,
+                body: self.block()?,
+                line: self.line()?,
+            }),
+            5 => SerializableStatement::ReturnStmt(SerializableReturnStmt { value: self.option(Self::expr)?, line: self.line()? }),
+            6 => SerializableStatement::WriteStmt(SerializableWriteStmt { arguments: self.list(Self::expr)?, line: self.line()? }),
+            7 => SerializableStatement::ReadStmt(SerializableReadStmt { target: self.expr()?, line: self.line()? }),
+            tag => return Err(self.invalid(&format!("tag de statement inválida {}", tag))),
+        };
+        self.depth -= 1;
+        Ok(statement)
+    }
+
+    fn expr(&mut self) -> Result<SerializableExpr, SerializationError> {
+        self.enter()?;
+        let expr = match self.tag()? {
+            0 => {
+                let value = match self.tag()? {
+                    0 => {
+                        let n = self.varint()?;
+                        SerializableLiteral::Inteiro(((n >> 1) as i64) ^ -((n & 1) as i64))
+                    }
+                    1 => SerializableLiteral::Decimal(f64::from_le_bytes(self.take(8)?.try_into().expect("8 bytes"))),
+                    2 => SerializableLiteral::Texto(self.string()?),
+                    3 => SerializableLiteral::Logico(match self.tag()? {
+                        0 => false,
+                        1 => true,
+                        tag => return Err(self.invalid(&format!("valor lógico inválido {}", tag))),
+                    }),
+                    tag => return Err(self.invalid(&format!("tag de literal inválida {}", tag))),
+                };
+                SerializableExpr::Literal { value, expr_type: self.ty()? }
+            }
+            1 => SerializableExpr::Variable { name: self.string()?, expr_type: self.ty()? },
+            2 => SerializableExpr::Call {
+                call: SerializableCallExpr { function: self.string()?, arguments: self.list(Self::expr)? },
+                expr_type: self.ty()?,
+            },
+            3 => SerializableExpr::BinaryOp {
+                op: self.variant(&BINARY_OPERATORS, "operador binário")?,
+                left: Box::new(self.expr()?),
+                right: Box::new(self.expr()?),
+                expr_type: self.ty()?,
+            },
+            4 => SerializableExpr::UnaryOp {
+                op: self.variant(&UNARY_OPERATORS, "operador unário")?,
+                operand: Box::new(self.expr()?),
+                expr_type: self.ty()?,
+            },
+            tag => return Err(self.invalid(&format!("tag de expressão inválida {}", tag))),
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn ty(&mut self) -> Result<SerializableType, SerializationError> {
+        self.variant(&TYPES, "tipo")
+    }
+
+    fn variant<T: Copy>(&mut self, table: &[T], what: &str) -> Result<T, SerializationError> {
+        let tag = self.tag()?;
+        table.get(tag as usize).copied().ok_or_else(|| self.invalid(&format!("tag de {} inválida {}", what, tag)))
+    }
+}
+
+// ==================== FUNÇÕES PÚBLICAS ====================
+
+// as mesmas operações de `serialization`, em binário
+
+pub fn save_program_to_binary(program: &Program, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(filename, encode(&SerializableProgram::from(program)))?;
+    Ok(())
+}
+
+pub fn save_semantic_result_to_binary(
+    result: &SemanticAnalysisResult,
+    filename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(filename, encode(&SerializableProgram::from(result)))?;
+    Ok(())
+}
+
+pub fn program_from_binary(bytes: &[u8]) -> Result<Program, Box<dyn std::error::Error>> {
+    Ok(Program::try_from(&decode(bytes)?)?)
+}
+
+pub fn load_program_from_binary(filename: &str) -> Result<Program, Box<dyn std::error::Error>> {
+    program_from_binary(&fs::read(filename)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::semantic::semantic::SemanticAnalyzer;
+
+    const SOURCE: &str = "importe \"m.sym\";
+        funcao decimal media(inteiro a, inteiro b) { retorne (a + b) / 2.0; }
+        funcao principal() {
+            inteiro n = -9223372036854775807 - 1; texto t = \"olá\";
+            para i de 1 ate 3 faca { se !(i % 2 == 0) entao { escreva(media(i, n), t); } senao { leia(n); } fimse } fimpara
+            enquanto falso faca { } fimenquanto
+        }";
+
+    fn to_json(program: &SerializableProgram) -> serde_json::Value {
+        serde_json::to_value(program).unwrap()
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let program = Parser::parse_from_source(SOURCE).unwrap();
+        let typed = SerializableProgram::from(&SemanticAnalyzer::new().analyze(program.clone()));
+
+        // as duas codificações descrevem o mesmo programa, anotações incluídas
+        let bytes = encode(&typed);
+        assert!(bytes.starts_with(MAGIC));
+        assert_eq!(to_json(&decode(&bytes).unwrap()), to_json(&typed));
+        assert!(bytes.len() < serde_json::to_vec(&typed).unwrap().len() / 4);
+
+        let untyped = encode(&SerializableProgram::from(&program));
+        assert_eq!(program_from_binary(&untyped).unwrap(), program);
+    }
+
+    #[test]
+    fn test_invalid_binary() {
+        let bytes = encode(&SerializableProgram::from(&Parser::parse_from_source(SOURCE).unwrap()));
+        let message = |bytes: &[u8]| decode(bytes).err().map(|e| e.message).unwrap_or_default();
+
+        assert!(message(b"{\"functions\": []}").contains("cabeçalho"));
+        assert!(message(&bytes[..bytes.len() - 1]).contains("truncado"));
+        assert!(message(&[bytes.as_slice(), &[0]].concat()).contains("sobrando"));
+        assert!(message(b"SYMA\x09").contains("formato 9"));
+        // quantidade de strings absurda não deve reservar memória
+        assert!(message(b"SYMA\x02\xff\xff\xff\xff\x0f").contains("maior que o arquivo"));
+
+        // `-(-(-(...)))` com 200 mil níveis: erro, não estouro de pilha (os
+        // mil níveis aceitos já passam da pilha de uma thread de teste)
+        let mut deep = b"SYMA\x02\x01\x00\x00\x00\x00\x00\x00\x00\x01\x01".to_vec();
+        deep.extend([0x04, 0x00].repeat(200_000));
+        assert!(crate::interpreter::with_large_stack(move || message(&deep)).contains("níveis de aninhamento"));
+    }
+}
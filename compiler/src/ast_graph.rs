@@ -0,0 +1,297 @@
+use crate::parser::ast::*;
+use crate::parser::printer::format_literal;
+use crate::semantic::symbol_table::{Symbol, SymbolTable};
+use crate::semantic::type_checker::TypeChecker;
+
+// Árvore do programa em Graphviz (DOT) ou Mermaid, para `--emit ast-dot` e
+// `--emit ast-mermaid`. Cada nó mostra o tipo do statement ou da expressão;
+// statements são caixas e expressões são elipses. No programa já analisado
+// (`typed`), as expressões mostram também o tipo inferido, calculado pelo
+// mesmo `TypeChecker` da análise semântica.
+//
+//     dot -Tsvg programa.dot -o programa.svg
+
+pub fn to_dot(program: &Program, typed: bool) -> String {
+    let graph = Graph::build(program, typed);
+    let mut out = String::from("digraph AST {\n    node [fontname=\"monospace\"];\n");
+    for (id, node) in graph.nodes.iter().enumerate() {
+        let shape = match node.shape {
+            Shape::Box => "box",
+            Shape::Ellipse => "ellipse",
+        };
+        out.push_str(&format!("    n{} [shape={}, label=\"{}\"];\n", id, shape, escape_dot(&node.label)));
+    }
+    for edge in &graph.edges {
+        match edge.role {
+            Some(role) => out.push_str(&format!("    n{} -> n{} [label=\"{}\"];\n", edge.from, edge.to, role)),
+            None => out.push_str(&format!("    n{} -> n{};\n", edge.from, edge.to)),
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+pub fn to_mermaid(program: &Program, typed: bool) -> String {
+    let graph = Graph::build(program, typed);
+    let mut out = String::from("graph TD\n");
+    for (id, node) in graph.nodes.iter().enumerate() {
+        let label = escape_mermaid(&node.label);
+        match node.shape {
+            Shape::Box => out.push_str(&format!("    n{}[\"{}\"]\n", id, label)),
+            Shape::Ellipse => out.push_str(&format!("    n{}([\"{}\"])\n", id, label)),
+        }
+    }
+    for edge in &graph.edges {
+        match edge.role {
+            Some(role) => out.push_str(&format!("    n{} -->|{}| n{}\n", edge.from, role, edge.to)),
+            None => out.push_str(&format!("    n{} --> n{}\n", edge.from, edge.to)),
+        }
+    }
+    out
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// entidades do Mermaid; `#` primeiro para não escapar os próprios escapes
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('\n', "<br/>")
+}
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Box,
+    Ellipse,
+}
+
+struct Node {
+    label: String,
+    shape: Shape,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    // papel do filho no pai (condição, corpo, ...); nenhum nas listas
+    role: Option<&'static str>,
+}
+
+struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    // escopos do programa analisado, para inferir o tipo das expressões
+    types: Option<SymbolTable>,
+}
+
+impl Graph {
+    fn build(program: &Program, typed: bool) -> Self {
+        let mut graph = Graph { nodes: Vec::new(), edges: Vec::new(), types: None };
+        if typed {
+            let mut table = SymbolTable::new();
+            for function in &program.functions {
+                let _ = table.insert_symbol(function.name.clone(), Symbol::Function { declaration: function.clone() });
+            }
+            graph.types = Some(table);
+        }
+
+        let root = graph.node("Program".to_string(), Shape::Box);
+        for import in &program.imports {
+            let node = graph.node(format!("Import \"{}\"", import.path), Shape::Box);
+            graph.edge(root, node, None);
+        }
+        for statement in &program.statements {
+            graph.statement(root, statement);
+        }
+        for function in &program.functions {
+            graph.function(root, function);
+        }
+        graph
+    }
+
+    fn node(&mut self, label: String, shape: Shape) -> usize {
+        self.nodes.push(Node { label, shape });
+        self.nodes.len() - 1
+    }
+
+    fn edge(&mut self, from: usize, to: usize, role: Option<&'static str>) {
+        self.edges.push(Edge { from, to, role });
+    }
+
+    fn declare(&mut self, name: &str, type_: &Type) {
+        if let Some(table) = &mut self.types {
+            let symbol = Symbol::Variable { name: name.to_string(), type_: type_.clone(), defined: true };
+            let _ = table.insert_symbol(name.to_string(), symbol);
+        }
+    }
+
+    fn enter_scope(&mut self) {
+        if let Some(table) = &mut self.types {
+            table.enter_scope();
+        }
+    }
+
+    fn exit_scope(&mut self) {
+        if let Some(table) = &mut self.types {
+            table.exit_scope();
+        }
+    }
+
+    fn function(&mut self, parent: usize, function: &FunctionDecl) {
+        let parameters: Vec<String> = function.parameters.iter().map(|p| format!("{} {}", p.param_type, p.name)).collect();
+        let mut label = format!("FunctionDecl {}({})", function.name, parameters.join(", "));
+        if let Some(return_type) = &function.return_type {
+            label.push_str(&format!(": {}", return_type));
+        }
+        let node = self.node(with_line(label, function.line), Shape::Box);
+        self.edge(parent, node, None);
+
+        self.enter_scope();
+        for parameter in &function.parameters {
+            self.declare(&parameter.name, &parameter.param_type);
+        }
+        self.block(node, "corpo", &function.body);
+        self.exit_scope();
+    }
+
+    fn block(&mut self, parent: usize, role: &'static str, block: &Block) {
+        let node = self.node("Block".to_string(), Shape::Box);
+        self.edge(parent, node, Some(role));
+        self.enter_scope();
+        for statement in &block.statements {
+            self.statement(node, statement);
+        }
+        self.exit_scope();
+    }
+
+    fn statement(&mut self, parent: usize, statement: &Statement) {
+        let label = match statement {
+            Statement::VariableDecl(decl) => format!("VariableDecl {} {}", decl.var_type, decl.name),
+            Statement::ExprStmt(_) => "ExprStmt".to_string(),
+            Statement::IfStmt(_) => "IfStmt".to_string(),
+            Statement::WhileStmt(_) => "WhileStmt".to_string(),
+            Statement::ForStmt(stmt) => format!("ForStmt {}", stmt.variable),
+            Statement::ReturnStmt(_) => "ReturnStmt".to_string(),
+            Statement::WriteStmt(_) => "WriteStmt".to_string(),
+            Statement::ReadStmt(_) => "ReadStmt".to_string(),
+        };
+        let node = self.node(with_line(label, statement.line()), Shape::Box);
+        self.edge(parent, node, None);
+
+        match statement {
+            Statement::VariableDecl(decl) => {
+                if let Some(initializer) = &decl.initializer {
+                    self.expr(node, Some("valor"), initializer);
+                }
+                self.declare(&decl.name, &decl.var_type);
+            }
+            Statement::ExprStmt(stmt) => self.expr(node, None, &stmt.expr),
+            Statement::IfStmt(stmt) => {
+                self.expr(node, Some("condição"), &stmt.condition);
+                self.block(node, "entao", &stmt.then_branch);
+                if let Some(else_branch) = &stmt.else_branch {
+                    self.block(node, "senao", else_branch);
+                }
+            }
+            Statement::WhileStmt(stmt) => {
+                self.expr(node, Some("condição"), &stmt.condition);
+                self.block(node, "corpo", &stmt.body);
+            }
+            Statement::ForStmt(stmt) => {
+                // como na análise semântica, sem uma variável numérica com esse
+                // nome o `para` declara uma inteira que só vale no laço
+                self.enter_scope();
+                if let Some(table) = &self.types
+                    && !matches!(table.lookup(&stmt.variable), Some(Symbol::Variable { type_: Type::Inteiro | Type::Decimal, .. }))
+                {
+                    self.declare(&stmt.variable, &Type::Inteiro);
+                }
+                self.expr(node, Some("de"), &stmt.start);
+                self.expr(node, Some("ate"), &stmt.end);
+                self.block(node, "corpo", &stmt.body);
+                self.exit_scope();
+            }
+            Statement::ReturnStmt(stmt) => {
+                if let Some(value) = &stmt.value {
+                    self.expr(node, Some("valor"), value);
+                }
+            }
+            Statement::WriteStmt(stmt) => stmt.arguments.iter().for_each(|argument| self.expr(node, None, argument)),
+            Statement::ReadStmt(stmt) => self.expr(node, Some("alvo"), &stmt.target),
+        }
+    }
+
+    fn expr(&mut self, parent: usize, role: Option<&'static str>, expr: &Expr) {
+        let mut label = match expr {
+            Expr::Literal(literal) => format!("Literal {}", format_literal(literal)),
+            Expr::Variable(name) => format!("Variable {}", name),
+            Expr::Call(call) => format!("Call {}", call.function),
+            Expr::BinaryOp(op, _, _) => format!("BinaryOp {}", op),
+            Expr::UnaryOp(op, _) => format!("UnaryOp {}", op),
+        };
+        // chamadas de funções sem retorno não têm tipo
+        if let Some(table) = &self.types
+            && let Ok(type_) = TypeChecker::infer_expression_type(expr, table)
+        {
+            label.push_str(&format!(" : {}", type_));
+        }
+        let node = self.node(label, Shape::Ellipse);
+        self.edge(parent, node, role);
+
+        match expr {
+            Expr::Literal(_) | Expr::Variable(_) => {}
+            Expr::Call(call) => call.arguments.iter().for_each(|argument| self.expr(node, None, argument)),
+            Expr::BinaryOp(_, left, right) => {
+                self.expr(node, Some("esquerda"), left);
+                self.expr(node, Some("direita"), right);
+            }
+            Expr::UnaryOp(_, operand) => self.expr(node, None, operand),
+        }
+    }
+}
+
+fn with_line(label: String, line: usize) -> String {
+    if line > 0 { format!("{}\nlinha {}", label, line) } else { label }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_ast_graph() {
+        let program = Parser::parse_from_source(
+            "funcao decimal metade(inteiro n) { retorne n / 2.0; }\nfuncao principal() { escreva(\"a\\\"b\", metade(3) > 1); }",
+        )
+        .unwrap();
+
+        let dot = to_dot(&program, false);
+        assert!(dot.starts_with("digraph AST {\n"));
+        assert!(dot.contains("n1 [shape=box, label=\"FunctionDecl metade(inteiro n): decimal\\nlinha 1\"];"));
+        assert!(dot.contains("[shape=ellipse, label=\"Literal \\\"a\\\\\\\"b\\\"\"];"));
+        assert!(dot.contains("n1 -> n2 [label=\"corpo\"];"));
+        assert!(!dot.contains(" : "));
+
+        let mermaid = to_mermaid(&program, true);
+        assert!(mermaid.starts_with("graph TD\n    n0[\"Program\"]\n"));
+        assert!(mermaid.contains("([\"BinaryOp / : decimal\"])"));
+        assert!(mermaid.contains("([\"BinaryOp #gt; : logico\"])"));
+        assert!(mermaid.contains("([\"Variable n : inteiro\"])"));
+        assert!(mermaid.contains("-->|esquerda|"));
+
+        // a variável declarada pelo `para` é inteira só dentro do laço
+        let program = Parser::parse_from_source(
+            "funcao principal() { decimal x = 0.0; para i de 1 ate 3 faca { escreva(i); } fimpara para x de 1 ate 2 faca { escreva(x); } fimpara }",
+        )
+        .unwrap();
+        let mermaid = to_mermaid(&program, true);
+        assert!(mermaid.contains("([\"Variable i : inteiro\"])"));
+        assert!(mermaid.contains("([\"Variable x : decimal\"])"));
+    }
+}
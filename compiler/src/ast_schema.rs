@@ -0,0 +1,447 @@
+use crate::serialization::SerializationError;
+use serde_json::{json, Map, Value};
+
+// Formato dos arquivos `.ast.json` (ver `serialization`): a versão gravada
+// em `metadata.format_version`, o JSON Schema do formato atual e as migrações
+// das versões anteriores. Os arquivos são lidos por ferramentas externas (o
+// codegen em Python, por exemplo) muito depois de gerados; qualquer mudança
+// nas estruturas serializáveis precisa de uma nova versão, uma migração e o
+// `codegen/ast.schema.json` gerado de novo (`compiler schema`).
+//
+// Versões:
+//   1  sem `format_version`, sem `imports` e sem linhas
+//   2  `imports` no programa e `line` nos statements e funções
+//   3  decimais infinitos e NaN como as strings "inf", "-inf" e "nan"
+
+pub const AST_FORMAT_VERSION: u64 = 3;
+
+// migração da versão i + 1 para a i + 2
+const MIGRATIONS: [fn(&mut Value); (AST_FORMAT_VERSION - 1) as usize] = [migrate_v1_to_v2, migrate_v2_to_v3];
+
+// ==================== JSON SCHEMA ====================
+
+pub fn schema() -> Value {
+    let types = ["Inteiro", "Decimal", "Texto", "Logico"];
+    let binary = [
+        "Add", "Subtract", "Multiply", "Divide", "Modulo", "Equal", "NotEqual", "Less", "LessEqual", "Greater",
+        "GreaterEqual", "And", "Or",
+    ];
+    let expr = |kind: &str, fields: Value| tagged(kind, fields, json!({ "expr_type": reference("TypeAnnotation") }));
+    let statement = |kind: &str, fields: Value| tagged(kind, fields, json!({ "line": line() }));
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "AST Symplia",
+        "description": format!("Programa Symplia serializado pelo compilador (formato {})", AST_FORMAT_VERSION),
+        "type": "object",
+        "properties": {
+            "imports": { "type": "array", "items": { "type": "string" } },
+            "functions": { "type": "array", "items": reference("Function") },
+            "global_statements": { "type": "array", "items": reference("Statement") },
+            "metadata": reference("Metadata"),
+        },
+        "required": ["imports", "functions", "global_statements", "metadata"],
+        "additionalProperties": false,
+        "$defs": {
+            "Metadata": {
+                "type": "object",
+                "properties": {
+                    "format_version": { "const": AST_FORMAT_VERSION },
+                    "source_file": { "type": "string" },
+                    "timestamp": { "type": "string" },
+                    "version": { "type": "string" },
+                    "entry_point": { "type": ["string", "null"] },
+                },
+                "required": ["format_version", "source_file", "timestamp", "version", "entry_point"],
+            },
+            "Type": { "enum": types },
+            // tipo calculado pela análise semântica; `Void` em chamadas sem retorno
+            "TypeAnnotation": { "enum": ["Inteiro", "Decimal", "Texto", "Logico", "Void"] },
+            "BinaryOperator": { "enum": binary },
+            "UnaryOperator": { "enum": ["Negate", "Plus", "Not"] },
+            "Literal": {
+                "oneOf": [
+                    variant("Inteiro", json!({ "type": "integer" })),
+                    variant("Decimal", json!({ "oneOf": [{ "type": "number" }, { "enum": ["inf", "-inf", "nan"] }] })),
+                    variant("Texto", json!({ "type": "string" })),
+                    variant("Logico", json!({ "type": "boolean" })),
+                ]
+            },
+            "Expr": {
+                "oneOf": [
+                    expr("Literal", json!({ "value": reference("Literal") })),
+                    expr("Variable", json!({ "name": { "type": "string" } })),
+                    expr("Call", json!({ "call": reference("CallExpr") })),
+                    expr("BinaryOp", json!({
+                        "op": reference("BinaryOperator"),
+                        "left": reference("Expr"),
+                        "right": reference("Expr"),
+                    })),
+                    expr("UnaryOp", json!({ "op": reference("UnaryOperator"), "operand": reference("Expr") })),
+                ]
+            },
+            "CallExpr": object(json!({
+                "function": { "type": "string" },
+                "arguments": { "type": "array", "items": reference("Expr") },
+            })),
+            "Parameter": object(json!({ "param_type": reference("Type"), "name": { "type": "string" } })),
+            "Block": object(json!({ "statements": { "type": "array", "items": reference("Statement") } })),
+            "Statement": {
+                "oneOf": [
+                    statement("VariableDecl", json!({
+                        "var_type": reference("Type"),
+                        "name": { "type": "string" },
+                        "initializer": nullable("Expr"),
+                    })),
+                    statement("ExprStmt", json!({ "expr": reference("Expr") })),
+                    statement("IfStmt", json!({
+                        "condition": reference("Expr"),
+                        "then_branch": reference("Block"),
+                        "else_branch": nullable("Block"),
+                    })),
+                    statement("WhileStmt", json!({ "condition": reference("Expr"), "body": reference("Block") })),
+                    statement("ForStmt", json!({
+                        "variable": { "type": "string" },
+                        "start": reference("Expr"),
+                        "end": reference("Expr"),
+                        "body": reference("Block"),
+                    })),
+                    statement("ReturnStmt", json!({ "value": nullable("Expr") })),
+                    statement("WriteStmt", json!({ "arguments": { "type": "array", "items": reference("Expr") } })),
+                    statement("ReadStmt", json!({ "target": reference("Expr") })),
+                ]
+            },
+            "Function": object(json!({
+                "name": { "type": "string" },
+                // `Void` é o mesmo que null
+                "return_type": { "oneOf": [reference("TypeAnnotation"), { "type": "null" }] },
+                "parameters": { "type": "array", "items": reference("Parameter") },
+                "body": reference("Block"),
+                "line": line(),
+            })),
+        },
+    })
+}
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+fn nullable(name: &str) -> Value {
+    json!({ "oneOf": [reference(name), { "type": "null" }] })
+}
+
+fn line() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+// objeto fechado com todos os campos obrigatórios
+fn object(properties: Value) -> Value {
+    let required: Vec<&String> = properties.as_object().map(|p| p.keys().collect()).unwrap_or_default();
+    json!({ "type": "object", "properties": properties, "required": required, "additionalProperties": false })
+}
+
+// variante de enum com tag externa do serde, como `{"Inteiro": 42}`
+fn variant(name: &str, value: Value) -> Value {
+    object(json!({ name: value }))
+}
+
+// variante de enum com `#[serde(tag = "type")]`, mais os campos comuns a
+// todas as variantes (`expr_type`, `line`)
+fn tagged(kind: &str, fields: Value, common: Value) -> Value {
+    let mut properties = Map::new();
+    properties.insert("type".to_string(), json!({ "const": kind }));
+    for part in [fields, common] {
+        if let Value::Object(part) = part {
+            properties.extend(part);
+        }
+    }
+    object(Value::Object(properties))
+}
+
+// ==================== VALIDAÇÃO ====================
+
+// Valida contra `schema()`. Implementa só as palavras-chave que o schema
+// gerado usa; os erros apontam o caminho do valor, como
+// `$.functions[0].body.statements[2].line`.
+pub fn validate(value: &Value) -> Result<(), SerializationError> {
+    let schema = schema();
+    Validator { defs: &schema["$defs"] }
+        .check(&schema, value, "$")
+        .map_err(|message| SerializationError { message })
+}
+
+struct Validator<'a> {
+    defs: &'a Value,
+}
+
+impl Validator<'_> {
+    fn resolve<'s>(&'s self, schema: &'s Value) -> &'s Value {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => &self.defs[reference.trim_start_matches("#/$defs/")],
+            None => schema,
+        }
+    }
+
+    fn check(&self, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+        let schema = self.resolve(schema);
+
+        if let Some(branches) = schema.get("oneOf").and_then(Value::as_array) {
+            return self.check_one_of(branches, value, path);
+        }
+        if let Some(expected) = schema.get("const")
+            && value != expected
+        {
+            return Err(format!("{}: esperado {}, encontrado {}", path, expected, value));
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array)
+            && !options.contains(value)
+        {
+            let names: Vec<String> = options.iter().map(Value::to_string).collect();
+            return Err(format!("{}: {} não é um de {}", path, value, names.join(", ")));
+        }
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                other => other.as_str().into_iter().collect(),
+            };
+            if !allowed.iter().any(|ty| has_type(value, ty)) {
+                return Err(format!("{}: esperado {}, encontrado {}", path, allowed.join(" ou "), type_name(value)));
+            }
+        }
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+            && value.as_f64().is_some_and(|n| n < minimum)
+        {
+            return Err(format!("{}: {} é menor que {}", path, value, minimum));
+        }
+
+        if let Some(items) = schema.get("items")
+            && let Some(array) = value.as_array()
+        {
+            for (i, item) in array.iter().enumerate() {
+                self.check(items, item, &format!("{}[{}]", path, i))?;
+            }
+        }
+        if let Some(object) = value.as_object() {
+            self.check_object(schema, object, path)?;
+        }
+        Ok(())
+    }
+
+    fn check_object(&self, schema: &Value, object: &Map<String, Value>, path: &str) -> Result<(), String> {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                return Err(format!("{}: falta o campo obrigatório '{}'", path, key));
+            }
+        }
+        for (key, field) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => self.check(field_schema, field, &format!("{}.{}", path, key))?,
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    return Err(format!("{}: campo desconhecido '{}'", path, key));
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Para mensagens úteis, o erro mostrado é o da alternativa que o valor
+    // "quis dizer": a de mesmo `type` (ou mesma chave, nos literais).
+    fn check_one_of(&self, branches: &[Value], value: &Value, path: &str) -> Result<(), String> {
+        let mut intended = Vec::new();
+        for branch in branches {
+            match self.check(branch, value, path) {
+                Ok(()) => return Ok(()),
+                Err(error) if self.discriminates(branch, value) => intended.push(error),
+                Err(_) => {}
+            }
+        }
+        match intended.as_slice() {
+            [error] => Err(error.clone()),
+            _ => Err(format!("{}: {} não corresponde a nenhuma das formas aceitas", path, describe(value))),
+        }
+    }
+
+    fn discriminates(&self, branch: &Value, value: &Value) -> bool {
+        let branch = self.resolve(branch);
+        if let Some(branches) = branch.get("oneOf").and_then(Value::as_array) {
+            return branches.iter().any(|branch| self.discriminates(branch, value));
+        }
+        let Some(object) = value.as_object() else {
+            return false;
+        };
+        let Some(properties) = branch.get("properties").and_then(Value::as_object) else {
+            return false;
+        };
+        match properties.get("type").and_then(|ty| ty.get("const")) {
+            Some(tag) => object.get("type") == Some(tag),
+            None => properties.len() == 1 && properties.keys().all(|key| object.contains_key(key)),
+        }
+    }
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value.get("type").and_then(Value::as_str) {
+        Some(kind) => format!("'{}'", kind),
+        None => type_name(value).to_string(),
+    }
+}
+
+// ==================== MIGRAÇÕES ====================
+
+// versão gravada no arquivo; arquivos sem `format_version` são da versão 1
+pub fn format_version(value: &Value) -> Result<u64, SerializationError> {
+    match value.get("metadata").and_then(|metadata| metadata.get("format_version")) {
+        None => Ok(1),
+        Some(version) => version
+            .as_u64()
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| SerializationError { message: format!("format_version inválida: {}", version) }),
+    }
+}
+
+// Leva um arquivo de qualquer versão conhecida para a atual
+pub fn migrate(mut value: Value) -> Result<Value, SerializationError> {
+    let version = format_version(&value)?;
+    if version > AST_FORMAT_VERSION {
+        return Err(SerializationError {
+            message: format!(
+                "o arquivo usa o formato {} da AST, mais novo que o {} deste compilador; atualize o compilador",
+                version, AST_FORMAT_VERSION
+            ),
+        });
+    }
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(&mut value);
+    }
+    if let Some(metadata) = value.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.insert("format_version".to_string(), json!(AST_FORMAT_VERSION));
+    }
+    Ok(value)
+}
+
+// linha 0 é "desconhecida", como nos programas montados à mão
+fn migrate_v1_to_v2(value: &mut Value) {
+    let Some(program) = value.as_object_mut() else {
+        return;
+    };
+    program.entry("imports").or_insert_with(|| json!([]));
+    if let Some(functions) = program.get_mut("functions").and_then(Value::as_array_mut) {
+        for function in functions {
+            default_line(function);
+            add_lines(&mut function["body"]);
+        }
+    }
+    if let Some(statements) = program.get_mut("global_statements").and_then(Value::as_array_mut) {
+        statements.iter_mut().for_each(add_statement_lines);
+    }
+}
+
+// a versão 2 gravava `null` no lugar dos decimais não finitos, e esses
+// arquivos nunca carregaram; os outros não mudam
+fn migrate_v2_to_v3(_value: &mut Value) {}
+
+fn add_lines(block: &mut Value) {
+    if let Some(statements) = block.get_mut("statements").and_then(Value::as_array_mut) {
+        statements.iter_mut().for_each(add_statement_lines);
+    }
+}
+
+fn add_statement_lines(statement: &mut Value) {
+    default_line(statement);
+    for key in ["then_branch", "else_branch", "body"] {
+        if let Some(block) = statement.get_mut(key) {
+            add_lines(block);
+        }
+    }
+}
+
+fn default_line(value: &mut Value) {
+    if let Some(object) = value.as_object_mut() {
+        object.entry("line").or_insert_with(|| json!(0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::serialization::{program_from_json, SerializableProgram};
+
+    #[test]
+    fn test_schema_file_is_current() {
+        let generated = serde_json::to_string_pretty(&schema()).unwrap() + "\n";
+        assert!(
+            include_str!("../codegen/ast.schema.json") == generated,
+            "codegen/ast.schema.json desatualizado; gere de novo com `compiler schema > codegen/ast.schema.json`"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let program = Parser::parse_from_source(
+            "importe \"m.sym\"; inteiro g; funcao decimal f(inteiro n) { se n > 0 entao { retorne 1.5; } fimse retorne -n; }
+             funcao principal() { para i de 1 ate 3 faca { escreva(f(i), \"x\"); } fimpara leia(g); }",
+        )
+        .unwrap();
+        let mut value = serde_json::to_value(SerializableProgram::from(&program)).unwrap();
+        assert_eq!(validate(&value), Ok(()));
+
+        value["functions"][0]["body"]["statements"][0]["then_branch"]["statements"][0]["line"] = json!("1");
+        assert_eq!(
+            validate(&value).unwrap_err().message,
+            "$.functions[0].body.statements[0].then_branch.statements[0].line: esperado integer, encontrado string"
+        );
+        value["functions"][0]["body"]["statements"][0]["type"] = json!("SeStmt");
+        assert!(validate(&value).unwrap_err().message.contains("'SeStmt' não corresponde"));
+        value["extra"] = json!(1);
+        assert_eq!(validate(&value).unwrap_err().message, "$: campo desconhecido 'extra'");
+    }
+
+    #[test]
+    fn test_migrate_v1() {
+        let v1 = r#"{
+            "functions": [{"name": "principal", "return_type": null, "parameters": [], "body": {"statements": [
+                {"type": "WhileStmt", "condition": {"type": "Literal", "value": {"Logico": false}, "expr_type": "Logico"},
+                 "body": {"statements": [{"type": "WriteStmt", "arguments": []}]}}
+            ]}}],
+            "global_statements": [],
+            "metadata": {"source_file": "a.sym", "timestamp": "", "version": "0.1.0", "entry_point": "principal"}
+        }"#;
+        let value = migrate(serde_json::from_str(v1).unwrap()).unwrap();
+        assert_eq!(value["metadata"]["format_version"], json!(AST_FORMAT_VERSION));
+        assert_eq!(value["functions"][0]["body"]["statements"][0]["body"]["statements"][0]["line"], json!(0));
+        assert_eq!(validate(&value), Ok(()));
+        assert!(program_from_json(v1).is_ok());
+
+        let future = json!({ "metadata": { "format_version": AST_FORMAT_VERSION + 1 } });
+        assert!(migrate(future).unwrap_err().message.contains("atualize o compilador"));
+    }
+}
@@ -0,0 +1,858 @@
+use std::collections::HashMap;
+use crate::parser::ast::*;
+use crate::parser::printer::{binary_precedence, function_signature, statement_summary};
+use crate::backend::{entry_point, numeric_result, BackendError, ScopedEnv};
+use crate::semantic::builtins::{Builtin, Overload};
+
+// Traduz a AST anotada para um script JavaScript independente.
+//
+// Mapeamento de tipos:
+//   inteiro -> BigInt (aritmética reduzida a 64 bits com BigInt.asIntN;
+//              a divisão de BigInt já trunca, e rt.dividir e rt.resto
+//              trocam o RangeError do divisor zero pelo erro de Symplia)
+//   decimal -> Number, texto -> string, logico -> boolean
+//
+// O programa vira a função `executar(io)`, que recebe a E/S como callbacks:
+//   io.escrever(linha)  recebe cada linha de `escreva` (padrão: console.log)
+//   io.ler() -> texto   devolve a próxima palavra da entrada ou null
+// Executado diretamente pelo node, o script lê as palavras da entrada padrão
+// no primeiro `leia` e, se a execução falhar, escreve "Erro: <mensagem>" e
+// encerra com código 1, como os outros backends.
+//
+// Cada comando gerado vem precedido do trecho Symplia que o originou.
+
+const RUNTIME: &str = r#"const rt = {
+    inteiro(valor) {
+        return BigInt.asIntN(64, valor);
+    },
+    dividir(a, b) {
+        if (b === 0n) throw new Error("divisão por zero");
+        return BigInt.asIntN(64, a / b);
+    },
+    // o resto nunca sai da faixa de 64 bits
+    resto(a, b) {
+        if (b === 0n) throw new Error("divisão por zero");
+        return a % b;
+    },
+    decimal(valor) {
+        if (Number.isNaN(valor)) return "nan";
+        if (!Number.isFinite(valor)) return valor > 0 ? "inf" : "-inf";
+        if (Object.is(valor, -0)) return "-0.000000";
+        // toFixed usa notação exponencial a partir de 1e21
+        if (Math.abs(valor) >= 1e21) return BigInt(valor).toString() + ".000000";
+        return valor.toFixed(6);
+    },
+    logico(valor) {
+        return valor ? "verdadeiro" : "falso";
+    },
+    // ordem dos pontos de código (a mesma dos bytes UTF-8 nos outros backends)
+    comparar(a, b) {
+        const x = Array.from(a, (c) => c.codePointAt(0));
+        const y = Array.from(b, (c) => c.codePointAt(0));
+        for (let i = 0; i < x.length && i < y.length; i++) {
+            if (x[i] !== y[i]) return x[i] < y[i] ? -1 : 1;
+        }
+        return Math.sign(x.length - y.length);
+    },
+    // && e || avaliam os dois lados, como nos backends nativos
+    e(a, b) {
+        return a && b;
+    },
+    ou(a, b) {
+        return a || b;
+    },
+    // com expoente negativo só as bases 1 e -1 não resultam em 0
+    potenciaInteira(base, expoente) {
+        if (expoente < 0n) {
+            if (base === 1n || base === -1n) return expoente % 2n === 0n ? 1n : base;
+            return 0n;
+        }
+        let resultado = 1n;
+        while (expoente > 0n) {
+            if (expoente & 1n) resultado = BigInt.asIntN(64, resultado * base);
+            base = BigInt.asIntN(64, base * base);
+            expoente >>= 1n;
+        }
+        return resultado;
+    },
+    // Math.pow difere do pow do C em 1 ** NaN e (-1) ** Infinity
+    potencia(base, expoente) {
+        if (base === 1 || (Math.abs(base) === 1 && Math.abs(expoente) === Infinity)) return 1;
+        return Math.pow(base, expoente);
+    },
+    absInteiro(valor) {
+        return BigInt.asIntN(64, valor < 0n ? -valor : valor);
+    },
+    // Math.round arredonda -2.5 para -2; aqui a metade se afasta do zero
+    arredondar(valor) {
+        const parte = Math.trunc(valor);
+        return Math.abs(valor - parte) >= 0.5 ? parte + Math.sign(valor) : parte;
+    },
+    // textos contam pontos de código, não unidades UTF-16
+    tamanho(texto) {
+        return BigInt(Array.from(texto).length);
+    },
+    // só A-Z e as letras do Latin-1, como nos backends nativos (toUpperCase
+    // trocaria também ß por SS, ÿ por Ÿ...)
+    maiusculo(texto) {
+        return texto.replace(/[a-z\u00e0-\u00f6\u00f8-\u00fe]/g, (c) => String.fromCharCode(c.charCodeAt(0) ^ 0x20));
+    },
+    minusculo(texto) {
+        return texto.replace(/[A-Z\u00c0-\u00d6\u00d8-\u00de]/g, (c) => String.fromCharCode(c.charCodeAt(0) ^ 0x20));
+    },
+    subtexto(texto, inicio, quantidade) {
+        const caracteres = Array.from(texto);
+        const tamanho = BigInt(caracteres.length);
+        const de = inicio < 1n ? 0n : inicio - 1n < tamanho ? inicio - 1n : tamanho;
+        const ate = quantidade <= 0n ? de : quantidade < tamanho - de ? de + quantidade : tamanho;
+        return caracteres.slice(Number(de), Number(ate)).join("");
+    },
+    contem(texto, trecho) {
+        return texto.includes(trecho);
+    },
+    substituir(texto, de, por) {
+        return de === "" ? texto : texto.split(de).join(por);
+    },
+    // trunca em direção a zero, saturando nos limites; nan vira 0
+    decimalParaInteiro(valor) {
+        if (Number.isNaN(valor)) return 0n;
+        if (valor >= 9223372036854775808) return 9223372036854775807n;
+        if (valor <= -9223372036854775808) return -9223372036854775808n;
+        return BigInt(Math.trunc(valor));
+    },
+    textoParaInteiro(texto) {
+        const valor = /^[+-]?[0-9]+$/.test(texto) ? BigInt(texto) : null;
+        if (valor === null || valor !== BigInt.asIntN(64, valor)) {
+            throw new Error(`Conversão inválida para inteiro: ${texto}`);
+        }
+        return valor;
+    },
+    // Number aceitaria também "0x10", " 1", "Infinity"...
+    textoParaDecimal(texto) {
+        if (!/^[+-]?[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?$/.test(texto)) {
+            throw new Error(`Conversão inválida para decimal: ${texto}`);
+        }
+        return Number(texto);
+    },
+    textoParaLogico(texto) {
+        if (texto !== "verdadeiro" && texto !== "falso") {
+            throw new Error(`Conversão inválida para logico: ${texto}`);
+        }
+        return texto === "verdadeiro";
+    },
+    lerInteiro(palavra) {
+        if (palavra === null || !/^[+-]?\d+$/.test(palavra)) {
+            throw new Error(`Entrada inválida para inteiro: ${palavra}`);
+        }
+        return BigInt.asIntN(64, BigInt(palavra));
+    },
+    lerDecimal(palavra) {
+        const valor = palavra === null ? NaN : Number(palavra);
+        if (Number.isNaN(valor) && palavra !== "nan") {
+            throw new Error(`Entrada inválida para decimal: ${palavra}`);
+        }
+        return valor;
+    },
+    lerTexto(palavra) {
+        return palavra ?? "";
+    },
+    lerLogico(palavra) {
+        return palavra === "verdadeiro";
+    },
+};
+"#;
+
+const MAIN_MODULE: &str = r#"if (typeof require !== "undefined" && typeof module !== "undefined" && require.main === module) {
+    // ler a entrada só no primeiro `leia` não deixa esperando num terminal
+    // um programa que nunca lê
+    let palavras = null;
+    try {
+        executar({
+            escrever: (linha) => process.stdout.write(linha + "\n"),
+            ler: () => {
+                palavras ??= require("fs").readFileSync(0, "utf8").split(/\s+/).filter((p) => p !== "");
+                return palavras.length > 0 ? palavras.shift() : null;
+            },
+        });
+    } catch (erro) {
+        process.stdout.write(`Erro: ${erro.message}\n`);
+        process.exitCode = 1;
+    }
+}
+
+if (typeof module !== "undefined") {
+    module.exports = { executar };
+}
+"#;
+
+// palavras reservadas do JavaScript e nomes usados pelo código gerado
+const RESERVED: [&str; 52] = [
+    "arguments", "await", "break", "case", "catch", "class", "const", "continue",
+    "debugger", "default", "delete", "do", "else", "enum", "eval", "export",
+    "extends", "false", "finally", "for", "function", "if", "implements", "import",
+    "in", "instanceof", "interface", "let", "new", "null", "package", "private",
+    "protected", "public", "return", "static", "super", "switch", "this", "throw",
+    "true", "try", "typeof", "var", "void", "while", "with", "yield",
+    "rt", "escrever", "ler", "executar",
+];
+
+// globais do ambiente referenciados pelo código gerado
+const ENVIRONMENT: [&str; 9] = ["BigInt", "Number", "String", "Math", "Object", "Array", "Error", "NaN", "Infinity"];
+
+// o `$` nunca aparece num identificador de Symplia
+pub fn js_identifier(name: &str) -> String {
+    if RESERVED.contains(&name) || ENVIRONMENT.contains(&name) || name == "undefined" {
+        format!("{}$", name)
+    } else {
+        // funções de módulos importados se chamam `modulo.funcao`
+        name.replace('.', "$")
+    }
+}
+
+const UNARY_PRECEDENCE: u8 = 7;
+const PRIMARY_PRECEDENCE: u8 = 8;
+
+// código JavaScript de uma expressão, com seu tipo e a precedência do
+// operador mais externo (para decidir onde pôr parênteses)
+struct JsExpr {
+    code: String,
+    ty: Type,
+    precedence: u8,
+}
+
+impl JsExpr {
+    fn new(code: String, ty: Type, precedence: u8) -> Self {
+        Self { code, ty, precedence }
+    }
+
+    fn primary(code: String, ty: Type) -> Self {
+        Self::new(code, ty, PRIMARY_PRECEDENCE)
+    }
+
+    fn at_least(&self, minimum: u8) -> String {
+        if self.precedence < minimum {
+            format!("({})", self.code)
+        } else {
+            self.code.clone()
+        }
+    }
+}
+
+pub struct JsGenerator<'a> {
+    program: &'a Program,
+    env: ScopedEnv<String>,
+
+    // estado da função sendo gerada
+    name_counts: HashMap<String, usize>,
+    lines: Vec<String>,
+    indent: usize,
+}
+
+impl<'a> JsGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            env: ScopedEnv::new(program),
+            name_counts: HashMap::new(),
+            lines: Vec::new(),
+            indent: 1,
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String, BackendError> {
+        self.emit("const escrever = io.escrever ?? ((linha) => console.log(linha));");
+        self.emit("const ler = io.ler ?? (() => null);");
+
+        let globals: Vec<&VariableDecl> = self.program.statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::VariableDecl(decl) => Some(decl),
+                _ => None,
+            })
+            .collect();
+        if !globals.is_empty() {
+            self.emit("");
+        }
+        for decl in globals {
+            let name = self.unique_name(&decl.name);
+            self.emit(&format!("let {} = {};", name, default_value(&decl.var_type)));
+            self.env.declare(&decl.name, decl.var_type.clone(), name);
+        }
+
+        for function in &self.program.functions {
+            self.emit("");
+            self.generate_function(function).map_err(|e| e.at_line(function.line))?;
+        }
+
+        self.emit("");
+        for statement in &self.program.statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    if let Some(initializer) = &decl.initializer {
+                        self.emit_source(statement);
+                        let value = self.generate_coerced(initializer, &decl.var_type).map_err(|e| e.at_line(decl.line))?;
+                        let (name, _) = self.lookup(&decl.name).map_err(|e| e.at_line(decl.line))?;
+                        self.emit(&format!("{} = {};", name, value));
+                    }
+                }
+                other => self.generate_statement(other, &None).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+
+        if let Some(principal) = entry_point(self.program) {
+            self.emit(&format!("{}();", js_identifier(&principal.name)));
+        }
+
+        let mut out = String::new();
+        out.push_str("// Gerado pelo compilador Symplia\n");
+        out.push_str("\"use strict\";\n\n");
+        out.push_str(RUNTIME);
+        out.push('\n');
+        out.push_str("function executar(io = {}) {\n");
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("}\n\n");
+        out.push_str(MAIN_MODULE);
+        Ok(out)
+    }
+
+    // === FUNÇÕES ===
+
+    fn generate_function(&mut self, function: &FunctionDecl) -> Result<(), BackendError> {
+        let outer_counts = std::mem::take(&mut self.name_counts);
+        self.env.enter_scope();
+
+        let mut parameters = Vec::new();
+        for param in &function.parameters {
+            let name = self.unique_name(&param.name);
+            self.env.declare(&param.name, param.param_type.clone(), name.clone());
+            parameters.push(name);
+        }
+
+        self.emit(&format!("// {}", function_signature(function)));
+        self.emit(&format!("function {}({}) {{", js_identifier(&function.name), parameters.join(", ")));
+        self.indent += 1;
+        self.generate_statements(&function.body, &function.return_type)?;
+
+        // funções com retorno devolvem o valor padrão se o corpo terminar sem `retorne`
+        if let Some(return_type) = &function.return_type
+            && !matches!(function.body.statements.last(), Some(Statement::ReturnStmt(_)))
+        {
+            self.emit(&format!("return {};", default_value(return_type)));
+        }
+        self.indent -= 1;
+        self.emit("}");
+
+        self.env.exit_scope();
+        self.name_counts = outer_counts;
+        Ok(())
+    }
+
+    // === STATEMENTS ===
+
+    fn generate_statements(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+        for statement in &block.statements {
+            self.generate_statement(statement, return_type).map_err(|e| e.at_line(statement.line()))?;
+        }
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    fn generate_block(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.indent += 1;
+        self.generate_statements(block, return_type)?;
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn generate_statement(&mut self, statement: &Statement, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.emit_source(statement);
+
+        match statement {
+            Statement::VariableDecl(decl) => {
+                let value = match &decl.initializer {
+                    Some(initializer) => self.generate_coerced(initializer, &decl.var_type)?,
+                    None => default_value(&decl.var_type).to_string(),
+                };
+                let name = self.unique_name(&decl.name);
+                self.env.declare(&decl.name, decl.var_type.clone(), name.clone());
+                self.emit(&format!("let {} = {};", name, value));
+            }
+            Statement::ExprStmt(expr_stmt) => {
+                let expr = self.generate_expr(&expr_stmt.expr)?;
+                self.emit(&format!("{};", expr.code));
+            }
+            Statement::IfStmt(if_stmt) => {
+                let condition = self.generate_expr(&if_stmt.condition)?;
+                self.emit(&format!("if ({}) {{", condition.code));
+                self.generate_block(&if_stmt.then_branch, return_type)?;
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.emit("} else {");
+                    self.generate_block(else_branch, return_type)?;
+                }
+                self.emit("}");
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let condition = self.generate_expr(&while_stmt.condition)?;
+                self.emit(&format!("while ({}) {{", condition.code));
+                self.generate_block(&while_stmt.body, return_type)?;
+                self.emit("}");
+            }
+            Statement::ForStmt(for_stmt) => {
+                self.env.enter_scope();
+
+                let visible = self.env
+                    .lookup(&for_stmt.variable)
+                    .filter(|(_, ty)| matches!(ty, Type::Inteiro | Type::Decimal));
+                let start = self.generate_coerced(&for_stmt.start, visible.as_ref().map_or(&Type::Inteiro, |(_, ty)| ty))?;
+                let (name, var_type, init) = match visible {
+                    Some((name, ty)) => (name.clone(), ty, format!("{} = {}", name, start)),
+                    None => {
+                        let name = self.unique_name(&for_stmt.variable);
+                        self.env.declare(&for_stmt.variable, Type::Inteiro, name.clone());
+                        (name.clone(), Type::Inteiro, format!("let {} = {}", name, start))
+                    }
+                };
+
+                // o limite é reavaliado a cada volta, como a condição do `for`
+                let end = self.generate_expr(&for_stmt.end)?;
+                let compare_type = numeric_result(&var_type, &end.ty);
+                let current = JsExpr::primary(name.clone(), var_type);
+                let precedence = binary_precedence(&BinaryOperator::LessEqual);
+                let condition = format!(
+                    "{} <= {}",
+                    coerce(&current, &compare_type).at_least(precedence),
+                    coerce(&end, &compare_type).at_least(precedence + 1),
+                );
+
+                self.emit(&format!("for ({}; {}; {}++) {{", init, condition, name));
+                self.generate_block(&for_stmt.body, return_type)?;
+                self.emit("}");
+
+                self.env.exit_scope();
+            }
+            Statement::ReturnStmt(return_stmt) => match (&return_stmt.value, return_type) {
+                (Some(value), Some(return_type)) => {
+                    let value = self.generate_coerced(value, return_type)?;
+                    self.emit(&format!("return {};", value));
+                }
+                (None, None) => self.emit("return;"),
+                _ => return Err(BackendError::new("Retorno incompatível com o tipo da função")),
+            },
+            Statement::WriteStmt(write_stmt) => {
+                let mut line = String::new();
+                for (i, arg) in write_stmt.arguments.iter().enumerate() {
+                    if i > 0 {
+                        line.push(' ');
+                    }
+                    if let Expr::Literal(Literal::Texto(text)) = arg {
+                        line.push_str(&escape_template(text));
+                        continue;
+                    }
+                    let value = self.generate_expr(arg)?;
+                    let text = match value.ty {
+                        Type::Inteiro | Type::Texto => value.code,
+                        Type::Decimal => format!("rt.decimal({})", value.code),
+                        Type::Logico => format!("rt.logico({})", value.code),
+                    };
+                    line.push_str(&format!("${{{}}}", text));
+                }
+                self.emit(&format!("escrever(`{}`);", line));
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(BackendError::new("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let (name, ty) = self.lookup(name)?;
+                let reader = match ty {
+                    Type::Inteiro => "lerInteiro",
+                    Type::Decimal => "lerDecimal",
+                    Type::Texto => "lerTexto",
+                    Type::Logico => "lerLogico",
+                };
+                self.emit(&format!("{} = rt.{}(ler());", name, reader));
+            }
+        }
+        Ok(())
+    }
+
+    // === EXPRESSÕES ===
+
+    fn generate_coerced(&mut self, expr: &Expr, target: &Type) -> Result<String, BackendError> {
+        let value = self.generate_expr(expr)?;
+        Ok(coerce(&value, target).code)
+    }
+
+    fn generate_expr(&mut self, expr: &Expr) -> Result<JsExpr, BackendError> {
+        let ty = self.env.expr_type(expr)?;
+
+        Ok(match expr {
+            Expr::Literal(literal) => {
+                let code = match literal {
+                    Literal::Inteiro(n) => format!("{}n", n),
+                    Literal::Decimal(n) => js_number(*n),
+                    // o JSON é um subconjunto das strings de JavaScript
+                    Literal::Texto(s) => serde_json::to_string(s).map_err(|e| BackendError::new(e.to_string()))?,
+                    Literal::Logico(b) => b.to_string(),
+                };
+                let precedence = if code.starts_with('-') { UNARY_PRECEDENCE } else { PRIMARY_PRECEDENCE };
+                JsExpr::new(code, ty, precedence)
+            }
+            Expr::Variable(name) => JsExpr::primary(self.lookup(name)?.0, ty),
+            Expr::Call(call) => JsExpr::primary(self.generate_call(call)?, ty),
+            // só -i64::MIN transborda
+            Expr::UnaryOp(UnaryOperator::Negate, operand)
+                if matches!(**operand, Expr::Literal(Literal::Inteiro(n)) if n != i64::MIN) =>
+            {
+                let Expr::Literal(Literal::Inteiro(n)) = **operand else { unreachable!() };
+                JsExpr::new(format!("{}n", -n), ty, UNARY_PRECEDENCE)
+            }
+            Expr::UnaryOp(op, operand) => {
+                let operand = self.generate_expr(operand)?;
+                match (op, &operand.ty) {
+                    (UnaryOperator::Plus, _) => operand,
+                    (UnaryOperator::Negate, Type::Inteiro) => {
+                        JsExpr::primary(format!("rt.inteiro(-{})", operand.at_least(PRIMARY_PRECEDENCE)), ty)
+                    }
+                    (UnaryOperator::Negate, _) => {
+                        JsExpr::new(format!("-{}", operand.at_least(PRIMARY_PRECEDENCE)), ty, UNARY_PRECEDENCE)
+                    }
+                    (UnaryOperator::Not, _) => {
+                        JsExpr::new(format!("!{}", operand.at_least(PRIMARY_PRECEDENCE)), ty, UNARY_PRECEDENCE)
+                    }
+                }
+            }
+            Expr::BinaryOp(op, left, right) => self.generate_binary(op, left, right, ty)?,
+        })
+    }
+
+    fn generate_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr, ty: Type) -> Result<JsExpr, BackendError> {
+        let right_has_effects = has_effects(right);
+        let left = self.generate_expr(left)?;
+        let right = self.generate_expr(right)?;
+        let precedence = binary_precedence(op);
+
+        if matches!(op, BinaryOperator::And | BinaryOperator::Or) {
+            // o curto-circuito de && e || pularia chamadas (e erros) do lado direito
+            if right_has_effects {
+                let helper = if *op == BinaryOperator::And { "e" } else { "ou" };
+                return Ok(JsExpr::primary(format!("rt.{}({}, {})", helper, left.code, right.code), ty));
+            }
+            let code = format!("{} {} {}", left.at_least(precedence), op, right.at_least(precedence + 1));
+            return Ok(JsExpr::new(code, ty, precedence));
+        }
+
+        if *op == BinaryOperator::Add && (left.ty == Type::Texto || right.ty == Type::Texto) {
+            let code = format!("{} + {}", text_operand(&left).at_least(precedence), text_operand(&right).at_least(precedence + 1));
+            return Ok(JsExpr::new(code, ty, precedence));
+        }
+
+        if left.ty == Type::Texto && right.ty == Type::Texto {
+            let operator = match op {
+                BinaryOperator::Equal => "===",
+                BinaryOperator::NotEqual => "!==",
+                BinaryOperator::Less | BinaryOperator::LessEqual
+                | BinaryOperator::Greater | BinaryOperator::GreaterEqual => {
+                    let code = format!("rt.comparar({}, {}) {} 0", left.code, right.code, op);
+                    return Ok(JsExpr::new(code, ty, precedence));
+                }
+                _ => return Err(BackendError::new(format!("Operador '{}' não suportado para texto", op))),
+            };
+            let code = format!("{} {} {}", left.at_least(precedence), operator, right.at_least(precedence + 1));
+            return Ok(JsExpr::new(code, ty, precedence));
+        }
+
+        let operand_type = if left.ty == Type::Logico {
+            Type::Logico
+        } else {
+            numeric_result(&left.ty, &right.ty)
+        };
+        let left = coerce(&left, &operand_type);
+        let right = coerce(&right, &operand_type);
+
+        let operator = match op {
+            BinaryOperator::Equal => "===",
+            BinaryOperator::NotEqual => "!==",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+        };
+        if operand_type == Type::Inteiro && matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo) {
+            let function = if *op == BinaryOperator::Divide { "rt.dividir" } else { "rt.resto" };
+            return Ok(JsExpr::primary(format!("{}({}, {})", function, left.code, right.code), ty));
+        }
+        let code = format!("{} {} {}", left.at_least(precedence), operator, right.at_least(precedence + 1));
+
+        let wraps = operand_type == Type::Inteiro
+            && matches!(op, BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply);
+        if wraps {
+            Ok(JsExpr::primary(format!("rt.inteiro({})", code), ty))
+        } else {
+            Ok(JsExpr::new(code, ty, precedence))
+        }
+    }
+
+    fn generate_call(&mut self, call: &CallExpr) -> Result<String, BackendError> {
+        if let Some((builtin, overload)) = self.env.builtin(call)? {
+            return self.generate_builtin(builtin, overload, &call.arguments);
+        }
+
+        let parameters: Vec<Type> = self.env
+            .function(&call.function)
+            .ok_or_else(|| BackendError::new(format!("Função '{}' não declarada", call.function)))?
+            .parameters
+            .iter()
+            .map(|p| p.param_type.clone())
+            .collect();
+
+        let mut arguments = Vec::new();
+        for (arg, param_type) in call.arguments.iter().zip(parameters.iter()) {
+            arguments.push(self.generate_coerced(arg, param_type)?);
+        }
+        Ok(format!("{}({})", js_identifier(&call.function), arguments.join(", ")))
+    }
+
+    fn generate_builtin(&mut self, builtin: Builtin, overload: &Overload, arguments: &[Expr]) -> Result<String, BackendError> {
+        if let Some(conversion) = self.generate_conversion(builtin, overload, arguments)? {
+            return Ok(conversion);
+        }
+
+        let mut values = Vec::new();
+        for (arg, param_type) in arguments.iter().zip(overload.parameters) {
+            values.push(self.generate_coerced(arg, param_type)?);
+        }
+        let integer = overload.parameters[0] == Type::Inteiro;
+
+        let function = match builtin {
+            Builtin::Raiz => "Math.sqrt",
+            Builtin::Potencia if integer => "rt.potenciaInteira",
+            Builtin::Potencia => "rt.potencia",
+            Builtin::Abs if integer => "rt.absInteiro",
+            Builtin::Abs => "Math.abs",
+            // arredondar um inteiro não muda nada
+            Builtin::Arredondar | Builtin::Piso | Builtin::Teto if integer => return Ok(format!("({})", values[0])),
+            Builtin::Arredondar => "rt.arredondar",
+            Builtin::Piso => "Math.floor",
+            Builtin::Teto => "Math.ceil",
+            Builtin::Tamanho => "rt.tamanho",
+            Builtin::Maiusculo => "rt.maiusculo",
+            Builtin::Minusculo => "rt.minusculo",
+            Builtin::Subtexto => "rt.subtexto",
+            Builtin::Contem => "rt.contem",
+            Builtin::Substituir => "rt.substituir",
+            Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico => {
+                unreachable!("conversões são geradas em generate_conversion")
+            }
+        };
+        Ok(format!("{}({})", function, values.join(", ")))
+    }
+
+    fn generate_conversion(&mut self, builtin: Builtin, overload: &Overload, arguments: &[Expr]) -> Result<Option<String>, BackendError> {
+        if !matches!(builtin, Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico) {
+            return Ok(None);
+        }
+        let from = &overload.parameters[0];
+        let to = &overload.return_type;
+        // mesmo tipo ou inteiro para decimal: basta a coerção de sempre
+        if from == to || (*from == Type::Inteiro && *to == Type::Decimal) {
+            return Ok(Some(format!("({})", self.generate_coerced(&arguments[0], to)?)));
+        }
+
+        let value = self.generate_coerced(&arguments[0], from)?;
+        Ok(Some(match (from, to) {
+            (Type::Decimal, Type::Inteiro) => format!("rt.decimalParaInteiro({})", value),
+            (Type::Texto, Type::Inteiro) => format!("rt.textoParaInteiro({})", value),
+            (Type::Logico, Type::Inteiro) => format!("(({}) ? 1n : 0n)", value),
+            (Type::Texto, Type::Decimal) => format!("rt.textoParaDecimal({})", value),
+            (Type::Logico, Type::Decimal) => format!("(({}) ? 1 : 0)", value),
+            (Type::Inteiro, Type::Texto) => format!("String({})", value),
+            (Type::Decimal, Type::Texto) => format!("rt.decimal({})", value),
+            (Type::Logico, Type::Texto) => format!("rt.logico({})", value),
+            (Type::Inteiro, Type::Logico) => format!("(({}) !== 0n)", value),
+            (Type::Texto, Type::Logico) => format!("rt.textoParaLogico({})", value),
+            _ => return Err(BackendError::new(format!("Conversão de {} para {} não suportada", from, to))),
+        }))
+    }
+
+    // === AUXILIARES ===
+
+    fn lookup(&self, name: &str) -> Result<(String, Type), BackendError> {
+        self.env
+            .lookup(name)
+            .ok_or_else(|| BackendError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    // `let` não pode repetir um nome no mesmo escopo (nem o de um parâmetro
+    // no corpo da função), então redeclarações na função ganham sufixo: x, x$1
+    fn unique_name(&mut self, name: &str) -> String {
+        let base = js_identifier(name);
+        let count = self.name_counts.entry(base.clone()).or_insert(0);
+        let unique = if *count == 0 { base } else { format!("{}${}", base, count) };
+        *count += 1;
+        unique
+    }
+
+    fn emit_source(&mut self, statement: &Statement) {
+        self.emit(&format!("// {}", statement_summary(statement)));
+    }
+
+    fn emit(&mut self, line: &str) {
+        if line.is_empty() {
+            self.lines.push(String::new());
+        } else {
+            self.lines.push(format!("{}{}", "    ".repeat(self.indent), line));
+        }
+    }
+}
+
+pub fn generate_js(program: &Program) -> Result<String, BackendError> {
+    JsGenerator::new(program).generate()
+}
+
+fn default_value(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "0n",
+        Type::Decimal => "0",
+        Type::Texto => "\"\"",
+        Type::Logico => "false",
+    }
+}
+
+fn coerce(value: &JsExpr, target: &Type) -> JsExpr {
+    if value.ty == Type::Inteiro && *target == Type::Decimal {
+        JsExpr::primary(format!("Number({})", value.code), Type::Decimal)
+    } else {
+        JsExpr::new(value.code.clone(), value.ty.clone(), value.precedence)
+    }
+}
+
+// lado não textual de uma concatenação, formatado como em paraTexto; BigInt
+// já vira texto sem o sufixo n
+fn text_operand(value: &JsExpr) -> JsExpr {
+    match value.ty {
+        Type::Decimal => JsExpr::primary(format!("rt.decimal({})", value.code), Type::Texto),
+        Type::Logico => JsExpr::primary(format!("rt.logico({})", value.code), Type::Texto),
+        _ => JsExpr::new(value.code.clone(), value.ty.clone(), value.precedence),
+    }
+}
+
+fn js_number(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "Infinity".to_string() } else { "-Infinity".to_string() }
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+fn escape_template(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '`' => out.push_str("\\`"),
+            '\\' => out.push_str("\\\\"),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// chamadas podem escrever ou ler; divisões podem falhar
+fn has_effects(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(_) => true,
+        Expr::BinaryOp(BinaryOperator::Divide | BinaryOperator::Modulo, _, _) => true,
+        Expr::BinaryOp(_, left, right) => has_effects(left) || has_effects(right),
+        Expr::UnaryOp(_, operand) => has_effects(operand),
+        Expr::Literal(_) | Expr::Variable(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+    use crate::parser::Parser;
+
+    fn generate(source: &str) -> String {
+        let program = Parser::parse_from_source(source).unwrap();
+        generate_js(&program).unwrap()
+    }
+
+    #[test]
+    fn test_integer_semantics_and_comments() {
+        let js = generate(
+            "funcao inteiro media(inteiro a, inteiro b) { retorne (a + b) / 2; }
+             funcao principal() { escreva(\"Média:\", media(7, -2), 1.5 * media(1, 2)); }",
+        );
+        assert!(js.contains("// funcao inteiro media(inteiro a, inteiro b)\n    function media(a, b) {"));
+        assert!(js.contains("// retorne (a + b) / 2;\n        return rt.dividir(rt.inteiro(a + b), 2n);"));
+        assert!(js.contains("escrever(`Média: ${media(7n, -2n)} ${rt.decimal(1.5 * Number(media(1n, 2n)))}`);"));
+        assert!(js.trim_end().ends_with("module.exports = { executar };\n}"));
+        assert!(js.contains("    principal();\n}"));
+    }
+
+    #[test]
+    fn test_loops_names_and_logical_operators() {
+        let js = generate(
+            "funcao logico positivo(inteiro n) { retorne n > 0; }
+             funcao principal() {
+                 inteiro i = 0;
+                 texto var = \"a\";
+                 para i de 1 ate 3 faca { escreva(i, var < \"b\"); } fimpara
+                 se i > 1 && positivo(i) || falso entao { leia(var); } fimse
+             }",
+        );
+        assert!(js.contains("let var$ = \"a\";"));
+        assert!(js.contains("for (i = 1n; i <= 3n; i++) {"));
+        assert!(js.contains("escrever(`${i} ${rt.logico(rt.comparar(var$, \"b\") < 0)}`);"));
+        assert!(js.contains("if (rt.e(i > 1n, positivo(i)) || false) {"));
+        assert!(js.contains("var$ = rt.lerTexto(ler());"));
+        // corpo sem `retorne` no fim ganha o valor padrão só quando precisa
+        assert!(!js.contains("return false;"));
+    }
+
+    #[test]
+    fn test_runner_division_by_zero_and_lazy_input() {
+        let js = generate(
+            "funcao principal() {
+                 inteiro m = -9223372036854775807 - 1;
+                 inteiro z = 0;
+                 escreva(m / -1, m % -1, -7 / 2, -7 % 2);
+                 escreva(7 % z);
+                 escreva(1);
+             }",
+        );
+        assert!(js.contains("${rt.dividir(m, -1n)} ${rt.resto(m, -1n)}"));
+
+        // com `node -e` o script não seria o módulo principal
+        let path = std::env::temp_dir().join(format!("symplia_js_{}.js", std::process::id()));
+        std::fs::write(&path, &js).unwrap();
+        // a entrada fica aberta: ler tudo antes de executar travaria aqui
+        let node = Command::new("node").arg(&path).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn();
+        let Ok(mut node) = node else {
+            std::fs::remove_file(&path).unwrap();
+            return;
+        };
+        let _input = node.stdin.take();
+        let run = node.wait_with_output().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(String::from_utf8_lossy(&run.stdout), "-9223372036854775808 0 -3 -1\nErro: divisão por zero\n");
+        assert_eq!(run.status.code(), Some(1));
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use crate::parser::ast::{CallExpr, Expr, FunctionDecl, Program, Type};
+use crate::semantic::builtins::{Builtin, Overload};
+use crate::semantic::symbol_table::{Symbol, SymbolTable};
+use crate::semantic::type_checker::TypeChecker;
+
+pub mod wat;
+pub mod x86_64;
+pub mod riscv;
+pub mod javascript;
+pub mod python;
+
+pub use wat::WatGenerator;
+pub use x86_64::X86Generator;
+pub use riscv::RiscvGenerator;
+pub use javascript::JsGenerator;
+pub use python::PythonGenerator;
+
+// Convenções de execução compartilhadas por todos os backends:
+//  - os comandos globais executam primeiro; depois `principal()` é chamada,
+//    se existir e não tiver parâmetros;
+//  - `escreva` separa os argumentos com um espaço e termina a linha;
+//  - decimais são escritos com 6 casas (como o printf("%f") do backend LLVM);
+//  - lógicos são escritos como "verdadeiro" / "falso";
+//  - `para v de a ate b` inclui o limite `b`, que é reavaliado a cada volta.
+pub const DECIMAL_PRECISION: usize = 6;
+
+// decimal como `escreva` o mostra ("nan", "inf", "-0.000000"...)
+pub fn format_decimal(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else {
+        format!("{:.*}", DECIMAL_PRECISION, value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BackendError {
+    pub message: String,
+    // linha do comando (ou da função) que falhou; 0 se desconhecida
+    pub line: usize,
+}
+
+impl BackendError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into(), line: 0 }
+    }
+
+    // os geradores marcam o erro no caminho de volta, então fica a linha do
+    // comando mais interno
+    pub fn at_line(self, line: usize) -> Self {
+        if self.line == 0 { Self { line, ..self } } else { self }
+    }
+}
+
+impl std::fmt::Display for BackendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "ERRO NA GERAÇÃO DE CÓDIGO: {}", self.message)
+        } else {
+            write!(f, "ERRO NA GERAÇÃO DE CÓDIGO: Linha {} - {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+// Escopos usados pelos geradores de código. Os tipos ficam numa tabela de
+// símbolos (para reaproveitar o TypeChecker) e, em paralelo, cada backend
+// guarda onde a variável mora (local, global, posição na pilha...).
+pub struct ScopedEnv<T> {
+    symbol_table: SymbolTable,
+    bindings: Vec<HashMap<String, T>>,
+}
+
+impl<T: Clone> ScopedEnv<T> {
+    pub fn new(program: &Program) -> Self {
+        let mut symbol_table = SymbolTable::new();
+        for function in &program.functions {
+            // nomes duplicados já foram reportados pela análise semântica
+            let _ = symbol_table.insert_symbol(
+                function.name.clone(),
+                Symbol::Function { declaration: function.clone() },
+            );
+        }
+
+        Self {
+            symbol_table,
+            bindings: vec![HashMap::new()],
+        }
+    }
+
+    pub fn enter_scope(&mut self) {
+        self.symbol_table.enter_scope();
+        self.bindings.push(HashMap::new());
+    }
+
+    pub fn exit_scope(&mut self) {
+        self.symbol_table.exit_scope();
+        self.bindings.pop();
+    }
+
+    pub fn declare(&mut self, name: &str, var_type: Type, binding: T) {
+        let _ = self.symbol_table.insert_symbol(name.to_string(), Symbol::Variable {
+            name: name.to_string(),
+            type_: var_type,
+            defined: true,
+        });
+        if let Some(scope) = self.bindings.last_mut() {
+            scope.insert(name.to_string(), binding);
+        }
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<(T, Type)> {
+        let binding = self.bindings.iter().rev().find_map(|scope| scope.get(name))?;
+        match self.symbol_table.lookup(name) {
+            Some(Symbol::Variable { type_, .. }) => Some((binding.clone(), type_.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&FunctionDecl> {
+        match self.symbol_table.lookup(name) {
+            Some(Symbol::Function { declaration }) => Some(declaration),
+            _ => None,
+        }
+    }
+
+    pub fn expr_type(&self, expr: &Expr) -> Result<Type, BackendError> {
+        TypeChecker::infer_expression_type(expr, &self.symbol_table).map_err(BackendError::new)
+    }
+
+    // sobrecarga da biblioteca padrão escolhida para a chamada, se for uma
+    pub fn builtin(&self, call: &CallExpr) -> Result<Option<(Builtin, &'static Overload)>, BackendError> {
+        let Some(builtin) = Builtin::from_name(&call.function) else {
+            return Ok(None);
+        };
+        let argument_types = call.arguments
+            .iter()
+            .map(|arg| self.expr_type(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let overload = builtin.resolve(&argument_types).map_err(BackendError::new)?;
+        Ok(Some((builtin, overload)))
+    }
+}
+
+// `principal` só é chamada automaticamente quando não recebe parâmetros
+pub fn entry_point(program: &Program) -> Option<&FunctionDecl> {
+    program.functions
+        .iter()
+        .find(|f| f.name == "principal" && f.parameters.is_empty())
+}
+
+// Tipo resultante de uma operação aritmética (com promoção inteiro -> decimal)
+pub fn numeric_result(left: &Type, right: &Type) -> Type {
+    if *left == Type::Decimal || *right == Type::Decimal {
+        Type::Decimal
+    } else {
+        Type::Inteiro
+    }
+}
+
+// Identificadores de Symplia podem ter letras acentuadas, mas WAT e os
+// montadores só aceitam ASCII: cada caractere fora disso vira `.uXXXX`
+// (o ponto nunca aparece num identificador da linguagem)
+pub fn ascii_identifier(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            out.push(c);
+        } else {
+            out.push_str(&format!(".u{:04x}", c as u32));
+        }
+    }
+    out
+}
+
+// Conteúdo de uma diretiva `.string` do GNU as; bytes fora do ASCII
+// imprimível viram escapes octais
+pub fn escape_gas_string(value: &str) -> String {
+    let mut out = String::new();
+    for &b in value.as_bytes() {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(b as char),
+            _ => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out
+}
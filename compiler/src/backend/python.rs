@@ -0,0 +1,1109 @@
+use std::collections::{HashMap, HashSet};
+use crate::parser::ast::*;
+use crate::backend::{entry_point, numeric_result, BackendError, ScopedEnv};
+use crate::semantic::builtins::{Builtin, Overload};
+
+// Traduz a AST anotada para Python 3 idiomático, para quem está passando de
+// Symplia para Python comparar os dois programas lado a lado.
+//
+// Mapeamento de tipos (anotados nas declarações):
+//   inteiro -> int, decimal -> float, texto -> str, logico -> bool
+//
+// Diferenças assumidas em troca de um código natural:
+//   - `leia` usa input(), que lê uma linha inteira;
+//   - `and`/`or` não avaliam o lado direito quando desnecessário;
+//   - dividir um decimal por zero gera ZeroDivisionError (e não inf/nan);
+//   - `para` vira `range`, que avalia o limite uma única vez e deixa a
+//     variável com o último valor (e não com limite + 1);
+//   - as funções de `math` geram ValueError/OverflowError onde Symplia
+//     resultaria em nan ou inf (raiz(-1.0), piso(inf), potencia(10.0, 400.0)),
+//     e piso/teto devolvem 0.0 em vez de -0.0.
+// A divisão e o resto de inteiros truncam em direção a zero, como em
+// Symplia, por meio de funções auxiliares (o `//` do Python arredonda para baixo).
+// O int do Python não tem limite, então soma, subtração, multiplicação,
+// negação, abs e potência de inteiros passam por inteiro64, que dá a volta
+// nos 64 bits como os outros backends.
+
+const WRAP_HELPER: &str = r#"def {name}(x: int) -> int:
+    """Leva x aos 64 bits com sinal, dando a volta como em Symplia."""
+    return (x + 2**63) % 2**64 - 2**63
+"#;
+
+// `//` com divisor zero geraria um ZeroDivisionError com rastreamento
+const INT_DIVISION_HELPER: &str = r#"def {name}(a: int, b: int) -> int:
+    """Divisão inteira de Symplia: trunca em direção a zero."""
+    if b == 0:
+        print("Erro: divisão por zero")
+        raise SystemExit(1)
+    quociente = abs(a) // abs(b)
+    return {wrap}(quociente if (a < 0) == (b < 0) else -quociente)
+"#;
+
+const INT_REMAINDER_HELPER: &str = r#"def {name}(a: int, b: int) -> int:
+    """Resto com o sinal do dividendo, como em Symplia."""
+    return {wrap}(a - b * {division}(a, b))
+"#;
+
+const INT_POWER_HELPER: &str = r#"def {name}(base: int, expoente: int) -> int:
+    """Potência inteira: com expoente negativo só 1 e -1 não resultam em 0."""
+    if expoente < 0:
+        return base ** -expoente if base in (1, -1) else 0
+    return {wrap}(pow(base, expoente, 2**64))
+"#;
+
+const ROUND_HELPER: &str = r#"def {name}(x: float) -> float:
+    """Arredonda com a metade se afastando do zero (round() vai para o par)."""
+    parte = float(math.trunc(x))
+    if abs(x - parte) >= 0.5:
+        parte += math.copysign(1.0, x)
+    return math.copysign(parte, x)
+"#;
+
+const SUBSTRING_HELPER: &str = r#"def {name}(texto: str, inicio: int, quantidade: int) -> str:
+    """Até `quantidade` caracteres a partir da posição `inicio`, contada de 1."""
+    de = max(inicio, 1) - 1
+    return texto[de:de + max(quantidade, 0)]
+"#;
+
+// int() e float() aceitariam também espaços nas pontas, "1_000", "nan" e "inf";
+// as conversões de texto seguem parse_integer/parse_decimal e, como nos outros
+// backends, uma conversão inválida escreve a mensagem e encerra com código 1
+const TEXT_TO_INTEGER_HELPER: &str = r#"def {name}(texto: str) -> int:
+    """paraInteiro: sinal opcional e dígitos, dentro dos 64 bits."""
+    corpo = texto[1:] if texto[:1] in ("+", "-") else texto
+    if not (corpo.isascii() and corpo.isdigit()) or not -2**63 <= int(texto) < 2**63:
+        print("Erro: conversão inválida para inteiro")
+        raise SystemExit(1)
+    return int(texto)
+"#;
+
+const TEXT_TO_DECIMAL_HELPER: &str = r#"def {name}(texto: str) -> float:
+    """paraDecimal: sinal opcional, dígitos, fração e expoente opcionais."""
+    if re.fullmatch(r"[+-]?[0-9]+(\.[0-9]+)?([eE][+-]?[0-9]+)?", texto) is None:
+        print("Erro: conversão inválida para decimal")
+        raise SystemExit(1)
+    return float(texto)
+"#;
+
+const TEXT_TO_LOGICAL_HELPER: &str = r#"def {name}(texto: str) -> bool:
+    """paraLogico: só "verdadeiro" e "falso" são aceitos."""
+    if texto not in ("verdadeiro", "falso"):
+        print("Erro: conversão inválida para logico")
+        raise SystemExit(1)
+    return texto == "verdadeiro"
+"#;
+
+// int() falharia com nan e inf e não tem o limite de 64 bits
+const DECIMAL_TO_INTEGER_HELPER: &str = r#"def {name}(x: float) -> int:
+    """paraInteiro de decimal: trunca, satura nos limites e leva nan a 0."""
+    if x != x:
+        return 0
+    if x >= 2.0**63:
+        return 2**63 - 1
+    if x <= -2.0**63:
+        return -2**63
+    return int(x)
+"#;
+
+// upper() e lower() trocariam a caixa de todo o Unicode ("ß".upper() == "SS")
+const UPPERCASE_HELPER: &str = r#"def {name}(texto: str) -> str:
+    """Só a-z e as letras do Latin-1 (à-þ, sem ÷), como nos outros backends."""
+    return "".join(chr(ord(c) ^ 0x20) if "a" <= c <= "z" or "\u00e0" <= c <= "\u00fe" and c != "\u00f7" else c for c in texto)
+"#;
+
+const LOWERCASE_HELPER: &str = r#"def {name}(texto: str) -> str:
+    """Só A-Z e as letras do Latin-1 (À-Þ, sem ×), como nos outros backends."""
+    return "".join(chr(ord(c) ^ 0x20) if "A" <= c <= "Z" or "\u00c0" <= c <= "\u00de" and c != "\u00d7" else c for c in texto)
+"#;
+
+const REPLACE_HELPER: &str = r#"def {name}(texto: str, de: str, por: str) -> str:
+    """str.replace, exceto que `de` vazio não troca nada."""
+    return texto.replace(de, por) if de else texto
+"#;
+
+// funções auxiliares, na ordem em que aparecem no módulo
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Helper {
+    Wrap,
+    Division,
+    Remainder,
+    Power,
+    Round,
+    Uppercase,
+    Lowercase,
+    Substring,
+    Replace,
+    TextToInteger,
+    TextToDecimal,
+    TextToLogical,
+    DecimalToInteger,
+}
+
+const HELPERS: [Helper; 13] = [
+    Helper::Wrap,
+    Helper::Division,
+    Helper::Remainder,
+    Helper::Power,
+    Helper::Round,
+    Helper::Uppercase,
+    Helper::Lowercase,
+    Helper::Substring,
+    Helper::Replace,
+    Helper::TextToInteger,
+    Helper::TextToDecimal,
+    Helper::TextToLogical,
+    Helper::DecimalToInteger,
+];
+
+impl Helper {
+    fn base_name(self) -> &'static str {
+        match self {
+            Helper::Wrap => "inteiro64",
+            Helper::Division => "divisao_inteira",
+            Helper::Remainder => "resto",
+            Helper::Power => "potencia_inteira",
+            Helper::Round => "arredondar",
+            Helper::Uppercase => "maiusculo",
+            Helper::Lowercase => "minusculo",
+            Helper::Substring => "subtexto",
+            Helper::Replace => "substituir",
+            Helper::TextToInteger => "texto_para_inteiro",
+            Helper::TextToDecimal => "texto_para_decimal",
+            Helper::TextToLogical => "texto_para_logico",
+            Helper::DecimalToInteger => "decimal_para_inteiro",
+        }
+    }
+
+    fn template(self) -> &'static str {
+        match self {
+            Helper::Wrap => WRAP_HELPER,
+            Helper::Division => INT_DIVISION_HELPER,
+            Helper::Remainder => INT_REMAINDER_HELPER,
+            Helper::Power => INT_POWER_HELPER,
+            Helper::Round => ROUND_HELPER,
+            Helper::Uppercase => UPPERCASE_HELPER,
+            Helper::Lowercase => LOWERCASE_HELPER,
+            Helper::Substring => SUBSTRING_HELPER,
+            Helper::Replace => REPLACE_HELPER,
+            Helper::TextToInteger => TEXT_TO_INTEGER_HELPER,
+            Helper::TextToDecimal => TEXT_TO_DECIMAL_HELPER,
+            Helper::TextToLogical => TEXT_TO_LOGICAL_HELPER,
+            Helper::DecimalToInteger => DECIMAL_TO_INTEGER_HELPER,
+        }
+    }
+}
+
+const KEYWORDS: [&str; 35] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break",
+    "class", "continue", "def", "del", "elif", "else", "except", "finally", "for",
+    "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not",
+    "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+// funções embutidas usadas pelo código gerado
+const BUILTINS: [&str; 13] = ["print", "input", "int", "float", "str", "abs", "range", "format", "math", "re", "len", "max", "SystemExit"];
+
+const CONDITIONAL_PRECEDENCE: u8 = 0;
+const OR_PRECEDENCE: u8 = 1;
+const AND_PRECEDENCE: u8 = 2;
+const NOT_PRECEDENCE: u8 = 3;
+const COMPARISON_PRECEDENCE: u8 = 4;
+const ADDITIVE_PRECEDENCE: u8 = 5;
+const MULTIPLICATIVE_PRECEDENCE: u8 = 6;
+const NEGATE_PRECEDENCE: u8 = 7;
+const PRIMARY_PRECEDENCE: u8 = 8;
+
+struct PyExpr {
+    code: String,
+    ty: Type,
+    precedence: u8,
+}
+
+impl PyExpr {
+    fn new(code: String, ty: Type, precedence: u8) -> Self {
+        Self { code, ty, precedence }
+    }
+
+    fn at_least(&self, minimum: u8) -> String {
+        if self.precedence < minimum {
+            format!("({})", self.code)
+        } else {
+            self.code.clone()
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Binding {
+    name: String,
+    global: bool,
+}
+
+pub struct PythonGenerator<'a> {
+    program: &'a Program,
+    env: ScopedEnv<Binding>,
+
+    // todos os identificadores do programa, para que nomes novos não colidam
+    source_names: HashSet<String>,
+    module_names: HashSet<String>,
+    functions: HashMap<String, String>,
+    helper_names: HashMap<Helper, String>,
+    used_helpers: HashSet<Helper>,
+    uses_math: bool,
+    uses_re: bool,
+
+    // estado da função sendo gerada (None no nível do módulo)
+    function_names: Option<HashSet<String>>,
+    assigned_globals: Vec<String>,
+    lines: Vec<String>,
+    indent: usize,
+}
+
+impl<'a> PythonGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        let mut generator = Self {
+            program,
+            env: ScopedEnv::new(program),
+            source_names: collect_names(program),
+            module_names: HashSet::new(),
+            functions: HashMap::new(),
+            helper_names: HashMap::new(),
+            used_helpers: HashSet::new(),
+            uses_math: false,
+            uses_re: false,
+            function_names: None,
+            assigned_globals: Vec::new(),
+            lines: Vec::new(),
+            indent: 0,
+        };
+        for function in &program.functions {
+            let name = generator.fresh_name(&function.name, true);
+            generator.module_names.insert(name.clone());
+            generator.functions.insert(function.name.clone(), name);
+        }
+        for helper in HELPERS {
+            let name = generator.fresh_name(helper.base_name(), false);
+            generator.module_names.insert(name.clone());
+            generator.helper_names.insert(helper, name);
+        }
+        generator
+    }
+
+    pub fn generate(mut self) -> Result<String, BackendError> {
+        // as globais são reservadas antes das funções, que podem usá-las
+        let mut globals = Vec::new();
+        for statement in &self.program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                globals.push(self.declare(&decl.name, decl.var_type.clone()));
+            }
+        }
+
+        let mut functions = Vec::new();
+        for function in &self.program.functions {
+            functions.push(self.generate_function(function).map_err(|e| e.at_line(function.line))?);
+        }
+
+        let mut globals = globals.into_iter();
+        for statement in &self.program.statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    let value = match &decl.initializer {
+                        Some(initializer) => self.generate_expr(initializer).map_err(|e| e.at_line(decl.line))?.code,
+                        None => default_value(&decl.var_type).to_string(),
+                    };
+                    let name = globals.next().unwrap_or_default();
+                    self.emit(&format!("{}: {} = {}", name, python_type(&decl.var_type), value));
+                }
+                other => self.generate_statement(other, &None).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+        let statements = std::mem::take(&mut self.lines);
+
+        let mut sections = Vec::new();
+        let imports: Vec<&str> = [("math", self.uses_math), ("re", self.uses_re)]
+            .into_iter()
+            .filter_map(|(module, used)| used.then_some(module))
+            .collect();
+        if !imports.is_empty() {
+            sections.push(imports.iter().map(|module| format!("import {}\n", module)).collect());
+        }
+        for helper in HELPERS {
+            if self.used_helpers.contains(&helper) {
+                sections.push(
+                    helper
+                        .template()
+                        .replace("{name}", &self.helper_names[&helper])
+                        .replace("{division}", &self.helper_names[&Helper::Division])
+                        .replace("{wrap}", &self.helper_names[&Helper::Wrap]),
+                );
+            }
+        }
+        sections.extend(functions);
+
+        let mut out = String::from("# Gerado pelo compilador Symplia\n");
+        for section in &sections {
+            out.push_str("\n\n");
+            out.push_str(section);
+        }
+        if !statements.is_empty() {
+            out.push_str("\n\n");
+            for line in &statements {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        if let Some(principal) = entry_point(self.program) {
+            out.push_str("\n\n");
+            out.push_str("if __name__ == \"__main__\":\n");
+            out.push_str(&format!("    {}()\n", self.function_name(&principal.name)));
+        }
+        Ok(out)
+    }
+
+    // === FUNÇÕES ===
+
+    fn generate_function(&mut self, function: &FunctionDecl) -> Result<String, BackendError> {
+        // nomes do módulo ficam reservados: um local homônimo esconderia a
+        // global na função inteira, não só no bloco
+        self.function_names = Some(self.module_names.clone());
+        self.assigned_globals.clear();
+        self.indent = 1;
+        self.env.enter_scope();
+
+        let mut parameters = Vec::new();
+        for param in &function.parameters {
+            let name = self.declare(&param.name, param.param_type.clone());
+            parameters.push(format!("{}: {}", name, python_type(&param.param_type)));
+        }
+
+        self.generate_statements(&function.body, &function.return_type)?;
+        if let Some(return_type) = &function.return_type
+            && !matches!(function.body.statements.last(), Some(Statement::ReturnStmt(_)))
+        {
+            self.emit(&format!("return {}", default_value(return_type)));
+        }
+        if self.lines.is_empty() {
+            self.emit("pass");
+        }
+
+        self.env.exit_scope();
+        self.function_names = None;
+        self.indent = 0;
+
+        let return_type = function.return_type.as_ref().map_or("None", python_type);
+        let mut out = format!(
+            "def {}({}) -> {}:\n",
+            self.function_name(&function.name),
+            parameters.join(", "),
+            return_type
+        );
+        if !self.assigned_globals.is_empty() {
+            out.push_str(&format!("    global {}\n", self.assigned_globals.join(", ")));
+        }
+        for line in std::mem::take(&mut self.lines) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    // === STATEMENTS ===
+
+    fn generate_statements(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+        for statement in &block.statements {
+            self.generate_statement(statement, return_type).map_err(|e| e.at_line(statement.line()))?;
+        }
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    fn generate_block(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.indent += 1;
+        if block.statements.is_empty() {
+            self.emit("pass");
+        }
+        self.generate_statements(block, return_type)?;
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn generate_statement(&mut self, statement: &Statement, return_type: &Option<Type>) -> Result<(), BackendError> {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                let value = match &decl.initializer {
+                    Some(initializer) => self.generate_expr(initializer)?.code,
+                    None => default_value(&decl.var_type).to_string(),
+                };
+                let name = self.declare(&decl.name, decl.var_type.clone());
+                self.emit(&format!("{}: {} = {}", name, python_type(&decl.var_type), value));
+            }
+            Statement::ExprStmt(expr_stmt) => {
+                let expr = self.generate_expr(&expr_stmt.expr)?;
+                self.emit(&expr.code);
+            }
+            Statement::IfStmt(if_stmt) => {
+                let condition = self.generate_expr(&if_stmt.condition)?;
+                self.emit(&format!("if {}:", condition.code));
+                self.generate_block(&if_stmt.then_branch, return_type)?;
+
+                // `senao { se ... }` vira elif
+                let mut else_branch = &if_stmt.else_branch;
+                while let Some(block) = else_branch {
+                    if let [Statement::IfStmt(nested)] = block.statements.as_slice() {
+                        let condition = self.generate_expr(&nested.condition)?;
+                        self.emit(&format!("elif {}:", condition.code));
+                        self.generate_block(&nested.then_branch, return_type)?;
+                        else_branch = &nested.else_branch;
+                    } else {
+                        self.emit("else:");
+                        self.generate_block(block, return_type)?;
+                        break;
+                    }
+                }
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let condition = self.generate_expr(&while_stmt.condition)?;
+                self.emit(&format!("while {}:", condition.code));
+                self.generate_block(&while_stmt.body, return_type)?;
+            }
+            Statement::ForStmt(for_stmt) => self.generate_for(for_stmt, return_type)?,
+            Statement::ReturnStmt(return_stmt) => match (&return_stmt.value, return_type) {
+                (Some(value), Some(_)) => {
+                    let value = self.generate_expr(value)?;
+                    self.emit(&format!("return {}", value.code));
+                }
+                (None, None) => self.emit("return"),
+                _ => return Err(BackendError::new("Retorno incompatível com o tipo da função")),
+            },
+            Statement::WriteStmt(write_stmt) => {
+                let mut arguments = Vec::new();
+                for arg in &write_stmt.arguments {
+                    let value = self.generate_expr(arg)?;
+                    arguments.push(match value.ty {
+                        Type::Decimal => decimal_text(&value).code,
+                        Type::Logico => format!("\"verdadeiro\" if {} else \"falso\"", value.at_least(OR_PRECEDENCE)),
+                        Type::Inteiro | Type::Texto => value.code,
+                    });
+                }
+                self.emit(&format!("print({})", arguments.join(", ")));
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(BackendError::new("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let (binding, ty) = self.lookup(name)?;
+                let value = match ty {
+                    Type::Inteiro => "int(input())",
+                    Type::Decimal => "float(input())",
+                    Type::Texto => "input()",
+                    Type::Logico => "input() == \"verdadeiro\"",
+                };
+                self.assign(&binding);
+                self.emit(&format!("{} = {}", binding.name, value));
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_for(&mut self, for_stmt: &ForStmt, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+
+        let (binding, var_type) = match self.env.lookup(&for_stmt.variable) {
+            Some((binding, ty)) if matches!(ty, Type::Inteiro | Type::Decimal) => (binding, ty),
+            _ => {
+                let name = self.declare(&for_stmt.variable, Type::Inteiro);
+                (Binding { name, global: false }, Type::Inteiro)
+            }
+        };
+        self.assign(&binding);
+
+        let start = self.generate_expr(&for_stmt.start)?;
+        let end = self.generate_expr(&for_stmt.end)?;
+
+        if var_type == Type::Inteiro && start.ty == Type::Inteiro && end.ty == Type::Inteiro {
+            let stop = match &for_stmt.end {
+                Expr::Literal(Literal::Inteiro(n)) => (n + 1).to_string(),
+                _ => format!("{} + 1", end.at_least(ADDITIVE_PRECEDENCE)),
+            };
+            let range = if start.code == "0" {
+                format!("range({})", stop)
+            } else {
+                format!("range({}, {})", start.code, stop)
+            };
+            self.emit(&format!("for {} in {}:", binding.name, range));
+            self.generate_block(&for_stmt.body, return_type)?;
+        } else {
+            // laço com decimal: o range do Python só aceita inteiros
+            self.emit(&format!("{} = {}", binding.name, start.code));
+            self.emit(&format!("while {} <= {}:", binding.name, end.at_least(ADDITIVE_PRECEDENCE)));
+            self.generate_block(&for_stmt.body, return_type)?;
+            self.indent += 1;
+            self.emit(&format!("{} += 1", binding.name));
+            self.indent -= 1;
+        }
+
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    // === EXPRESSÕES ===
+
+    fn generate_expr(&mut self, expr: &Expr) -> Result<PyExpr, BackendError> {
+        let ty = self.env.expr_type(expr)?;
+
+        Ok(match expr {
+            Expr::Literal(literal) => {
+                let code = match literal {
+                    Literal::Inteiro(n) => n.to_string(),
+                    Literal::Decimal(n) => python_float(*n),
+                    Literal::Texto(s) => python_string(s),
+                    Literal::Logico(true) => "True".to_string(),
+                    Literal::Logico(false) => "False".to_string(),
+                };
+                let precedence = if code.starts_with('-') { NEGATE_PRECEDENCE } else { PRIMARY_PRECEDENCE };
+                PyExpr::new(code, ty, precedence)
+            }
+            Expr::Variable(name) => PyExpr::new(self.lookup(name)?.0.name, ty, PRIMARY_PRECEDENCE),
+            Expr::Call(call) => {
+                if let Some((builtin, overload)) = self.env.builtin(call)? {
+                    return self.generate_builtin(builtin, overload, &call.arguments);
+                }
+                let mut arguments = Vec::new();
+                for arg in &call.arguments {
+                    arguments.push(self.generate_expr(arg)?.code);
+                }
+                let code = format!("{}({})", self.function_name(&call.function), arguments.join(", "));
+                PyExpr::new(code, ty, PRIMARY_PRECEDENCE)
+            }
+            Expr::UnaryOp(op, expr) => {
+                let operand = self.generate_expr(expr)?;
+                match op {
+                    UnaryOperator::Plus => operand,
+                    UnaryOperator::Negate => {
+                        let negated = PyExpr::new(format!("-{}", operand.at_least(PRIMARY_PRECEDENCE)), ty, NEGATE_PRECEDENCE);
+                        // só -(-2**63) sai dos 64 bits
+                        match expr.as_ref() {
+                            Expr::Literal(Literal::Inteiro(n)) if *n != i64::MIN => negated,
+                            _ if operand.ty == Type::Inteiro => self.wrap(negated),
+                            _ => negated,
+                        }
+                    }
+                    UnaryOperator::Not => {
+                        PyExpr::new(format!("not {}", operand.at_least(NOT_PRECEDENCE)), ty, NOT_PRECEDENCE)
+                    }
+                }
+            }
+            Expr::BinaryOp(op, left, right) => self.generate_binary(op, left, right, ty)?,
+        })
+    }
+
+    fn generate_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr, ty: Type) -> Result<PyExpr, BackendError> {
+        let left = self.generate_expr(left)?;
+        let right = self.generate_expr(right)?;
+        if *op == BinaryOperator::Add && (left.ty == Type::Texto || right.ty == Type::Texto) {
+            let code = format!(
+                "{} + {}",
+                text_value(&left).at_least(ADDITIVE_PRECEDENCE),
+                text_value(&right).at_least(ADDITIVE_PRECEDENCE + 1)
+            );
+            return Ok(PyExpr::new(code, ty, ADDITIVE_PRECEDENCE));
+        }
+        let integers = numeric_result(&left.ty, &right.ty) == Type::Inteiro
+            && left.ty != Type::Logico
+            && left.ty != Type::Texto;
+
+        let (operator, precedence) = match op {
+            BinaryOperator::Or => ("or", OR_PRECEDENCE),
+            BinaryOperator::And => ("and", AND_PRECEDENCE),
+            BinaryOperator::Equal => ("==", COMPARISON_PRECEDENCE),
+            BinaryOperator::NotEqual => ("!=", COMPARISON_PRECEDENCE),
+            BinaryOperator::Less => ("<", COMPARISON_PRECEDENCE),
+            BinaryOperator::LessEqual => ("<=", COMPARISON_PRECEDENCE),
+            BinaryOperator::Greater => (">", COMPARISON_PRECEDENCE),
+            BinaryOperator::GreaterEqual => (">=", COMPARISON_PRECEDENCE),
+            BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply if integers => {
+                let (operator, precedence) = match op {
+                    BinaryOperator::Add => ("+", ADDITIVE_PRECEDENCE),
+                    BinaryOperator::Subtract => ("-", ADDITIVE_PRECEDENCE),
+                    _ => ("*", MULTIPLICATIVE_PRECEDENCE),
+                };
+                let code = format!("{} {} {}", left.at_least(precedence), operator, right.at_least(precedence + 1));
+                return Ok(self.wrap(PyExpr::new(code, ty, precedence)));
+            }
+            BinaryOperator::Add => ("+", ADDITIVE_PRECEDENCE),
+            BinaryOperator::Subtract => ("-", ADDITIVE_PRECEDENCE),
+            BinaryOperator::Multiply => ("*", MULTIPLICATIVE_PRECEDENCE),
+            BinaryOperator::Divide if integers => {
+                let code = format!("{}({}, {})", self.helper(Helper::Division), left.code, right.code);
+                return Ok(PyExpr::new(code, ty, PRIMARY_PRECEDENCE));
+            }
+            BinaryOperator::Divide => ("/", MULTIPLICATIVE_PRECEDENCE),
+            BinaryOperator::Modulo if integers => {
+                let code = format!("{}({}, {})", self.helper(Helper::Remainder), left.code, right.code);
+                return Ok(PyExpr::new(code, ty, PRIMARY_PRECEDENCE));
+            }
+            BinaryOperator::Modulo => {
+                // o % do Python segue o sinal do divisor; fmod, o do dividendo
+                self.uses_math = true;
+                let code = format!("math.fmod({}, {})", left.code, right.code);
+                return Ok(PyExpr::new(code, ty, PRIMARY_PRECEDENCE));
+            }
+        };
+
+        // comparações do Python se encadeiam (a < b == c), então um operando
+        // que também é comparação precisa de parênteses
+        let left_minimum = if precedence == COMPARISON_PRECEDENCE { precedence + 1 } else { precedence };
+        let code = format!("{} {} {}", left.at_least(left_minimum), operator, right.at_least(precedence + 1));
+        Ok(PyExpr::new(code, ty, precedence))
+    }
+
+    fn generate_builtin(&mut self, builtin: Builtin, overload: &Overload, arguments: &[Expr]) -> Result<PyExpr, BackendError> {
+        let mut values = Vec::new();
+        for arg in arguments {
+            values.push(self.generate_expr(arg)?);
+        }
+        let ty = overload.return_type.clone();
+        let integer = overload.parameters[0] == Type::Inteiro;
+        let call = |function: &str, values: &[PyExpr]| {
+            let arguments: Vec<&str> = values.iter().map(|v| v.code.as_str()).collect();
+            PyExpr::new(format!("{}({})", function, arguments.join(", ")), ty.clone(), PRIMARY_PRECEDENCE)
+        };
+
+        Ok(match builtin {
+            Builtin::Raiz => {
+                self.uses_math = true;
+                call("math.sqrt", &values)
+            }
+            // `**` com expoente negativo resultaria num float, e com um expoente
+            // grande calcularia um número enorme antes de dar a volta
+            Builtin::Potencia if integer && matches!(arguments[1], Expr::Literal(Literal::Inteiro(n)) if (0..=64).contains(&n)) => {
+                let code = format!("{} ** {}", values[0].at_least(PRIMARY_PRECEDENCE), values[1].code);
+                self.wrap(PyExpr::new(code, ty, NEGATE_PRECEDENCE))
+            }
+            Builtin::Potencia if integer => call(&self.helper(Helper::Power), &values),
+            Builtin::Potencia => {
+                self.uses_math = true;
+                call("math.pow", &values)
+            }
+            // abs(-2**63) dá a volta para -2**63
+            Builtin::Abs if integer => {
+                let absolute = call("abs", &values);
+                self.wrap(absolute)
+            }
+            Builtin::Abs => call("abs", &values),
+            Builtin::Arredondar | Builtin::Piso | Builtin::Teto if integer => values.remove(0),
+            Builtin::Arredondar => call(&self.helper(Helper::Round), &values),
+            // math.floor e math.ceil devolvem int
+            Builtin::Piso | Builtin::Teto => {
+                self.uses_math = true;
+                let function = if builtin == Builtin::Piso { "math.floor" } else { "math.ceil" };
+                let code = format!("float({}({}))", function, values[0].code);
+                PyExpr::new(code, ty, PRIMARY_PRECEDENCE)
+            }
+            Builtin::Tamanho => call("len", &values),
+            Builtin::Maiusculo => call(&self.helper(Helper::Uppercase), &values),
+            Builtin::Minusculo => call(&self.helper(Helper::Lowercase), &values),
+            // com posições conhecidas a fatia fica à vista (subtexto(t, 3, 2) -> t[2:4]);
+            // nas demais, um limite negativo contaria do fim do texto
+            Builtin::Subtexto => match (&arguments[1], &arguments[2]) {
+                (Expr::Literal(Literal::Inteiro(inicio)), Expr::Literal(Literal::Inteiro(quantidade))) => {
+                    let de = inicio.max(&1) - 1;
+                    let ate = de.saturating_add(*quantidade.max(&0));
+                    let start = if de == 0 { String::new() } else { de.to_string() };
+                    let code = format!("{}[{}:{}]", values[0].at_least(PRIMARY_PRECEDENCE), start, ate);
+                    PyExpr::new(code, ty, PRIMARY_PRECEDENCE)
+                }
+                _ => call(&self.helper(Helper::Substring), &values),
+            },
+            Builtin::Contem => {
+                let code = format!(
+                    "{} in {}",
+                    values[1].at_least(COMPARISON_PRECEDENCE + 1),
+                    values[0].at_least(COMPARISON_PRECEDENCE + 1)
+                );
+                PyExpr::new(code, ty, COMPARISON_PRECEDENCE)
+            }
+            // o replace do Python com `de` vazio inseriria `por` entre os caracteres
+            Builtin::Substituir => match &arguments[1] {
+                Expr::Literal(Literal::Texto(de)) if !de.is_empty() => {
+                    let code = format!("{}.replace({}, {})", values[0].at_least(PRIMARY_PRECEDENCE), values[1].code, values[2].code);
+                    PyExpr::new(code, ty, PRIMARY_PRECEDENCE)
+                }
+                _ => call(&self.helper(Helper::Replace), &values),
+            },
+            Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico => {
+                match (&overload.parameters[0], &ty) {
+                    (from, to) if from == to => values.remove(0),
+                    (Type::Logico, Type::Inteiro) => call("int", &values),
+                    (Type::Logico, Type::Decimal) => call("float", &values),
+                    (Type::Decimal, Type::Inteiro) => call(&self.helper(Helper::DecimalToInteger), &values),
+                    (Type::Inteiro, Type::Decimal) => call("float", &values),
+                    (_, Type::Inteiro) => call(&self.helper(Helper::TextToInteger), &values),
+                    (_, Type::Decimal) => call(&self.helper(Helper::TextToDecimal), &values),
+                    (_, Type::Texto) => text_value(&values[0]),
+                    (Type::Inteiro, _) => {
+                        let code = format!("{} != 0", values[0].at_least(COMPARISON_PRECEDENCE + 1));
+                        PyExpr::new(code, ty, COMPARISON_PRECEDENCE)
+                    }
+                    _ => call(&self.helper(Helper::TextToLogical), &values),
+                }
+            }
+        })
+    }
+
+    fn helper(&mut self, helper: Helper) -> String {
+        self.used_helpers.insert(helper);
+        match helper {
+            Helper::Division | Helper::Power => {
+                self.used_helpers.insert(Helper::Wrap);
+            }
+            Helper::Remainder => {
+                self.used_helpers.extend([Helper::Division, Helper::Wrap]);
+            }
+            Helper::Round => self.uses_math = true,
+            Helper::TextToDecimal => self.uses_re = true,
+            _ => {}
+        }
+        self.helper_names[&helper].clone()
+    }
+
+    fn wrap(&mut self, value: PyExpr) -> PyExpr {
+        let code = format!("{}({})", self.helper(Helper::Wrap), value.code);
+        PyExpr::new(code, value.ty, PRIMARY_PRECEDENCE)
+    }
+
+    // === NOMES ===
+
+    fn lookup(&self, name: &str) -> Result<(Binding, Type), BackendError> {
+        self.env
+            .lookup(name)
+            .ok_or_else(|| BackendError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    fn function_name(&self, name: &str) -> String {
+        self.functions.get(name).cloned().unwrap_or_else(|| python_identifier(name))
+    }
+
+    // variáveis do Python valem para a função inteira: um nome já usado na
+    // função (ou no módulo) ganha sufixo, evitando os nomes do programa
+    fn declare(&mut self, name: &str, var_type: Type) -> String {
+        let unique = self.fresh_name(name, true);
+        let global = self.function_names.is_none();
+        match &mut self.function_names {
+            Some(names) => names.insert(unique.clone()),
+            None => self.module_names.insert(unique.clone()),
+        };
+        self.env.declare(name, var_type, Binding { name: unique.clone(), global });
+        unique
+    }
+
+    fn fresh_name(&self, name: &str, from_source: bool) -> String {
+        let in_use = |candidate: &str| match &self.function_names {
+            Some(names) => names.contains(candidate),
+            None => self.module_names.contains(candidate),
+        };
+
+        // o próprio nome do programa pode ser usado; qualquer outro dele, não
+        let base = python_identifier(name);
+        let taken = self.source_names.contains(&base) && !(from_source && base == name);
+        if !in_use(&base) && !taken {
+            return base;
+        }
+        (2..)
+            .map(|i| format!("{}_{}", base, i))
+            .find(|candidate| !in_use(candidate) && !self.source_names.contains(candidate))
+            .unwrap()
+    }
+
+    // atribuir a uma global dentro de uma função exige `global`
+    fn assign(&mut self, binding: &Binding) {
+        if binding.global && self.function_names.is_some() && !self.assigned_globals.contains(&binding.name) {
+            self.assigned_globals.push(binding.name.clone());
+        }
+    }
+
+    fn emit(&mut self, line: &str) {
+        self.lines.push(format!("{}{}", "    ".repeat(self.indent), line));
+    }
+}
+
+pub fn generate_python(program: &Program) -> Result<String, BackendError> {
+    PythonGenerator::new(program).generate()
+}
+
+pub fn python_identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) || BUILTINS.contains(&name) {
+        format!("{}_", name)
+    } else {
+        // funções de módulos importados se chamam `modulo.funcao`
+        name.replace('.', "__")
+    }
+}
+
+// um decimal como `escreva` o mostra (6 casas); a f-string não pode conter
+// aspas nem barras invertidas antes do Python 3.12
+// texto de um valor de qualquer tipo, no formato de paraTexto
+fn text_value(value: &PyExpr) -> PyExpr {
+    match value.ty {
+        Type::Texto => PyExpr::new(value.code.clone(), Type::Texto, value.precedence),
+        Type::Inteiro => PyExpr::new(format!("str({})", value.code), Type::Texto, PRIMARY_PRECEDENCE),
+        Type::Decimal => decimal_text(value),
+        Type::Logico => {
+            let code = format!("\"verdadeiro\" if {} else \"falso\"", value.at_least(OR_PRECEDENCE));
+            PyExpr::new(code, Type::Texto, CONDITIONAL_PRECEDENCE)
+        }
+    }
+}
+
+fn decimal_text(value: &PyExpr) -> PyExpr {
+    let code = if value.code.contains(['"', '\\']) {
+        format!("format({}, \".6f\")", value.code)
+    } else {
+        format!("f\"{{{}:.6f}}\"", value.code)
+    };
+    PyExpr::new(code, Type::Texto, PRIMARY_PRECEDENCE)
+}
+
+fn python_type(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "int",
+        Type::Decimal => "float",
+        Type::Texto => "str",
+        Type::Logico => "bool",
+    }
+}
+
+fn default_value(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "0",
+        Type::Decimal => "0.0",
+        Type::Texto => "\"\"",
+        Type::Logico => "False",
+    }
+}
+
+fn python_float(value: f64) -> String {
+    if value.is_nan() {
+        "float(\"nan\")".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "float(\"inf\")".to_string() } else { "-float(\"inf\")".to_string() }
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+fn python_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn collect_names(program: &Program) -> HashSet<String> {
+    fn block(names: &mut HashSet<String>, block: &Block) {
+        for s in &block.statements {
+            statement(names, s);
+        }
+    }
+    fn statement(names: &mut HashSet<String>, s: &Statement) {
+        match s {
+            Statement::VariableDecl(decl) => {
+                names.insert(decl.name.clone());
+            }
+            Statement::IfStmt(if_stmt) => {
+                block(names, &if_stmt.then_branch);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    block(names, else_branch);
+                }
+            }
+            Statement::WhileStmt(while_stmt) => block(names, &while_stmt.body),
+            Statement::ForStmt(for_stmt) => {
+                names.insert(for_stmt.variable.clone());
+                block(names, &for_stmt.body);
+            }
+            _ => {}
+        }
+    }
+
+    let mut names = HashSet::new();
+    for function in &program.functions {
+        names.insert(function.name.clone());
+        for param in &function.parameters {
+            names.insert(param.name.clone());
+        }
+        block(&mut names, &function.body);
+    }
+    for s in &program.statements {
+        statement(&mut names, s);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use crate::backend::riscv::{assemble, generate_riscv, Simulator};
+    use crate::parser::Parser;
+    use crate::semantic::SemanticAnalyzer;
+
+    fn generate(source: &str) -> String {
+        let program = Parser::parse_from_source(source).unwrap();
+        generate_python(&program).unwrap()
+    }
+
+    // o programa precisa escrever o mesmo e sair com o mesmo código no
+    // python3 (se houver um instalado) e no simulador RISC-V
+    fn assert_same_as_riscv(source: &str) {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let python = generate_python(&result.annotated_ast).unwrap();
+        let Ok(run) = Command::new("python3").arg("-c").arg(&python).output() else {
+            return;
+        };
+
+        let assembled = assemble(&generate_riscv(&result.annotated_ast).unwrap()).unwrap();
+        let mut output = Vec::new();
+        let code = Simulator::new(&assembled, &mut "".as_bytes(), &mut output).run(10_000_000).unwrap();
+        assert_eq!(String::from_utf8_lossy(&run.stdout), String::from_utf8_lossy(&output), "{}\n{}", python, String::from_utf8_lossy(&run.stderr));
+        assert_eq!(run.status.code(), Some(code as i32), "{}", python);
+    }
+
+    #[test]
+    fn test_idiomatic_translation() {
+        let python = generate(
+            "funcao inteiro soma(inteiro a, inteiro b) { retorne a + b; }
+             funcao principal() {
+                 inteiro total = 0;
+                 decimal média = 2.5;
+                 para i de 1 ate 10 faca { escreva(\"Soma:\", soma(i, 2), média, i > 5); } fimpara
+                 leia(total);
+             }",
+        );
+        let expected = "# Gerado pelo compilador Symplia
+
+
+def inteiro64(x: int) -> int:
+    \"\"\"Leva x aos 64 bits com sinal, dando a volta como em Symplia.\"\"\"
+    return (x + 2**63) % 2**64 - 2**63
+
+
+def soma(a: int, b: int) -> int:
+    return inteiro64(a + b)
+
+
+def principal() -> None:
+    total: int = 0
+    média: float = 2.5
+    for i in range(1, 11):
+        print(\"Soma:\", soma(i, 2), f\"{média:.6f}\", \"verdadeiro\" if i > 5 else \"falso\")
+    total = int(input())
+
+
+if __name__ == \"__main__\":
+    principal()
+";
+        assert_eq!(python, expected);
+    }
+
+    #[test]
+    fn test_semantics_that_differ_from_python() {
+        let python = generate(
+            "funcao principal() {
+                 inteiro x = -7 / 2;
+                 se x < 0 == verdadeiro entao { inteiro x = x % 3; escreva(x); } fimse
+                 inteiro print = 1;
+             }",
+        );
+        assert!(python.contains("def divisao_inteira(a: int, b: int) -> int:"));
+        assert!(python.contains("x: int = divisao_inteira(-7, 2)"));
+        // comparações encadeadas do Python exigem parênteses
+        assert!(python.contains("if (x < 0) == True:"));
+        // o `x` interno esconderia o externo na função inteira
+        assert!(python.contains("x_2: int = resto(x, 3)"));
+        assert!(python.contains("print_: int = 1"));
+    }
+
+    #[test]
+    fn test_text_builtins() {
+        let python = generate(
+            "funcao principal() {
+                 texto t = \"ação\";
+                 inteiro i = 2;
+                 escreva(tamanho(t), maiusculo(t), subtexto(t, 2, 2), subtexto(t, i, 1), contem(t, \"çã\") == verdadeiro);
+             }",
+        );
+        assert!(python.contains("def subtexto(texto: str, inicio: int, quantidade: int) -> str:"));
+        assert!(python.contains(
+            "print(len(t), maiusculo(t), t[1:3], subtexto(t, i, 1), \"verdadeiro\" if (\"çã\" in t) == True else \"falso\")"
+        ));
+
+        // ß e ÿ não têm par no Latin-1 e ficam como estão
+        assert_same_as_riscv("funcao principal() { escreva(maiusculo(\"ßação ÿ÷\"), minusculo(\"ÀÉ×Þ ZÇ\")); }");
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        let python = generate(
+            "funcao principal() {
+                 inteiro n = 3;
+                 escreva(potencia(-2, 10), potencia(2, -n), raiz(n), piso(n), teto(2.5), arredondar(-2.5));
+             }",
+        );
+        assert!(python.contains("import math"));
+        assert!(python.contains("def arredondar(x: float) -> float:"));
+        assert!(!python.contains("def subtexto"));
+        assert!(python.contains(
+            "print(inteiro64((-2) ** 10), potencia_inteira(2, inteiro64(-n)), f\"{math.sqrt(n):.6f}\", n, f\"{float(math.ceil(2.5)):.6f}\", f\"{arredondar(-2.5):.6f}\")"
+        ));
+    }
+
+    #[test]
+    fn test_integer_overflow_and_division_by_zero() {
+        assert_same_as_riscv(
+            "funcao inteiro fat(inteiro n) { se n <= 1 entao { retorne 1; } fimse retorne n * fat(n - 1); }
+             funcao principal() {
+                 inteiro m = -9223372036854775807 - 1;
+                 escreva(fat(25), m - 1, -m, abs(m), m / -1, m % -1, potencia(3, 100), potencia(-2, 63));
+             }",
+        );
+        for division in ["7 / z", "7 % z"] {
+            assert_same_as_riscv(&format!(
+                "funcao principal() {{ inteiro z = 0; escreva(1); escreva({}); escreva(2); }}",
+                division
+            ));
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let python = generate("funcao principal() { escreva(paraInteiro(\"7\"), paraDecimal(\"1.5\"), paraInteiro(2.5)); }");
+        assert!(python.starts_with("# Gerado pelo compilador Symplia\n\n\nimport re\n"));
+        assert!(python.contains("print(texto_para_inteiro(\"7\"), format(texto_para_decimal(\"1.5\"), \".6f\"), decimal_para_inteiro(2.5))"));
+
+        assert_same_as_riscv(
+            "funcao principal() {
+                 escreva(paraInteiro(\"+7\"), paraInteiro(\"-9223372036854775808\"), paraDecimal(\"-1e3\"), paraDecimal(\"2.50\"));
+                 escreva(paraInteiro(-2.9), paraInteiro(paraDecimal(\"1e400\") - paraDecimal(\"1e400\")), paraInteiro(paraDecimal(\"1e400\")), paraInteiro(-1000000000000000000000.0), paraLogico(\"falso\"));
+             }",
+        );
+        for invalid in ["paraInteiro(\"abc\")", "paraInteiro(\" 1\")", "paraInteiro(\"1_000\")", "paraInteiro(\"9223372036854775808\")"] {
+            assert_same_as_riscv(&format!("funcao principal() {{ escreva(1); escreva({}); escreva(2); }}", invalid));
+        }
+        for invalid in ["paraDecimal(\"nan\")", "paraDecimal(\"inf\")", "paraDecimal(\" 1.5\")", "paraDecimal(\"1_0\")", "paraLogico(\"sim\")"] {
+            assert_same_as_riscv(&format!("funcao principal() {{ escreva(1); escreva({}); escreva(2); }}", invalid));
+        }
+    }
+}
@@ -0,0 +1,1070 @@
+use std::collections::HashMap;
+use crate::backend::riscv::TEXT_BASE;
+
+// Montador para o subconjunto RV64IM + D usado pelo gerador de código (e
+// suficiente para exercícios da disciplina de arquitetura). Aceita a sintaxe
+// do GNU as: rótulos, comentários com '#', diretivas de dados comuns e as
+// pseudoinstruções mais usadas (li, la, mv, call, ret, beqz...).
+//
+// Desvios condicionais fora do alcance de 4 KiB são relaxados para um desvio
+// invertido sobre um `jal`, como o GNU as faz.
+
+#[derive(Debug, Clone)]
+pub struct AssemblerError {
+    pub message: String,
+    pub linha: usize,
+}
+
+impl std::fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ERRO DE MONTAGEM na linha {}: {}", self.linha, self.message)
+    }
+}
+
+impl std::error::Error for AssemblerError {}
+
+#[derive(Debug, Clone)]
+pub struct AssembledProgram {
+    // imagem contígua a partir de TEXT_BASE: código seguido dos dados
+    pub image: Vec<u8>,
+    pub text_size: u64,
+    pub entry: u64,
+    pub symbols: HashMap<String, u64>,
+}
+
+impl AssembledProgram {
+    pub fn end(&self) -> u64 {
+        TEXT_BASE + self.image.len() as u64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Text,
+    Data,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Instr {
+        mnemonic: String,
+        operands: Vec<String>,
+        // desvio condicional relaxado (ocupa 8 bytes)
+        far: bool,
+    },
+    Bytes(Vec<u8>),
+    Align(u64),
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    item: Item,
+    linha: usize,
+}
+
+pub fn assemble(source: &str) -> Result<AssembledProgram, AssemblerError> {
+    Assembler::new().assemble(source)
+}
+
+struct Assembler {
+    text: Vec<Line>,
+    data: Vec<Line>,
+    // rótulo -> (seção, índice do item que o segue)
+    labels: Vec<(String, Section, usize, usize)>,
+}
+
+impl Assembler {
+    fn new() -> Self {
+        Self {
+            text: Vec::new(),
+            data: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    fn assemble(mut self, source: &str) -> Result<AssembledProgram, AssemblerError> {
+        self.parse(source)?;
+
+        // relaxa desvios até que os endereços se estabilizem
+        let symbols = loop {
+            let symbols = self.layout()?;
+            let mut changed = false;
+            let mut address = TEXT_BASE;
+            for line in &mut self.text {
+                if let Item::Align(alignment) = line.item {
+                    address = address.div_ceil(alignment) * alignment;
+                }
+                let size = item_size(&line.item, line.linha)?;
+                if let Item::Instr { mnemonic, operands, far: far @ false } = &mut line.item
+                    && branch_kind(mnemonic).is_some()
+                    && let Some(target) = operands.last().and_then(|op| symbols.get(op))
+                {
+                    let offset = *target as i64 - address as i64;
+                    if !(-4096..4096).contains(&offset) {
+                        *far = true;
+                        changed = true;
+                    }
+                }
+                address += size;
+            }
+            if !changed {
+                break symbols;
+            }
+        };
+
+        let mut image = Vec::new();
+        for line in &self.text {
+            let pc = TEXT_BASE + image.len() as u64;
+            match &line.item {
+                Item::Instr { mnemonic, operands, far } => {
+                    let words = encode(mnemonic, operands, *far, pc, &symbols)
+                        .map_err(|message| AssemblerError { message, linha: line.linha })?;
+                    for word in words {
+                        image.extend_from_slice(&word.to_le_bytes());
+                    }
+                }
+                Item::Align(alignment) => {
+                    while !(image.len() as u64).is_multiple_of(*alignment) {
+                        // nop
+                        image.extend_from_slice(&0x0000_0013u32.to_le_bytes());
+                    }
+                }
+                Item::Bytes(_) => {
+                    return Err(AssemblerError {
+                        message: "Dados não são permitidos na seção .text".to_string(),
+                        linha: line.linha,
+                    });
+                }
+            }
+        }
+
+        let text_size = image.len() as u64;
+        for line in &self.data {
+            match &line.item {
+                Item::Bytes(bytes) => image.extend_from_slice(bytes),
+                Item::Align(alignment) => {
+                    while !(TEXT_BASE + image.len() as u64).is_multiple_of(*alignment) {
+                        image.push(0);
+                    }
+                }
+                Item::Instr { .. } => {
+                    return Err(AssemblerError {
+                        message: "Instruções só são permitidas na seção .text".to_string(),
+                        linha: line.linha,
+                    });
+                }
+            }
+        }
+
+        let entry = symbols
+            .get("_start")
+            .or_else(|| symbols.get("main"))
+            .copied()
+            .ok_or_else(|| AssemblerError {
+                message: "Ponto de entrada '_start' (ou 'main') não encontrado".to_string(),
+                linha: 0,
+            })?;
+
+        Ok(AssembledProgram { image, text_size, entry, symbols })
+    }
+
+    // === LEITURA ===
+
+    fn parse(&mut self, source: &str) -> Result<(), AssemblerError> {
+        let mut section = Section::Text;
+
+        for (index, raw) in source.lines().enumerate() {
+            let linha = index + 1;
+            let error = |message: String| AssemblerError { message, linha };
+
+            let mut rest = strip_comment(raw).trim();
+            while let Some((label, after)) = split_label(rest) {
+                let items = self.section(section);
+                let position = items.len();
+                if self.labels.iter().any(|(name, ..)| name == label) {
+                    return Err(error(format!("Rótulo '{}' definido mais de uma vez", label)));
+                }
+                self.labels.push((label.to_string(), section, position, linha));
+                rest = after.trim();
+            }
+            if rest.is_empty() {
+                continue;
+            }
+
+            let (name, args) = match rest.find(char::is_whitespace) {
+                Some(i) => (&rest[..i], rest[i..].trim()),
+                None => (rest, ""),
+            };
+
+            if name.starts_with('.') {
+                if let Some(item) = self.directive(name, args, &mut section).map_err(error)? {
+                    self.section(section).push(Line { item, linha });
+                }
+            } else {
+                let operands = split_operands(args);
+                let item = Item::Instr { mnemonic: name.to_lowercase(), operands, far: false };
+                self.section(section).push(Line { item, linha });
+            }
+        }
+        Ok(())
+    }
+
+    fn section(&mut self, section: Section) -> &mut Vec<Line> {
+        match section {
+            Section::Text => &mut self.text,
+            Section::Data => &mut self.data,
+        }
+    }
+
+    fn directive(&mut self, name: &str, args: &str, section: &mut Section) -> Result<Option<Item>, String> {
+        let values = || -> Result<Vec<i64>, String> { split_operands(args).iter().map(|v| parse_int(v)).collect() };
+
+        let item = match name {
+            ".text" => {
+                *section = Section::Text;
+                return Ok(None);
+            }
+            ".data" | ".rodata" | ".bss" => {
+                *section = Section::Data;
+                return Ok(None);
+            }
+            ".section" => {
+                let target = args.split(',').next().unwrap_or("").trim();
+                *section = if target.starts_with(".text") { Section::Text } else { Section::Data };
+                return Ok(None);
+            }
+            ".globl" | ".global" | ".type" | ".size" | ".option" | ".file" | ".attribute" | ".local" => return Ok(None),
+            ".align" | ".p2align" => Item::Align(1 << parse_int(args)?),
+            ".balign" => Item::Align(parse_int(args)? as u64),
+            ".zero" | ".space" => Item::Bytes(vec![0; parse_int(args)? as usize]),
+            ".byte" => Item::Bytes(values()?.iter().map(|v| *v as u8).collect()),
+            ".half" | ".short" => Item::Bytes(values()?.iter().flat_map(|v| (*v as u16).to_le_bytes()).collect()),
+            ".word" | ".long" => Item::Bytes(values()?.iter().flat_map(|v| (*v as u32).to_le_bytes()).collect()),
+            ".dword" | ".quad" => Item::Bytes(values()?.iter().flat_map(|v| v.to_le_bytes()).collect()),
+            ".string" | ".asciz" | ".ascii" => {
+                let mut bytes = parse_string(args)?;
+                if name != ".ascii" {
+                    bytes.push(0);
+                }
+                Item::Bytes(bytes)
+            }
+            _ => return Err(format!("Diretiva desconhecida '{}'", name)),
+        };
+        Ok(Some(item))
+    }
+
+    // calcula o endereço de cada rótulo com os tamanhos atuais
+    fn layout(&self) -> Result<HashMap<String, u64>, AssemblerError> {
+        let mut text_addresses = Vec::with_capacity(self.text.len() + 1);
+        let mut address = TEXT_BASE;
+        for line in &self.text {
+            if let Item::Align(alignment) = line.item {
+                address = address.div_ceil(alignment) * alignment;
+            }
+            text_addresses.push(address);
+            address += item_size(&line.item, line.linha)?;
+        }
+        text_addresses.push(address);
+
+        let mut data_addresses = Vec::with_capacity(self.data.len() + 1);
+        for line in &self.data {
+            match &line.item {
+                Item::Align(alignment) => address = address.div_ceil(*alignment) * alignment,
+                Item::Bytes(bytes) => {
+                    data_addresses.push(address);
+                    address += bytes.len() as u64;
+                    continue;
+                }
+                Item::Instr { .. } => {}
+            }
+            data_addresses.push(address);
+        }
+        data_addresses.push(address);
+
+        Ok(self.labels
+            .iter()
+            .map(|(name, section, index, _)| {
+                let address = match section {
+                    Section::Text => text_addresses[*index],
+                    Section::Data => data_addresses[*index],
+                };
+                (name.clone(), address)
+            })
+            .collect())
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if in_string => escaped = !escaped,
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => escaped = false,
+        }
+        if c != '\\' {
+            escaped = false;
+        }
+    }
+    line
+}
+
+fn split_label(line: &str) -> Option<(&str, &str)> {
+    let end = line.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '$'))?;
+    if end > 0 && line[end..].starts_with(':') {
+        Some((&line[..end], &line[end + 1..]))
+    } else {
+        None
+    }
+}
+
+fn split_operands(args: &str) -> Vec<String> {
+    if args.trim().is_empty() {
+        return Vec::new();
+    }
+    args.split(',').map(|op| op.trim().to_string()).collect()
+}
+
+fn parse_int(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).map(|v| v as i64)
+    } else {
+        digits.parse::<u64>().map(|v| v as i64)
+    }
+    .map_err(|_| format!("Valor numérico inválido '{}'", text))?;
+    Ok(if negative { value.wrapping_neg() } else { value })
+}
+
+fn parse_string(text: &str) -> Result<Vec<u8>, String> {
+    let inner = text
+        .trim()
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or_else(|| format!("Texto inválido: {}", text))?;
+
+    let bytes = inner.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let escape = *bytes.get(i).ok_or("Escape incompleto")?;
+        i += 1;
+        match escape {
+            b'n' => out.push(b'\n'),
+            b't' => out.push(b'\t'),
+            b'r' => out.push(b'\r'),
+            b'"' => out.push(b'"'),
+            b'\\' => out.push(b'\\'),
+            b'x' => {
+                let start = i;
+                while i < bytes.len() && i < start + 2 && bytes[i].is_ascii_hexdigit() {
+                    i += 1;
+                }
+                let hex = std::str::from_utf8(&bytes[start..i]).unwrap_or("");
+                out.push(u8::from_str_radix(hex, 16).map_err(|_| "Escape \\x inválido")?);
+            }
+            b'0'..=b'7' => {
+                let start = i - 1;
+                while i < bytes.len() && i < start + 3 && (b'0'..=b'7').contains(&bytes[i]) {
+                    i += 1;
+                }
+                let octal = std::str::from_utf8(&bytes[start..i]).unwrap_or("");
+                out.push(u16::from_str_radix(octal, 8).map_err(|_| "Escape octal inválido")? as u8);
+            }
+            other => return Err(format!("Escape desconhecido '\\{}'", other as char)),
+        }
+    }
+    Ok(out)
+}
+
+// === REGISTRADORES ===
+
+fn int_register(name: &str) -> Option<u32> {
+    const ABI: [&str; 32] = [
+        "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+        "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+    ];
+    if name == "fp" {
+        return Some(8);
+    }
+    if let Some(n) = name.strip_prefix('x').and_then(|n| n.parse::<u32>().ok()) {
+        return (n < 32).then_some(n);
+    }
+    ABI.iter().position(|r| *r == name).map(|n| n as u32)
+}
+
+fn float_register(name: &str) -> Option<u32> {
+    const ABI: [&str; 32] = [
+        "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2", "fa3", "fa4", "fa5",
+        "fa6", "fa7", "fs2", "fs3", "fs4", "fs5", "fs6", "fs7", "fs8", "fs9", "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+    ];
+    if let Some(n) = name.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+        return (n < 32).then_some(n);
+    }
+    ABI.iter().position(|r| *r == name).map(|n| n as u32)
+}
+
+// === CODIFICAÇÃO ===
+
+fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+fn i_type(imm: i64, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    (((imm as u32) & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+fn s_type(imm: i64, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 5) & 0x7f) << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | ((imm & 0x1f) << 7) | opcode
+}
+
+fn b_type(offset: i64, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    let imm = offset as u32;
+    (((imm >> 12) & 1) << 31)
+        | (((imm >> 5) & 0x3f) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (((imm >> 1) & 0xf) << 8)
+        | (((imm >> 11) & 1) << 7)
+        | 0x63
+}
+
+fn u_type(imm20: i64, rd: u32, opcode: u32) -> u32 {
+    (((imm20 as u32) & 0xfffff) << 12) | (rd << 7) | opcode
+}
+
+fn j_type(offset: i64, rd: u32) -> u32 {
+    let imm = offset as u32;
+    (((imm >> 20) & 1) << 31)
+        | (((imm >> 1) & 0x3ff) << 21)
+        | (((imm >> 11) & 1) << 20)
+        | (((imm >> 12) & 0xff) << 12)
+        | (rd << 7)
+        | 0x6f
+}
+
+const OP_IMM: u32 = 0x13;
+const OP_IMM_32: u32 = 0x1b;
+const OP: u32 = 0x33;
+const OP_32: u32 = 0x3b;
+const OP_FP: u32 = 0x53;
+
+// (rd, imediato) da sequência de `li`
+#[derive(Debug, Clone, Copy)]
+enum LiStep {
+    Lui(i64),
+    Addi(i64),
+    Addiw(i64),
+    Slli(i64),
+}
+
+// mesma estratégia do LLVM: lui/addiw para 32 bits e, acima disso,
+// materializa a parte alta recursivamente, desloca e soma os 12 bits baixos
+fn li_sequence(value: i64) -> Vec<LiStep> {
+    if value == value as i32 as i64 {
+        let hi20 = ((value + 0x800) >> 12) & 0xfffff;
+        let lo12 = sign_extend(value & 0xfff, 12);
+        let mut steps = Vec::new();
+        if hi20 != 0 {
+            steps.push(LiStep::Lui(hi20));
+        }
+        if lo12 != 0 || hi20 == 0 {
+            steps.push(if hi20 != 0 { LiStep::Addiw(lo12) } else { LiStep::Addi(lo12) });
+        }
+        return steps;
+    }
+
+    let lo12 = sign_extend(value & 0xfff, 12);
+    let hi52 = ((value as u64).wrapping_add(0x800) >> 12) as i64;
+    let shift = 12 + hi52.trailing_zeros() as i64;
+    let hi = sign_extend(((hi52 as u64) >> (shift - 12)) as i64, 64 - shift as u32);
+
+    let mut steps = li_sequence(hi);
+    steps.push(LiStep::Slli(shift));
+    if lo12 != 0 {
+        steps.push(LiStep::Addi(lo12));
+    }
+    steps
+}
+
+fn sign_extend(value: i64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    (value << shift) >> shift
+}
+
+fn branch_kind(mnemonic: &str) -> Option<()> {
+    matches!(
+        mnemonic,
+        "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" | "beqz" | "bnez" | "blez" | "bgez" | "bltz" | "bgtz"
+            | "bgt" | "ble" | "bgtu" | "bleu"
+    )
+    .then_some(())
+}
+
+fn item_size(item: &Item, linha: usize) -> Result<u64, AssemblerError> {
+    Ok(match item {
+        Item::Bytes(bytes) => bytes.len() as u64,
+        Item::Align(_) => 0,
+        Item::Instr { mnemonic, operands, far } => match mnemonic.as_str() {
+            "li" => {
+                let value = operands
+                    .get(1)
+                    .ok_or_else(|| AssemblerError { message: "li espera 2 operandos".to_string(), linha })
+                    .and_then(|v| parse_int(v).map_err(|message| AssemblerError { message, linha }))?;
+                4 * li_sequence(value).len() as u64
+            }
+            "la" | "call" | "tail" => 8,
+            _ if *far => 8,
+            _ => 4,
+        },
+    })
+}
+
+struct Operands<'a> {
+    mnemonic: &'a str,
+    list: &'a [String],
+    pc: u64,
+    symbols: &'a HashMap<String, u64>,
+}
+
+impl Operands<'_> {
+    fn expect(&self, count: usize) -> Result<(), String> {
+        if self.list.len() == count {
+            Ok(())
+        } else {
+            Err(format!("'{}' espera {} operandos, encontrados {}", self.mnemonic, count, self.list.len()))
+        }
+    }
+
+    // `count` operandos e mais o modo de arredondamento opcional
+    fn expect_rounding(&self, count: usize) -> Result<(), String> {
+        if self.list.len() == count + 1 { Ok(()) } else { self.expect(count) }
+    }
+
+    // um operando que falta é erro de montagem, mesmo num caminho que
+    // esqueceu de conferir a quantidade
+    fn operand(&self, i: usize) -> Result<&str, String> {
+        self.list
+            .get(i)
+            .map(String::as_str)
+            .ok_or_else(|| format!("'{}' espera mais de {} operandos, encontrados {}", self.mnemonic, i, self.list.len()))
+    }
+
+    fn reg(&self, i: usize) -> Result<u32, String> {
+        let text = self.operand(i)?;
+        int_register(text).ok_or_else(|| format!("Registrador inválido '{}'", text))
+    }
+
+    fn freg(&self, i: usize) -> Result<u32, String> {
+        let text = self.operand(i)?;
+        float_register(text).ok_or_else(|| format!("Registrador de ponto flutuante inválido '{}'", text))
+    }
+
+    fn imm(&self, i: usize, bits: u32) -> Result<i64, String> {
+        let value = parse_int(self.operand(i)?)?;
+        check_signed(value, bits)
+    }
+
+    // "deslocamento(registrador)"
+    fn mem(&self, i: usize) -> Result<(i64, u32), String> {
+        let text = self.operand(i)?;
+        let open = text.find('(').ok_or_else(|| format!("Operando de memória inválido '{}'", text))?;
+        let register = text[open + 1..]
+            .strip_suffix(')')
+            .and_then(int_register)
+            .ok_or_else(|| format!("Operando de memória inválido '{}'", text))?;
+        let offset = if open == 0 { 0 } else { check_signed(parse_int(&text[..open])?, 12)? };
+        Ok((offset, register))
+    }
+
+    fn target(&self, i: usize) -> Result<i64, String> {
+        let name = self.operand(i)?;
+        let address = self.symbols
+            .get(name)
+            .ok_or_else(|| format!("Rótulo '{}' não definido", name))?;
+        Ok(*address as i64 - self.pc as i64)
+    }
+
+    // modo de arredondamento opcional (último operando)
+    fn rounding(&self, i: usize) -> Result<u32, String> {
+        match self.list.get(i).map(String::as_str) {
+            None | Some("dyn") => Ok(7),
+            Some("rne") => Ok(0),
+            Some("rtz") => Ok(1),
+            Some("rdn") => Ok(2),
+            Some("rup") => Ok(3),
+            Some("rmm") => Ok(4),
+            Some(other) => Err(format!("Modo de arredondamento inválido '{}'", other)),
+        }
+    }
+}
+
+fn check_signed(value: i64, bits: u32) -> Result<i64, String> {
+    let limit = 1i64 << (bits - 1);
+    if (-limit..limit).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("Imediato {} não cabe em {} bits", value, bits))
+    }
+}
+
+fn check_branch(offset: i64) -> Result<i64, String> {
+    if (-4096..4096).contains(&offset) { Ok(offset) } else { Err("Desvio fora do alcance".to_string()) }
+}
+
+fn check_jump(offset: i64) -> Result<i64, String> {
+    if (-(1 << 20)..(1 << 20)).contains(&offset) { Ok(offset) } else { Err("Salto fora do alcance".to_string()) }
+}
+
+// separa um deslocamento de 32 bits em (hi20, lo12) para auipc + addi/jalr
+fn split_pc_relative(offset: i64) -> (i64, i64) {
+    let hi20 = (offset + 0x800) >> 12;
+    let lo12 = offset - (hi20 << 12);
+    (hi20, lo12)
+}
+
+fn encode(mnemonic: &str, operands: &[String], far: bool, pc: u64, symbols: &HashMap<String, u64>) -> Result<Vec<u32>, String> {
+    let ops = Operands { mnemonic, list: operands, pc, symbols };
+
+    // R-type inteiro: (funct7, funct3, opcode)
+    let r_int = match mnemonic {
+        "add" => Some((0x00, 0, OP)),
+        "sub" => Some((0x20, 0, OP)),
+        "sll" => Some((0x00, 1, OP)),
+        "slt" => Some((0x00, 2, OP)),
+        "sltu" => Some((0x00, 3, OP)),
+        "xor" => Some((0x00, 4, OP)),
+        "srl" => Some((0x00, 5, OP)),
+        "sra" => Some((0x20, 5, OP)),
+        "or" => Some((0x00, 6, OP)),
+        "and" => Some((0x00, 7, OP)),
+        "mul" => Some((0x01, 0, OP)),
+        "mulh" => Some((0x01, 1, OP)),
+        "mulhsu" => Some((0x01, 2, OP)),
+        "mulhu" => Some((0x01, 3, OP)),
+        "div" => Some((0x01, 4, OP)),
+        "divu" => Some((0x01, 5, OP)),
+        "rem" => Some((0x01, 6, OP)),
+        "remu" => Some((0x01, 7, OP)),
+        "addw" => Some((0x00, 0, OP_32)),
+        "subw" => Some((0x20, 0, OP_32)),
+        "sllw" => Some((0x00, 1, OP_32)),
+        "srlw" => Some((0x00, 5, OP_32)),
+        "sraw" => Some((0x20, 5, OP_32)),
+        "mulw" => Some((0x01, 0, OP_32)),
+        "divw" => Some((0x01, 4, OP_32)),
+        "divuw" => Some((0x01, 5, OP_32)),
+        "remw" => Some((0x01, 6, OP_32)),
+        "remuw" => Some((0x01, 7, OP_32)),
+        _ => None,
+    };
+    if let Some((funct7, funct3, opcode)) = r_int {
+        ops.expect(3)?;
+        return Ok(vec![r_type(funct7, ops.reg(2)?, ops.reg(1)?, funct3, ops.reg(0)?, opcode)]);
+    }
+
+    let i_int = match mnemonic {
+        "addi" => Some((0, OP_IMM)),
+        "slti" => Some((2, OP_IMM)),
+        "sltiu" => Some((3, OP_IMM)),
+        "xori" => Some((4, OP_IMM)),
+        "ori" => Some((6, OP_IMM)),
+        "andi" => Some((7, OP_IMM)),
+        "addiw" => Some((0, OP_IMM_32)),
+        _ => None,
+    };
+    if let Some((funct3, opcode)) = i_int {
+        ops.expect(3)?;
+        return Ok(vec![i_type(ops.imm(2, 12)?, ops.reg(1)?, funct3, ops.reg(0)?, opcode)]);
+    }
+
+    // deslocamentos com imediato: (funct6/7 alto, funct3, opcode, bits do shamt)
+    let shift = match mnemonic {
+        "slli" => Some((0x00, 1, OP_IMM, 6)),
+        "srli" => Some((0x00, 5, OP_IMM, 6)),
+        "srai" => Some((0x10, 5, OP_IMM, 6)),
+        "slliw" => Some((0x00, 1, OP_IMM_32, 5)),
+        "srliw" => Some((0x00, 5, OP_IMM_32, 5)),
+        "sraiw" => Some((0x20, 5, OP_IMM_32, 5)),
+        _ => None,
+    };
+    if let Some((high, funct3, opcode, bits)) = shift {
+        ops.expect(3)?;
+        let shamt = parse_int(ops.operand(2)?)?;
+        if !(0..1 << bits).contains(&shamt) {
+            return Err(format!("Deslocamento {} inválido", shamt));
+        }
+        let imm = ((high as i64) << bits) | shamt;
+        return Ok(vec![i_type(imm, ops.reg(1)?, funct3, ops.reg(0)?, opcode)]);
+    }
+
+    let load = match mnemonic {
+        "lb" => Some(0),
+        "lh" => Some(1),
+        "lw" => Some(2),
+        "ld" => Some(3),
+        "lbu" => Some(4),
+        "lhu" => Some(5),
+        "lwu" => Some(6),
+        _ => None,
+    };
+    if let Some(funct3) = load {
+        ops.expect(2)?;
+        let (offset, base) = ops.mem(1)?;
+        return Ok(vec![i_type(offset, base, funct3, ops.reg(0)?, 0x03)]);
+    }
+
+    let store = match mnemonic {
+        "sb" => Some(0),
+        "sh" => Some(1),
+        "sw" => Some(2),
+        "sd" => Some(3),
+        _ => None,
+    };
+    if let Some(funct3) = store {
+        ops.expect(2)?;
+        let (offset, base) = ops.mem(1)?;
+        return Ok(vec![s_type(offset, ops.reg(0)?, base, funct3, 0x23)]);
+    }
+
+    // desvios: (funct3, rs1, rs2) já resolvidos para as pseudoinstruções
+    let branch = match mnemonic {
+        "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" => {
+            ops.expect(3)?;
+            let funct3 = match mnemonic {
+                "beq" => 0,
+                "bne" => 1,
+                "blt" => 4,
+                "bge" => 5,
+                "bltu" => 6,
+                _ => 7,
+            };
+            Some((funct3, ops.reg(0)?, ops.reg(1)?))
+        }
+        "bgt" | "ble" | "bgtu" | "bleu" => {
+            ops.expect(3)?;
+            let funct3 = match mnemonic {
+                "bgt" => 4,
+                "ble" => 5,
+                "bgtu" => 6,
+                _ => 7,
+            };
+            Some((funct3, ops.reg(1)?, ops.reg(0)?))
+        }
+        "beqz" | "bnez" | "blez" | "bgez" | "bltz" | "bgtz" => {
+            ops.expect(2)?;
+            let rs = ops.reg(0)?;
+            Some(match mnemonic {
+                "beqz" => (0, rs, 0),
+                "bnez" => (1, rs, 0),
+                "blez" => (5, 0, rs),
+                "bgez" => (5, rs, 0),
+                "bltz" => (4, rs, 0),
+                _ => (4, 0, rs),
+            })
+        }
+        _ => None,
+    };
+    if let Some((funct3, rs1, rs2)) = branch {
+        let offset = ops.target(operands.len() - 1)?;
+        if far {
+            // condição invertida salta sobre o jal
+            let jump = check_jump(offset - 4)?;
+            return Ok(vec![b_type(8, rs2, rs1, funct3 ^ 1), j_type(jump, 0)]);
+        }
+        return Ok(vec![b_type(check_branch(offset)?, rs2, rs1, funct3)]);
+    }
+
+    let float_arith = match mnemonic {
+        "fadd.d" => Some(0x01),
+        "fsub.d" => Some(0x05),
+        "fmul.d" => Some(0x09),
+        "fdiv.d" => Some(0x0d),
+        _ => None,
+    };
+    if let Some(funct7) = float_arith {
+        ops.expect_rounding(3)?;
+        let rm = ops.rounding(3)?;
+        return Ok(vec![r_type(funct7, ops.freg(2)?, ops.freg(1)?, rm, ops.freg(0)?, OP_FP)]);
+    }
+
+    // ponto flutuante com funct3 fixo: (funct7, funct3, destino inteiro?)
+    let float_fixed = match mnemonic {
+        "fsgnj.d" => Some((0x11, 0, false)),
+        "fsgnjn.d" => Some((0x11, 1, false)),
+        "fsgnjx.d" => Some((0x11, 2, false)),
+        "fmin.d" => Some((0x15, 0, false)),
+        "fmax.d" => Some((0x15, 1, false)),
+        "feq.d" => Some((0x51, 2, true)),
+        "flt.d" => Some((0x51, 1, true)),
+        "fle.d" => Some((0x51, 0, true)),
+        _ => None,
+    };
+    if let Some((funct7, funct3, int_dest)) = float_fixed {
+        ops.expect(3)?;
+        let rd = if int_dest { ops.reg(0)? } else { ops.freg(0)? };
+        return Ok(vec![r_type(funct7, ops.freg(2)?, ops.freg(1)?, funct3, rd, OP_FP)]);
+    }
+
+    let word = match mnemonic {
+        "lui" | "auipc" => {
+            ops.expect(2)?;
+            let imm = parse_int(ops.operand(1)?)?;
+            if !(0..1 << 20).contains(&imm) {
+                return Err(format!("Imediato {} não cabe em 20 bits", imm));
+            }
+            u_type(imm, ops.reg(0)?, if mnemonic == "lui" { 0x37 } else { 0x17 })
+        }
+        "jal" => match operands.len() {
+            1 => j_type(check_jump(ops.target(0)?)?, 1),
+            _ => {
+                ops.expect(2)?;
+                j_type(check_jump(ops.target(1)?)?, ops.reg(0)?)
+            }
+        },
+        "j" => {
+            ops.expect(1)?;
+            j_type(check_jump(ops.target(0)?)?, 0)
+        }
+        "jalr" => match operands.len() {
+            1 => i_type(0, ops.reg(0)?, 0, 1, 0x67),
+            2 => {
+                let (offset, base) = ops.mem(1)?;
+                i_type(offset, base, 0, ops.reg(0)?, 0x67)
+            }
+            _ => {
+                ops.expect(3)?;
+                i_type(ops.imm(2, 12)?, ops.reg(1)?, 0, ops.reg(0)?, 0x67)
+            }
+        },
+        "jr" => {
+            ops.expect(1)?;
+            i_type(0, ops.reg(0)?, 0, 0, 0x67)
+        }
+        "ret" => {
+            ops.expect(0)?;
+            i_type(0, 1, 0, 0, 0x67)
+        }
+        "ecall" => {
+            ops.expect(0)?;
+            0x0000_0073
+        }
+        "ebreak" => {
+            ops.expect(0)?;
+            0x0010_0073
+        }
+        "nop" => {
+            ops.expect(0)?;
+            i_type(0, 0, 0, 0, OP_IMM)
+        }
+        "mv" => {
+            ops.expect(2)?;
+            i_type(0, ops.reg(1)?, 0, ops.reg(0)?, OP_IMM)
+        }
+        "not" => {
+            ops.expect(2)?;
+            i_type(-1, ops.reg(1)?, 4, ops.reg(0)?, OP_IMM)
+        }
+        "neg" | "negw" => {
+            ops.expect(2)?;
+            let opcode = if mnemonic == "neg" { OP } else { OP_32 };
+            r_type(0x20, ops.reg(1)?, 0, 0, ops.reg(0)?, opcode)
+        }
+        "sext.w" => {
+            ops.expect(2)?;
+            i_type(0, ops.reg(1)?, 0, ops.reg(0)?, OP_IMM_32)
+        }
+        "seqz" => {
+            ops.expect(2)?;
+            i_type(1, ops.reg(1)?, 3, ops.reg(0)?, OP_IMM)
+        }
+        "snez" => {
+            ops.expect(2)?;
+            r_type(0, ops.reg(1)?, 0, 3, ops.reg(0)?, OP)
+        }
+        "sltz" => {
+            ops.expect(2)?;
+            r_type(0, 0, ops.reg(1)?, 2, ops.reg(0)?, OP)
+        }
+        "sgtz" => {
+            ops.expect(2)?;
+            r_type(0, ops.reg(1)?, 0, 2, ops.reg(0)?, OP)
+        }
+        "fld" | "fsd" => {
+            ops.expect(2)?;
+            let (offset, base) = ops.mem(1)?;
+            if mnemonic == "fld" {
+                i_type(offset, base, 3, ops.freg(0)?, 0x07)
+            } else {
+                s_type(offset, ops.freg(0)?, base, 3, 0x27)
+            }
+        }
+        "fmv.d" | "fneg.d" | "fabs.d" => {
+            ops.expect(2)?;
+            let funct3 = match mnemonic {
+                "fmv.d" => 0,
+                "fneg.d" => 1,
+                _ => 2,
+            };
+            let rs = ops.freg(1)?;
+            r_type(0x11, rs, rs, funct3, ops.freg(0)?, OP_FP)
+        }
+        "fsqrt.d" => {
+            ops.expect_rounding(2)?;
+            let rm = ops.rounding(2)?;
+            r_type(0x2d, 0, ops.freg(1)?, rm, ops.freg(0)?, OP_FP)
+        }
+        // conversões: (funct7, rs2) com origem/destino inteiro
+        "fcvt.d.l" | "fcvt.d.w" => {
+            ops.expect_rounding(2)?;
+            let rs2 = if mnemonic == "fcvt.d.l" { 2 } else { 0 };
+            r_type(0x69, rs2, ops.reg(1)?, ops.rounding(2)?, ops.freg(0)?, OP_FP)
+        }
+        "fcvt.l.d" | "fcvt.w.d" => {
+            ops.expect_rounding(2)?;
+            let rs2 = if mnemonic == "fcvt.l.d" { 2 } else { 0 };
+            r_type(0x61, rs2, ops.freg(1)?, ops.rounding(2)?, ops.reg(0)?, OP_FP)
+        }
+        "fmv.x.d" => {
+            ops.expect(2)?;
+            r_type(0x71, 0, ops.freg(1)?, 0, ops.reg(0)?, OP_FP)
+        }
+        "fmv.d.x" => {
+            ops.expect(2)?;
+            r_type(0x79, 0, ops.reg(1)?, 0, ops.freg(0)?, OP_FP)
+        }
+        _ => return encode_multi(mnemonic, &ops),
+    };
+    Ok(vec![word])
+}
+
+// pseudoinstruções que expandem para mais de uma instrução
+fn encode_multi(mnemonic: &str, ops: &Operands) -> Result<Vec<u32>, String> {
+    match mnemonic {
+        "li" => {
+            ops.expect(2)?;
+            let rd = ops.reg(0)?;
+            let value = parse_int(ops.operand(1)?)?;
+            Ok(li_sequence(value)
+                .into_iter()
+                .enumerate()
+                .map(|(i, step)| {
+                    // o primeiro passo parte de x0; os seguintes, do próprio rd
+                    let source = if i == 0 { 0 } else { rd };
+                    match step {
+                        LiStep::Lui(imm) => u_type(imm, rd, 0x37),
+                        LiStep::Addi(imm) => i_type(imm, source, 0, rd, OP_IMM),
+                        LiStep::Addiw(imm) => i_type(imm, source, 0, rd, OP_IMM_32),
+                        LiStep::Slli(shamt) => i_type(shamt, rd, 1, rd, OP_IMM),
+                    }
+                })
+                .collect())
+        }
+        "la" => {
+            ops.expect(2)?;
+            let rd = ops.reg(0)?;
+            let (hi20, lo12) = split_pc_relative(ops.target(1)?);
+            Ok(vec![u_type(hi20, rd, 0x17), i_type(lo12, rd, 0, rd, OP_IMM)])
+        }
+        "call" | "tail" => {
+            ops.expect(1)?;
+            let (hi20, lo12) = split_pc_relative(ops.target(0)?);
+            // call usa ra e t1 como no GNU as; tail descarta o retorno
+            let (scratch, link) = if mnemonic == "call" { (1, 1) } else { (6, 0) };
+            Ok(vec![u_type(hi20, scratch, 0x17), i_type(lo12, scratch, 0, link, 0x67)])
+        }
+        _ => Err(format!("Instrução desconhecida '{}'", mnemonic)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(source: &str) -> Vec<u32> {
+        let program = assemble(&format!("_start:\n{}", source)).unwrap();
+        program.image[..program.text_size as usize]
+            .chunks(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_encodings_match_gnu_as() {
+        // valores conferidos com llvm-mc -triple=riscv64 -mattr=+m,+d
+        assert_eq!(words("addi a0, a0, 1"), vec![0x00150513]);
+        assert_eq!(words("ld ra, 8(sp)"), vec![0x00813083]);
+        assert_eq!(words("sd s0, -16(s0)"), vec![0xfe843823]);
+        assert_eq!(words("mul a0, a0, t1"), vec![0x02650533]);
+        assert_eq!(words("fadd.d fa0, fa0, ft1"), vec![0x02157553]);
+        assert_eq!(words("feq.d a0, fa0, ft1"), vec![0xa2152553]);
+        assert_eq!(words("fcvt.d.l fa0, a0"), vec![0xd2257553]);
+        assert_eq!(words("ecall"), vec![0x00000073]);
+        assert_eq!(words("loop:\nbeqz a0, loop"), vec![0x00050063]);
+        assert_eq!(words("j fim\nnop\nfim:"), vec![0x0080006f, 0x00000013]);
+    }
+
+    #[test]
+    fn test_far_branches_are_relaxed() {
+        let mut source = String::from("beqz a0, longe\n");
+        for _ in 0..1100 {
+            source.push_str("nop\n");
+        }
+        source.push_str("longe:\nret\n");
+
+        let encoded = words(&source);
+        // bnez a0, +8 seguido de jal x0, longe
+        assert_eq!(encoded[0], 0x00051463);
+        assert_eq!(encoded[1] & 0x7f, 0x6f);
+        assert_eq!(encoded.len(), 1100 + 3);
+    }
+
+    #[test]
+    fn test_data_and_errors() {
+        let program = assemble(
+            "    .section .rodata\nmsg: .string \"ol\\303\\241\\n\"\n    .text\n_start:\n    la a0, msg\n",
+        )
+        .unwrap();
+        let address = program.symbols["msg"];
+        let offset = (address - TEXT_BASE) as usize;
+        let expected = "olá\n\0".as_bytes();
+        assert_eq!(&program.image[offset..offset + expected.len()], expected);
+
+        let error = assemble("_start:\n    addi a0, a0, 5000\n").unwrap_err();
+        assert_eq!(error.linha, 2);
+        assert!(assemble("_start:\n    j nenhum\n").is_err());
+    }
+
+    #[test]
+    fn test_missing_operands_are_errors() {
+        let mnemonics = [
+            "add", "addi", "slli", "lui", "ld", "sd", "beq", "bgt", "beqz", "jal", "jalr", "j", "jr", "mv", "not", "neg",
+            "sext.w", "seqz", "snez", "sltz", "sgtz", "fadd.d", "feq.d", "fld", "fsd", "fmv.d", "fneg.d", "fabs.d",
+            "fsqrt.d", "fcvt.d.l", "fcvt.d.w", "fcvt.l.d", "fcvt.w.d", "fmv.x.d", "fmv.d.x", "li", "la", "call", "tail",
+        ];
+        for mnemonic in mnemonics {
+            for source in [format!("_start:\n    {}\n", mnemonic), format!("_start:\n    {} a0\n", mnemonic)] {
+                // `jalr a0` e `jr a0` são válidos com um operando
+                if source.ends_with("jalr a0\n") || source.ends_with("jr a0\n") {
+                    continue;
+                }
+                let error = assemble(&source).expect_err(&source);
+                assert_eq!(error.linha, 2, "{}", source);
+            }
+        }
+    }
+}
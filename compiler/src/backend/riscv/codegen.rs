@@ -0,0 +1,1739 @@
+use std::collections::HashMap;
+use crate::parser::ast::*;
+use crate::backend::riscv::*;
+use crate::backend::{ascii_identifier, entry_point, escape_gas_string, numeric_result, BackendError, ScopedEnv};
+use crate::semantic::builtins::{Builtin, Overload};
+
+// Gera assembly RV64IMD (sintaxe do GNU as) a partir da AST anotada. O
+// resultado roda no simulador embutido (`--simular`) e usa as chamadas de
+// sistema definidas em `backend::riscv` para E/S.
+//
+// Modelo de execução (o mesmo do backend x86-64):
+//   - cada expressão deixa o resultado em a0 (inteiro, logico, texto) ou em
+//     fa0 (decimal); o operando direito de uma operação binária vai para
+//     t1/ft1 e o esquerdo espera na pilha;
+//   - s0 aponta para o topo do quadro: ra e o s0 anterior ficam logo abaixo
+//     e as variáveis locais em seguida, de 8 em 8 bytes;
+//   - argumentos seguem a ABI LP64D (a0-a7, fa0-fa7 e depois a pilha);
+//   - t0 é reservado para cálculo de endereços;
+//   - conversões inválidas (paraInteiro("abc")) escrevem uma mensagem e
+//     encerram o programa com código 1, como a divisão por zero.
+
+const INT_ARG_REGISTERS: usize = 8;
+const FLOAT_ARG_REGISTERS: usize = 8;
+const READ_BUFFER_SIZE: u64 = 256;
+// ra e s0 salvos
+const SAVED_REGISTERS_SIZE: i64 = 16;
+
+#[derive(Clone)]
+enum Location {
+    Frame(i64),
+    Global(String),
+}
+
+// Classificação LP64D: decimais vão para fa0-fa7 e, esgotados esses, para
+// os registradores inteiros livres (como padrão de bits) antes da pilha
+enum ArgClass {
+    Int(usize),
+    Float(usize),
+    Stack(usize),
+}
+
+fn classify_arguments(types: &[Type]) -> Vec<ArgClass> {
+    let (mut ints, mut floats, mut stack) = (0, 0, 0);
+    types
+        .iter()
+        .map(|ty| {
+            if *ty == Type::Decimal && floats < FLOAT_ARG_REGISTERS {
+                floats += 1;
+                ArgClass::Float(floats - 1)
+            } else if ints < INT_ARG_REGISTERS {
+                ints += 1;
+                ArgClass::Int(ints - 1)
+            } else {
+                stack += 1;
+                ArgClass::Stack(stack - 1)
+            }
+        })
+        .collect()
+}
+
+fn fits_imm12(value: i64) -> bool {
+    (-2048..2048).contains(&value)
+}
+
+pub struct RiscvGenerator<'a> {
+    program: &'a Program,
+    env: ScopedEnv<Location>,
+
+    strings: HashMap<String, String>,
+    rodata: Vec<String>,
+    label_counter: usize,
+    // rotinas do runtime, na ordem do primeiro uso
+    runtime: Vec<&'static str>,
+    uses_division_check: bool,
+
+    // estado da função sendo gerada
+    lines: Vec<String>,
+    frame_size: i64,
+    stack_depth: i64,
+    return_label: String,
+}
+
+impl<'a> RiscvGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            env: ScopedEnv::new(program),
+            strings: HashMap::new(),
+            rodata: Vec::new(),
+            label_counter: 0,
+            runtime: Vec::new(),
+            uses_division_check: false,
+            lines: Vec::new(),
+            frame_size: 0,
+            stack_depth: 0,
+            return_label: String::new(),
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String, BackendError> {
+        let mut data = Vec::new();
+        for statement in &self.program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                let symbol = format!("var.{}", ascii_identifier(&decl.name));
+                data.push(format!("{}:\n    .dword 0", symbol));
+                self.env.declare(&decl.name, decl.var_type.clone(), Location::Global(symbol));
+            }
+        }
+
+        let mut functions = Vec::new();
+        for function in &self.program.functions {
+            functions.push(self.generate_function(function).map_err(|e| e.at_line(function.line))?);
+        }
+        functions.push(self.generate_main()?);
+
+        let mut out = String::new();
+        out.push_str("# Gerado pelo compilador Symplia (RV64IMD)\n");
+        out.push_str("\n    .text\n    .globl _start\n_start:\n    call main\n");
+        out.push_str(&format!("    li a7, {}\n    ecall\n", SYSCALL_EXIT));
+        for function in functions {
+            out.push('\n');
+            out.push_str(&function);
+        }
+        for routine in &self.runtime {
+            out.push('\n');
+            out.push_str(routine);
+        }
+        if self.uses_division_check {
+            let message = self.intern_string("Erro: divisão por zero\n");
+            out.push('\n');
+            out.push_str(&DIVISION_BY_ZERO_HANDLER.replace("{mensagem}", &message));
+        }
+
+        if !data.is_empty() {
+            out.push_str("\n    .data\n    .p2align 3\n");
+            for item in &data {
+                out.push_str(item);
+                out.push('\n');
+            }
+        }
+        if !self.rodata.is_empty() {
+            out.push_str("\n    .section .rodata\n");
+            for item in &self.rodata {
+                out.push_str(item);
+                out.push('\n');
+            }
+        }
+
+        Ok(out)
+    }
+
+    // === FUNÇÕES ===
+
+    fn generate_function(&mut self, function: &FunctionDecl) -> Result<String, BackendError> {
+        let symbol = format!("fn.{}", ascii_identifier(&function.name));
+        self.begin_function();
+        self.env.enter_scope();
+
+        let types: Vec<Type> = function.parameters.iter().map(|p| p.param_type.clone()).collect();
+        for (param, class) in function.parameters.iter().zip(classify_arguments(&types)) {
+            let location = match class {
+                ArgClass::Int(i) => {
+                    let location = self.allocate_slot();
+                    let operand = self.address(&location);
+                    self.emit(&format!("sd a{}, {}", i, operand));
+                    location
+                }
+                ArgClass::Float(i) => {
+                    let location = self.allocate_slot();
+                    let operand = self.address(&location);
+                    self.emit(&format!("fsd fa{}, {}", i, operand));
+                    location
+                }
+                // o chamador deixa os argumentos excedentes logo acima do quadro
+                ArgClass::Stack(i) => Location::Frame(8 * i as i64),
+            };
+            self.env.declare(&param.name, param.param_type.clone(), location);
+        }
+
+        self.generate_block(&function.body, &function.return_type)?;
+
+        // funções que terminam sem `retorne` devolvem o valor padrão do tipo
+        if let Some(return_type) = &function.return_type {
+            self.load_default(return_type);
+        }
+
+        self.env.exit_scope();
+        Ok(self.finish_function(&symbol))
+    }
+
+    fn generate_main(&mut self) -> Result<String, BackendError> {
+        self.begin_function();
+        self.env.enter_scope();
+
+        for statement in &self.program.statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    // o .dword 0 não serve de texto vazio
+                    match &decl.initializer {
+                        Some(initializer) => self.generate_coerced(initializer, &decl.var_type).map_err(|e| e.at_line(decl.line))?,
+                        None => self.load_default(&decl.var_type),
+                    }
+                    let (location, _) = self.lookup(&decl.name).map_err(|e| e.at_line(decl.line))?;
+                    self.store(&location, &decl.var_type);
+                }
+                other => self.generate_statement(other, &None).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+
+        if let Some(principal) = entry_point(self.program) {
+            self.emit_call(&format!("fn.{}", ascii_identifier(&principal.name)));
+        }
+        self.emit("li a0, 0");
+
+        self.env.exit_scope();
+        Ok(self.finish_function("main"))
+    }
+
+    fn begin_function(&mut self) {
+        self.lines.clear();
+        self.frame_size = 0;
+        self.stack_depth = 0;
+        self.return_label = self.new_label();
+    }
+
+    fn finish_function(&mut self, symbol: &str) -> String {
+        let locals = (self.frame_size + 15) / 16 * 16;
+
+        let mut out = String::new();
+        out.push_str(&format!("{}:\n", symbol));
+        out.push_str("    addi sp, sp, -16\n");
+        out.push_str("    sd ra, 8(sp)\n");
+        out.push_str("    sd s0, 0(sp)\n");
+        out.push_str("    addi s0, sp, 16\n");
+        if locals > 0 {
+            out.push_str(&adjust_sp(-locals));
+        }
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&format!("{}:\n", self.return_label));
+        out.push_str("    addi sp, s0, -16\n");
+        out.push_str("    ld ra, 8(sp)\n");
+        out.push_str("    ld s0, 0(sp)\n");
+        out.push_str("    addi sp, sp, 16\n");
+        out.push_str("    ret\n");
+        out
+    }
+
+    // === STATEMENTS ===
+
+    fn generate_block(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+        for statement in &block.statements {
+            self.generate_statement(statement, return_type).map_err(|e| e.at_line(statement.line()))?;
+        }
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    fn generate_statement(&mut self, statement: &Statement, return_type: &Option<Type>) -> Result<(), BackendError> {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                match &decl.initializer {
+                    Some(initializer) => self.generate_coerced(initializer, &decl.var_type)?,
+                    None => self.load_default(&decl.var_type),
+                }
+                let location = self.allocate_slot();
+                self.store(&location, &decl.var_type);
+                self.env.declare(&decl.name, decl.var_type.clone(), location);
+            }
+            Statement::ExprStmt(expr_stmt) => match &expr_stmt.expr {
+                Expr::Call(call) => {
+                    self.generate_call(call)?;
+                }
+                expr => {
+                    self.generate_expr(expr)?;
+                }
+            },
+            Statement::IfStmt(if_stmt) => {
+                let else_label = self.new_label();
+                let end_label = self.new_label();
+
+                self.generate_expr(&if_stmt.condition)?;
+                self.emit(&format!("beqz a0, {}", else_label));
+                self.generate_block(&if_stmt.then_branch, return_type)?;
+                self.emit(&format!("j {}", end_label));
+                self.emit_label(&else_label);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.generate_block(else_branch, return_type)?;
+                }
+                self.emit_label(&end_label);
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let loop_label = self.new_label();
+                let end_label = self.new_label();
+
+                self.emit_label(&loop_label);
+                self.generate_expr(&while_stmt.condition)?;
+                self.emit(&format!("beqz a0, {}", end_label));
+                self.generate_block(&while_stmt.body, return_type)?;
+                self.emit(&format!("j {}", loop_label));
+                self.emit_label(&end_label);
+            }
+            Statement::ForStmt(for_stmt) => {
+                self.env.enter_scope();
+
+                let (location, var_type) = match self.env.lookup(&for_stmt.variable) {
+                    Some((location, ty)) if matches!(ty, Type::Inteiro | Type::Decimal) => (location, ty),
+                    _ => {
+                        let location = self.allocate_slot();
+                        self.env.declare(&for_stmt.variable, Type::Inteiro, location.clone());
+                        (location, Type::Inteiro)
+                    }
+                };
+
+                self.generate_coerced(&for_stmt.start, &var_type)?;
+                self.store(&location, &var_type);
+
+                let loop_label = self.new_label();
+                let end_label = self.new_label();
+                self.emit_label(&loop_label);
+
+                // condição: variável <= fim (o fim é reavaliado a cada volta)
+                let end_type = self.env.expr_type(&for_stmt.end)?;
+                let compare_type = numeric_result(&var_type, &end_type);
+                self.load(&location, &var_type);
+                self.convert(&var_type, &compare_type);
+                self.push(&compare_type);
+                self.generate_coerced(&for_stmt.end, &compare_type)?;
+                self.pop_left_operand(&compare_type);
+                self.compare(&BinaryOperator::LessEqual, &compare_type);
+                self.emit(&format!("beqz a0, {}", end_label));
+
+                self.generate_block(&for_stmt.body, return_type)?;
+
+                self.load(&location, &var_type);
+                if var_type == Type::Decimal {
+                    self.emit("li t0, 1");
+                    self.emit("fcvt.d.l ft1, t0");
+                    self.emit("fadd.d fa0, fa0, ft1");
+                } else {
+                    self.emit("addi a0, a0, 1");
+                }
+                self.store(&location, &var_type);
+                self.emit(&format!("j {}", loop_label));
+                self.emit_label(&end_label);
+
+                self.env.exit_scope();
+            }
+            Statement::ReturnStmt(return_stmt) => {
+                match (&return_stmt.value, return_type) {
+                    (Some(value), Some(return_type)) => self.generate_coerced(value, return_type)?,
+                    (None, None) => {}
+                    _ => return Err(BackendError::new("Retorno incompatível com o tipo da função")),
+                }
+                let label = self.return_label.clone();
+                self.emit(&format!("j {}", label));
+            }
+            Statement::WriteStmt(write_stmt) => {
+                for (i, arg) in write_stmt.arguments.iter().enumerate() {
+                    if i > 0 {
+                        self.print_char(b' ');
+                    }
+                    let ty = self.generate_expr(arg)?;
+                    self.print_value(&ty);
+                }
+                self.print_char(b'\n');
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(BackendError::new("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let (location, ty) = self.lookup(name)?;
+                self.generate_read(&location, &ty);
+            }
+        }
+        Ok(())
+    }
+
+    fn print_char(&mut self, c: u8) {
+        self.emit(&format!("li a0, {}", c));
+        self.syscall(SYSCALL_PRINT_CHAR);
+    }
+
+    fn print_value(&mut self, ty: &Type) {
+        match ty {
+            Type::Inteiro => self.syscall(SYSCALL_PRINT_INT),
+            Type::Decimal => self.syscall(SYSCALL_PRINT_DOUBLE),
+            Type::Texto => self.syscall(SYSCALL_PRINT_STRING),
+            Type::Logico => {
+                self.select_logico_text();
+                self.syscall(SYSCALL_PRINT_STRING);
+            }
+        }
+    }
+
+    fn generate_read(&mut self, location: &Location, ty: &Type) {
+        match ty {
+            Type::Inteiro => self.syscall(SYSCALL_READ_INT),
+            Type::Decimal => self.syscall(SYSCALL_READ_DOUBLE),
+            Type::Texto | Type::Logico => {
+                // lê uma palavra para um buffer novo no heap
+                self.emit(&format!("li a0, {}", READ_BUFFER_SIZE));
+                self.syscall(SYSCALL_SBRK);
+                self.emit(&format!("li a1, {}", READ_BUFFER_SIZE));
+                self.syscall(SYSCALL_READ_STRING);
+
+                if *ty == Type::Logico {
+                    let verdadeiro = self.intern_string("verdadeiro");
+                    self.emit(&format!("la a1, {}", verdadeiro));
+                    self.use_runtime(TEXT_COMPARE_FUNCTION);
+                    self.emit_call("rt.texto_comparar");
+                    self.emit("seqz a0, a0");
+                }
+            }
+        }
+        self.store(location, ty);
+    }
+
+    // === EXPRESSÕES ===
+
+    fn generate_coerced(&mut self, expr: &Expr, target: &Type) -> Result<(), BackendError> {
+        let ty = self.generate_expr(expr)?;
+        self.convert(&ty, target);
+        Ok(())
+    }
+
+    fn generate_expr(&mut self, expr: &Expr) -> Result<Type, BackendError> {
+        let ty = self.env.expr_type(expr)?;
+
+        match expr {
+            Expr::Literal(literal) => match literal {
+                Literal::Inteiro(n) => self.emit(&format!("li a0, {}", n)),
+                Literal::Decimal(n) => {
+                    // decimais são carregados pelo padrão de bits
+                    self.emit(&format!("li t0, {}", n.to_bits() as i64));
+                    self.emit("fmv.d.x fa0, t0");
+                }
+                Literal::Texto(s) => {
+                    let label = self.intern_string(s);
+                    self.emit(&format!("la a0, {}", label));
+                }
+                Literal::Logico(b) => self.emit(&format!("li a0, {}", *b as i32)),
+            },
+            Expr::Variable(name) => {
+                let (location, var_type) = self.lookup(name)?;
+                self.load(&location, &var_type);
+            }
+            Expr::Call(call) => {
+                self.generate_call(call)?;
+            }
+            Expr::UnaryOp(op, operand) => {
+                let operand_type = self.generate_expr(operand)?;
+                match (op, operand_type) {
+                    (UnaryOperator::Plus, _) => {}
+                    (UnaryOperator::Negate, Type::Decimal) => self.emit("fneg.d fa0, fa0"),
+                    (UnaryOperator::Negate, _) => self.emit("neg a0, a0"),
+                    (UnaryOperator::Not, _) => self.emit("xori a0, a0, 1"),
+                }
+            }
+            Expr::BinaryOp(op, left, right) => self.generate_binary(op, left, right)?,
+        }
+
+        Ok(ty)
+    }
+
+    fn generate_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr) -> Result<(), BackendError> {
+        let left_type = self.env.expr_type(left)?;
+        let right_type = self.env.expr_type(right)?;
+
+        // concatenação: cada lado vira texto antes de ir para a pilha
+        if *op == BinaryOperator::Add && (left_type == Type::Texto || right_type == Type::Texto) {
+            self.generate_expr(left)?;
+            self.convert_explicitly(&left_type, &Type::Texto);
+            self.push(&Type::Texto);
+            self.generate_expr(right)?;
+            self.convert_explicitly(&right_type, &Type::Texto);
+            self.pop_left_operand(&Type::Texto);
+            self.emit("mv a1, t1");
+            self.use_runtime(CONCAT_FUNCTION);
+            self.emit_call("rt.concatenar");
+            return Ok(());
+        }
+
+        let operand_type = match (&left_type, &right_type) {
+            (Type::Texto, Type::Texto) => Type::Texto,
+            (Type::Logico, Type::Logico) => Type::Logico,
+            (Type::Inteiro | Type::Decimal, Type::Inteiro | Type::Decimal) => numeric_result(&left_type, &right_type),
+            _ => {
+                return Err(BackendError::new(format!(
+                    "Operador '{}' não suportado para tipos {} e {}", op, left_type, right_type
+                )));
+            }
+        };
+
+        self.generate_coerced(left, &operand_type)?;
+        self.push(&operand_type);
+        self.generate_coerced(right, &operand_type)?;
+        self.pop_left_operand(&operand_type);
+
+        match op {
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::Less
+            | BinaryOperator::LessEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual => {
+                if operand_type == Type::Logico && !matches!(op, BinaryOperator::Equal | BinaryOperator::NotEqual) {
+                    return Err(BackendError::new(format!("Operador '{}' não suportado para lógicos", op)));
+                }
+                self.compare(op, &operand_type);
+            }
+            BinaryOperator::And | BinaryOperator::Or => {
+                if operand_type != Type::Logico {
+                    return Err(BackendError::new(format!("Operador '{}' requer operandos lógicos", op)));
+                }
+                let instr = if *op == BinaryOperator::And { "and" } else { "or" };
+                self.emit(&format!("{} a0, a0, t1", instr));
+            }
+            arithmetic => self.arithmetic(arithmetic, &operand_type)?,
+        }
+
+        Ok(())
+    }
+
+    fn arithmetic(&mut self, op: &BinaryOperator, ty: &Type) -> Result<(), BackendError> {
+        match ty {
+            Type::Decimal => {
+                let instr = match op {
+                    BinaryOperator::Add => "fadd.d",
+                    BinaryOperator::Subtract => "fsub.d",
+                    BinaryOperator::Multiply => "fmul.d",
+                    BinaryOperator::Divide => "fdiv.d",
+                    _ => return Err(BackendError::new(format!("Operador '{}' não suportado para decimais", op))),
+                };
+                self.emit(&format!("{} fa0, fa0, ft1", instr));
+            }
+            Type::Inteiro => {
+                let instr = match op {
+                    BinaryOperator::Add => "add",
+                    BinaryOperator::Subtract => "sub",
+                    BinaryOperator::Multiply => "mul",
+                    BinaryOperator::Divide => "div",
+                    BinaryOperator::Modulo => "rem",
+                    _ => return Err(BackendError::new(format!("Operador '{}' não suportado para inteiros", op))),
+                };
+                // o RISC-V não gera exceção na divisão por zero; os outros backends param
+                if matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo) {
+                    self.uses_division_check = true;
+                    self.emit("beqz t1, rt.divisao_por_zero");
+                }
+                self.emit(&format!("{} a0, a0, t1", instr));
+            }
+            _ => return Err(BackendError::new(format!("Operador '{}' não suportado para o tipo {}", op, ty))),
+        }
+        Ok(())
+    }
+
+    // compara a0/fa0 (esquerdo) com t1/ft1 (direito); resultado 0/1 em a0
+    fn compare(&mut self, op: &BinaryOperator, ty: &Type) {
+        if *ty == Type::Decimal {
+            let code = match op {
+                BinaryOperator::Equal => "feq.d a0, fa0, ft1",
+                BinaryOperator::NotEqual => "feq.d a0, fa0, ft1\n    xori a0, a0, 1",
+                BinaryOperator::Less => "flt.d a0, fa0, ft1",
+                BinaryOperator::LessEqual => "fle.d a0, fa0, ft1",
+                BinaryOperator::Greater => "flt.d a0, ft1, fa0",
+                _ => "fle.d a0, ft1, fa0",
+            };
+            self.emit(code);
+            return;
+        }
+
+        if *ty == Type::Texto {
+            self.use_runtime(TEXT_COMPARE_FUNCTION);
+            self.emit("mv a1, t1");
+            self.emit_call("rt.texto_comparar");
+            self.emit("li t1, 0");
+        }
+
+        let code = match op {
+            BinaryOperator::Equal => "sub a0, a0, t1\n    seqz a0, a0",
+            BinaryOperator::NotEqual => "sub a0, a0, t1\n    snez a0, a0",
+            BinaryOperator::Less => "slt a0, a0, t1",
+            BinaryOperator::LessEqual => "slt a0, t1, a0\n    xori a0, a0, 1",
+            BinaryOperator::Greater => "slt a0, t1, a0",
+            _ => "slt a0, a0, t1\n    xori a0, a0, 1",
+        };
+        self.emit(code);
+    }
+
+    fn generate_call(&mut self, call: &CallExpr) -> Result<Option<Type>, BackendError> {
+        if let Some((builtin, overload)) = self.env.builtin(call)? {
+            self.generate_builtin(builtin, overload, &call.arguments)?;
+            return Ok(Some(overload.return_type.clone()));
+        }
+
+        let function = self.env
+            .function(&call.function)
+            .ok_or_else(|| BackendError::new(format!("Função '{}' não declarada", call.function)))?;
+        let return_type = function.return_type.clone();
+        let types: Vec<Type> = function.parameters.iter().map(|p| p.param_type.clone()).collect();
+
+        if types.len() != call.arguments.len() {
+            return Err(BackendError::new(format!(
+                "Função '{}' espera {} argumentos, mas {} foram fornecidos",
+                call.function, types.len(), call.arguments.len()
+            )));
+        }
+
+        self.call_with_arguments(&format!("fn.{}", ascii_identifier(&call.function)), &call.arguments, &types)?;
+        Ok(return_type)
+    }
+
+    fn call_with_arguments(&mut self, symbol: &str, arguments: &[Expr], types: &[Type]) -> Result<(), BackendError> {
+        for (arg, ty) in arguments.iter().zip(types.iter()) {
+            self.generate_coerced(arg, ty)?;
+            self.push(ty);
+        }
+
+        let classes = classify_arguments(types);
+        let stack_args = classes.iter().filter(|c| matches!(c, ArgClass::Stack(_))).count() as i64;
+        let pushed = 8 * types.len() as i64;
+        let padding = if (self.stack_depth + 8 * stack_args) % 16 == 0 { 0 } else { 8 };
+        let reserved = 8 * stack_args + padding;
+
+        if reserved > 0 {
+            self.lines.push(adjust_sp(-reserved).trim_end().to_string());
+        }
+
+        // o argumento i foi empilhado em (pushed - 8 * (i + 1)) acima da área reservada
+        for (i, class) in classes.iter().enumerate() {
+            let source = reserved + pushed - 8 * (i as i64 + 1);
+            let operand = self.stack_operand(source);
+            match class {
+                ArgClass::Int(r) => self.emit(&format!("ld a{}, {}", r, operand)),
+                ArgClass::Float(r) => self.emit(&format!("fld fa{}, {}", r, operand)),
+                ArgClass::Stack(s) => {
+                    self.emit(&format!("ld t1, {}", operand));
+                    let target = self.stack_operand(8 * *s as i64);
+                    self.emit(&format!("sd t1, {}", target));
+                }
+            }
+        }
+
+        self.emit(&format!("call {}", symbol));
+        if reserved + pushed > 0 {
+            self.lines.push(adjust_sp(reserved + pushed).trim_end().to_string());
+        }
+        self.stack_depth -= pushed;
+        Ok(())
+    }
+
+    fn generate_builtin(&mut self, builtin: Builtin, overload: &Overload, arguments: &[Expr]) -> Result<(), BackendError> {
+        if matches!(builtin, Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico) {
+            let from = &overload.parameters[0];
+            self.generate_coerced(&arguments[0], from)?;
+            self.convert_explicitly(from, &overload.return_type);
+            return Ok(());
+        }
+
+        let text_routine = match builtin {
+            Builtin::Tamanho => Some(("rt.tamanho", TEXT_LENGTH_FUNCTION)),
+            Builtin::Maiusculo => Some(("rt.maiusculo", CASE_FUNCTION)),
+            Builtin::Minusculo => Some(("rt.minusculo", CASE_FUNCTION)),
+            Builtin::Subtexto => Some(("rt.subtexto", SUBSTRING_FUNCTION)),
+            Builtin::Contem => Some(("rt.contem", CONTAINS_FUNCTION)),
+            Builtin::Substituir => Some(("rt.substituir", REPLACE_FUNCTION)),
+            _ => None,
+        };
+        if let Some((symbol, routine)) = text_routine {
+            if matches!(builtin, Builtin::Contem | Builtin::Substituir) {
+                self.use_runtime(SEARCH_FUNCTION);
+            }
+            self.use_runtime(routine);
+            return self.call_with_arguments(symbol, arguments, overload.parameters);
+        }
+
+        let ty = &overload.parameters[0];
+        self.generate_coerced(&arguments[0], ty)?;
+
+        match (builtin, ty) {
+            (Builtin::Raiz, _) => self.emit("fsqrt.d fa0, fa0"),
+            (Builtin::Potencia, _) => {
+                self.push(ty);
+                self.generate_coerced(&arguments[1], &overload.parameters[1])?;
+                self.pop_left_operand(ty);
+                if *ty == Type::Inteiro {
+                    self.emit("mv a1, t1");
+                    self.use_runtime(INT_POWER_FUNCTION);
+                    self.emit_call("rt.potencia_inteira");
+                } else {
+                    self.emit("fmv.d fa1, ft1");
+                    self.use_runtime(POWER_FUNCTION);
+                    self.emit_call("rt.potencia");
+                }
+            }
+            (Builtin::Abs, Type::Inteiro) => {
+                self.emit("srai t1, a0, 63");
+                self.emit("xor a0, a0, t1");
+                self.emit("sub a0, a0, t1");
+            }
+            (Builtin::Abs, _) => self.emit("fabs.d fa0, fa0"),
+            (Builtin::Arredondar | Builtin::Piso | Builtin::Teto, Type::Inteiro) => {}
+            (Builtin::Arredondar, _) => self.round_decimal("rmm"),
+            (Builtin::Piso, _) => self.round_decimal("rdn"),
+            (Builtin::Teto, _) => self.round_decimal("rup"),
+            _ => unreachable!("funções de texto são rotinas do runtime"),
+        }
+        Ok(())
+    }
+
+    // arredonda fa0 passando por um inteiro; a partir de 2^52 todo decimal
+    // já é inteiro (e nan/inf ficam como estão). O sinal de x é mantido
+    // para que teto(-0.5) dê -0.0, como na libm
+    fn round_decimal(&mut self, mode: &str) {
+        let done = self.new_label();
+        self.emit("fabs.d ft0, fa0");
+        self.emit(&format!("li t1, 0x{:x}", 2f64.powi(52).to_bits()));
+        self.emit("fmv.d.x ft1, t1");
+        self.emit("flt.d t1, ft0, ft1");
+        self.emit(&format!("beqz t1, {}", done));
+        self.emit(&format!("fcvt.l.d t1, fa0, {}", mode));
+        self.emit("fcvt.d.l ft0, t1");
+        self.emit("fsgnj.d fa0, ft0, fa0");
+        self.emit_label(&done);
+    }
+
+    // valor em a0/fa0, resultado no mesmo lugar
+    fn convert_explicitly(&mut self, from: &Type, to: &Type) {
+        let routine = match (from, to) {
+            (Type::Texto, Type::Inteiro) => ("rt.texto_para_inteiro", TEXT_TO_INT_FUNCTION),
+            (Type::Texto, Type::Decimal) => ("rt.texto_para_decimal", TEXT_TO_DECIMAL_FUNCTION),
+            (Type::Texto, Type::Logico) => {
+                self.use_runtime(TEXT_COMPARE_FUNCTION);
+                ("rt.texto_para_logico", TEXT_TO_LOGICAL_FUNCTION)
+            }
+            (Type::Inteiro, Type::Texto) => ("rt.inteiro_para_texto", INT_TO_TEXT_FUNCTION),
+            // fcvt satura sozinho, mas leva nan ao maior inteiro
+            (Type::Decimal, Type::Inteiro) => {
+                self.emit("fcvt.l.d a0, fa0, rtz");
+                self.emit("feq.d t1, fa0, fa0");
+                self.emit("neg t1, t1");
+                self.emit("and a0, a0, t1");
+                return;
+            }
+            (Type::Decimal, Type::Texto) => return self.syscall(SYSCALL_FORMAT_DOUBLE),
+            (Type::Logico, Type::Decimal) => return self.emit("fcvt.d.l fa0, a0"),
+            (Type::Logico, Type::Texto) => return self.select_logico_text(),
+            (Type::Inteiro, Type::Logico) => return self.emit("snez a0, a0"),
+            // lógicos já são 0 ou 1
+            _ => return self.convert(from, to),
+        };
+
+        let (symbol, code) = routine;
+        if *from == Type::Texto {
+            self.use_runtime(CONVERSION_ERROR_FUNCTION);
+        }
+        self.use_runtime(code);
+        self.emit_call(symbol);
+    }
+
+    // === AUXILIARES ===
+
+    fn use_runtime(&mut self, routine: &'static str) {
+        if !self.runtime.contains(&routine) {
+            self.runtime.push(routine);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<(Location, Type), BackendError> {
+        self.env
+            .lookup(name)
+            .ok_or_else(|| BackendError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    fn allocate_slot(&mut self) -> Location {
+        self.frame_size += 8;
+        Location::Frame(-SAVED_REGISTERS_SIZE - self.frame_size)
+    }
+
+    // operando de memória para a variável; usa t0 quando o deslocamento
+    // não cabe em 12 bits ou a variável é global
+    fn address(&mut self, location: &Location) -> String {
+        match location {
+            Location::Frame(offset) if fits_imm12(*offset) => format!("{}(s0)", offset),
+            Location::Frame(offset) => {
+                self.emit(&format!("li t0, {}", offset));
+                self.emit("add t0, t0, s0");
+                "0(t0)".to_string()
+            }
+            Location::Global(symbol) => {
+                self.emit(&format!("la t0, {}", symbol));
+                "0(t0)".to_string()
+            }
+        }
+    }
+
+    fn stack_operand(&mut self, offset: i64) -> String {
+        if fits_imm12(offset) {
+            format!("{}(sp)", offset)
+        } else {
+            self.emit(&format!("li t0, {}", offset));
+            self.emit("add t0, t0, sp");
+            "0(t0)".to_string()
+        }
+    }
+
+    fn load(&mut self, location: &Location, ty: &Type) {
+        let operand = self.address(location);
+        if *ty == Type::Decimal {
+            self.emit(&format!("fld fa0, {}", operand));
+        } else {
+            self.emit(&format!("ld a0, {}", operand));
+        }
+    }
+
+    fn store(&mut self, location: &Location, ty: &Type) {
+        let operand = self.address(location);
+        if *ty == Type::Decimal {
+            self.emit(&format!("fsd fa0, {}", operand));
+        } else {
+            self.emit(&format!("sd a0, {}", operand));
+        }
+    }
+
+    fn load_default(&mut self, ty: &Type) {
+        match ty {
+            Type::Decimal => self.emit("fmv.d.x fa0, zero"),
+            Type::Texto => {
+                let empty = self.intern_string("");
+                self.emit(&format!("la a0, {}", empty));
+            }
+            _ => self.emit("li a0, 0"),
+        }
+    }
+
+    fn convert(&mut self, from: &Type, to: &Type) {
+        if *from == Type::Inteiro && *to == Type::Decimal {
+            self.emit("fcvt.d.l fa0, a0");
+        }
+    }
+
+    fn push(&mut self, ty: &Type) {
+        self.emit("addi sp, sp, -8");
+        if *ty == Type::Decimal {
+            self.emit("fsd fa0, 0(sp)");
+        } else {
+            self.emit("sd a0, 0(sp)");
+        }
+        self.stack_depth += 8;
+    }
+
+    // move o operando direito para t1/ft1 e recupera o esquerdo da pilha
+    fn pop_left_operand(&mut self, ty: &Type) {
+        if *ty == Type::Decimal {
+            self.emit("fmv.d ft1, fa0");
+            self.emit("fld fa0, 0(sp)");
+        } else {
+            self.emit("mv t1, a0");
+            self.emit("ld a0, 0(sp)");
+        }
+        self.emit("addi sp, sp, 8");
+        self.stack_depth -= 8;
+    }
+
+    // troca o valor lógico em a0 pelo texto "verdadeiro" / "falso"
+    fn select_logico_text(&mut self) {
+        let verdadeiro = self.intern_string("verdadeiro");
+        let falso = self.intern_string("falso");
+        let done = self.new_label();
+        self.emit("mv t1, a0");
+        self.emit(&format!("la a0, {}", verdadeiro));
+        self.emit(&format!("bnez t1, {}", done));
+        self.emit(&format!("la a0, {}", falso));
+        self.emit_label(&done);
+    }
+
+    fn syscall(&mut self, number: u64) {
+        self.emit(&format!("li a7, {}", number));
+        self.emit("ecall");
+    }
+
+    // chamada com sp alinhado em 16 bytes, como exige a ABI
+    fn emit_call(&mut self, symbol: &str) {
+        if self.stack_depth % 16 == 0 {
+            self.emit(&format!("call {}", symbol));
+        } else {
+            self.emit("addi sp, sp, -8");
+            self.emit(&format!("call {}", symbol));
+            self.emit("addi sp, sp, 8");
+        }
+    }
+
+    fn intern_string(&mut self, value: &str) -> String {
+        if let Some(label) = self.strings.get(value) {
+            return label.clone();
+        }
+
+        let label = format!(".Lstr.{}", self.strings.len());
+        self.rodata.push(format!("{}:\n    .string \"{}\"", label, escape_gas_string(value)));
+        self.strings.insert(value.to_string(), label.clone());
+        label
+    }
+
+    fn new_label(&mut self) -> String {
+        self.label_counter += 1;
+        format!(".L{}", self.label_counter)
+    }
+
+    fn emit(&mut self, line: &str) {
+        self.lines.push(format!("    {}", line));
+    }
+
+    fn emit_label(&mut self, label: &str) {
+        self.lines.push(format!("{}:", label));
+    }
+}
+
+pub fn generate_riscv(program: &Program) -> Result<String, BackendError> {
+    RiscvGenerator::new(program).generate()
+}
+
+fn adjust_sp(amount: i64) -> String {
+    if fits_imm12(amount) {
+        format!("    addi sp, sp, {}\n", amount)
+    } else {
+        format!("    li t0, {}\n    add sp, sp, t0\n", amount)
+    }
+}
+
+// compara dois textos byte a byte (ordem UTF-8, como strcmp): -1, 0 ou 1
+const TEXT_COMPARE_FUNCTION: &str = "rt.texto_comparar:
+.Lrt.cmp.laco:
+    lbu t0, 0(a0)
+    lbu t1, 0(a1)
+    bne t0, t1, .Lrt.cmp.diferentes
+    beqz t0, .Lrt.cmp.iguais
+    addi a0, a0, 1
+    addi a1, a1, 1
+    j .Lrt.cmp.laco
+.Lrt.cmp.diferentes:
+    sltu a0, t0, t1
+    sltu t1, t1, t0
+    sub a0, t1, a0
+    ret
+.Lrt.cmp.iguais:
+    li a0, 0
+    ret
+";
+
+// a0 elevado a a1 por quadrados sucessivos; com expoente negativo só as
+// bases 1 e -1 não resultam em 0
+const INT_POWER_FUNCTION: &str = "rt.potencia_inteira:
+    mv t1, a0
+    li a0, 1
+    bgez a1, .Lrt.poti.laco
+    addi t2, t1, 1
+    li t3, 2
+    bgtu t2, t3, .Lrt.poti.zero
+    neg a1, a1
+.Lrt.poti.laco:
+    blez a1, .Lrt.poti.fim
+    andi t2, a1, 1
+    beqz t2, .Lrt.poti.par
+    mul a0, a0, t1
+.Lrt.poti.par:
+    mul t1, t1, t1
+    srli a1, a1, 1
+    j .Lrt.poti.laco
+.Lrt.poti.zero:
+    li a0, 0
+.Lrt.poti.fim:
+    ret
+";
+
+// fa0 elevado a fa1 (não há libm no simulador). Expoentes inteiros usam
+// quadrados sucessivos; os demais, exp(e * ln(b)) com redução de faixa:
+//   ln(b) = k ln 2 + ln(m), m em [sqrt(2)/2, sqrt(2)], pela série de atanh
+//   exp(y) = 2^n exp(r), |r| <= ln(2)/2, pela série de Taylor
+// ln 2 vai em duas partes (alta, exata em produtos com n, e baixa). Sem
+// precisão estendida em e * ln(b), o erro relativo cresce com |e ln(b)|
+// (cerca de |e ln(b)| * 2^-52, longe do pow da libm só nas últimas casas);
+// casos especiais seguem o pow do C.
+const POWER_FUNCTION: &str = "rt.potencia:
+    fmv.d.x ft0, zero
+    li t0, 0x3ff0000000000000
+    fmv.d.x ft2, t0
+    feq.d t1, fa1, ft0
+    bnez t1, .Lrt.pot.um
+    feq.d t1, fa0, ft2
+    bnez t1, .Lrt.pot.um
+    feq.d t1, fa0, fa0
+    beqz t1, .Lrt.pot.nan
+    feq.d t1, fa1, fa1
+    beqz t1, .Lrt.pot.nan
+    # expoente inteiro com |e| < 2^53
+    fabs.d ft3, fa1
+    li t0, 0x4340000000000000
+    fmv.d.x ft4, t0
+    flt.d t1, ft3, ft4
+    beqz t1, .Lrt.pot.geral
+    fcvt.l.d t2, fa1, rtz
+    fcvt.d.l ft4, t2
+    feq.d t1, ft4, fa1
+    beqz t1, .Lrt.pot.geral
+    li t3, 0
+    bgez t2, .Lrt.pot.positivo
+    neg t2, t2
+    li t3, 1
+.Lrt.pot.positivo:
+    fmv.d ft5, ft2
+    fmv.d ft6, fa0
+.Lrt.pot.quadrados:
+    andi t1, t2, 1
+    beqz t1, .Lrt.pot.par
+    fmul.d ft5, ft5, ft6
+.Lrt.pot.par:
+    fmul.d ft6, ft6, ft6
+    srli t2, t2, 1
+    bnez t2, .Lrt.pot.quadrados
+    beqz t3, .Lrt.pot.fim_quadrados
+    fdiv.d ft5, ft2, ft5
+.Lrt.pot.fim_quadrados:
+    fmv.d fa0, ft5
+    ret
+.Lrt.pot.geral:
+    # base negativa só tem potência real com expoente inteiro (os de
+    # módulo >= 2^53 são todos pares)
+    flt.d t1, fa0, ft0
+    beqz t1, .Lrt.pot.base_positiva
+    li t0, 0x4340000000000000
+    fmv.d.x ft4, t0
+    flt.d t1, ft3, ft4
+    bnez t1, .Lrt.pot.nan
+    fabs.d fa0, fa0
+.Lrt.pot.base_positiva:
+    feq.d t1, fa0, ft2
+    bnez t1, .Lrt.pot.um
+    # 0 e inf: o resultado depende só do sinal do expoente
+    flt.d t4, ft0, fa1
+    feq.d t1, fa0, ft0
+    bnez t1, .Lrt.pot.base_zero
+    li t0, 0x7ff0000000000000
+    fmv.d.x ft4, t0
+    feq.d t1, fa0, ft4
+    bnez t1, .Lrt.pot.base_infinita
+    # ln(b): separa expoente k e mantissa m (subnormais são normalizados antes)
+    fmv.x.d t0, fa0
+    srli t1, t0, 52
+    bnez t1, .Lrt.pot.normal
+    li t2, 0x4350000000000000
+    fmv.d.x ft4, t2
+    fmul.d fa0, fa0, ft4
+    fmv.x.d t0, fa0
+    srli t1, t0, 52
+    addi t1, t1, -54
+.Lrt.pot.normal:
+    addi t1, t1, -1023
+    li t2, 0x000fffffffffffff
+    and t0, t0, t2
+    li t2, 0x3ff0000000000000
+    or t0, t0, t2
+    fmv.d.x ft3, t0
+    li t2, 0x3ff6a09e667f3bcd
+    fmv.d.x ft4, t2
+    flt.d t2, ft4, ft3
+    beqz t2, .Lrt.pot.mantissa
+    li t2, 0x3fe0000000000000
+    fmv.d.x ft4, t2
+    fmul.d ft3, ft3, ft4
+    addi t1, t1, 1
+.Lrt.pot.mantissa:
+    # ln(m) = 2 (s + s^3/3 + s^5/5 + ...), s = (m - 1) / (m + 1)
+    fsub.d ft4, ft3, ft2
+    fadd.d ft5, ft3, ft2
+    fdiv.d ft4, ft4, ft5
+    fmul.d ft5, ft4, ft4
+    fmv.d ft6, ft4
+    fmv.d ft7, ft4
+    li t2, 3
+    li t3, 41
+.Lrt.pot.serie_ln:
+    fmul.d ft6, ft6, ft5
+    fcvt.d.l ft8, t2
+    fdiv.d ft8, ft6, ft8
+    fadd.d ft7, ft7, ft8
+    addi t2, t2, 2
+    blt t2, t3, .Lrt.pot.serie_ln
+    fadd.d ft7, ft7, ft7
+    # ln(b) = k ln2_alto + (k ln2_baixo + ln(m))
+    fcvt.d.l ft8, t1
+    li t2, 0x3dea39ef35793c76
+    fmv.d.x ft9, t2
+    fmul.d ft9, ft8, ft9
+    fadd.d ft7, ft7, ft9
+    li t2, 0x3fe62e42fee00000
+    fmv.d.x ft9, t2
+    fmul.d ft9, ft8, ft9
+    fadd.d ft7, ft7, ft9
+    # y = e ln(b); fora de [-745.13, 709.78] o resultado é 0 ou inf
+    fmul.d fa0, fa1, ft7
+    li t2, 0x40862e42fefa39ef
+    fmv.d.x ft3, t2
+    flt.d t1, ft3, fa0
+    bnez t1, .Lrt.pot.infinito
+    li t2, 0xc0874910d52d3051
+    fmv.d.x ft3, t2
+    flt.d t1, fa0, ft3
+    bnez t1, .Lrt.pot.zero
+    # n = round(y / ln 2), r = y - n ln 2
+    li t2, 0x3ff71547652b82fe
+    fmv.d.x ft3, t2
+    fmul.d ft3, fa0, ft3
+    fcvt.l.d t1, ft3, rne
+    fcvt.d.l ft3, t1
+    li t2, 0x3fe62e42fee00000
+    fmv.d.x ft4, t2
+    fmul.d ft4, ft3, ft4
+    fsub.d fa0, fa0, ft4
+    li t2, 0x3dea39ef35793c76
+    fmv.d.x ft4, t2
+    fmul.d ft4, ft3, ft4
+    fsub.d fa0, fa0, ft4
+    # exp(r) = 1 + r (1 + r/2 (1 + r/3 (...)))
+    li t2, 20
+    fmv.d ft5, ft2
+.Lrt.pot.serie_exp:
+    fcvt.d.l ft6, t2
+    fdiv.d ft6, fa0, ft6
+    fmul.d ft5, ft5, ft6
+    fadd.d ft5, ft5, ft2
+    addi t2, t2, -1
+    bnez t2, .Lrt.pot.serie_exp
+    # 2^n em duas metades, pois n pode sair da faixa dos expoentes normais
+    srai t2, t1, 1
+    sub t1, t1, t2
+    addi t2, t2, 1023
+    slli t2, t2, 52
+    fmv.d.x ft6, t2
+    fmul.d ft5, ft5, ft6
+    addi t1, t1, 1023
+    slli t1, t1, 52
+    fmv.d.x ft6, t1
+    fmul.d fa0, ft5, ft6
+    ret
+.Lrt.pot.base_zero:
+    bnez t4, .Lrt.pot.zero
+    j .Lrt.pot.infinito
+.Lrt.pot.base_infinita:
+    bnez t4, .Lrt.pot.infinito
+.Lrt.pot.zero:
+    fmv.d.x fa0, zero
+    ret
+.Lrt.pot.infinito:
+    li t0, 0x7ff0000000000000
+    fmv.d.x fa0, t0
+    ret
+.Lrt.pot.um:
+    fmv.d fa0, ft2
+    ret
+.Lrt.pot.nan:
+    li t0, 0x7ff8000000000000
+    fmv.d.x fa0, t0
+    ret
+";
+
+// conta os bytes que não são de continuação do UTF-8 (10xxxxxx)
+const TEXT_LENGTH_FUNCTION: &str = "rt.tamanho:
+    li a1, 0
+    li t2, 0x80
+.Lrt.tamanho.laco:
+    lbu t1, 0(a0)
+    beqz t1, .Lrt.tamanho.fim
+    andi t1, t1, 0xc0
+    beq t1, t2, .Lrt.tamanho.proximo
+    addi a1, a1, 1
+.Lrt.tamanho.proximo:
+    addi a0, a0, 1
+    j .Lrt.tamanho.laco
+.Lrt.tamanho.fim:
+    mv a0, a1
+    ret
+";
+
+// Copia o texto trocando a caixa das letras: a1 é a primeira letra ASCII a
+// trocar e a2 o primeiro segundo byte das letras Latin-1 (c3 80-9e
+// maiúsculas, c3 a0-be minúsculas, sem × e ÷). Nos dois casos a troca é
+// inverter o bit 0x20, então o tamanho em bytes não muda.
+const CASE_FUNCTION: &str = "rt.maiusculo:
+    li a1, 0x61
+    li a2, 0xa0
+    j rt.trocar_caixa
+rt.minusculo:
+    li a1, 0x41
+    li a2, 0x80
+rt.trocar_caixa:
+    mv a3, a0
+    mv t1, a0
+.Lrt.caixa.comprimento:
+    lbu t2, 0(t1)
+    addi t1, t1, 1
+    bnez t2, .Lrt.caixa.comprimento
+    sub a0, t1, a3
+    li a7, 9
+    ecall
+    mv a5, a0
+    mv a4, a0
+    li t3, 0
+    li t4, 0xc3
+    li t5, 26
+    li t6, 0x1f
+.Lrt.caixa.laco:
+    lbu t2, 0(a3)
+    sub t1, t2, a1
+    bltu t1, t5, .Lrt.caixa.troca
+    bne t3, t4, .Lrt.caixa.copia
+    sub t1, t2, a2
+    bgeu t1, t6, .Lrt.caixa.copia
+    li t0, 0x17
+    beq t1, t0, .Lrt.caixa.copia
+.Lrt.caixa.troca:
+    xori t2, t2, 0x20
+.Lrt.caixa.copia:
+    sb t2, 0(a4)
+    mv t3, t2
+    addi a3, a3, 1
+    addi a4, a4, 1
+    bnez t2, .Lrt.caixa.laco
+    mv a0, a5
+    ret
+";
+
+// junta a0 e a1 num texto novo; só usa registradores temporários
+const CONCAT_FUNCTION: &str = "rt.concatenar:
+    mv a3, a0
+    mv a4, a1
+    mv t1, a0
+.Lrt.concatenar.primeiro:
+    lbu t2, 0(t1)
+    addi t1, t1, 1
+    bnez t2, .Lrt.concatenar.primeiro
+    sub a0, t1, a3
+    mv t1, a1
+.Lrt.concatenar.segundo:
+    lbu t2, 0(t1)
+    addi t1, t1, 1
+    bnez t2, .Lrt.concatenar.segundo
+    sub t1, t1, a4
+    add a0, a0, t1
+    li a7, 9
+    ecall
+    mv a5, a0
+.Lrt.concatenar.copia_primeiro:
+    lbu t2, 0(a3)
+    beqz t2, .Lrt.concatenar.copia_segundo
+    sb t2, 0(a0)
+    addi a3, a3, 1
+    addi a0, a0, 1
+    j .Lrt.concatenar.copia_primeiro
+.Lrt.concatenar.copia_segundo:
+    lbu t2, 0(a4)
+    sb t2, 0(a0)
+    addi a4, a4, 1
+    addi a0, a0, 1
+    bnez t2, .Lrt.concatenar.copia_segundo
+    mv a0, a5
+    ret
+";
+
+// subtexto(a0, a1 = início a partir de 1, a2 = quantidade): avança
+// caractere a caractere até o início e depois até o fim do trecho, sem
+// passar do terminador
+const SUBSTRING_FUNCTION: &str = "rt.subtexto:
+    bgtz a1, .Lrt.subtexto.inicio
+    li a1, 1
+.Lrt.subtexto.inicio:
+    addi a1, a1, -1
+    li t3, 0x80
+.Lrt.subtexto.pula:
+    blez a1, .Lrt.subtexto.trecho
+    lbu t1, 0(a0)
+    beqz t1, .Lrt.subtexto.trecho
+.Lrt.subtexto.pula_continuacao:
+    addi a0, a0, 1
+    lbu t1, 0(a0)
+    andi t1, t1, 0xc0
+    beq t1, t3, .Lrt.subtexto.pula_continuacao
+    addi a1, a1, -1
+    j .Lrt.subtexto.pula
+.Lrt.subtexto.trecho:
+    mv a3, a0
+.Lrt.subtexto.conta:
+    blez a2, .Lrt.subtexto.copia
+    lbu t1, 0(a0)
+    beqz t1, .Lrt.subtexto.copia
+.Lrt.subtexto.conta_continuacao:
+    addi a0, a0, 1
+    lbu t1, 0(a0)
+    andi t1, t1, 0xc0
+    beq t1, t3, .Lrt.subtexto.conta_continuacao
+    addi a2, a2, -1
+    j .Lrt.subtexto.conta
+.Lrt.subtexto.copia:
+    sub a4, a0, a3
+    addi a0, a4, 1
+    li a7, 9
+    ecall
+    mv a5, a0
+    add t2, a3, a4
+.Lrt.subtexto.byte:
+    beq a3, t2, .Lrt.subtexto.fim
+    lbu t1, 0(a3)
+    sb t1, 0(a0)
+    addi a3, a3, 1
+    addi a0, a0, 1
+    j .Lrt.subtexto.byte
+.Lrt.subtexto.fim:
+    sb zero, 0(a0)
+    mv a0, a5
+    ret
+";
+
+// primeira ocorrência de a1 em a0 (0 se não houver); só usa t1-t4
+const SEARCH_FUNCTION: &str = "rt.buscar:
+    mv t1, a0
+    mv t2, a1
+.Lrt.buscar.compara:
+    lbu t4, 0(t2)
+    beqz t4, .Lrt.buscar.fim
+    lbu t3, 0(t1)
+    bne t3, t4, .Lrt.buscar.proximo
+    addi t1, t1, 1
+    addi t2, t2, 1
+    j .Lrt.buscar.compara
+.Lrt.buscar.proximo:
+    lbu t3, 0(a0)
+    beqz t3, .Lrt.buscar.nada
+    addi a0, a0, 1
+    j rt.buscar
+.Lrt.buscar.nada:
+    li a0, 0
+.Lrt.buscar.fim:
+    ret
+";
+
+const CONTAINS_FUNCTION: &str = "rt.contem:
+    addi sp, sp, -16
+    sd ra, 8(sp)
+    call rt.buscar
+    snez a0, a0
+    ld ra, 8(sp)
+    addi sp, sp, 16
+    ret
+";
+
+// substituir(a0 = texto, a1 = de, a2 = por): uma passada calcula o tamanho
+// do resultado e outra o monta. Textos são imutáveis, então `de` vazio
+// devolve o próprio texto.
+//   s1 origem, s2 de, s3 por, s4/s5 tamanhos de `de`/`por`,
+//   s6 tamanho e depois o resultado, s7 posição da busca e depois o destino
+const REPLACE_FUNCTION: &str = "rt.substituir:
+    lbu t1, 0(a1)
+    bnez t1, .Lrt.substituir.inicio
+    ret
+.Lrt.substituir.inicio:
+    addi sp, sp, -64
+    sd ra, 56(sp)
+    sd s1, 48(sp)
+    sd s2, 40(sp)
+    sd s3, 32(sp)
+    sd s4, 24(sp)
+    sd s5, 16(sp)
+    sd s6, 8(sp)
+    sd s7, 0(sp)
+    mv s1, a0
+    mv s2, a1
+    mv s3, a2
+    mv a0, s2
+    call .Lrt.substituir.comprimento
+    mv s4, a0
+    mv a0, s3
+    call .Lrt.substituir.comprimento
+    mv s5, a0
+    mv a0, s1
+    call .Lrt.substituir.comprimento
+    mv s6, a0
+    mv s7, s1
+.Lrt.substituir.conta:
+    mv a0, s7
+    mv a1, s2
+    call rt.buscar
+    beqz a0, .Lrt.substituir.aloca
+    add s7, a0, s4
+    add s6, s6, s5
+    sub s6, s6, s4
+    j .Lrt.substituir.conta
+.Lrt.substituir.aloca:
+    addi a0, s6, 1
+    li a7, 9
+    ecall
+    mv s6, a0
+    mv s7, a0
+.Lrt.substituir.copia:
+    mv a0, s1
+    mv a1, s2
+    call rt.buscar
+    beqz a0, .Lrt.substituir.resto
+.Lrt.substituir.antes:
+    beq s1, a0, .Lrt.substituir.por
+    lbu t1, 0(s1)
+    sb t1, 0(s7)
+    addi s1, s1, 1
+    addi s7, s7, 1
+    j .Lrt.substituir.antes
+.Lrt.substituir.por:
+    add s1, s1, s4
+    mv t2, s3
+.Lrt.substituir.por_byte:
+    lbu t1, 0(t2)
+    beqz t1, .Lrt.substituir.copia
+    sb t1, 0(s7)
+    addi t2, t2, 1
+    addi s7, s7, 1
+    j .Lrt.substituir.por_byte
+.Lrt.substituir.resto:
+    lbu t1, 0(s1)
+    sb t1, 0(s7)
+    addi s1, s1, 1
+    addi s7, s7, 1
+    bnez t1, .Lrt.substituir.resto
+    mv a0, s6
+    ld ra, 56(sp)
+    ld s1, 48(sp)
+    ld s2, 40(sp)
+    ld s3, 32(sp)
+    ld s4, 24(sp)
+    ld s5, 16(sp)
+    ld s6, 8(sp)
+    ld s7, 0(sp)
+    addi sp, sp, 64
+    ret
+.Lrt.substituir.comprimento:
+    mv t1, a0
+.Lrt.substituir.comprimento_laco:
+    lbu t2, 0(t1)
+    beqz t2, .Lrt.substituir.comprimento_fim
+    addi t1, t1, 1
+    j .Lrt.substituir.comprimento_laco
+.Lrt.substituir.comprimento_fim:
+    sub a0, t1, a0
+    ret
+";
+
+// sinal opcional e dígitos; o valor é acumulado em negativo (a faixa vai
+// até -2^63) e comparado com -922337203685477580 (menor inteiro / 10) antes
+// de cada dígito
+const TEXT_TO_INT_FUNCTION: &str = "rt.texto_para_inteiro:
+    lbu t1, 0(a0)
+    li t3, 0
+    li t2, 0x2b
+    beq t1, t2, .Lrt.texto_inteiro.sinal
+    li t2, 0x2d
+    bne t1, t2, .Lrt.texto_inteiro.inicio
+    li t3, 1
+.Lrt.texto_inteiro.sinal:
+    addi a0, a0, 1
+.Lrt.texto_inteiro.inicio:
+    lbu t1, 0(a0)
+    beqz t1, .Lrt.texto_inteiro.invalido
+    li t4, 0
+    li t5, -922337203685477580
+.Lrt.texto_inteiro.laco:
+    lbu t1, 0(a0)
+    beqz t1, .Lrt.texto_inteiro.fim
+    addi t1, t1, -48
+    li t2, 9
+    bgtu t1, t2, .Lrt.texto_inteiro.invalido
+    blt t4, t5, .Lrt.texto_inteiro.invalido
+    bne t4, t5, .Lrt.texto_inteiro.cabe
+    li t2, 8
+    bgtu t1, t2, .Lrt.texto_inteiro.invalido
+.Lrt.texto_inteiro.cabe:
+    li t2, 10
+    mul t4, t4, t2
+    sub t4, t4, t1
+    addi a0, a0, 1
+    j .Lrt.texto_inteiro.laco
+.Lrt.texto_inteiro.fim:
+    mv a0, t4
+    bnez t3, .Lrt.texto_inteiro.retorno
+    neg a0, a0
+    bltz a0, .Lrt.texto_inteiro.invalido
+.Lrt.texto_inteiro.retorno:
+    ret
+.Lrt.texto_inteiro.invalido:
+    la a0, .Lrt.conversao.inteiro
+    j rt.conversao_invalida
+";
+
+// o simulador confere o formato e converte
+const TEXT_TO_DECIMAL_FUNCTION: &str = "rt.texto_para_decimal:
+    li a7, 100
+    ecall
+    beqz a0, .Lrt.texto_decimal.invalido
+    ret
+.Lrt.texto_decimal.invalido:
+    la a0, .Lrt.conversao.decimal
+    j rt.conversao_invalida
+";
+
+const TEXT_TO_LOGICAL_FUNCTION: &str = "rt.texto_para_logico:
+    addi sp, sp, -16
+    sd ra, 8(sp)
+    sd a0, 0(sp)
+    la a1, .Lrt.texto_logico.verdadeiro
+    call rt.texto_comparar
+    beqz a0, .Lrt.texto_logico.sim
+    ld a0, 0(sp)
+    la a1, .Lrt.texto_logico.falso
+    call rt.texto_comparar
+    bnez a0, .Lrt.texto_logico.invalido
+    ld ra, 8(sp)
+    addi sp, sp, 16
+    ret
+.Lrt.texto_logico.sim:
+    li a0, 1
+    ld ra, 8(sp)
+    addi sp, sp, 16
+    ret
+.Lrt.texto_logico.invalido:
+    la a0, .Lrt.conversao.logico
+    j rt.conversao_invalida
+    .section .rodata
+.Lrt.texto_logico.verdadeiro:
+    .string \"verdadeiro\"
+.Lrt.texto_logico.falso:
+    .string \"falso\"
+    .text
+";
+
+// até 20 caracteres, escritos do fim de um bloco de 24 bytes para o começo;
+// os dígitos saem de um valor negativo, para que -2^63 também funcione
+const INT_TO_TEXT_FUNCTION: &str = "rt.inteiro_para_texto:
+    mv t1, a0
+    li a0, 24
+    li a7, 9
+    ecall
+    addi t2, a0, 23
+    sb zero, 0(t2)
+    mv t3, t1
+    bltz t3, .Lrt.inteiro_texto.laco
+    neg t3, t3
+.Lrt.inteiro_texto.laco:
+    li t4, 10
+    rem t5, t3, t4
+    div t3, t3, t4
+    addi t2, t2, -1
+    li t4, 48
+    sub t5, t4, t5
+    sb t5, 0(t2)
+    bnez t3, .Lrt.inteiro_texto.laco
+    bgez t1, .Lrt.inteiro_texto.fim
+    addi t2, t2, -1
+    li t4, 0x2d
+    sb t4, 0(t2)
+.Lrt.inteiro_texto.fim:
+    mv a0, t2
+    ret
+";
+
+// escreve "Erro: conversão inválida para <a0>" e encerra com código 1
+const CONVERSION_ERROR_FUNCTION: &str = "rt.conversao_invalida:
+    mv t1, a0
+    la a0, .Lrt.conversao.mensagem
+    li a7, 4
+    ecall
+    mv a0, t1
+    ecall
+    li a0, 10
+    li a7, 11
+    ecall
+    li a0, 1
+    li a7, 93
+    ecall
+    .section .rodata
+.Lrt.conversao.mensagem:
+    .string \"Erro: convers\\303\\243o inv\\303\\241lida para \"
+.Lrt.conversao.inteiro:
+    .string \"inteiro\"
+.Lrt.conversao.decimal:
+    .string \"decimal\"
+.Lrt.conversao.logico:
+    .string \"logico\"
+    .text
+";
+
+const DIVISION_BY_ZERO_HANDLER: &str = "rt.divisao_por_zero:
+    la a0, {mensagem}
+    li a7, 4
+    ecall
+    li a0, 1
+    li a7, 93
+    ecall
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::semantic::SemanticAnalyzer;
+
+    fn run(source: &str, input: &str) -> String {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let asm = generate_riscv(&result.annotated_ast).unwrap();
+        let assembled = assemble(&asm).unwrap_or_else(|e| panic!("{}\n{}", e, asm));
+        let mut input = input.as_bytes();
+        let mut output = Vec::new();
+        Simulator::new(&assembled, &mut input, &mut output).run(10_000_000).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_recursion_loops_and_io() {
+        let output = run(
+            "funcao inteiro fat(inteiro n) { se n <= 1 entao { retorne 1; } fimse retorne n * fat(n - 1); }
+             funcao principal() {
+                inteiro i = 0;
+                para i de 1 ate 4 faca { escreva(i, fat(i), i / 2.0, i > 2 && i < 4); } fimpara
+                texto nome = \"\";
+                leia(nome);
+                escreva(nome == \"ação\", nome < \"b\", -7 / 2, -7 % 2);
+             }",
+            "ação\n",
+        );
+        assert_eq!(
+            output,
+            "1 1 0.500000 falso\n2 2 1.000000 falso\n3 6 1.500000 verdadeiro\n4 24 2.000000 falso\nverdadeiro verdadeiro -3 -1\n"
+        );
+    }
+
+    #[test]
+    fn test_arguments_on_stack() {
+        let output = run(
+            "funcao decimal soma(inteiro a, inteiro b, inteiro c, inteiro d, inteiro e, inteiro f,
+                                 inteiro g, inteiro h, inteiro i, decimal x, texto t) {
+                escreva(t, i);
+                retorne a + b + c + d + e + f + g + h + i + x;
+             }
+             funcao principal() { escreva(soma(1, 2, 3, 4, 5, 6, 7, 8, 9, 0.5, \"nove:\")); }",
+            "",
+        );
+        assert_eq!(output, "nove: 9\n45.500000\n");
+    }
+
+    #[test]
+    fn test_globals_without_initializer() {
+        let output = run("texto t;\ndecimal d;\nfuncao principal() { escreva(t + \"x\", tamanho(t), d); }", "");
+        assert_eq!(output, "x 0 0.000000\n");
+    }
+
+    #[test]
+    fn test_division_by_zero_stops_the_program() {
+        let output = run("funcao principal() { inteiro z = 0; escreva(1); escreva(1 / z); }", "");
+        assert_eq!(output, "1\nErro: divisão por zero\n");
+    }
+
+    #[test]
+    fn test_math_builtins() {
+        let output = run(
+            "funcao principal() {
+                escreva(raiz(2), potencia(2, 10), potencia(-2, -1), abs(-7), abs(-2.5));
+                escreva(arredondar(-2.5), piso(-2.5), teto(-0.5), potencia(2.0, -2), potencia(4.0, 0.5));
+            }",
+            "",
+        );
+        assert_eq!(output, "1.414214 1024 0 7 2.500000\n-3.000000 -3.000000 -0.000000 0.250000 2.000000\n");
+    }
+
+    #[test]
+    fn test_text_builtins() {
+        let output = run(
+            "funcao principal() {
+                texto t = \"Ação\";
+                escreva(tamanho(t), maiusculo(t), minusculo(t), subtexto(t, 2, 2), subtexto(t, 0, 9));
+                escreva(contem(t, \"çã\"), contem(t, \"ca\"), substituir(\"banana\", \"na\", \"_\"), substituir(t, \"\", \"x\"));
+            }",
+            "",
+        );
+        assert_eq!(output, "4 AÇÃO ação çã Ação\nverdadeiro falso ba__ Ação\n");
+    }
+
+    #[test]
+    fn test_conversions() {
+        let output = run(
+            "funcao decimal metade(decimal x) { retorne x / 2; }
+             funcao principal() {
+                decimal d = 3;
+                escreva(metade(d), d == 3, paraInteiro(-2.9), paraInteiro(0.0 / 0.0), paraInteiro(1000000000000000000000.0));
+                texto t;
+                leia(t);
+                escreva(paraInteiro(t) + 1, paraDecimal(\"-1e3\"), paraTexto(-9223372036854775807 - 1), paraTexto(2.5));
+                escreva(paraLogico(\"falso\"), paraLogico(7), paraTexto(verdadeiro), paraInteiro(\"1.5\"));
+            }",
+            "-9223372036854775808",
+        );
+        assert_eq!(
+            output,
+            "1.500000 verdadeiro -2 0 9223372036854775807\n\
+             -9223372036854775807 -1000.000000 -9223372036854775808 2.500000\n\
+             falso verdadeiro verdadeiro Erro: conversão inválida para inteiro\n"
+        );
+    }
+
+    #[test]
+    fn test_errors_carry_the_statement_line() {
+        // sem a análise semântica, a variável desconhecida só aparece aqui
+        let program = Parser::parse_from_source(
+            "inteiro g = h;
+             funcao principal() {
+                 inteiro a = 1;
+                 se a > 0 entao {
+                     escreva(b);
+                 } fimse
+             }",
+        )
+        .unwrap();
+        let error = generate_riscv(&program).unwrap_err();
+        assert_eq!((error.line, error.to_string()), (5, "ERRO NA GERAÇÃO DE CÓDIGO: Linha 5 - Variável 'b' não declarada".to_string()));
+
+        let program = Parser::parse_from_source("funcao principal() { }\ninteiro g = h;").unwrap();
+        assert_eq!(generate_riscv(&program).unwrap_err().line, 2);
+    }
+}
@@ -0,0 +1,29 @@
+pub mod codegen;
+pub mod assembler;
+pub mod simulator;
+
+pub use codegen::{generate_riscv, RiscvGenerator};
+pub use assembler::{assemble, AssembledProgram, AssemblerError};
+pub use simulator::{Simulator, SimulatorError};
+
+// Chamadas de sistema (ecall) entendidas pelo simulador. O número do serviço
+// vai em a7, como no simulador RARS usado na disciplina de arquitetura.
+pub const SYSCALL_PRINT_INT: u64 = 1; // a0: inteiro
+pub const SYSCALL_PRINT_DOUBLE: u64 = 3; // fa0: decimal (6 casas)
+pub const SYSCALL_PRINT_STRING: u64 = 4; // a0: endereço de texto terminado em zero
+pub const SYSCALL_READ_INT: u64 = 5; // -> a0
+pub const SYSCALL_READ_DOUBLE: u64 = 7; // -> fa0
+pub const SYSCALL_READ_STRING: u64 = 8; // a0: buffer, a1: capacidade (lê uma palavra)
+pub const SYSCALL_SBRK: u64 = 9; // a0: bytes -> a0: endereço
+pub const SYSCALL_PRINT_CHAR: u64 = 11; // a0: caractere
+pub const SYSCALL_EXIT: u64 = 93; // a0: código de saída
+// Serviços próprios do simulador, fora da numeração do RARS
+pub const SYSCALL_PARSE_DOUBLE: u64 = 100; // a0: texto -> fa0, a0: 1 se válido
+pub const SYSCALL_FORMAT_DOUBLE: u64 = 101; // fa0 -> a0: texto novo no heap (6 casas)
+
+// Mapa de memória do simulador
+pub const TEXT_BASE: u64 = 0x1_0000;
+pub const MEMORY_SIZE: u64 = 16 * 1024 * 1024;
+pub const STACK_SIZE: u64 = 1024 * 1024;
+// `ra` começa com este endereço: retornar de `_start`/`main` encerra o programa
+pub const EXIT_ADDRESS: u64 = 0;
@@ -0,0 +1,539 @@
+use std::io::{BufRead, Write};
+use crate::backend::format_decimal;
+use crate::backend::riscv::*;
+use crate::semantic::builtins::parse_decimal;
+
+// Simulador RV64IM + D (subconjunto usado pelo gerador de código).
+// Decodifica as palavras de 32 bits da imagem montada e executa uma
+// instrução por vez; E/S acontece pelas chamadas de sistema em `ecall`.
+
+#[derive(Debug, Clone)]
+pub struct SimulatorError {
+    pub message: String,
+    pub pc: u64,
+}
+
+impl std::fmt::Display for SimulatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ERRO NA SIMULAÇÃO (pc = 0x{:x}): {}", self.pc, self.message)
+    }
+}
+
+impl std::error::Error for SimulatorError {}
+
+pub struct Simulator<'io> {
+    regs: [u64; 32],
+    fregs: [u64; 32],
+    pc: u64,
+    memory: Vec<u8>,
+    text_end: u64,
+    heap_end: u64,
+    input: &'io mut dyn BufRead,
+    output: &'io mut dyn Write,
+    pending_input: Vec<String>,
+    pub steps: u64,
+}
+
+impl<'io> Simulator<'io> {
+    pub fn new(program: &AssembledProgram, input: &'io mut dyn BufRead, output: &'io mut dyn Write) -> Self {
+        let mut memory = vec![0; MEMORY_SIZE as usize];
+        let start = TEXT_BASE as usize;
+        memory[start..start + program.image.len()].copy_from_slice(&program.image);
+
+        let mut regs = [0; 32];
+        regs[1] = EXIT_ADDRESS;
+        regs[2] = MEMORY_SIZE;
+
+        Self {
+            regs,
+            fregs: [0; 32],
+            pc: program.entry,
+            memory,
+            text_end: TEXT_BASE + program.text_size,
+            heap_end: program.end().div_ceil(16) * 16,
+            input,
+            output,
+            pending_input: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    // executa até o programa terminar; devolve o código de saída
+    pub fn run(&mut self, max_steps: u64) -> Result<i64, SimulatorError> {
+        loop {
+            if self.pc == EXIT_ADDRESS {
+                self.flush()?;
+                return Ok(self.regs[10] as i64);
+            }
+            if self.steps >= max_steps {
+                return Err(self.error(format!("Limite de {} instruções excedido", max_steps)));
+            }
+            self.steps += 1;
+            if let Some(code) = self.step()? {
+                self.flush()?;
+                return Ok(code);
+            }
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> SimulatorError {
+        SimulatorError { message: message.into(), pc: self.pc }
+    }
+
+    fn flush(&mut self) -> Result<(), SimulatorError> {
+        self.output.flush().map_err(|e| self.error(format!("Erro de escrita: {}", e)))
+    }
+
+    // === MEMÓRIA ===
+
+    fn check_access(&self, address: u64, size: u64, write: bool) -> Result<usize, SimulatorError> {
+        if address < TEXT_BASE || address.checked_add(size).is_none_or(|end| end > MEMORY_SIZE) {
+            return Err(self.error(format!("Acesso inválido à memória no endereço 0x{:x}", address)));
+        }
+        if write && address < self.text_end {
+            return Err(self.error(format!("Escrita na região de código (0x{:x})", address)));
+        }
+        Ok(address as usize)
+    }
+
+    fn load(&self, address: u64, size: u64) -> Result<u64, SimulatorError> {
+        let start = self.check_access(address, size, false)?;
+        let mut bytes = [0u8; 8];
+        bytes[..size as usize].copy_from_slice(&self.memory[start..start + size as usize]);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn store(&mut self, address: u64, size: u64, value: u64) -> Result<(), SimulatorError> {
+        let start = self.check_access(address, size, true)?;
+        self.memory[start..start + size as usize].copy_from_slice(&value.to_le_bytes()[..size as usize]);
+        Ok(())
+    }
+
+    fn read_c_string(&self, address: u64) -> Result<Vec<u8>, SimulatorError> {
+        let mut bytes = Vec::new();
+        let mut current = address;
+        loop {
+            let byte = self.load(current, 1)? as u8;
+            if byte == 0 {
+                return Ok(bytes);
+            }
+            bytes.push(byte);
+            current += 1;
+        }
+    }
+
+    // === EXECUÇÃO ===
+
+    fn set(&mut self, rd: usize, value: u64) {
+        if rd != 0 {
+            self.regs[rd] = value;
+        }
+    }
+
+    fn fset(&mut self, rd: usize, value: f64) {
+        self.fregs[rd] = value.to_bits();
+    }
+
+    fn f(&self, index: usize) -> f64 {
+        f64::from_bits(self.fregs[index])
+    }
+
+    // executa uma instrução; Some(código) quando o programa chama exit
+    fn step(&mut self) -> Result<Option<i64>, SimulatorError> {
+        if !self.pc.is_multiple_of(4) || self.pc < TEXT_BASE || self.pc >= self.text_end {
+            return Err(self.error("Execução fora da região de código"));
+        }
+        let instr = self.load(self.pc, 4)? as u32;
+
+        let opcode = instr & 0x7f;
+        let rd = ((instr >> 7) & 0x1f) as usize;
+        let funct3 = (instr >> 12) & 0x7;
+        let rs1 = ((instr >> 15) & 0x1f) as usize;
+        let rs2 = ((instr >> 20) & 0x1f) as usize;
+        let funct7 = instr >> 25;
+
+        let imm_i = (instr as i32 >> 20) as i64;
+        let imm_s = (((instr as i32) >> 25) << 5) as i64 | ((instr >> 7) & 0x1f) as i64;
+        let imm_b = ((((instr as i32) >> 31) as i64) << 12)
+            | (((instr >> 7) & 1) << 11) as i64
+            | (((instr >> 25) & 0x3f) << 5) as i64
+            | (((instr >> 8) & 0xf) << 1) as i64;
+        let imm_u = ((instr & 0xffff_f000) as i32) as i64;
+        let imm_j = ((((instr as i32) >> 31) as i64) << 20)
+            | (instr & 0x000f_f000) as i64
+            | (((instr >> 20) & 1) << 11) as i64
+            | (((instr >> 21) & 0x3ff) << 1) as i64;
+
+        let a = self.regs[rs1];
+        let b = self.regs[rs2];
+        let mut next_pc = self.pc.wrapping_add(4);
+        let illegal = || self.error(format!("Instrução inválida 0x{:08x}", instr));
+
+        match opcode {
+            0x37 => self.set(rd, imm_u as u64),
+            0x17 => self.set(rd, self.pc.wrapping_add(imm_u as u64)),
+            0x6f => {
+                self.set(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j as u64);
+            }
+            0x67 => {
+                let target = a.wrapping_add(imm_i as u64) & !1;
+                self.set(rd, next_pc);
+                next_pc = target;
+            }
+            0x63 => {
+                let taken = match funct3 {
+                    0 => a == b,
+                    1 => a != b,
+                    4 => (a as i64) < (b as i64),
+                    5 => (a as i64) >= (b as i64),
+                    6 => a < b,
+                    7 => a >= b,
+                    _ => return Err(illegal()),
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b as u64);
+                }
+            }
+            0x03 => {
+                let address = a.wrapping_add(imm_i as u64);
+                let value = match funct3 {
+                    0 => self.load(address, 1)? as i8 as i64 as u64,
+                    1 => self.load(address, 2)? as i16 as i64 as u64,
+                    2 => self.load(address, 4)? as i32 as i64 as u64,
+                    3 => self.load(address, 8)?,
+                    4 => self.load(address, 1)?,
+                    5 => self.load(address, 2)?,
+                    6 => self.load(address, 4)?,
+                    _ => return Err(illegal()),
+                };
+                self.set(rd, value);
+            }
+            0x23 => {
+                let address = a.wrapping_add(imm_s as u64);
+                if funct3 > 3 {
+                    return Err(illegal());
+                }
+                self.store(address, 1 << funct3, b)?;
+            }
+            0x13 => {
+                let shamt = (imm_i & 0x3f) as u32;
+                let value = match funct3 {
+                    0 => a.wrapping_add(imm_i as u64),
+                    1 => a << shamt,
+                    2 => ((a as i64) < imm_i) as u64,
+                    3 => (a < imm_i as u64) as u64,
+                    4 => a ^ imm_i as u64,
+                    5 if (instr >> 26) == 0 => a >> shamt,
+                    5 if (instr >> 26) == 0x10 => ((a as i64) >> shamt) as u64,
+                    6 => a | imm_i as u64,
+                    7 => a & imm_i as u64,
+                    _ => return Err(illegal()),
+                };
+                self.set(rd, value);
+            }
+            0x1b => {
+                let shamt = (imm_i & 0x1f) as u32;
+                let a32 = a as u32;
+                let value = match (funct3, funct7) {
+                    (0, _) => a32.wrapping_add(imm_i as u32),
+                    (1, 0) => a32 << shamt,
+                    (5, 0) => a32 >> shamt,
+                    (5, 0x20) => ((a32 as i32) >> shamt) as u32,
+                    _ => return Err(illegal()),
+                };
+                self.set(rd, value as i32 as i64 as u64);
+            }
+            0x33 => {
+                let value = match (funct7, funct3) {
+                    (0x00, 0) => a.wrapping_add(b),
+                    (0x20, 0) => a.wrapping_sub(b),
+                    (0x00, 1) => a << (b & 0x3f),
+                    (0x00, 2) => ((a as i64) < (b as i64)) as u64,
+                    (0x00, 3) => (a < b) as u64,
+                    (0x00, 4) => a ^ b,
+                    (0x00, 5) => a >> (b & 0x3f),
+                    (0x20, 5) => ((a as i64) >> (b & 0x3f)) as u64,
+                    (0x00, 6) => a | b,
+                    (0x00, 7) => a & b,
+                    (0x01, 0) => a.wrapping_mul(b),
+                    (0x01, 1) => ((a as i64 as i128 * b as i64 as i128) >> 64) as u64,
+                    (0x01, 2) => ((a as i64 as i128 * b as i128) >> 64) as u64,
+                    (0x01, 3) => ((a as u128 * b as u128) >> 64) as u64,
+                    (0x01, 4) => divide(a as i64, b as i64) as u64,
+                    (0x01, 5) => a.checked_div(b).unwrap_or(u64::MAX),
+                    (0x01, 6) => remainder(a as i64, b as i64) as u64,
+                    (0x01, 7) => if b == 0 { a } else { a % b },
+                    _ => return Err(illegal()),
+                };
+                self.set(rd, value);
+            }
+            0x3b => {
+                let (a32, b32) = (a as i32, b as i32);
+                let value = match (funct7, funct3) {
+                    (0x00, 0) => a32.wrapping_add(b32),
+                    (0x20, 0) => a32.wrapping_sub(b32),
+                    (0x00, 1) => ((a32 as u32) << (b & 0x1f)) as i32,
+                    (0x00, 5) => ((a32 as u32) >> (b & 0x1f)) as i32,
+                    (0x20, 5) => a32 >> (b & 0x1f),
+                    (0x01, 0) => a32.wrapping_mul(b32),
+                    (0x01, 4) => divide(a32 as i64, b32 as i64) as i32,
+                    (0x01, 5) => (a32 as u32).checked_div(b32 as u32).unwrap_or(u32::MAX) as i32,
+                    (0x01, 6) => remainder(a32 as i64, b32 as i64) as i32,
+                    (0x01, 7) => if b32 == 0 { a32 } else { ((a32 as u32) % (b32 as u32)) as i32 },
+                    _ => return Err(illegal()),
+                };
+                self.set(rd, value as i64 as u64);
+            }
+            0x07 if funct3 == 3 => self.fregs[rd] = self.load(a.wrapping_add(imm_i as u64), 8)?,
+            0x27 if funct3 == 3 => self.store(a.wrapping_add(imm_s as u64), 8, self.fregs[rs2])?,
+            0x53 => self.execute_fp(instr, rd, rs1, rs2, funct3, funct7)?,
+            0x73 if instr == 0x0000_0073 => {
+                if let Some(code) = self.syscall()? {
+                    return Ok(Some(code));
+                }
+            }
+            0x73 if instr == 0x0010_0073 => return Err(self.error("ebreak executado")),
+            _ => return Err(illegal()),
+        }
+
+        self.pc = next_pc;
+        Ok(None)
+    }
+
+    fn execute_fp(&mut self, instr: u32, rd: usize, rs1: usize, rs2: usize, funct3: u32, funct7: u32) -> Result<(), SimulatorError> {
+        let (x, y) = (self.f(rs1), self.f(rs2));
+        match funct7 {
+            0x01 => self.fset(rd, x + y),
+            0x05 => self.fset(rd, x - y),
+            0x09 => self.fset(rd, x * y),
+            0x0d => self.fset(rd, x / y),
+            0x2d => self.fset(rd, x.sqrt()),
+            0x11 => {
+                let sign_y = self.fregs[rs2] & (1 << 63);
+                let magnitude = self.fregs[rs1] & !(1 << 63);
+                self.fregs[rd] = match funct3 {
+                    0 => magnitude | sign_y,
+                    1 => magnitude | (sign_y ^ (1 << 63)),
+                    2 => self.fregs[rs1] ^ sign_y,
+                    _ => return Err(self.error(format!("Instrução inválida 0x{:08x}", instr))),
+                };
+            }
+            0x15 => {
+                let value = match funct3 {
+                    0 => x.min(y),
+                    _ => x.max(y),
+                };
+                self.fset(rd, value);
+            }
+            0x51 => {
+                let value = match funct3 {
+                    2 => x == y,
+                    1 => x < y,
+                    _ => x <= y,
+                };
+                self.set(rd, value as u64);
+            }
+            // fcvt.d.l / fcvt.d.w
+            0x69 => {
+                let value = if rs2 == 2 { self.regs[rs1] as i64 as f64 } else { self.regs[rs1] as i32 as f64 };
+                self.fset(rd, value);
+            }
+            // fcvt.l.d / fcvt.w.d: arredonda conforme o modo e satura
+            0x61 => {
+                let rounded = match funct3 {
+                    1 => x.trunc(),
+                    2 => x.floor(),
+                    3 => x.ceil(),
+                    4 => x.round(),
+                    _ => x.round_ties_even(),
+                };
+                let value = if rs2 == 2 {
+                    if x.is_nan() { i64::MAX } else { rounded as i64 }
+                } else if x.is_nan() {
+                    i32::MAX as i64
+                } else {
+                    rounded as i32 as i64
+                };
+                self.set(rd, value as u64);
+            }
+            0x71 => self.set(rd, self.fregs[rs1]),
+            0x79 => self.fregs[rd] = self.regs[rs1],
+            _ => return Err(self.error(format!("Instrução inválida 0x{:08x}", instr))),
+        }
+        Ok(())
+    }
+
+    // === CHAMADAS DE SISTEMA ===
+
+    fn syscall(&mut self) -> Result<Option<i64>, SimulatorError> {
+        let io_error = |e: std::io::Error| SimulatorError { message: format!("Erro de E/S: {}", e), pc: self.pc };
+        let a0 = self.regs[10];
+
+        match self.regs[17] {
+            SYSCALL_PRINT_INT => write!(self.output, "{}", a0 as i64).map_err(io_error)?,
+            SYSCALL_PRINT_DOUBLE => {
+                let text = format_decimal(self.f(10));
+                self.output.write_all(text.as_bytes()).map_err(io_error)?;
+            }
+            SYSCALL_PRINT_STRING => {
+                let bytes = self.read_c_string(a0)?;
+                self.output.write_all(&bytes).map_err(io_error)?;
+            }
+            SYSCALL_PRINT_CHAR => self.output.write_all(&[a0 as u8]).map_err(io_error)?,
+            SYSCALL_READ_INT => {
+                let word = self.next_word()?;
+                let value = word.parse::<i64>().map_err(|_| self.error(format!("Entrada inválida para inteiro: '{}'", word)))?;
+                self.set(10, value as u64);
+            }
+            SYSCALL_READ_DOUBLE => {
+                let word = self.next_word()?;
+                let value = word.parse::<f64>().map_err(|_| self.error(format!("Entrada inválida para decimal: '{}'", word)))?;
+                self.fset(10, value);
+            }
+            SYSCALL_READ_STRING => {
+                let capacity = self.regs[11] as usize;
+                let word = self.next_word()?;
+                let mut bytes = word.into_bytes();
+                // corta sem partir um caractere UTF-8 ao meio
+                let mut length = bytes.len().min(capacity.saturating_sub(1));
+                while length > 0 && length < bytes.len() && (bytes[length] & 0xc0) == 0x80 {
+                    length -= 1;
+                }
+                bytes.truncate(length);
+                bytes.push(0);
+                for (i, byte) in bytes.iter().enumerate() {
+                    self.store(a0 + i as u64, 1, *byte as u64)?;
+                }
+            }
+            SYSCALL_SBRK => {
+                let address = self.sbrk(a0)?;
+                self.set(10, address);
+            }
+            SYSCALL_EXIT => return Ok(Some(a0 as i64)),
+            // o mesmo formato aceito por paraDecimal nos outros backends
+            SYSCALL_PARSE_DOUBLE => {
+                let bytes = self.read_c_string(a0)?;
+                let value = std::str::from_utf8(&bytes).ok().and_then(parse_decimal);
+                self.fset(10, value.unwrap_or(0.0));
+                self.set(10, value.is_some() as u64);
+            }
+            SYSCALL_FORMAT_DOUBLE => {
+                let mut bytes = format_decimal(self.f(10)).into_bytes();
+                bytes.push(0);
+                let address = self.sbrk(bytes.len() as u64)?;
+                for (i, byte) in bytes.iter().enumerate() {
+                    self.store(address + i as u64, 1, *byte as u64)?;
+                }
+                self.set(10, address);
+            }
+            other => return Err(self.error(format!("Chamada de sistema desconhecida: {}", other))),
+        }
+        Ok(None)
+    }
+
+    fn sbrk(&mut self, bytes: u64) -> Result<u64, SimulatorError> {
+        let address = self.heap_end;
+        let new_end = (address + bytes).div_ceil(16) * 16;
+        if new_end > MEMORY_SIZE - STACK_SIZE {
+            return Err(self.error("Memória esgotada (sbrk)"));
+        }
+        self.heap_end = new_end;
+        Ok(address)
+    }
+
+    // entrada lida palavra a palavra, como o scanf do backend nativo;
+    // o fim da entrada é tratado como texto vazio
+    fn next_word(&mut self) -> Result<String, SimulatorError> {
+        while self.pending_input.is_empty() {
+            self.flush()?;
+            let mut line = String::new();
+            let read = self.input
+                .read_line(&mut line)
+                .map_err(|e| SimulatorError { message: format!("Erro de leitura: {}", e), pc: self.pc })?;
+            if read == 0 {
+                return Ok(String::new());
+            }
+            self.pending_input = line.split_whitespace().rev().map(str::to_string).collect();
+        }
+        Ok(self.pending_input.pop().unwrap_or_default())
+    }
+}
+
+// divisão com a semântica do RISC-V: sem exceções
+fn divide(a: i64, b: i64) -> i64 {
+    if b == 0 { -1 } else { a.wrapping_div(b) }
+}
+
+fn remainder(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { a.wrapping_rem(b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, input: &str) -> (i64, String) {
+        let program = assemble(source).unwrap();
+        let mut input = input.as_bytes();
+        let mut output = Vec::new();
+        let code = Simulator::new(&program, &mut input, &mut output).run(1_000_000).unwrap();
+        (code, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_li_materializes_any_constant() {
+        let values = [
+            0i64, 1, -1, 2047, -2048, 2048, 0x7fff_f800, 0x7fff_ffff, -0x8000_0000, 0x8000_0000,
+            0x1234_5678_9abc_def0, i64::MAX, i64::MIN, -0x1234_5678_9abc, 0xfff0_0000_0000,
+        ];
+        for value in values {
+            let (code, _) = run(&format!("_start:\n    li a0, {}\n    ret\n", value), "");
+            assert_eq!(code, value, "li {}", value);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_and_syscalls() {
+        let (code, output) = run(
+            "_start:
+                li a7, 5
+                ecall
+                li t0, 7
+                div t1, a0, t0
+                rem t2, a0, t0
+                div t3, a0, zero
+                mv a0, t1
+                li a7, 1
+                ecall
+                li a0, 32
+                li a7, 11
+                ecall
+                mv a0, t2
+                li a7, 1
+                ecall
+                fcvt.d.l fa0, t3
+                li t0, 2
+                fcvt.d.l ft0, t0
+                fdiv.d fa0, fa0, ft0
+                li a7, 3
+                ecall
+                li a0, 3
+                li a7, 93
+                ecall",
+            "-45\n",
+        );
+        assert_eq!(output, "-6 -3-0.500000");
+        assert_eq!(code, 3);
+    }
+
+    #[test]
+    fn test_memory_errors() {
+        let program = assemble("_start:\n    sd a0, 0(zero)\n").unwrap();
+        let mut input: &[u8] = b"";
+        let mut output = Vec::new();
+        let error = Simulator::new(&program, &mut input, &mut output).run(100).unwrap_err();
+        assert!(error.message.contains("Acesso inválido"));
+        assert_eq!(error.pc, TEXT_BASE);
+    }
+}
@@ -0,0 +1,1221 @@
+use std::collections::HashMap;
+use crate::parser::ast::*;
+use crate::ir::ENTRY_FUNCTION;
+use crate::backend::{ascii_identifier, entry_point, numeric_result, BackendError, ScopedEnv};
+use crate::semantic::builtins::{Builtin, Overload};
+
+// Gera um módulo WebAssembly em formato texto (WAT) a partir da AST anotada.
+//
+// Mapeamento de tipos:
+//   inteiro -> i64, decimal -> f64, logico -> i32 (0/1),
+//   texto   -> i32 (endereço na memória linear de [tamanho: i32][bytes UTF-8])
+//
+// Interface com o hospedeiro (módulo de importação "symplia"):
+//   escreva_inteiro(i64)  escreva_decimal(f64)  escreva_logico(i32)
+//   escreva_texto(i32)    escreva_nova_linha()
+//   leia_inteiro() -> i64  leia_decimal() -> f64  leia_logico() -> i32
+//   leia_texto() -> i32    (o hospedeiro usa `alocar` para guardar o texto)
+// Importadas só quando o programa precisa delas:
+//   potencia(f64, f64) -> f64      potencia com decimais
+//   texto_para_decimal(i32) -> f64 paraDecimal de um texto (já validado aqui)
+//   decimal_para_texto(f64) -> i32 paraTexto de um decimal, com 6 casas como
+//                                  escreva_decimal (o texto vem de `alocar`)
+//
+// Conversões inválidas (paraInteiro("abc"), paraLogico("sim")) terminam com
+// `unreachable`, como a divisão inteira por zero.
+//
+// Exportações: "memoria", "alocar(tamanho) -> endereço" e "inicio", que
+// executa os comandos globais e chama `principal`.
+
+const HOST_MODULE: &str = "symplia";
+const DATA_START: u32 = 16;
+
+#[derive(Clone)]
+enum Location {
+    Local(String),
+    Global(String),
+}
+
+pub struct WatGenerator<'a> {
+    program: &'a Program,
+    env: ScopedEnv<Location>,
+
+    // textos literais: conteúdo -> endereço na memória
+    strings: HashMap<String, u32>,
+    data: Vec<(u32, Vec<u8>)>,
+    data_end: u32,
+    // funções auxiliares do runtime, na ordem do primeiro uso
+    runtime: Vec<&'static str>,
+    // importações opcionais do hospedeiro: (nome, assinatura)
+    host_functions: Vec<(&'static str, &'static str)>,
+
+    // estado da função sendo gerada
+    locals: Vec<(String, Type)>,
+    local_counts: HashMap<String, usize>,
+    lines: Vec<String>,
+    indent: usize,
+    label_counter: usize,
+}
+
+impl<'a> WatGenerator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            env: ScopedEnv::new(program),
+            strings: HashMap::new(),
+            data: Vec::new(),
+            data_end: DATA_START,
+            runtime: Vec::new(),
+            host_functions: Vec::new(),
+            locals: Vec::new(),
+            local_counts: HashMap::new(),
+            lines: Vec::new(),
+            indent: 0,
+            label_counter: 0,
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String, BackendError> {
+        let mut globals = Vec::new();
+        for statement in &self.program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                let name = ascii_identifier(&decl.name);
+                globals.push(format!(
+                    "(global ${} (mut {}) {})",
+                    name, wat_type(&decl.var_type), default_const(&decl.var_type)
+                ));
+                self.env.declare(&decl.name, decl.var_type.clone(), Location::Global(name));
+            }
+        }
+
+        let mut functions = Vec::new();
+        for function in &self.program.functions {
+            functions.push(self.generate_function(function).map_err(|e| e.at_line(function.line))?);
+        }
+        functions.push(self.generate_entry()?);
+
+        let mut out = String::new();
+        out.push_str(";; Gerado pelo compilador Symplia\n");
+        out.push_str("(module\n");
+        for (name, params, result) in HOST_IMPORTS {
+            out.push_str(&format!(
+                "  (import \"{}\" \"{}\" (func $rt.{}{}{}))\n",
+                HOST_MODULE, name, name, params, result
+            ));
+        }
+        for (name, signature) in &self.host_functions {
+            out.push_str(&format!(
+                "  (import \"{}\" \"{}\" (func $rt.{}{}))\n",
+                HOST_MODULE, name, name, signature
+            ));
+        }
+        out.push('\n');
+        out.push_str("  (memory (export \"memoria\") 1)\n");
+        for (offset, bytes) in &self.data {
+            out.push_str(&format!("  (data (i32.const {}) \"{}\")\n", offset, escape_bytes(bytes)));
+        }
+        out.push_str(&format!("  (global $rt.heap (mut i32) (i32.const {}))\n", align4(self.data_end)));
+        for global in &globals {
+            out.push_str(&format!("  {}\n", global));
+        }
+        out.push('\n');
+        out.push_str(ALLOC_FUNCTION);
+        for function in &self.runtime {
+            out.push('\n');
+            out.push_str(function);
+        }
+        for function in functions {
+            out.push('\n');
+            out.push_str(&function);
+        }
+        out.push_str(")\n");
+
+        Ok(out)
+    }
+
+    // === FUNÇÕES ===
+
+    fn generate_function(&mut self, function: &FunctionDecl) -> Result<String, BackendError> {
+        self.begin_function();
+        self.env.enter_scope();
+
+        let mut signature = format!("  (func ${}", ascii_identifier(&function.name));
+        for param in &function.parameters {
+            let name = self.unique_local(&param.name);
+            signature.push_str(&format!(" (param ${} {})", name, wat_type(&param.param_type)));
+            self.env.declare(&param.name, param.param_type.clone(), Location::Local(name));
+        }
+        if let Some(return_type) = &function.return_type {
+            signature.push_str(&format!(" (result {})", wat_type(return_type)));
+        }
+
+        self.generate_block(&function.body, &function.return_type)?;
+
+        // a validação exige um valor no fim de funções com retorno
+        if let Some(return_type) = &function.return_type {
+            self.emit(default_const(return_type));
+        }
+
+        self.env.exit_scope();
+        Ok(self.finish_function(signature))
+    }
+
+    fn generate_entry(&mut self) -> Result<String, BackendError> {
+        self.begin_function();
+        self.env.enter_scope();
+
+        for statement in &self.program.statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    if let Some(initializer) = &decl.initializer {
+                        let value = self.generate_coerced(initializer, &decl.var_type).map_err(|e| e.at_line(decl.line))?;
+                        self.emit(&format!("(global.set ${} {})", ascii_identifier(&decl.name), value));
+                    }
+                }
+                other => self.generate_statement(other, &None).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+
+        if let Some(principal) = entry_point(self.program) {
+            let call = format!("(call ${})", ascii_identifier(&principal.name));
+            if principal.return_type.is_some() {
+                self.emit(&format!("(drop {})", call));
+            } else {
+                self.emit(&call);
+            }
+        }
+
+        self.env.exit_scope();
+        let signature = format!("  (func ${} (export \"inicio\")", ENTRY_FUNCTION);
+        Ok(self.finish_function(signature))
+    }
+
+    fn begin_function(&mut self) {
+        self.locals.clear();
+        self.local_counts.clear();
+        self.lines.clear();
+        self.indent = 2;
+        self.label_counter = 0;
+    }
+
+    fn finish_function(&mut self, signature: String) -> String {
+        let mut out = signature;
+        out.push('\n');
+        for (name, ty) in &self.locals {
+            out.push_str(&format!("    (local ${} {})\n", name, wat_type(ty)));
+        }
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        // fecha o "(func"
+        out.pop();
+        out.push_str(")\n");
+        out
+    }
+
+    // === STATEMENTS ===
+
+    fn generate_block(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+        for statement in &block.statements {
+            self.generate_statement(statement, return_type).map_err(|e| e.at_line(statement.line()))?;
+        }
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    fn generate_statement(&mut self, statement: &Statement, return_type: &Option<Type>) -> Result<(), BackendError> {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                let value = match &decl.initializer {
+                    Some(initializer) => self.generate_coerced(initializer, &decl.var_type)?,
+                    None => default_const(&decl.var_type).to_string(),
+                };
+                let name = self.declare_local(&decl.name, decl.var_type.clone());
+                self.emit(&format!("(local.set ${} {})", name, value));
+            }
+            Statement::ExprStmt(expr_stmt) => {
+                let returns_nothing = match &expr_stmt.expr {
+                    Expr::Call(call) => self.env.function(&call.function).is_some_and(|f| f.return_type.is_none()),
+                    _ => false,
+                };
+                if returns_nothing {
+                    let Expr::Call(call) = &expr_stmt.expr else { unreachable!() };
+                    let code = self.generate_call(call)?;
+                    self.emit(&code);
+                } else {
+                    let (code, _) = self.generate_expr(&expr_stmt.expr)?;
+                    self.emit(&format!("(drop {})", code));
+                }
+            }
+            Statement::IfStmt(if_stmt) => {
+                let (condition, _) = self.generate_expr(&if_stmt.condition)?;
+                self.emit(&format!("(if {}", condition));
+                self.indent += 1;
+                self.emit("(then");
+                self.indent += 1;
+                self.generate_block(&if_stmt.then_branch, return_type)?;
+                self.indent -= 1;
+                self.append(")");
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.emit("(else");
+                    self.indent += 1;
+                    self.generate_block(else_branch, return_type)?;
+                    self.indent -= 1;
+                    self.append(")");
+                }
+                self.indent -= 1;
+                self.append(")");
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let label = self.new_label();
+                let (condition, _) = self.generate_expr(&while_stmt.condition)?;
+                self.open_loop(label);
+                self.emit(&format!("(br_if $fim_{} (i32.eqz {}))", label, condition));
+                self.generate_block(&while_stmt.body, return_type)?;
+                self.close_loop(label);
+            }
+            Statement::ForStmt(for_stmt) => {
+                self.env.enter_scope();
+
+                let (location, var_type) = match self.env.lookup(&for_stmt.variable) {
+                    Some((location, ty)) if matches!(ty, Type::Inteiro | Type::Decimal) => (location, ty),
+                    _ => {
+                        let name = self.declare_local(&for_stmt.variable, Type::Inteiro);
+                        (Location::Local(name), Type::Inteiro)
+                    }
+                };
+
+                let start = self.generate_coerced(&for_stmt.start, &var_type)?;
+                self.emit(&set_location(&location, &start));
+
+                let label = self.new_label();
+                let current = get_location(&location);
+                let (end, end_type) = self.generate_expr(&for_stmt.end)?;
+                let compare_type = numeric_result(&var_type, &end_type);
+                let condition = format!(
+                    "({}.le{} {} {})",
+                    wat_type(&compare_type),
+                    if compare_type == Type::Inteiro { "_s" } else { "" },
+                    coerce(&current, &var_type, &compare_type),
+                    coerce(&end, &end_type, &compare_type),
+                );
+
+                self.open_loop(label);
+                self.emit(&format!("(br_if $fim_{} (i32.eqz {}))", label, condition));
+                self.generate_block(&for_stmt.body, return_type)?;
+                let one = if var_type == Type::Decimal { "(f64.const 1)" } else { "(i64.const 1)" };
+                let next = format!("({}.add {} {})", wat_type(&var_type), current, one);
+                self.emit(&set_location(&location, &next));
+                self.close_loop(label);
+
+                self.env.exit_scope();
+            }
+            Statement::ReturnStmt(return_stmt) => match (&return_stmt.value, return_type) {
+                (Some(value), Some(return_type)) => {
+                    let value = self.generate_coerced(value, return_type)?;
+                    self.emit(&format!("(return {})", value));
+                }
+                (None, None) => self.emit("(return)"),
+                _ => return Err(BackendError::new("Retorno incompatível com o tipo da função")),
+            },
+            Statement::WriteStmt(write_stmt) => {
+                for (i, arg) in write_stmt.arguments.iter().enumerate() {
+                    if i > 0 {
+                        let space = self.intern_string(" ");
+                        self.emit(&format!("(call $rt.escreva_texto (i32.const {}))", space));
+                    }
+                    let (code, ty) = self.generate_expr(arg)?;
+                    self.emit(&format!("(call $rt.escreva_{} {})", type_suffix(&ty), code));
+                }
+                self.emit("(call $rt.escreva_nova_linha)");
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(BackendError::new("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let (location, ty) = self.lookup(name)?;
+                let value = format!("(call $rt.leia_{})", type_suffix(&ty));
+                self.emit(&set_location(&location, &value));
+            }
+        }
+        Ok(())
+    }
+
+    fn open_loop(&mut self, label: usize) {
+        self.emit(&format!("(block $fim_{}", label));
+        self.indent += 1;
+        self.emit(&format!("(loop $laco_{}", label));
+        self.indent += 1;
+    }
+
+    fn close_loop(&mut self, label: usize) {
+        self.emit(&format!("(br $laco_{})", label));
+        self.indent -= 2;
+        self.append("))");
+    }
+
+    // === EXPRESSÕES ===
+
+    fn generate_coerced(&mut self, expr: &Expr, target: &Type) -> Result<String, BackendError> {
+        let (code, ty) = self.generate_expr(expr)?;
+        Ok(coerce(&code, &ty, target))
+    }
+
+    fn generate_expr(&mut self, expr: &Expr) -> Result<(String, Type), BackendError> {
+        let ty = self.env.expr_type(expr)?;
+
+        let code = match expr {
+            Expr::Literal(literal) => match literal {
+                Literal::Inteiro(n) => format!("(i64.const {})", n),
+                Literal::Decimal(n) => format!("(f64.const {})", wat_float(*n)),
+                Literal::Texto(s) => format!("(i32.const {})", self.intern_string(s)),
+                Literal::Logico(b) => format!("(i32.const {})", *b as i32),
+            },
+            Expr::Variable(name) => get_location(&self.lookup(name)?.0),
+            Expr::Call(call) => self.generate_call(call)?,
+            Expr::UnaryOp(op, operand) => {
+                let (operand, operand_type) = self.generate_expr(operand)?;
+                match (op, operand_type) {
+                    (UnaryOperator::Plus, _) => operand,
+                    (UnaryOperator::Negate, Type::Inteiro) => format!("(i64.sub (i64.const 0) {})", operand),
+                    (UnaryOperator::Negate, _) => format!("(f64.neg {})", operand),
+                    (UnaryOperator::Not, _) => format!("(i32.eqz {})", operand),
+                }
+            }
+            Expr::BinaryOp(op, left, right) => self.generate_binary(op, left, right)?,
+        };
+
+        Ok((code, ty))
+    }
+
+    fn generate_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr) -> Result<String, BackendError> {
+        let (left, left_type) = self.generate_expr(left)?;
+        let (right, right_type) = self.generate_expr(right)?;
+
+        if *op == BinaryOperator::Add && (left_type == Type::Texto || right_type == Type::Texto) {
+            let left = self.generate_conversion(&left_type, &Type::Texto, &left);
+            let right = self.generate_conversion(&right_type, &Type::Texto, &right);
+            self.use_runtime(COPY_FUNCTION);
+            self.use_runtime(CONCAT_FUNCTION);
+            return Ok(format!("(call $rt.concatenar {} {})", left, right));
+        }
+
+        if left_type == Type::Texto && right_type == Type::Texto {
+            self.use_runtime(TEXT_COMPARE_FUNCTION);
+            let compare = format!("(call $rt.texto_comparar {} {})", left, right);
+            let test = match op {
+                BinaryOperator::Equal => "eq",
+                BinaryOperator::NotEqual => "ne",
+                BinaryOperator::Less => "lt_s",
+                BinaryOperator::LessEqual => "le_s",
+                BinaryOperator::Greater => "gt_s",
+                BinaryOperator::GreaterEqual => "ge_s",
+                _ => return Err(BackendError::new(format!("Operador '{}' não suportado para texto", op))),
+            };
+            return Ok(format!("(i32.{} {} (i32.const 0))", test, compare));
+        }
+
+        if left_type == Type::Logico {
+            let instr = match op {
+                BinaryOperator::And => "and",
+                BinaryOperator::Or => "or",
+                BinaryOperator::Equal => "eq",
+                BinaryOperator::NotEqual => "ne",
+                _ => return Err(BackendError::new(format!("Operador '{}' não suportado para lógicos", op))),
+            };
+            return Ok(format!("(i32.{} {} {})", instr, left, right));
+        }
+
+        let ty = numeric_result(&left_type, &right_type);
+        let left = coerce(&left, &left_type, &ty);
+        let right = coerce(&right, &right_type, &ty);
+        let is_int = ty == Type::Inteiro;
+
+        let instr = match op {
+            BinaryOperator::Add => "add",
+            BinaryOperator::Subtract => "sub",
+            BinaryOperator::Multiply => "mul",
+            BinaryOperator::Divide if is_int => "div_s",
+            BinaryOperator::Divide => "div",
+            BinaryOperator::Modulo => "rem_s",
+            BinaryOperator::Equal => "eq",
+            BinaryOperator::NotEqual => "ne",
+            BinaryOperator::Less if is_int => "lt_s",
+            BinaryOperator::Less => "lt",
+            BinaryOperator::LessEqual if is_int => "le_s",
+            BinaryOperator::LessEqual => "le",
+            BinaryOperator::Greater if is_int => "gt_s",
+            BinaryOperator::Greater => "gt",
+            BinaryOperator::GreaterEqual if is_int => "ge_s",
+            BinaryOperator::GreaterEqual => "ge",
+            BinaryOperator::And | BinaryOperator::Or => {
+                return Err(BackendError::new(format!("Operador '{}' requer operandos lógicos", op)));
+            }
+        };
+
+        Ok(format!("({}.{} {} {})", wat_type(&ty), instr, left, right))
+    }
+
+    fn generate_call(&mut self, call: &CallExpr) -> Result<String, BackendError> {
+        if let Some((builtin, overload)) = self.env.builtin(call)? {
+            let mut arguments = Vec::new();
+            for (arg, param_type) in call.arguments.iter().zip(overload.parameters) {
+                arguments.push(self.generate_coerced(arg, param_type)?);
+            }
+            return Ok(self.generate_builtin(builtin, overload, &arguments));
+        }
+
+        let parameters: Vec<Type> = self.env
+            .function(&call.function)
+            .ok_or_else(|| BackendError::new(format!("Função '{}' não declarada", call.function)))?
+            .parameters
+            .iter()
+            .map(|p| p.param_type.clone())
+            .collect();
+
+        let mut code = format!("(call ${}", ascii_identifier(&call.function));
+        for (arg, param_type) in call.arguments.iter().zip(parameters.iter()) {
+            code.push(' ');
+            code.push_str(&self.generate_coerced(arg, param_type)?);
+        }
+        code.push(')');
+        Ok(code)
+    }
+
+    fn generate_builtin(&mut self, builtin: Builtin, overload: &Overload, arguments: &[String]) -> String {
+        let integer = overload.parameters[0] == Type::Inteiro;
+        let args = arguments.join(" ");
+        match builtin {
+            Builtin::Raiz => format!("(f64.sqrt {})", args),
+            Builtin::Potencia if integer => {
+                self.use_runtime(INT_POWER_FUNCTION);
+                format!("(call $rt.potencia_inteira {})", args)
+            }
+            Builtin::Potencia => {
+                self.use_host(HOST_POWER);
+                format!("(call $rt.potencia {})", args)
+            }
+            Builtin::Abs if integer => {
+                self.use_runtime(INT_ABS_FUNCTION);
+                format!("(call $rt.abs_inteiro {})", args)
+            }
+            Builtin::Abs => format!("(f64.abs {})", args),
+            Builtin::Arredondar | Builtin::Piso | Builtin::Teto if integer => args,
+            Builtin::Arredondar => {
+                self.use_runtime(ROUND_FUNCTION);
+                format!("(call $rt.arredondar {})", args)
+            }
+            Builtin::Piso => format!("(f64.floor {})", args),
+            Builtin::Teto => format!("(f64.ceil {})", args),
+            Builtin::Tamanho => {
+                self.use_runtime(TEXT_LENGTH_FUNCTION);
+                format!("(call $rt.tamanho {})", args)
+            }
+            // primeira letra ASCII e primeiro segundo byte UTF-8 (após c3) a trocar
+            Builtin::Maiusculo => {
+                self.use_runtime(CASE_FUNCTION);
+                format!("(call $rt.trocar_caixa {} (i32.const 0x61) (i32.const 0xa0))", args)
+            }
+            Builtin::Minusculo => {
+                self.use_runtime(CASE_FUNCTION);
+                format!("(call $rt.trocar_caixa {} (i32.const 0x41) (i32.const 0x80))", args)
+            }
+            Builtin::Subtexto => {
+                self.use_runtime(COPY_FUNCTION);
+                self.use_runtime(SUBSTRING_FUNCTION);
+                format!("(call $rt.subtexto {})", args)
+            }
+            Builtin::Contem => {
+                self.use_runtime(SEARCH_FUNCTION);
+                format!("(i32.ge_s (call $rt.buscar {} (i32.const 0)) (i32.const 0))", args)
+            }
+            Builtin::Substituir => {
+                self.use_runtime(COPY_FUNCTION);
+                self.use_runtime(SEARCH_FUNCTION);
+                self.use_runtime(REPLACE_FUNCTION);
+                format!("(call $rt.substituir {})", args)
+            }
+            Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico => {
+                self.generate_conversion(&overload.parameters[0], &overload.return_type, &args)
+            }
+        }
+    }
+
+    // inteiro -> decimal é a coerção de sempre, e a conversão para o
+    // próprio tipo não faz nada
+    fn generate_conversion(&mut self, from: &Type, to: &Type, value: &str) -> String {
+        match (from, to) {
+            (Type::Decimal, Type::Inteiro) => format!("(i64.trunc_sat_f64_s {})", value),
+            (Type::Texto, Type::Inteiro) => {
+                self.use_runtime(NUMBER_SCAN_FUNCTIONS);
+                self.use_runtime(TEXT_TO_INT_FUNCTION);
+                format!("(call $rt.texto_para_inteiro {})", value)
+            }
+            (Type::Logico, Type::Inteiro) => format!("(i64.extend_i32_u {})", value),
+            (Type::Texto, Type::Decimal) => {
+                self.use_runtime(NUMBER_SCAN_FUNCTIONS);
+                self.use_runtime(DECIMAL_CHECK_FUNCTION);
+                self.use_host(HOST_TEXT_TO_DECIMAL);
+                format!("(call $rt.texto_para_decimal (call $rt.validar_decimal {}))", value)
+            }
+            (Type::Logico, Type::Decimal) => format!("(f64.convert_i32_u {})", value),
+            (Type::Inteiro, Type::Texto) => {
+                self.use_runtime(INT_TO_TEXT_FUNCTION);
+                format!("(call $rt.inteiro_para_texto {})", value)
+            }
+            (Type::Decimal, Type::Texto) => {
+                self.use_host(HOST_DECIMAL_TO_TEXT);
+                format!("(call $rt.decimal_para_texto {})", value)
+            }
+            (Type::Logico, Type::Texto) => {
+                let verdadeiro = self.intern_string("verdadeiro");
+                let falso = self.intern_string("falso");
+                format!("(select (i32.const {}) (i32.const {}) {})", verdadeiro, falso, value)
+            }
+            (Type::Inteiro, Type::Logico) => format!("(i64.ne {} (i64.const 0))", value),
+            (Type::Texto, Type::Logico) => {
+                self.use_runtime(TEXT_COMPARE_FUNCTION);
+                self.use_runtime(TEXT_TO_LOGICAL_FUNCTION);
+                let verdadeiro = self.intern_string("verdadeiro");
+                let falso = self.intern_string("falso");
+                format!("(call $rt.texto_para_logico {} (i32.const {}) (i32.const {}))", value, verdadeiro, falso)
+            }
+            _ => coerce(value, from, to),
+        }
+    }
+
+    // === AUXILIARES ===
+
+    fn use_runtime(&mut self, function: &'static str) {
+        if !self.runtime.contains(&function) {
+            self.runtime.push(function);
+        }
+    }
+
+    fn use_host(&mut self, function: (&'static str, &'static str)) {
+        if !self.host_functions.contains(&function) {
+            self.host_functions.push(function);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<(Location, Type), BackendError> {
+        self.env
+            .lookup(name)
+            .ok_or_else(|| BackendError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    // locais do WebAssembly valem para a função inteira, então variáveis
+    // homônimas em escopos diferentes recebem sufixos (x, x.1);
+    // o ponto nunca aparece em identificadores de Symplia
+    fn unique_local(&mut self, name: &str) -> String {
+        let base = ascii_identifier(name);
+        let count = self.local_counts.entry(base.clone()).or_insert(0);
+        let unique = if *count == 0 { base } else { format!("{}.{}", base, count) };
+        *count += 1;
+        unique
+    }
+
+    fn declare_local(&mut self, name: &str, var_type: Type) -> String {
+        let unique = self.unique_local(name);
+        self.locals.push((unique.clone(), var_type.clone()));
+        self.env.declare(name, var_type, Location::Local(unique.clone()));
+        unique
+    }
+
+    fn intern_string(&mut self, value: &str) -> u32 {
+        if let Some(offset) = self.strings.get(value) {
+            return *offset;
+        }
+
+        let offset = align4(self.data_end);
+        let mut bytes = (value.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+
+        self.data_end = offset + bytes.len() as u32;
+        self.data.push((offset, bytes));
+        self.strings.insert(value.to_string(), offset);
+        offset
+    }
+
+    fn new_label(&mut self) -> usize {
+        self.label_counter += 1;
+        self.label_counter
+    }
+
+    fn emit(&mut self, line: &str) {
+        self.lines.push(format!("{}{}", "  ".repeat(self.indent), line));
+    }
+
+    // fecha parênteses na última linha emitida
+    fn append(&mut self, text: &str) {
+        if let Some(last) = self.lines.last_mut() {
+            last.push_str(text);
+        }
+    }
+}
+
+pub fn generate_wat(program: &Program) -> Result<String, BackendError> {
+    WatGenerator::new(program).generate()
+}
+
+const HOST_IMPORTS: [(&str, &str, &str); 9] = [
+    ("escreva_inteiro", " (param i64)", ""),
+    ("escreva_decimal", " (param f64)", ""),
+    ("escreva_logico", " (param i32)", ""),
+    ("escreva_texto", " (param i32)", ""),
+    ("escreva_nova_linha", "", ""),
+    ("leia_inteiro", "", " (result i64)"),
+    ("leia_decimal", "", " (result f64)"),
+    ("leia_logico", "", " (result i32)"),
+    ("leia_texto", "", " (result i32)"),
+];
+
+const HOST_POWER: (&str, &str) = ("potencia", " (param f64 f64) (result f64)");
+const HOST_TEXT_TO_DECIMAL: (&str, &str) = ("texto_para_decimal", " (param i32) (result f64)");
+const HOST_DECIMAL_TO_TEXT: (&str, &str) = ("decimal_para_texto", " (param f64) (result i32)");
+
+// alocador simples (bump) usado pelo hospedeiro para devolver textos lidos
+const ALLOC_FUNCTION: &str = r#"  (func $rt.alocar (export "alocar") (param $tamanho i32) (result i32)
+    (local $endereco i32)
+    (local.set $endereco (global.get $rt.heap))
+    (global.set $rt.heap
+      (i32.and (i32.add (i32.add (local.get $endereco) (local.get $tamanho)) (i32.const 3)) (i32.const -4)))
+    (if (i32.gt_u (global.get $rt.heap) (i32.mul (memory.size) (i32.const 65536)))
+      (then
+        (drop (memory.grow
+          (i32.add (i32.div_u (i32.sub (global.get $rt.heap) (i32.mul (memory.size) (i32.const 65536))) (i32.const 65536))
+                   (i32.const 1))))))
+    (local.get $endereco))
+"#;
+
+// compara dois textos byte a byte (ordem UTF-8): -1, 0 ou 1
+const TEXT_COMPARE_FUNCTION: &str = r#"  (func $rt.texto_comparar (param $a i32) (param $b i32) (result i32)
+    (local $tam_a i32) (local $tam_b i32) (local $i i32) (local $ca i32) (local $cb i32)
+    (local.set $tam_a (i32.load (local.get $a)))
+    (local.set $tam_b (i32.load (local.get $b)))
+    (block $fim
+      (loop $laco
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $tam_a)))
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $tam_b)))
+        (local.set $ca (i32.load8_u offset=4 (i32.add (local.get $a) (local.get $i))))
+        (local.set $cb (i32.load8_u offset=4 (i32.add (local.get $b) (local.get $i))))
+        (if (i32.ne (local.get $ca) (local.get $cb))
+          (then (return (select (i32.const -1) (i32.const 1) (i32.lt_u (local.get $ca) (local.get $cb))))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $laco)))
+    (select (i32.const -1)
+      (select (i32.const 1) (i32.const 0) (i32.gt_u (local.get $tam_a) (local.get $tam_b)))
+      (i32.lt_u (local.get $tam_a) (local.get $tam_b))))
+"#;
+
+// potência inteira por quadrados sucessivos; com expoente negativo só as
+// bases 1 e -1 não resultam em 0
+const INT_POWER_FUNCTION: &str = r#"  (func $rt.potencia_inteira (param $base i64) (param $expoente i64) (result i64)
+    (local $resultado i64)
+    (local.set $resultado (i64.const 1))
+    (if (i64.lt_s (local.get $expoente) (i64.const 0))
+      (then
+        (if (i64.gt_u (i64.add (local.get $base) (i64.const 1)) (i64.const 2))
+          (then (return (i64.const 0))))
+        (local.set $expoente (i64.sub (i64.const 0) (local.get $expoente)))))
+    (block $fim
+      (loop $laco
+        (br_if $fim (i64.le_s (local.get $expoente) (i64.const 0)))
+        (if (i32.wrap_i64 (i64.and (local.get $expoente) (i64.const 1)))
+          (then (local.set $resultado (i64.mul (local.get $resultado) (local.get $base)))))
+        (local.set $base (i64.mul (local.get $base) (local.get $base)))
+        (local.set $expoente (i64.shr_u (local.get $expoente) (i64.const 1)))
+        (br $laco)))
+    (local.get $resultado))
+"#;
+
+const INT_ABS_FUNCTION: &str = r#"  (func $rt.abs_inteiro (param $x i64) (result i64)
+    (select (i64.sub (i64.const 0) (local.get $x)) (local.get $x) (i64.lt_s (local.get $x) (i64.const 0))))
+"#;
+
+// arredonda afastando do zero (o f64.nearest desempata para o par)
+const ROUND_FUNCTION: &str = r#"  (func $rt.arredondar (param $x f64) (result f64)
+    (local $parte f64)
+    (local.set $parte (f64.trunc (local.get $x)))
+    (if (result f64) (f64.ge (f64.abs (f64.sub (local.get $x) (local.get $parte))) (f64.const 0.5))
+      (then (f64.add (local.get $parte) (f64.copysign (f64.const 1) (local.get $x))))
+      (else (local.get $parte))))
+"#;
+
+// conta os bytes que não são de continuação do UTF-8 (10xxxxxx)
+const TEXT_LENGTH_FUNCTION: &str = r#"  (func $rt.tamanho (param $t i32) (result i64)
+    (local $i i32) (local $fim i32) (local $n i64)
+    (local.set $i (i32.add (local.get $t) (i32.const 4)))
+    (local.set $fim (i32.add (local.get $i) (i32.load (local.get $t))))
+    (block $fim
+      (loop $laco
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $fim)))
+        (if (i32.ne (i32.and (i32.load8_u (local.get $i)) (i32.const 0xc0)) (i32.const 0x80))
+          (then (local.set $n (i64.add (local.get $n) (i64.const 1)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $laco)))
+    (local.get $n))
+"#;
+
+// Copia o texto trocando a caixa das letras: $ascii é a primeira letra ASCII
+// a trocar e $latin o primeiro segundo byte das letras Latin-1 (c3 80-9e
+// maiúsculas, c3 a0-be minúsculas, sem × e ÷). Nos dois casos a troca é
+// inverter o bit 0x20, então o tamanho em bytes não muda.
+const CASE_FUNCTION: &str = r#"  (func $rt.trocar_caixa (param $t i32) (param $ascii i32) (param $latin i32) (result i32)
+    (local $tamanho i32) (local $r i32) (local $i i32) (local $b i32) (local $anterior i32)
+    (local.set $tamanho (i32.load (local.get $t)))
+    (local.set $r (call $rt.alocar (i32.add (local.get $tamanho) (i32.const 4))))
+    (i32.store (local.get $r) (local.get $tamanho))
+    (block $fim
+      (loop $laco
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $tamanho)))
+        (local.set $b (i32.load8_u offset=4 (i32.add (local.get $t) (local.get $i))))
+        (if (i32.or
+              (i32.lt_u (i32.sub (local.get $b) (local.get $ascii)) (i32.const 26))
+              (i32.and
+                (i32.eq (local.get $anterior) (i32.const 0xc3))
+                (i32.and
+                  (i32.lt_u (i32.sub (local.get $b) (local.get $latin)) (i32.const 0x1f))
+                  (i32.ne (i32.sub (local.get $b) (local.get $latin)) (i32.const 0x17)))))
+          (then (local.set $b (i32.xor (local.get $b) (i32.const 0x20)))))
+        (i32.store8 offset=4 (i32.add (local.get $r) (local.get $i)) (local.get $b))
+        (local.set $anterior (local.get $b))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $laco)))
+    (local.get $r))
+"#;
+
+const COPY_FUNCTION: &str = r#"  (func $rt.copiar (param $destino i32) (param $origem i32) (param $n i32)
+    (block $fim
+      (loop $laco
+        (br_if $fim (i32.le_s (local.get $n) (i32.const 0)))
+        (i32.store8 (local.get $destino) (i32.load8_u (local.get $origem)))
+        (local.set $destino (i32.add (local.get $destino) (i32.const 1)))
+        (local.set $origem (i32.add (local.get $origem) (i32.const 1)))
+        (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+        (br $laco))))
+"#;
+
+const CONCAT_FUNCTION: &str = r#"  (func $rt.concatenar (param $a i32) (param $b i32) (result i32)
+    (local $tam_a i32) (local $tam_b i32) (local $t i32)
+    (local.set $tam_a (i32.load (local.get $a)))
+    (local.set $tam_b (i32.load (local.get $b)))
+    (local.set $t (call $rt.alocar (i32.add (i32.add (local.get $tam_a) (local.get $tam_b)) (i32.const 4))))
+    (i32.store (local.get $t) (i32.add (local.get $tam_a) (local.get $tam_b)))
+    (call $rt.copiar (i32.add (local.get $t) (i32.const 4)) (i32.add (local.get $a) (i32.const 4)) (local.get $tam_a))
+    (call $rt.copiar (i32.add (i32.add (local.get $t) (i32.const 4)) (local.get $tam_a))
+      (i32.add (local.get $b) (i32.const 4)) (local.get $tam_b))
+    (local.get $t))
+"#;
+
+// avança $n caracteres a partir do byte $i, sem passar do fim do texto
+const SUBSTRING_FUNCTION: &str = r#"  (func $rt.avancar (param $t i32) (param $i i32) (param $n i64) (result i32)
+    (local $tamanho i32)
+    (local.set $tamanho (i32.load (local.get $t)))
+    (block $fim
+      (loop $laco
+        (br_if $fim (i64.le_s (local.get $n) (i64.const 0)))
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $tamanho)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (block $caractere
+          (loop $continuacao
+            (br_if $caractere (i32.ge_u (local.get $i) (local.get $tamanho)))
+            (br_if $caractere
+              (i32.ne (i32.and (i32.load8_u offset=4 (i32.add (local.get $t) (local.get $i))) (i32.const 0xc0))
+                      (i32.const 0x80)))
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br $continuacao)))
+        (local.set $n (i64.sub (local.get $n) (i64.const 1)))
+        (br $laco)))
+    (local.get $i))
+  (func $rt.subtexto (param $t i32) (param $inicio i64) (param $quantidade i64) (result i32)
+    (local $de i32) (local $ate i32) (local $r i32)
+    (if (i64.lt_s (local.get $inicio) (i64.const 1))
+      (then (local.set $inicio (i64.const 1))))
+    (local.set $de (call $rt.avancar (local.get $t) (i32.const 0) (i64.sub (local.get $inicio) (i64.const 1))))
+    (local.set $ate (call $rt.avancar (local.get $t) (local.get $de) (local.get $quantidade)))
+    (local.set $r (call $rt.alocar (i32.add (i32.sub (local.get $ate) (local.get $de)) (i32.const 4))))
+    (i32.store (local.get $r) (i32.sub (local.get $ate) (local.get $de)))
+    (call $rt.copiar
+      (i32.add (local.get $r) (i32.const 4))
+      (i32.add (i32.add (local.get $t) (i32.const 4)) (local.get $de))
+      (i32.sub (local.get $ate) (local.get $de)))
+    (local.get $r))
+"#;
+
+// posição em bytes da primeira ocorrência de $trecho a partir de $inicio, ou -1
+const SEARCH_FUNCTION: &str = r#"  (func $rt.buscar (param $t i32) (param $trecho i32) (param $inicio i32) (result i32)
+    (local $ultimo i32) (local $n i32) (local $j i32)
+    (local.set $n (i32.load (local.get $trecho)))
+    (local.set $ultimo (i32.sub (i32.load (local.get $t)) (local.get $n)))
+    (block $nada
+      (loop $posicao
+        (br_if $nada (i32.gt_s (local.get $inicio) (local.get $ultimo)))
+        (local.set $j (i32.const 0))
+        (block $diferente
+          (loop $compara
+            (if (i32.ge_u (local.get $j) (local.get $n))
+              (then (return (local.get $inicio))))
+            (br_if $diferente
+              (i32.ne
+                (i32.load8_u offset=4 (i32.add (i32.add (local.get $t) (local.get $inicio)) (local.get $j)))
+                (i32.load8_u offset=4 (i32.add (local.get $trecho) (local.get $j)))))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br $compara)))
+        (local.set $inicio (i32.add (local.get $inicio) (i32.const 1)))
+        (br $posicao)))
+    (i32.const -1))
+"#;
+
+// uma passada calcula o tamanho do resultado e outra o monta; textos são
+// imutáveis, então `de` vazio devolve o próprio texto
+const REPLACE_FUNCTION: &str = r#"  (func $rt.substituir (param $t i32) (param $de i32) (param $por i32) (result i32)
+    (local $tam_de i32) (local $tam_por i32) (local $tamanho i32) (local $posicao i32)
+    (local $origem i32) (local $destino i32) (local $r i32)
+    (local.set $tam_de (i32.load (local.get $de)))
+    (local.set $tam_por (i32.load (local.get $por)))
+    (if (i32.eqz (local.get $tam_de))
+      (then (return (local.get $t))))
+    (local.set $tamanho (i32.load (local.get $t)))
+    (local.set $posicao (call $rt.buscar (local.get $t) (local.get $de) (i32.const 0)))
+    (block $contado
+      (loop $conta
+        (br_if $contado (i32.lt_s (local.get $posicao) (i32.const 0)))
+        (local.set $tamanho (i32.add (local.get $tamanho) (i32.sub (local.get $tam_por) (local.get $tam_de))))
+        (local.set $posicao
+          (call $rt.buscar (local.get $t) (local.get $de) (i32.add (local.get $posicao) (local.get $tam_de))))
+        (br $conta)))
+    (local.set $r (call $rt.alocar (i32.add (local.get $tamanho) (i32.const 4))))
+    (i32.store (local.get $r) (local.get $tamanho))
+    (local.set $destino (i32.add (local.get $r) (i32.const 4)))
+    (block $montado
+      (loop $monta
+        (local.set $posicao (call $rt.buscar (local.get $t) (local.get $de) (local.get $origem)))
+        (br_if $montado (i32.lt_s (local.get $posicao) (i32.const 0)))
+        (call $rt.copiar
+          (local.get $destino)
+          (i32.add (i32.add (local.get $t) (i32.const 4)) (local.get $origem))
+          (i32.sub (local.get $posicao) (local.get $origem)))
+        (local.set $destino (i32.add (local.get $destino) (i32.sub (local.get $posicao) (local.get $origem))))
+        (call $rt.copiar (local.get $destino) (i32.add (local.get $por) (i32.const 4)) (local.get $tam_por))
+        (local.set $destino (i32.add (local.get $destino) (local.get $tam_por)))
+        (local.set $origem (i32.add (local.get $posicao) (local.get $tam_de)))
+        (br $monta)))
+    (call $rt.copiar
+      (local.get $destino)
+      (i32.add (i32.add (local.get $t) (i32.const 4)) (local.get $origem))
+      (i32.sub (i32.load (local.get $t)) (local.get $origem)))
+    (local.get $r))
+"#;
+
+fn wat_type(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "i64",
+        Type::Decimal => "f64",
+        Type::Texto | Type::Logico => "i32",
+    }
+}
+
+fn type_suffix(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "inteiro",
+        Type::Decimal => "decimal",
+        Type::Texto => "texto",
+        Type::Logico => "logico",
+    }
+}
+
+fn default_const(ty: &Type) -> &'static str {
+    match ty {
+        Type::Inteiro => "(i64.const 0)",
+        Type::Decimal => "(f64.const 0)",
+        // endereço 0 guarda um texto vazio (tamanho 0)
+        Type::Texto | Type::Logico => "(i32.const 0)",
+    }
+}
+
+fn coerce(code: &str, from: &Type, to: &Type) -> String {
+    if *from == Type::Inteiro && *to == Type::Decimal {
+        format!("(f64.convert_i64_s {})", code)
+    } else {
+        code.to_string()
+    }
+}
+
+fn get_location(location: &Location) -> String {
+    match location {
+        Location::Local(name) => format!("(local.get ${})", name),
+        Location::Global(name) => format!("(global.get ${})", name),
+    }
+}
+
+fn set_location(location: &Location, value: &str) -> String {
+    match location {
+        Location::Local(name) => format!("(local.set ${} {})", name, value),
+        Location::Global(name) => format!("(global.set ${} {})", name, value),
+    }
+}
+
+fn wat_float(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else {
+        // {:?} imprime "inf", "-inf" e mantém o ponto decimal
+        format!("{:?}", value)
+    }
+}
+
+fn escape_bytes(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for &b in bytes {
+        if (0x20..0x7f).contains(&b) && b != b'"' && b != b'\\' {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("\\{:02x}", b));
+        }
+    }
+    out
+}
+
+fn align4(value: u32) -> u32 {
+    (value + 3) & !3
+}
+
+// byte na posição $i do texto, ou 0 depois do fim
+const NUMBER_SCAN_FUNCTIONS: &str = r#"  (func $rt.caractere (param $t i32) (param $i i32) (result i32)
+    (if (result i32) (i32.lt_u (local.get $i) (i32.load (local.get $t)))
+      (then (i32.load8_u offset=4 (i32.add (local.get $t) (local.get $i))))
+      (else (i32.const 0))))
+
+  (func $rt.sinal (param $t i32) (param $i i32) (result i32)
+    (local $c i32)
+    (local.set $c (call $rt.caractere (local.get $t) (local.get $i)))
+    (i32.add (local.get $i)
+      (i32.or (i32.eq (local.get $c) (i32.const 0x2b)) (i32.eq (local.get $c) (i32.const 0x2d)))))
+
+  (func $rt.digitos (param $t i32) (param $i i32) (result i32)
+    (block $fim
+      (loop $laco
+        (br_if $fim (i32.gt_u (i32.sub (call $rt.caractere (local.get $t) (local.get $i)) (i32.const 0x30)) (i32.const 9)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $laco)))
+    (local.get $i))
+"#;
+
+// sinal opcional e dígitos; o valor é acumulado em negativo, cuja faixa
+// vai até -2^63
+const TEXT_TO_INT_FUNCTION: &str = r#"  (func $rt.texto_para_inteiro (param $t i32) (result i64)
+    (local $i i32) (local $fim i32) (local $c i32) (local $valor i64)
+    (local.set $i (call $rt.sinal (local.get $t) (i32.const 0)))
+    (local.set $fim (call $rt.digitos (local.get $t) (local.get $i)))
+    (if (i32.or (i32.eq (local.get $fim) (local.get $i)) (i32.ne (local.get $fim) (i32.load (local.get $t))))
+      (then unreachable))
+    (loop $laco
+      (local.set $c (i32.sub (call $rt.caractere (local.get $t) (local.get $i)) (i32.const 0x30)))
+      (if (i32.or (i64.lt_s (local.get $valor) (i64.const -922337203685477580))
+                  (i32.and (i64.eq (local.get $valor) (i64.const -922337203685477580)) (i32.gt_u (local.get $c) (i32.const 8))))
+        (then unreachable))
+      (local.set $valor (i64.sub (i64.mul (local.get $valor) (i64.const 10)) (i64.extend_i32_u (local.get $c))))
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $laco (i32.lt_u (local.get $i) (local.get $fim))))
+    (if (i32.eq (call $rt.caractere (local.get $t) (i32.const 0)) (i32.const 0x2d))
+      (then (return (local.get $valor))))
+    (if (i64.eq (local.get $valor) (i64.const -9223372036854775808))
+      (then unreachable))
+    (i64.sub (i64.const 0) (local.get $valor)))
+"#;
+
+// sinal, dígitos, fração e expoente opcionais; devolve o próprio texto
+const DECIMAL_CHECK_FUNCTION: &str = r#"  (func $rt.validar_decimal (param $t i32) (result i32)
+    (local $i i32) (local $j i32)
+    (local.set $i (call $rt.sinal (local.get $t) (i32.const 0)))
+    (local.set $j (call $rt.digitos (local.get $t) (local.get $i)))
+    (if (i32.eq (local.get $j) (local.get $i)) (then unreachable))
+    (if (i32.eq (call $rt.caractere (local.get $t) (local.get $j)) (i32.const 0x2e))
+      (then
+        (local.set $i (i32.add (local.get $j) (i32.const 1)))
+        (local.set $j (call $rt.digitos (local.get $t) (local.get $i)))
+        (if (i32.eq (local.get $j) (local.get $i)) (then unreachable))))
+    (if (i32.eq (i32.or (call $rt.caractere (local.get $t) (local.get $j)) (i32.const 0x20)) (i32.const 0x65))
+      (then
+        (local.set $i (call $rt.sinal (local.get $t) (i32.add (local.get $j) (i32.const 1))))
+        (local.set $j (call $rt.digitos (local.get $t) (local.get $i)))
+        (if (i32.eq (local.get $j) (local.get $i)) (then unreachable))))
+    (if (i32.ne (local.get $j) (i32.load (local.get $t))) (then unreachable))
+    (local.get $t))
+"#;
+
+// até 20 caracteres, escritos do fim de um bloco de 24 bytes para o começo;
+// o tamanho fica nos 4 bytes antes do primeiro (a memória do WebAssembly
+// aceita acessos desalinhados). Os dígitos saem de um valor negativo, para
+// que -2^63 também funcione.
+const INT_TO_TEXT_FUNCTION: &str = r#"  (func $rt.inteiro_para_texto (param $x i64) (result i32)
+    (local $t i32) (local $i i32) (local $v i64)
+    (local.set $t (call $rt.alocar (i32.const 24)))
+    (local.set $i (i32.const 24))
+    (local.set $v (select (local.get $x) (i64.sub (i64.const 0) (local.get $x)) (i64.lt_s (local.get $x) (i64.const 0))))
+    (loop $laco
+      (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+      (i32.store8 (i32.add (local.get $t) (local.get $i))
+        (i32.sub (i32.const 0x30) (i32.wrap_i64 (i64.rem_s (local.get $v) (i64.const 10)))))
+      (local.set $v (i64.div_s (local.get $v) (i64.const 10)))
+      (br_if $laco (i64.ne (local.get $v) (i64.const 0))))
+    (if (i64.lt_s (local.get $x) (i64.const 0))
+      (then
+        (local.set $i (i32.sub (local.get $i) (i32.const 1)))
+        (i32.store8 (i32.add (local.get $t) (local.get $i)) (i32.const 0x2d))))
+    (i32.store (i32.add (local.get $t) (i32.sub (local.get $i) (i32.const 4))) (i32.sub (i32.const 24) (local.get $i)))
+    (i32.add (local.get $t) (i32.sub (local.get $i) (i32.const 4))))
+"#;
+
+const TEXT_TO_LOGICAL_FUNCTION: &str = r#"  (func $rt.texto_para_logico (param $t i32) (param $verdadeiro i32) (param $falso i32) (result i32)
+    (if (i32.eqz (call $rt.texto_comparar (local.get $t) (local.get $verdadeiro)))
+      (then (return (i32.const 1))))
+    (if (i32.eqz (call $rt.texto_comparar (local.get $t) (local.get $falso)))
+      (then (return (i32.const 0))))
+    unreachable)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::semantic::SemanticAnalyzer;
+
+    // S-expressão mínima, suficiente para checar a estrutura do módulo gerado
+    #[derive(Debug)]
+    enum Sexp {
+        Atom(String),
+        List(Vec<Sexp>),
+    }
+
+    fn parse_sexp(text: &str) -> Sexp {
+        let mut stack: Vec<Vec<Sexp>> = vec![Vec::new()];
+        let chars: Vec<char> = text.chars().collect();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                ';' if chars.get(i + 1) == Some(&';') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                '(' => {
+                    stack.push(Vec::new());
+                    i += 1;
+                }
+                ')' => {
+                    let list = stack.pop().expect("')' sem '(' correspondente");
+                    stack.last_mut().expect("')' sobrando").push(Sexp::List(list));
+                    i += 1;
+                }
+                '"' => {
+                    let start = i;
+                    i += 1;
+                    while chars[i] != '"' {
+                        i += if chars[i] == '\\' { 2 } else { 1 };
+                    }
+                    i += 1;
+                    stack.last_mut().unwrap().push(Sexp::Atom(chars[start..i].iter().collect()));
+                }
+                c if c.is_whitespace() => i += 1,
+                _ => {
+                    let start = i;
+                    while i < chars.len() && !chars[i].is_whitespace() && !"()".contains(chars[i]) {
+                        i += 1;
+                    }
+                    stack.last_mut().unwrap().push(Sexp::Atom(chars[start..i].iter().collect()));
+                }
+            }
+        }
+
+        assert_eq!(stack.len(), 1, "parênteses desbalanceados");
+        let mut top = stack.pop().unwrap();
+        assert_eq!(top.len(), 1, "esperado um único módulo");
+        top.pop().unwrap()
+    }
+
+    fn head(sexp: &Sexp) -> Option<&str> {
+        match sexp {
+            Sexp::List(items) => match items.first() {
+                Some(Sexp::Atom(atom)) => Some(atom),
+                _ => None,
+            },
+            Sexp::Atom(_) => None,
+        }
+    }
+
+    fn module_fields(source: &str) -> Vec<Sexp> {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let wat = generate_wat(&result.annotated_ast).unwrap();
+        match parse_sexp(&wat) {
+            Sexp::List(mut items) => {
+                assert!(matches!(items.remove(0), Sexp::Atom(ref a) if a == "module"));
+                items
+            }
+            other => panic!("esperado (module ...), encontrado {:?}", other),
+        }
+    }
+
+    fn function_names(fields: &[Sexp]) -> Vec<String> {
+        fields
+            .iter()
+            .filter(|f| head(f) == Some("func"))
+            .filter_map(|f| match f {
+                Sexp::List(items) => match &items[1] {
+                    Sexp::Atom(name) => Some(name.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_structure() {
+        let fields = module_fields(
+            "funcao inteiro soma(inteiro a, inteiro b) { retorne a + b; }
+             funcao principal() { escreva(\"Soma:\", soma(2, 3)); }",
+        );
+
+        let imports = fields.iter().filter(|f| head(f) == Some("import")).count();
+        assert_eq!(imports, HOST_IMPORTS.len());
+        assert!(fields.iter().any(|f| head(f) == Some("memory")));
+        assert!(fields.iter().any(|f| head(f) == Some("data")));
+        assert_eq!(function_names(&fields), vec!["$rt.alocar", "$soma", "$principal", "$__inicio__"]);
+    }
+
+    #[test]
+    fn test_types_and_control_flow() {
+        let fields = module_fields(
+            "funcao decimal media(inteiro n) {
+                decimal total = 1.5;
+                inteiro i = 0;
+                para i de 1 ate n faca { escreva(i); } fimpara
+                enquanto falso faca { } fimenquanto
+                se n > 0 && \"a\" < \"b\" entao { retorne total / n; } fimse
+                retorne 0.0;
+            }",
+        );
+        let names = function_names(&fields);
+        assert!(names.contains(&"$rt.texto_comparar".to_string()));
+
+        let wat = format!("{:?}", fields);
+        assert!(wat.contains("f64.convert_i64_s"));
+        assert!(wat.contains("\"loop\""));
+        assert!(wat.contains("\"i64.le_s\""));
+    }
+
+    #[test]
+    fn test_non_ascii_names_are_mangled() {
+        assert_eq!(ascii_identifier("ação"), "a.u00e7.u00e3o");
+        let fields = module_fields("funcao inteiro dobro(inteiro número) { retorne número * 2; }");
+        assert!(function_names(&fields).contains(&"$dobro".to_string()));
+    }
+}
@@ -0,0 +1,1534 @@
+use std::collections::HashMap;
+use crate::parser::ast::*;
+use crate::backend::{ascii_identifier, entry_point, escape_gas_string, numeric_result, BackendError, ScopedEnv};
+use crate::semantic::builtins::{Builtin, Overload};
+
+// Gera assembly x86-64 (sintaxe AT&T do GAS, ABI System V) a partir da AST
+// anotada. O resultado é montado e ligado com `gcc programa.s -o programa -lm`
+// (a libm fornece pow, floor, ceil e round da biblioteca padrão).
+//
+// Modelo de execução:
+//   - cada expressão deixa o resultado em %rax (inteiro, logico, texto) ou
+//     em %xmm0 (decimal); operandos intermediários vão para a pilha;
+//   - variáveis locais e parâmetros moram em posições fixas do quadro
+//     (relativas a %rbp); globais ficam na seção .data;
+//   - texto é um ponteiro para uma string terminada em zero;
+//   - E/S usa printf/scanf da libc; textos lidos ou criados pelas funções
+//     de texto são alocados com malloc (e nunca liberados);
+//   - conversões inválidas (paraInteiro("abc")) e a divisão inteira por
+//     zero escrevem uma mensagem e encerram o programa com código 1;
+//   - funções e globais ganham prefixos (fn., var.) para não colidir com a
+//     libc nem com `main`.
+
+const INT_ARG_REGISTERS: [&str; 6] = ["%rdi", "%rsi", "%rdx", "%rcx", "%r8", "%r9"];
+const FLOAT_ARG_REGISTERS: usize = 8;
+const READ_BUFFER_SIZE: usize = 256;
+
+#[derive(Clone)]
+enum Location {
+    Frame(i64),
+    Global(String),
+}
+
+impl Location {
+    fn operand(&self) -> String {
+        match self {
+            Location::Frame(offset) => format!("{}(%rbp)", offset),
+            Location::Global(symbol) => format!("{}(%rip)", symbol),
+        }
+    }
+}
+
+// Classificação de um argumento segundo a ABI System V
+enum ArgClass {
+    Int(usize),
+    Float(usize),
+    Stack(usize),
+}
+
+fn classify_arguments(types: &[Type]) -> Vec<ArgClass> {
+    let (mut ints, mut floats, mut stack) = (0, 0, 0);
+    types
+        .iter()
+        .map(|ty| {
+            if *ty == Type::Decimal && floats < FLOAT_ARG_REGISTERS {
+                floats += 1;
+                ArgClass::Float(floats - 1)
+            } else if *ty != Type::Decimal && ints < INT_ARG_REGISTERS.len() {
+                ints += 1;
+                ArgClass::Int(ints - 1)
+            } else {
+                stack += 1;
+                ArgClass::Stack(stack - 1)
+            }
+        })
+        .collect()
+}
+
+pub struct X86Generator<'a> {
+    program: &'a Program,
+    env: ScopedEnv<Location>,
+
+    strings: HashMap<String, String>,
+    rodata: Vec<String>,
+    label_counter: usize,
+    // rotinas auxiliares usadas pelo programa
+    runtime: Vec<&'static str>,
+
+    // estado da função sendo gerada
+    lines: Vec<String>,
+    frame_size: i64,
+    // bytes empilhados além do quadro (para alinhar as chamadas em 16)
+    stack_depth: i64,
+    return_label: String,
+}
+
+impl<'a> X86Generator<'a> {
+    pub fn new(program: &'a Program) -> Self {
+        Self {
+            program,
+            env: ScopedEnv::new(program),
+            strings: HashMap::new(),
+            rodata: Vec::new(),
+            label_counter: 0,
+            runtime: Vec::new(),
+            lines: Vec::new(),
+            frame_size: 0,
+            stack_depth: 0,
+            return_label: String::new(),
+        }
+    }
+
+    pub fn generate(mut self) -> Result<String, BackendError> {
+        let mut data = Vec::new();
+        for statement in &self.program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                let symbol = format!("var.{}", ascii_identifier(&decl.name));
+                data.push(format!("{}:\n    .quad 0", symbol));
+                self.env.declare(&decl.name, decl.var_type.clone(), Location::Global(symbol));
+            }
+        }
+
+        let mut functions = Vec::new();
+        for function in &self.program.functions {
+            functions.push(self.generate_function(function).map_err(|e| e.at_line(function.line))?);
+        }
+        functions.push(self.generate_main()?);
+
+        let mut out = String::new();
+        out.push_str("# Gerado pelo compilador Symplia (x86-64, System V, sintaxe AT&T)\n");
+        if !data.is_empty() {
+            out.push_str("\n    .data\n    .p2align 3\n");
+            for item in &data {
+                out.push_str(item);
+                out.push('\n');
+            }
+        }
+        if !self.rodata.is_empty() {
+            out.push_str("\n    .section .rodata\n");
+            for item in &self.rodata {
+                out.push_str(item);
+                out.push('\n');
+            }
+        }
+        out.push_str("\n    .text\n");
+        for function in functions {
+            out.push('\n');
+            out.push_str(&function);
+        }
+        for routine in &self.runtime {
+            out.push('\n');
+            out.push_str(routine);
+        }
+        out.push_str("\n    .section .note.GNU-stack,\"\",@progbits\n");
+
+        Ok(out)
+    }
+
+    // === FUNÇÕES ===
+
+    fn generate_function(&mut self, function: &FunctionDecl) -> Result<String, BackendError> {
+        let symbol = format!("fn.{}", ascii_identifier(&function.name));
+        self.begin_function();
+        self.env.enter_scope();
+
+        let types: Vec<Type> = function.parameters.iter().map(|p| p.param_type.clone()).collect();
+        for (param, class) in function.parameters.iter().zip(classify_arguments(&types)) {
+            let location = match class {
+                ArgClass::Int(i) => {
+                    let location = self.allocate_slot();
+                    self.emit(&format!("movq {}, {}", INT_ARG_REGISTERS[i], location.operand()));
+                    location
+                }
+                ArgClass::Float(i) => {
+                    let location = self.allocate_slot();
+                    self.emit(&format!("movsd %xmm{}, {}", i, location.operand()));
+                    location
+                }
+                // acima do endereço de retorno e do %rbp salvo
+                ArgClass::Stack(i) => Location::Frame(16 + 8 * i as i64),
+            };
+            self.env.declare(&param.name, param.param_type.clone(), location);
+        }
+
+        self.generate_block(&function.body, &function.return_type)?;
+
+        // funções que terminam sem `retorne` devolvem o valor padrão do tipo
+        match &function.return_type {
+            Some(Type::Decimal) => self.emit("pxor %xmm0, %xmm0"),
+            Some(_) => self.emit("xorl %eax, %eax"),
+            None => {}
+        }
+
+        self.env.exit_scope();
+        Ok(self.finish_function(&symbol, false))
+    }
+
+    fn generate_main(&mut self) -> Result<String, BackendError> {
+        self.begin_function();
+        self.env.enter_scope();
+
+        for statement in &self.program.statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    // o .quad 0 não serve de texto vazio
+                    match &decl.initializer {
+                        Some(initializer) => self.generate_coerced(initializer, &decl.var_type).map_err(|e| e.at_line(decl.line))?,
+                        None => self.load_default(&decl.var_type),
+                    }
+                    let (location, _) = self.lookup(&decl.name).map_err(|e| e.at_line(decl.line))?;
+                    self.store(&location, &decl.var_type);
+                }
+                other => self.generate_statement(other, &None).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+
+        if let Some(principal) = entry_point(self.program) {
+            self.emit_call(&format!("fn.{}", ascii_identifier(&principal.name)));
+        }
+        self.emit("xorl %eax, %eax");
+
+        self.env.exit_scope();
+        Ok(self.finish_function("main", true))
+    }
+
+    fn begin_function(&mut self) {
+        self.lines.clear();
+        self.frame_size = 0;
+        self.stack_depth = 0;
+        self.return_label = self.new_label();
+    }
+
+    fn finish_function(&mut self, symbol: &str, global: bool) -> String {
+        // o quadro é múltiplo de 16 para manter %rsp alinhado nas chamadas
+        let frame = (self.frame_size + 15) / 16 * 16;
+
+        let mut out = String::new();
+        if global {
+            out.push_str(&format!("    .globl {}\n", symbol));
+        }
+        out.push_str(&format!("    .type {}, @function\n", symbol));
+        out.push_str(&format!("{}:\n", symbol));
+        out.push_str("    pushq %rbp\n");
+        out.push_str("    movq %rsp, %rbp\n");
+        if frame > 0 {
+            out.push_str(&format!("    subq ${}, %rsp\n", frame));
+        }
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&format!("{}:\n", self.return_label));
+        out.push_str("    leave\n");
+        out.push_str("    ret\n");
+        out.push_str(&format!("    .size {}, .-{}\n", symbol, symbol));
+        out
+    }
+
+    // === STATEMENTS ===
+
+    fn generate_block(&mut self, block: &Block, return_type: &Option<Type>) -> Result<(), BackendError> {
+        self.env.enter_scope();
+        for statement in &block.statements {
+            self.generate_statement(statement, return_type).map_err(|e| e.at_line(statement.line()))?;
+        }
+        self.env.exit_scope();
+        Ok(())
+    }
+
+    fn generate_statement(&mut self, statement: &Statement, return_type: &Option<Type>) -> Result<(), BackendError> {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                match &decl.initializer {
+                    Some(initializer) => self.generate_coerced(initializer, &decl.var_type)?,
+                    None => self.load_default(&decl.var_type),
+                }
+                let location = self.allocate_slot();
+                self.store(&location, &decl.var_type);
+                self.env.declare(&decl.name, decl.var_type.clone(), location);
+            }
+            Statement::ExprStmt(expr_stmt) => {
+                match &expr_stmt.expr {
+                    Expr::Call(call) => {
+                        self.generate_call(call)?;
+                    }
+                    expr => {
+                        self.generate_expr(expr)?;
+                    }
+                }
+            }
+            Statement::IfStmt(if_stmt) => {
+                let else_label = self.new_label();
+                let end_label = self.new_label();
+
+                self.generate_expr(&if_stmt.condition)?;
+                self.emit("testq %rax, %rax");
+                self.emit(&format!("je {}", else_label));
+                self.generate_block(&if_stmt.then_branch, return_type)?;
+                self.emit(&format!("jmp {}", end_label));
+                self.emit_label(&else_label);
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.generate_block(else_branch, return_type)?;
+                }
+                self.emit_label(&end_label);
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let loop_label = self.new_label();
+                let end_label = self.new_label();
+
+                self.emit_label(&loop_label);
+                self.generate_expr(&while_stmt.condition)?;
+                self.emit("testq %rax, %rax");
+                self.emit(&format!("je {}", end_label));
+                self.generate_block(&while_stmt.body, return_type)?;
+                self.emit(&format!("jmp {}", loop_label));
+                self.emit_label(&end_label);
+            }
+            Statement::ForStmt(for_stmt) => {
+                self.env.enter_scope();
+
+                let (location, var_type) = match self.env.lookup(&for_stmt.variable) {
+                    Some((location, ty)) if matches!(ty, Type::Inteiro | Type::Decimal) => (location, ty),
+                    _ => {
+                        let location = self.allocate_slot();
+                        self.env.declare(&for_stmt.variable, Type::Inteiro, location.clone());
+                        (location, Type::Inteiro)
+                    }
+                };
+
+                self.generate_coerced(&for_stmt.start, &var_type)?;
+                self.store(&location, &var_type);
+
+                let loop_label = self.new_label();
+                let end_label = self.new_label();
+                self.emit_label(&loop_label);
+
+                // condição: variável <= fim (o fim é reavaliado a cada volta)
+                let end_type = self.env.expr_type(&for_stmt.end)?;
+                let compare_type = numeric_result(&var_type, &end_type);
+                self.load(&location, &var_type);
+                self.convert(&var_type, &compare_type);
+                self.push(&compare_type);
+                self.generate_coerced(&for_stmt.end, &compare_type)?;
+                self.pop_left_operand(&compare_type);
+                self.compare(&BinaryOperator::LessEqual, &compare_type);
+                self.emit("testq %rax, %rax");
+                self.emit(&format!("je {}", end_label));
+
+                self.generate_block(&for_stmt.body, return_type)?;
+
+                self.load(&location, &var_type);
+                if var_type == Type::Decimal {
+                    self.load_decimal_into("%xmm1", 1.0);
+                    self.emit("addsd %xmm1, %xmm0");
+                } else {
+                    self.emit("addq $1, %rax");
+                }
+                self.store(&location, &var_type);
+                self.emit(&format!("jmp {}", loop_label));
+                self.emit_label(&end_label);
+
+                self.env.exit_scope();
+            }
+            Statement::ReturnStmt(return_stmt) => {
+                match (&return_stmt.value, return_type) {
+                    (Some(value), Some(return_type)) => self.generate_coerced(value, return_type)?,
+                    (None, None) => {}
+                    _ => return Err(BackendError::new("Retorno incompatível com o tipo da função")),
+                }
+                let label = self.return_label.clone();
+                self.emit(&format!("jmp {}", label));
+            }
+            Statement::WriteStmt(write_stmt) => {
+                if write_stmt.arguments.is_empty() {
+                    let format = self.intern_string("\n");
+                    self.emit_printf(&format, false);
+                }
+                let count = write_stmt.arguments.len();
+                for (i, arg) in write_stmt.arguments.iter().enumerate() {
+                    let ty = self.generate_expr(arg)?;
+                    let specifier = match ty {
+                        Type::Inteiro => "%ld",
+                        Type::Decimal => "%f",
+                        Type::Texto => "%s",
+                        Type::Logico => {
+                            self.select_logico_text();
+                            "%s"
+                        }
+                    };
+                    let separator = if i + 1 == count { "\n" } else { " " };
+                    let format = self.intern_string(&format!("{}{}", specifier, separator));
+                    if ty != Type::Decimal {
+                        self.emit("movq %rax, %rsi");
+                    } else {
+                        // o NaN padrão do x86 tem o sinal ligado e o printf escreveria "-nan"
+                        let ordered = self.new_label();
+                        self.emit("ucomisd %xmm0, %xmm0");
+                        self.emit(&format!("jnp {}", ordered));
+                        self.emit("movq %xmm0, %rax");
+                        self.emit("btrq $63, %rax");
+                        self.emit("movq %rax, %xmm0");
+                        self.emit_label(&ordered);
+                    }
+                    self.emit_printf(&format, ty == Type::Decimal);
+                }
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(BackendError::new("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let (location, ty) = self.lookup(name)?;
+                self.generate_read(&location, &ty);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_read(&mut self, location: &Location, ty: &Type) {
+        match ty {
+            Type::Inteiro | Type::Decimal => {
+                let format = self.intern_string(if *ty == Type::Inteiro { "%ld" } else { "%lf" });
+                self.emit(&format!("leaq {}, %rsi", location.operand()));
+                self.emit(&format!("leaq {}(%rip), %rdi", format));
+                self.emit("xorl %eax, %eax");
+                self.emit_call("scanf@PLT");
+            }
+            Type::Texto | Type::Logico => {
+                // lê uma palavra para um buffer alocado com malloc
+                self.emit(&format!("movl ${}, %edi", READ_BUFFER_SIZE));
+                self.emit_call("malloc@PLT");
+                self.emit("movb $0, (%rax)");
+                self.push(&Type::Texto);
+                let format = self.intern_string(&format!(" %{}s", READ_BUFFER_SIZE - 1));
+                self.emit("movq %rax, %rsi");
+                self.emit(&format!("leaq {}(%rip), %rdi", format));
+                self.emit("xorl %eax, %eax");
+                self.emit_call("scanf@PLT");
+                self.pop("%rax");
+
+                if *ty == Type::Logico {
+                    let verdadeiro = self.intern_string("verdadeiro");
+                    self.emit("movq %rax, %rdi");
+                    self.emit(&format!("leaq {}(%rip), %rsi", verdadeiro));
+                    self.emit_call("strcmp@PLT");
+                    self.emit("testl %eax, %eax");
+                    self.emit("sete %al");
+                    self.emit("movzbq %al, %rax");
+                }
+                self.store(location, ty);
+            }
+        }
+    }
+
+    // === EXPRESSÕES ===
+
+    fn generate_coerced(&mut self, expr: &Expr, target: &Type) -> Result<(), BackendError> {
+        let ty = self.generate_expr(expr)?;
+        self.convert(&ty, target);
+        Ok(())
+    }
+
+    fn generate_expr(&mut self, expr: &Expr) -> Result<Type, BackendError> {
+        let ty = self.env.expr_type(expr)?;
+
+        match expr {
+            Expr::Literal(literal) => match literal {
+                Literal::Inteiro(n) => self.emit(&format!("movabsq ${}, %rax", n)),
+                Literal::Decimal(n) => self.load_decimal_into("%xmm0", *n),
+                Literal::Texto(s) => {
+                    let label = self.intern_string(s);
+                    self.emit(&format!("leaq {}(%rip), %rax", label));
+                }
+                Literal::Logico(b) => self.emit(&format!("movq ${}, %rax", *b as i32)),
+            },
+            Expr::Variable(name) => {
+                let (location, var_type) = self.lookup(name)?;
+                self.load(&location, &var_type);
+            }
+            Expr::Call(call) => {
+                self.generate_call(call)?;
+            }
+            Expr::UnaryOp(op, operand) => {
+                let operand_type = self.generate_expr(operand)?;
+                match (op, operand_type) {
+                    (UnaryOperator::Plus, _) => {}
+                    (UnaryOperator::Negate, Type::Decimal) => {
+                        // inverte o bit de sinal
+                        self.emit("movq %xmm0, %rax");
+                        self.emit("btcq $63, %rax");
+                        self.emit("movq %rax, %xmm0");
+                    }
+                    (UnaryOperator::Negate, _) => self.emit("negq %rax"),
+                    (UnaryOperator::Not, _) => self.emit("xorq $1, %rax"),
+                }
+            }
+            Expr::BinaryOp(op, left, right) => self.generate_binary(op, left, right)?,
+        }
+
+        Ok(ty)
+    }
+
+    fn generate_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr) -> Result<(), BackendError> {
+        let left_type = self.env.expr_type(left)?;
+        let right_type = self.env.expr_type(right)?;
+
+        // concatenação: cada lado vira texto antes de ir para a pilha
+        if *op == BinaryOperator::Add && (left_type == Type::Texto || right_type == Type::Texto) {
+            self.generate_expr(left)?;
+            self.convert_explicitly(&left_type, &Type::Texto);
+            self.push(&Type::Texto);
+            self.generate_expr(right)?;
+            self.convert_explicitly(&right_type, &Type::Texto);
+            self.pop_left_operand(&Type::Texto);
+            self.emit("movq %rax, %rdi");
+            self.emit("movq %rcx, %rsi");
+            self.use_runtime(CONCAT_ROUTINE);
+            self.emit_call("rt.concatenar");
+            return Ok(());
+        }
+
+        let operand_type = match (&left_type, &right_type) {
+            (Type::Texto, Type::Texto) => Type::Texto,
+            (Type::Logico, Type::Logico) => Type::Logico,
+            (Type::Inteiro | Type::Decimal, Type::Inteiro | Type::Decimal) => numeric_result(&left_type, &right_type),
+            _ => {
+                return Err(BackendError::new(format!(
+                    "Operador '{}' não suportado para tipos {} e {}", op, left_type, right_type
+                )));
+            }
+        };
+
+        // esquerdo na pilha; direito em %rcx / %xmm1; esquerdo volta para %rax / %xmm0
+        self.generate_coerced(left, &operand_type)?;
+        self.push(&operand_type);
+        self.generate_coerced(right, &operand_type)?;
+        self.pop_left_operand(&operand_type);
+
+        match op {
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::Less
+            | BinaryOperator::LessEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual => {
+                if operand_type == Type::Logico && !matches!(op, BinaryOperator::Equal | BinaryOperator::NotEqual) {
+                    return Err(BackendError::new(format!("Operador '{}' não suportado para lógicos", op)));
+                }
+                self.compare(op, &operand_type);
+            }
+            BinaryOperator::And | BinaryOperator::Or => {
+                if operand_type != Type::Logico {
+                    return Err(BackendError::new(format!("Operador '{}' requer operandos lógicos", op)));
+                }
+                let instr = if *op == BinaryOperator::And { "andq" } else { "orq" };
+                self.emit(&format!("{} %rcx, %rax", instr));
+            }
+            arithmetic => self.arithmetic(arithmetic, &operand_type)?,
+        }
+
+        Ok(())
+    }
+
+    fn arithmetic(&mut self, op: &BinaryOperator, ty: &Type) -> Result<(), BackendError> {
+        match ty {
+            Type::Decimal => {
+                let instr = match op {
+                    BinaryOperator::Add => "addsd",
+                    BinaryOperator::Subtract => "subsd",
+                    BinaryOperator::Multiply => "mulsd",
+                    BinaryOperator::Divide => "divsd",
+                    _ => return Err(BackendError::new(format!("Operador '{}' não suportado para decimais", op))),
+                };
+                self.emit(&format!("{} %xmm1, %xmm0", instr));
+            }
+            Type::Inteiro => match op {
+                BinaryOperator::Add => self.emit("addq %rcx, %rax"),
+                BinaryOperator::Subtract => self.emit("subq %rcx, %rax"),
+                BinaryOperator::Multiply => self.emit("imulq %rcx, %rax"),
+                // o idivq gera SIGFPE com divisor zero e em MIN / -1, e o
+                // sinal perderia a saída ainda no buffer: o zero vira o erro
+                // dos outros backends e o -1 vira o resultado do interpretador
+                // (MIN / -1 = MIN, MIN % -1 = 0)
+                BinaryOperator::Divide | BinaryOperator::Modulo => {
+                    self.use_runtime(DIVISION_BY_ZERO_ROUTINE);
+                    let divide = self.new_label();
+                    let end = self.new_label();
+                    self.emit("testq %rcx, %rcx");
+                    self.emit("jz rt.divisao_por_zero");
+                    self.emit("cmpq $-1, %rcx");
+                    self.emit(&format!("jne {}", divide));
+                    if *op == BinaryOperator::Divide {
+                        self.emit("negq %rax");
+                    } else {
+                        self.emit("xorl %eax, %eax");
+                    }
+                    self.emit(&format!("jmp {}", end));
+                    self.emit_label(&divide);
+                    self.emit("cqto");
+                    self.emit("idivq %rcx");
+                    if *op == BinaryOperator::Modulo {
+                        self.emit("movq %rdx, %rax");
+                    }
+                    self.emit_label(&end);
+                }
+                _ => return Err(BackendError::new(format!("Operador '{}' não suportado para inteiros", op))),
+            },
+            _ => return Err(BackendError::new(format!("Operador '{}' não suportado para o tipo {}", op, ty))),
+        }
+        Ok(())
+    }
+
+    // compara %rax/%xmm0 (esquerdo) com %rcx/%xmm1 (direito); resultado 0/1 em %rax
+    fn compare(&mut self, op: &BinaryOperator, ty: &Type) {
+        match ty {
+            Type::Decimal => {
+                // ucomisd sinaliza "não ordenado" (NaN) com PF; só != é verdadeiro
+                match op {
+                    BinaryOperator::Equal => {
+                        self.emit("ucomisd %xmm1, %xmm0");
+                        self.emit("sete %al");
+                        self.emit("setnp %cl");
+                        self.emit("andb %cl, %al");
+                    }
+                    BinaryOperator::NotEqual => {
+                        self.emit("ucomisd %xmm1, %xmm0");
+                        self.emit("setne %al");
+                        self.emit("setp %cl");
+                        self.emit("orb %cl, %al");
+                    }
+                    BinaryOperator::Less => {
+                        self.emit("ucomisd %xmm0, %xmm1");
+                        self.emit("seta %al");
+                    }
+                    BinaryOperator::LessEqual => {
+                        self.emit("ucomisd %xmm0, %xmm1");
+                        self.emit("setae %al");
+                    }
+                    BinaryOperator::Greater => {
+                        self.emit("ucomisd %xmm1, %xmm0");
+                        self.emit("seta %al");
+                    }
+                    _ => {
+                        self.emit("ucomisd %xmm1, %xmm0");
+                        self.emit("setae %al");
+                    }
+                }
+            }
+            _ => {
+                if *ty == Type::Texto {
+                    self.emit("movq %rax, %rdi");
+                    self.emit("movq %rcx, %rsi");
+                    self.emit_call("strcmp@PLT");
+                    self.emit("cmpl $0, %eax");
+                } else {
+                    self.emit("cmpq %rcx, %rax");
+                }
+                let set = match op {
+                    BinaryOperator::Equal => "sete",
+                    BinaryOperator::NotEqual => "setne",
+                    BinaryOperator::Less => "setl",
+                    BinaryOperator::LessEqual => "setle",
+                    BinaryOperator::Greater => "setg",
+                    _ => "setge",
+                };
+                self.emit(&format!("{} %al", set));
+            }
+        }
+        self.emit("movzbq %al, %rax");
+    }
+
+    fn generate_call(&mut self, call: &CallExpr) -> Result<Option<Type>, BackendError> {
+        if let Some((builtin, overload)) = self.env.builtin(call)? {
+            self.generate_builtin(builtin, overload, &call.arguments)?;
+            return Ok(Some(overload.return_type.clone()));
+        }
+
+        let function = self.env
+            .function(&call.function)
+            .ok_or_else(|| BackendError::new(format!("Função '{}' não declarada", call.function)))?;
+        let return_type = function.return_type.clone();
+        let types: Vec<Type> = function.parameters.iter().map(|p| p.param_type.clone()).collect();
+
+        if types.len() != call.arguments.len() {
+            return Err(BackendError::new(format!(
+                "Função '{}' espera {} argumentos, mas {} foram fornecidos",
+                call.function, types.len(), call.arguments.len()
+            )));
+        }
+
+        self.call_with_arguments(&format!("fn.{}", ascii_identifier(&call.function)), &call.arguments, &types)?;
+        Ok(return_type)
+    }
+
+    fn call_with_arguments(&mut self, symbol: &str, arguments: &[Expr], types: &[Type]) -> Result<(), BackendError> {
+        // avalia todos os argumentos na pilha, da esquerda para a direita
+        for (arg, ty) in arguments.iter().zip(types.iter()) {
+            self.generate_coerced(arg, ty)?;
+            self.push(ty);
+        }
+
+        let classes = classify_arguments(types);
+        let stack_args = classes.iter().filter(|c| matches!(c, ArgClass::Stack(_))).count() as i64;
+        let pushed = 8 * types.len() as i64;
+        let padding = if (self.stack_depth + 8 * stack_args) % 16 == 0 { 0 } else { 8 };
+        let reserved = 8 * stack_args + padding;
+
+        if reserved > 0 {
+            self.emit(&format!("subq ${}, %rsp", reserved));
+        }
+
+        // o argumento i foi empilhado em (pushed - 8 * (i + 1)) acima da área reservada
+        for (i, class) in classes.iter().enumerate() {
+            let source = format!("{}(%rsp)", reserved + pushed - 8 * (i as i64 + 1));
+            match class {
+                ArgClass::Int(r) => self.emit(&format!("movq {}, {}", source, INT_ARG_REGISTERS[*r])),
+                ArgClass::Float(r) => self.emit(&format!("movsd {}, %xmm{}", source, r)),
+                ArgClass::Stack(s) => {
+                    self.emit(&format!("movq {}, %rax", source));
+                    self.emit(&format!("movq %rax, {}(%rsp)", 8 * s));
+                }
+            }
+        }
+
+        self.emit(&format!("call {}", symbol));
+        if reserved + pushed > 0 {
+            self.emit(&format!("addq ${}, %rsp", reserved + pushed));
+        }
+        self.stack_depth -= pushed;
+        Ok(())
+    }
+
+    fn generate_builtin(&mut self, builtin: Builtin, overload: &Overload, arguments: &[Expr]) -> Result<(), BackendError> {
+        if matches!(builtin, Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico) {
+            let from = &overload.parameters[0];
+            self.generate_coerced(&arguments[0], from)?;
+            self.convert_explicitly(from, &overload.return_type);
+            return Ok(());
+        }
+
+        let text_routine = match builtin {
+            Builtin::Tamanho => Some(("rt.tamanho", TEXT_LENGTH_ROUTINE)),
+            Builtin::Maiusculo => Some(("rt.maiusculo", CASE_ROUTINE)),
+            Builtin::Minusculo => Some(("rt.minusculo", CASE_ROUTINE)),
+            Builtin::Subtexto => Some(("rt.subtexto", SUBSTRING_ROUTINE)),
+            Builtin::Contem => Some(("rt.contem", CONTAINS_ROUTINE)),
+            Builtin::Substituir => Some(("rt.substituir", REPLACE_ROUTINE)),
+            _ => None,
+        };
+        if let Some((symbol, routine)) = text_routine {
+            self.use_runtime(routine);
+            return self.call_with_arguments(symbol, arguments, overload.parameters);
+        }
+
+        let ty = &overload.parameters[0];
+        self.generate_coerced(&arguments[0], ty)?;
+
+        match (builtin, ty) {
+            (Builtin::Raiz, _) => self.emit("sqrtsd %xmm0, %xmm0"),
+            (Builtin::Potencia, _) => {
+                self.push(ty);
+                self.generate_coerced(&arguments[1], &overload.parameters[1])?;
+                self.pop_left_operand(ty);
+                if *ty == Type::Inteiro {
+                    self.emit("movq %rax, %rdi");
+                    self.emit("movq %rcx, %rsi");
+                    self.use_runtime(INT_POWER_ROUTINE);
+                    self.emit_call("rt.potencia_inteira");
+                } else {
+                    self.emit_call("pow@PLT");
+                }
+            }
+            (Builtin::Abs, Type::Inteiro) => {
+                self.emit("cqto");
+                self.emit("xorq %rdx, %rax");
+                self.emit("subq %rdx, %rax");
+            }
+            (Builtin::Abs, _) => {
+                self.emit("movq %xmm0, %rax");
+                self.emit("btrq $63, %rax");
+                self.emit("movq %rax, %xmm0");
+            }
+            (Builtin::Arredondar | Builtin::Piso | Builtin::Teto, Type::Inteiro) => {}
+            (Builtin::Arredondar, _) => self.emit_call("round@PLT"),
+            (Builtin::Piso, _) => self.emit_call("floor@PLT"),
+            (Builtin::Teto, _) => self.emit_call("ceil@PLT"),
+            _ => unreachable!("funções de texto são rotinas do runtime"),
+        }
+        Ok(())
+    }
+
+    // valor em %rax/%xmm0, resultado no mesmo lugar
+    fn convert_explicitly(&mut self, from: &Type, to: &Type) {
+        let routine = match (from, to) {
+            (Type::Decimal, Type::Inteiro) => ("rt.decimal_para_inteiro", DECIMAL_TO_INT_ROUTINE),
+            (Type::Texto, Type::Inteiro) => ("rt.texto_para_inteiro", TEXT_TO_INT_ROUTINE),
+            (Type::Texto, Type::Decimal) => ("rt.texto_para_decimal", TEXT_TO_DECIMAL_ROUTINE),
+            (Type::Inteiro, Type::Texto) => ("rt.inteiro_para_texto", INT_TO_TEXT_ROUTINE),
+            (Type::Decimal, Type::Texto) => ("rt.decimal_para_texto", DECIMAL_TO_TEXT_ROUTINE),
+            (Type::Texto, Type::Logico) => ("rt.texto_para_logico", TEXT_TO_LOGICAL_ROUTINE),
+            (Type::Logico, Type::Decimal) => return self.emit("cvtsi2sdq %rax, %xmm0"),
+            (Type::Logico, Type::Texto) => return self.select_logico_text(),
+            (Type::Inteiro, Type::Logico) => {
+                self.emit("testq %rax, %rax");
+                self.emit("setne %al");
+                self.emit("movzbl %al, %eax");
+                return;
+            }
+            // lógicos já são 0 ou 1
+            _ => return self.convert(from, to),
+        };
+
+        let (symbol, code) = routine;
+        if *from == Type::Texto {
+            self.use_runtime(CONVERSION_ERROR_ROUTINE);
+        }
+        self.use_runtime(code);
+        if *from != Type::Decimal {
+            self.emit("movq %rax, %rdi");
+        }
+        self.emit_call(symbol);
+    }
+
+    // === AUXILIARES ===
+
+    fn use_runtime(&mut self, routine: &'static str) {
+        if !self.runtime.contains(&routine) {
+            self.runtime.push(routine);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<(Location, Type), BackendError> {
+        self.env
+            .lookup(name)
+            .ok_or_else(|| BackendError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    fn allocate_slot(&mut self) -> Location {
+        self.frame_size += 8;
+        Location::Frame(-self.frame_size)
+    }
+
+    fn load(&mut self, location: &Location, ty: &Type) {
+        if *ty == Type::Decimal {
+            self.emit(&format!("movsd {}, %xmm0", location.operand()));
+        } else {
+            self.emit(&format!("movq {}, %rax", location.operand()));
+        }
+    }
+
+    fn store(&mut self, location: &Location, ty: &Type) {
+        if *ty == Type::Decimal {
+            self.emit(&format!("movsd %xmm0, {}", location.operand()));
+        } else {
+            self.emit(&format!("movq %rax, {}", location.operand()));
+        }
+    }
+
+    fn load_default(&mut self, ty: &Type) {
+        match ty {
+            Type::Decimal => self.emit("pxor %xmm0, %xmm0"),
+            Type::Texto => {
+                let empty = self.intern_string("");
+                self.emit(&format!("leaq {}(%rip), %rax", empty));
+            }
+            _ => self.emit("xorl %eax, %eax"),
+        }
+    }
+
+    // decimais são carregados pelo padrão de bits, sem tabela de constantes
+    fn load_decimal_into(&mut self, register: &str, value: f64) {
+        self.emit(&format!("movabsq ${}, %rax", value.to_bits() as i64));
+        self.emit(&format!("movq %rax, {}", register));
+    }
+
+    fn convert(&mut self, from: &Type, to: &Type) {
+        if *from == Type::Inteiro && *to == Type::Decimal {
+            self.emit("cvtsi2sdq %rax, %xmm0");
+        }
+    }
+
+    fn push(&mut self, ty: &Type) {
+        if *ty == Type::Decimal {
+            self.emit("subq $8, %rsp");
+            self.emit("movsd %xmm0, (%rsp)");
+        } else {
+            self.emit("pushq %rax");
+        }
+        self.stack_depth += 8;
+    }
+
+    fn pop(&mut self, register: &str) {
+        self.emit(&format!("popq {}", register));
+        self.stack_depth -= 8;
+    }
+
+    // move o operando direito para %rcx/%xmm1 e recupera o esquerdo da pilha
+    fn pop_left_operand(&mut self, ty: &Type) {
+        if *ty == Type::Decimal {
+            self.emit("movapd %xmm0, %xmm1");
+            self.emit("movsd (%rsp), %xmm0");
+            self.emit("addq $8, %rsp");
+            self.stack_depth -= 8;
+        } else {
+            self.emit("movq %rax, %rcx");
+            self.pop("%rax");
+        }
+    }
+
+    // troca o valor lógico em %rax pelo texto "verdadeiro" / "falso"
+    fn select_logico_text(&mut self) {
+        let verdadeiro = self.intern_string("verdadeiro");
+        let falso = self.intern_string("falso");
+        self.emit(&format!("leaq {}(%rip), %rcx", verdadeiro));
+        self.emit(&format!("leaq {}(%rip), %rdx", falso));
+        self.emit("testq %rax, %rax");
+        self.emit("cmovzq %rdx, %rcx");
+        self.emit("movq %rcx, %rax");
+    }
+
+    fn emit_printf(&mut self, format: &str, decimal: bool) {
+        self.emit(&format!("leaq {}(%rip), %rdi", format));
+        // %al informa quantos registradores vetoriais a função variádica recebe
+        self.emit(if decimal { "movl $1, %eax" } else { "xorl %eax, %eax" });
+        self.emit_call("printf@PLT");
+    }
+
+    // chamada com %rsp alinhado em 16 bytes, como exige a ABI
+    fn emit_call(&mut self, symbol: &str) {
+        if self.stack_depth % 16 == 0 {
+            self.emit(&format!("call {}", symbol));
+        } else {
+            self.emit("subq $8, %rsp");
+            self.emit(&format!("call {}", symbol));
+            self.emit("addq $8, %rsp");
+        }
+    }
+
+    fn intern_string(&mut self, value: &str) -> String {
+        if let Some(label) = self.strings.get(value) {
+            return label.clone();
+        }
+
+        let label = format!(".Lstr.{}", self.strings.len());
+        self.rodata.push(format!("{}:\n    .string \"{}\"", label, escape_gas_string(value)));
+        self.strings.insert(value.to_string(), label.clone());
+        label
+    }
+
+    fn new_label(&mut self) -> String {
+        self.label_counter += 1;
+        format!(".L{}", self.label_counter)
+    }
+
+    fn emit(&mut self, line: &str) {
+        self.lines.push(format!("    {}", line));
+    }
+
+    fn emit_label(&mut self, label: &str) {
+        self.lines.push(format!("{}:", label));
+    }
+}
+
+// %rdi elevado a %rsi por quadrados sucessivos; com expoente negativo só
+// as bases 1 e -1 não resultam em 0
+const INT_POWER_ROUTINE: &str = "rt.potencia_inteira:
+    movl $1, %eax
+    testq %rsi, %rsi
+    jns .Lrt.potencia.laco
+    leaq 1(%rdi), %rcx
+    cmpq $2, %rcx
+    ja .Lrt.potencia.zero
+    negq %rsi
+.Lrt.potencia.laco:
+    testq %rsi, %rsi
+    jle .Lrt.potencia.fim
+    testq $1, %rsi
+    jz .Lrt.potencia.par
+    imulq %rdi, %rax
+.Lrt.potencia.par:
+    imulq %rdi, %rdi
+    shrq $1, %rsi
+    jmp .Lrt.potencia.laco
+.Lrt.potencia.zero:
+    xorl %eax, %eax
+.Lrt.potencia.fim:
+    ret
+";
+
+// conta os bytes que não são de continuação do UTF-8 (10xxxxxx)
+const TEXT_LENGTH_ROUTINE: &str = "rt.tamanho:
+    xorl %eax, %eax
+.Lrt.tamanho.laco:
+    movzbl (%rdi), %ecx
+    testl %ecx, %ecx
+    jz .Lrt.tamanho.fim
+    andl $0xc0, %ecx
+    cmpl $0x80, %ecx
+    je .Lrt.tamanho.proximo
+    incq %rax
+.Lrt.tamanho.proximo:
+    incq %rdi
+    jmp .Lrt.tamanho.laco
+.Lrt.tamanho.fim:
+    ret
+";
+
+// Copia o texto trocando a caixa das letras: %esi é a primeira letra ASCII
+// a trocar e %edx o primeiro segundo byte das letras Latin-1 (c3 80-9e
+// maiúsculas, c3 a0-be minúsculas, sem × e ÷). Nos dois casos a troca é
+// inverter o bit 0x20, então o tamanho em bytes não muda.
+const CASE_ROUTINE: &str = "rt.maiusculo:
+    movl $0x61, %esi
+    movl $0xa0, %edx
+    jmp rt.trocar_caixa
+rt.minusculo:
+    movl $0x41, %esi
+    movl $0x80, %edx
+rt.trocar_caixa:
+    pushq %rbx
+    pushq %r12
+    pushq %r13
+    pushq %r14
+    pushq %r15
+    movq %rdi, %rbx
+    movl %esi, %r12d
+    movl %edx, %r13d
+    call strlen@PLT
+    movq %rax, %r14
+    leaq 1(%rax), %rdi
+    call malloc@PLT
+    movq %rax, %r15
+    xorl %ecx, %ecx
+    xorl %edx, %edx
+.Lrt.caixa.laco:
+    cmpq %r14, %rcx
+    ja .Lrt.caixa.fim
+    movzbl (%rbx,%rcx), %eax
+    movl %eax, %esi
+    subl %r12d, %esi
+    cmpl $26, %esi
+    jb .Lrt.caixa.troca
+    cmpl $0xc3, %edx
+    jne .Lrt.caixa.copia
+    movl %eax, %esi
+    subl %r13d, %esi
+    cmpl $0x1f, %esi
+    jae .Lrt.caixa.copia
+    cmpl $0x17, %esi
+    je .Lrt.caixa.copia
+.Lrt.caixa.troca:
+    xorl $0x20, %eax
+.Lrt.caixa.copia:
+    movb %al, (%r15,%rcx)
+    movl %eax, %edx
+    incq %rcx
+    jmp .Lrt.caixa.laco
+.Lrt.caixa.fim:
+    movq %r15, %rax
+    popq %r15
+    popq %r14
+    popq %r13
+    popq %r12
+    popq %rbx
+    ret
+";
+
+const CONCAT_ROUTINE: &str = "rt.concatenar:
+    pushq %rbx
+    pushq %r12
+    pushq %r13
+    pushq %r14
+    subq $8, %rsp
+    movq %rdi, %rbx
+    movq %rsi, %r12
+    call strlen@PLT
+    movq %rax, %r13
+    movq %r12, %rdi
+    call strlen@PLT
+    leaq 1(%r13,%rax), %rdi
+    call malloc@PLT
+    movq %rax, %r14
+    movq %rax, %rdi
+    movq %rbx, %rsi
+    movq %r13, %rdx
+    call memcpy@PLT
+    leaq (%r14,%r13), %rdi
+    movq %r12, %rsi
+    call strcpy@PLT
+    movq %r14, %rax
+    addq $8, %rsp
+    popq %r14
+    popq %r13
+    popq %r12
+    popq %rbx
+    ret
+";
+
+// subtexto(%rdi, %rsi = início a partir de 1, %rdx = quantidade): avança
+// caractere a caractere até o início e depois até o fim do trecho, sem
+// passar do terminador
+const SUBSTRING_ROUTINE: &str = "rt.subtexto:
+    pushq %rbx
+    pushq %r12
+    pushq %r13
+    movq %rdx, %r13
+    cmpq $1, %rsi
+    jge .Lrt.subtexto.inicio
+    movl $1, %esi
+.Lrt.subtexto.inicio:
+    leaq -1(%rsi), %rcx
+    call .Lrt.subtexto.avancar
+    movq %rdi, %rbx
+    movq %r13, %rcx
+    call .Lrt.subtexto.avancar
+    movq %rdi, %r12
+    subq %rbx, %r12
+    leaq 1(%r12), %rdi
+    call malloc@PLT
+    movq %rax, %r13
+    movq %rax, %rdi
+    movq %rbx, %rsi
+    movq %r12, %rdx
+    call memcpy@PLT
+    movb $0, (%r13,%r12)
+    movq %r13, %rax
+    popq %r13
+    popq %r12
+    popq %rbx
+    ret
+.Lrt.subtexto.avancar:
+    testq %rcx, %rcx
+    jle .Lrt.subtexto.avancou
+    cmpb $0, (%rdi)
+    je .Lrt.subtexto.avancou
+.Lrt.subtexto.continuacao:
+    incq %rdi
+    movzbl (%rdi), %eax
+    andl $0xc0, %eax
+    cmpl $0x80, %eax
+    je .Lrt.subtexto.continuacao
+    decq %rcx
+    jmp .Lrt.subtexto.avancar
+.Lrt.subtexto.avancou:
+    ret
+";
+
+const CONTAINS_ROUTINE: &str = "rt.contem:
+    subq $8, %rsp
+    call strstr@PLT
+    addq $8, %rsp
+    testq %rax, %rax
+    setne %al
+    movzbl %al, %eax
+    ret
+";
+
+// substituir(%rdi = texto, %rsi = de, %rdx = por): uma passada com strstr
+// calcula o tamanho do resultado e outra o monta. Textos são imutáveis,
+// então `de` vazio devolve o próprio texto.
+// Pilha: 0 = tamanho e depois o resultado, 8 = posição da busca e depois
+// o destino, 16 = bytes antes da ocorrência
+const REPLACE_ROUTINE: &str = "rt.substituir:
+    movq %rdi, %rax
+    cmpb $0, (%rsi)
+    je .Lrt.substituir.retorno
+    pushq %rbx
+    pushq %r12
+    pushq %r13
+    pushq %r14
+    pushq %r15
+    subq $32, %rsp
+    movq %rdi, %rbx
+    movq %rsi, %r12
+    movq %rdx, %r13
+    movq %rsi, %rdi
+    call strlen@PLT
+    movq %rax, %r14
+    movq %r13, %rdi
+    call strlen@PLT
+    movq %rax, %r15
+    movq %rbx, %rdi
+    call strlen@PLT
+    movq %rax, (%rsp)
+    movq %rbx, 8(%rsp)
+.Lrt.substituir.conta:
+    movq 8(%rsp), %rdi
+    movq %r12, %rsi
+    call strstr@PLT
+    testq %rax, %rax
+    jz .Lrt.substituir.aloca
+    addq %r14, %rax
+    movq %rax, 8(%rsp)
+    movq (%rsp), %rax
+    addq %r15, %rax
+    subq %r14, %rax
+    movq %rax, (%rsp)
+    jmp .Lrt.substituir.conta
+.Lrt.substituir.aloca:
+    movq (%rsp), %rdi
+    incq %rdi
+    call malloc@PLT
+    movq %rax, (%rsp)
+    movq %rax, 8(%rsp)
+.Lrt.substituir.copia:
+    movq %rbx, %rdi
+    movq %r12, %rsi
+    call strstr@PLT
+    testq %rax, %rax
+    jz .Lrt.substituir.resto
+    movq %rax, %rdx
+    subq %rbx, %rdx
+    movq %rdx, 16(%rsp)
+    leaq (%rax,%r14), %rbx
+    movq 8(%rsp), %rdi
+    movq %rax, %rsi
+    subq %rdx, %rsi
+    call memcpy@PLT
+    movq 8(%rsp), %rdi
+    addq 16(%rsp), %rdi
+    leaq (%rdi,%r15), %rax
+    movq %rax, 8(%rsp)
+    movq %r13, %rsi
+    movq %r15, %rdx
+    call memcpy@PLT
+    jmp .Lrt.substituir.copia
+.Lrt.substituir.resto:
+    movq 8(%rsp), %rdi
+    movq %rbx, %rsi
+    call strcpy@PLT
+    movq (%rsp), %rax
+    addq $32, %rsp
+    popq %r15
+    popq %r14
+    popq %r13
+    popq %r12
+    popq %rbx
+.Lrt.substituir.retorno:
+    ret
+";
+
+// cvttsd2si devolve o menor inteiro para nan e para valores fora da faixa;
+// aqui nan vira 0 e os positivos ficam no maior inteiro
+const DECIMAL_TO_INT_ROUTINE: &str = "rt.decimal_para_inteiro:
+    cvttsd2siq %xmm0, %rax
+    movabsq $-9223372036854775808, %rcx
+    cmpq %rcx, %rax
+    jne .Lrt.decimal_inteiro.fim
+    xorl %eax, %eax
+    ucomisd %xmm0, %xmm0
+    jp .Lrt.decimal_inteiro.fim
+    movq %rcx, %rax
+    pxor %xmm1, %xmm1
+    ucomisd %xmm1, %xmm0
+    jb .Lrt.decimal_inteiro.fim
+    notq %rax
+.Lrt.decimal_inteiro.fim:
+    ret
+";
+
+// sinal opcional e dígitos; o valor é acumulado em negativo (a faixa vai
+// até -2^63) e o transbordamento aparece no flag OF
+const TEXT_TO_INT_ROUTINE: &str = "rt.texto_para_inteiro:
+    xorl %eax, %eax
+    xorl %edx, %edx
+    movzbl (%rdi), %ecx
+    cmpl $0x2b, %ecx
+    je .Lrt.texto_inteiro.sinal
+    cmpl $0x2d, %ecx
+    jne .Lrt.texto_inteiro.vazio
+    incl %edx
+.Lrt.texto_inteiro.sinal:
+    incq %rdi
+.Lrt.texto_inteiro.vazio:
+    cmpb $0, (%rdi)
+    je .Lrt.texto_inteiro.invalido
+.Lrt.texto_inteiro.laco:
+    movzbl (%rdi), %ecx
+    testl %ecx, %ecx
+    jz .Lrt.texto_inteiro.fim
+    subl $0x30, %ecx
+    cmpl $9, %ecx
+    ja .Lrt.texto_inteiro.invalido
+    imulq $10, %rax
+    jo .Lrt.texto_inteiro.invalido
+    subq %rcx, %rax
+    jo .Lrt.texto_inteiro.invalido
+    incq %rdi
+    jmp .Lrt.texto_inteiro.laco
+.Lrt.texto_inteiro.fim:
+    testl %edx, %edx
+    jnz .Lrt.texto_inteiro.retorno
+    negq %rax
+    jo .Lrt.texto_inteiro.invalido
+.Lrt.texto_inteiro.retorno:
+    ret
+.Lrt.texto_inteiro.invalido:
+    leaq .Lrt.conversao.inteiro(%rip), %rsi
+    jmp rt.conversao_invalida
+";
+
+// confere o formato (sinal, dígitos, fração e expoente opcionais) antes do
+// strtod, que aceitaria também espaços, "nan", "0x10"...
+const TEXT_TO_DECIMAL_ROUTINE: &str = "rt.texto_para_decimal:
+    movq %rdi, %rsi
+    call .Lrt.texto_decimal.sinal
+    call .Lrt.texto_decimal.digitos
+    je .Lrt.texto_decimal.invalido
+    cmpb $0x2e, (%rsi)
+    jne .Lrt.texto_decimal.expoente
+    incq %rsi
+    call .Lrt.texto_decimal.digitos
+    je .Lrt.texto_decimal.invalido
+.Lrt.texto_decimal.expoente:
+    movzbl (%rsi), %eax
+    orl $0x20, %eax
+    cmpl $0x65, %eax
+    jne .Lrt.texto_decimal.fim
+    incq %rsi
+    call .Lrt.texto_decimal.sinal
+    call .Lrt.texto_decimal.digitos
+    je .Lrt.texto_decimal.invalido
+.Lrt.texto_decimal.fim:
+    cmpb $0, (%rsi)
+    jne .Lrt.texto_decimal.invalido
+    subq $8, %rsp
+    xorl %esi, %esi
+    call strtod@PLT
+    addq $8, %rsp
+    ret
+.Lrt.texto_decimal.invalido:
+    leaq .Lrt.conversao.decimal(%rip), %rsi
+    jmp rt.conversao_invalida
+.Lrt.texto_decimal.sinal:
+    movzbl (%rsi), %eax
+    cmpl $0x2b, %eax
+    je .Lrt.texto_decimal.pula
+    cmpl $0x2d, %eax
+    jne .Lrt.texto_decimal.sem_sinal
+.Lrt.texto_decimal.pula:
+    incq %rsi
+.Lrt.texto_decimal.sem_sinal:
+    ret
+.Lrt.texto_decimal.digitos:
+    movq %rsi, %rax
+.Lrt.texto_decimal.digito:
+    movzbl (%rsi), %ecx
+    subl $0x30, %ecx
+    cmpl $9, %ecx
+    ja .Lrt.texto_decimal.contou
+    incq %rsi
+    jmp .Lrt.texto_decimal.digito
+.Lrt.texto_decimal.contou:
+    cmpq %rax, %rsi
+    ret
+";
+
+const INT_TO_TEXT_ROUTINE: &str = "rt.inteiro_para_texto:
+    pushq %rbx
+    movq %rdi, %rbx
+    movl $24, %edi
+    call malloc@PLT
+    movq %rax, %rdi
+    leaq .Lrt.inteiro_texto.formato(%rip), %rsi
+    movq %rbx, %rdx
+    movq %rax, %rbx
+    xorl %eax, %eax
+    call sprintf@PLT
+    movq %rbx, %rax
+    popq %rbx
+    ret
+    .section .rodata
+.Lrt.inteiro_texto.formato:
+    .string \"%ld\"
+    .text
+";
+
+// o tamanho vem de um primeiro snprintf sem destino; nan perde o sinal,
+// como em `escreva`
+const DECIMAL_TO_TEXT_ROUTINE: &str = "rt.decimal_para_texto:
+    pushq %rbx
+    subq $16, %rsp
+    ucomisd %xmm0, %xmm0
+    jnp .Lrt.decimal_texto.ordenado
+    movq %xmm0, %rax
+    btrq $63, %rax
+    movq %rax, %xmm0
+.Lrt.decimal_texto.ordenado:
+    movsd %xmm0, (%rsp)
+    xorl %edi, %edi
+    xorl %esi, %esi
+    leaq .Lrt.decimal_texto.formato(%rip), %rdx
+    movl $1, %eax
+    call snprintf@PLT
+    movslq %eax, %rbx
+    incq %rbx
+    movq %rbx, %rdi
+    call malloc@PLT
+    movq %rax, 8(%rsp)
+    movq %rax, %rdi
+    movq %rbx, %rsi
+    leaq .Lrt.decimal_texto.formato(%rip), %rdx
+    movsd (%rsp), %xmm0
+    movl $1, %eax
+    call snprintf@PLT
+    movq 8(%rsp), %rax
+    addq $16, %rsp
+    popq %rbx
+    ret
+    .section .rodata
+.Lrt.decimal_texto.formato:
+    .string \"%f\"
+    .text
+";
+
+const TEXT_TO_LOGICAL_ROUTINE: &str = "rt.texto_para_logico:
+    pushq %rbx
+    movq %rdi, %rbx
+    leaq .Lrt.texto_logico.verdadeiro(%rip), %rsi
+    call strcmp@PLT
+    testl %eax, %eax
+    jz .Lrt.texto_logico.sim
+    movq %rbx, %rdi
+    leaq .Lrt.texto_logico.falso(%rip), %rsi
+    call strcmp@PLT
+    testl %eax, %eax
+    jnz .Lrt.texto_logico.invalido
+    popq %rbx
+    xorl %eax, %eax
+    ret
+.Lrt.texto_logico.sim:
+    popq %rbx
+    movl $1, %eax
+    ret
+.Lrt.texto_logico.invalido:
+    leaq .Lrt.conversao.logico(%rip), %rsi
+    jmp rt.conversao_invalida
+    .section .rodata
+.Lrt.texto_logico.verdadeiro:
+    .string \"verdadeiro\"
+.Lrt.texto_logico.falso:
+    .string \"falso\"
+    .text
+";
+
+// escreve "Erro: conversão inválida para <%rsi>" e encerra com código 1
+const CONVERSION_ERROR_ROUTINE: &str = "rt.conversao_invalida:
+    andq $-16, %rsp
+    leaq .Lrt.conversao.mensagem(%rip), %rdi
+    xorl %eax, %eax
+    call printf@PLT
+    movl $1, %edi
+    call exit@PLT
+    .section .rodata
+.Lrt.conversao.mensagem:
+    .string \"Erro: convers\\303\\243o inv\\303\\241lida para %s\\n\"
+.Lrt.conversao.inteiro:
+    .string \"inteiro\"
+.Lrt.conversao.decimal:
+    .string \"decimal\"
+.Lrt.conversao.logico:
+    .string \"logico\"
+    .text
+";
+
+// escreve "Erro: divisão por zero" e encerra com código 1; o exit esvazia o
+// buffer do printf, então a saída anterior aparece antes da mensagem
+const DIVISION_BY_ZERO_ROUTINE: &str = "rt.divisao_por_zero:
+    andq $-16, %rsp
+    leaq .Lrt.divisao.mensagem(%rip), %rdi
+    xorl %eax, %eax
+    call printf@PLT
+    movl $1, %edi
+    call exit@PLT
+    .section .rodata
+.Lrt.divisao.mensagem:
+    .string \"Erro: divis\\303\\243o por zero\\n\"
+    .text
+";
+
+pub fn generate_x86_64(program: &Program) -> Result<String, BackendError> {
+    X86Generator::new(program).generate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::semantic::SemanticAnalyzer;
+
+    fn generate(source: &str) -> String {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        generate_x86_64(&result.annotated_ast).unwrap()
+    }
+
+    #[test]
+    fn test_arguments_follow_system_v() {
+        let types = vec![
+            Type::Inteiro, Type::Decimal, Type::Texto, Type::Inteiro, Type::Logico,
+            Type::Inteiro, Type::Inteiro, Type::Inteiro, Type::Decimal,
+        ];
+        let classes = classify_arguments(&types);
+        assert!(matches!(classes[1], ArgClass::Float(0)));
+        assert!(matches!(classes[5], ArgClass::Int(4)));
+        assert!(matches!(classes[6], ArgClass::Int(5)));
+        assert!(matches!(classes[7], ArgClass::Stack(0)));
+        assert!(matches!(classes[8], ArgClass::Float(1)));
+    }
+
+    #[test]
+    fn test_functions_and_io() {
+        let asm = generate(
+            "funcao decimal media(inteiro a, decimal b) { retorne (a + b) / 2; }
+             funcao principal() { decimal m = media(1, 2.5); escreva(\"Média:\", m, m > 1.0); }",
+        );
+
+        assert!(asm.contains(".globl main"));
+        assert!(asm.contains("fn.media:"));
+        assert!(asm.contains("cvtsi2sdq %rax, %xmm0"));
+        assert!(asm.contains("divsd %xmm1, %xmm0"));
+        assert!(asm.contains("call printf@PLT"));
+        assert!(asm.contains("call fn.principal"));
+        // "Média:" tem um byte não ASCII escapado em octal
+        assert!(asm.contains("M\\303\\251dia:"));
+    }
+
+    #[test]
+    fn test_globals_without_initializer() {
+        // o texto global começa vazio, não com o ponteiro nulo do .quad 0
+        let asm = generate("texto t;\nfuncao principal() { escreva(t + \"x\"); }");
+        let main = &asm[asm.find("main:").unwrap()..];
+        assert!(main.contains("movq %rax, var.t(%rip)"), "{}", main);
+    }
+
+    #[test]
+    fn test_integer_division_checks_the_divisor() {
+        let asm = generate("funcao principal() { inteiro a = 7; inteiro b = 0; escreva(a / b, a % b); }");
+        // o desvio vem antes de cada idivq, e a rotina aparece uma vez
+        assert_eq!(asm.matches("jz rt.divisao_por_zero").count(), 2);
+        assert_eq!(asm.matches("cmpq $-1, %rcx").count(), 2);
+        assert_eq!(asm.matches("rt.divisao_por_zero:").count(), 1);
+        assert!(asm.find("jz rt.divisao_por_zero").unwrap() < asm.find("idivq %rcx").unwrap());
+    }
+}
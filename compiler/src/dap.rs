@@ -0,0 +1,653 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use serde_json::{json, Value as Json};
+use crate::debugger::{breakpoint_line, global_variables, local_variables, show_value, statement_lines, Resume, StopReason, Stepper};
+use crate::diagnostics::Diagnostic;
+use crate::golden::analyze;
+use crate::interpreter::{Frame, Hook, Interpreter, RuntimeError, Value, STACK_SIZE};
+use crate::Program;
+
+// Servidor do Debug Adapter Protocol (`compiler dap`) para depurar no editor.
+// As mensagens chegam pela entrada padrão e saem pela saída padrão, cada uma
+// com um cabeçalho Content-Length seguido do corpo em JSON.
+//
+// O programa roda no interpretador numa thread própria e para onde o
+// depurador de terminal pararia (ver `Stepper`). Enquanto ele está parado, a
+// thread principal responde stackTrace, scopes e variables com a cópia da
+// pilha tirada na parada. O que o `escreva` mostra vira evento output, e cada
+// linha digitada no console de depuração (evaluate no contexto repl) é uma
+// linha de entrada para o `leia`.
+//
+// Só há uma thread, e os pontos de parada valem para o arquivo do launch.
+
+const THREAD_ID: u64 = 1;
+// referências de variáveis: as globais e, a partir de LOCALS, as locais do
+// quadro N em LOCALS + N
+const GLOBALS: usize = 1;
+const LOCALS: usize = 2;
+
+// leitor alimentado por um canal; o canal fechado é o fim da entrada
+struct ChannelInput {
+    receiver: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl ChannelInput {
+    fn new(receiver: Receiver<Vec<u8>>) -> Self {
+        Self { receiver, chunk: Vec::new(), position: 0 }
+    }
+}
+
+impl Read for ChannelInput {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buffer.len());
+        buffer[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for ChannelInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position >= self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.position = 0;
+                }
+                Err(_) => return Ok(&[]),
+            }
+        }
+        Ok(&self.chunk[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+// a saída para o cliente, dividida entre a thread principal (respostas) e a
+// do interpretador (eventos)
+struct Connection {
+    writer: Mutex<(Box<dyn Write + Send>, u64)>,
+}
+
+impl Connection {
+    // sem o cliente do outro lado não há a quem avisar de um erro de escrita
+    fn send(&self, mut message: Json) {
+        let mut guard = self.writer.lock().expect("nenhuma thread para no meio de uma escrita");
+        let (writer, seq) = &mut *guard;
+        *seq += 1;
+        message["seq"] = json!(*seq);
+        let body = message.to_string();
+        let _ = write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+        let _ = writer.flush();
+    }
+
+    fn event(&self, event: &str, body: Json) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+
+    fn respond(&self, request: &Json, result: Result<Json, String>) {
+        let mut response = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": result.is_ok(),
+        });
+        match result {
+            Ok(body) => response["body"] = body,
+            Err(message) => response["message"] = json!(message),
+        }
+        self.send(response);
+    }
+}
+
+// a saída do programa em eventos output de linhas inteiras; o interpretador
+// esvazia a saída antes de cada `leia`, então perguntas sem quebra de linha
+// também aparecem a tempo
+struct EventOutput {
+    connection: Arc<Connection>,
+    pending: Vec<u8>,
+}
+
+impl EventOutput {
+    fn emit(&mut self, end: usize) {
+        let text: Vec<u8> = self.pending.drain(..end).collect();
+        if !text.is_empty() {
+            let output = String::from_utf8_lossy(&text);
+            self.connection.event("output", json!({ "category": "stdout", "output": output }));
+        }
+    }
+}
+
+impl Write for EventOutput {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buffer);
+        if let Some(newline) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            self.emit(newline + 1);
+        }
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.emit(self.pending.len());
+        Ok(())
+    }
+}
+
+// o que a thread principal manda para a do interpretador
+enum Control {
+    Resume(Resume),
+    Breakpoints(BTreeSet<usize>),
+    Terminate,
+}
+
+// o gancho do interpretador: avisa as paradas e espera o controle seguinte
+struct Adapter {
+    stepper: Stepper,
+    connection: Arc<Connection>,
+    frames: Arc<Mutex<Vec<Frame>>>,
+    controls: Receiver<Control>,
+    // a próxima parada é a do início (stopOnEntry)
+    entry: bool,
+}
+
+impl Adapter {
+    // true quando o controle manda o programa seguir
+    fn apply(&mut self, control: Control, line: usize) -> Result<bool, RuntimeError> {
+        match control {
+            Control::Resume(resume) => {
+                self.stepper.resume(resume);
+                Ok(true)
+            }
+            Control::Breakpoints(lines) => {
+                self.stepper.breakpoints = lines;
+                Ok(false)
+            }
+            Control::Terminate => Err(RuntimeError { message: "Execução interrompida".to_string(), line }),
+        }
+    }
+}
+
+impl Hook for Adapter {
+    fn before_statement(&mut self, frames: &[Frame]) -> Result<(), RuntimeError> {
+        let line = frames.last().map_or(0, |frame| frame.line);
+        // pontos de parada trocados com o programa rodando
+        while let Ok(control) = self.controls.try_recv() {
+            self.apply(control, line)?;
+        }
+        let Some(reason) = self.stepper.should_stop(frames) else {
+            return Ok(());
+        };
+        let reason = match reason {
+            _ if self.entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+        };
+        self.entry = false;
+        *self.frames.lock().expect("a pilha só é copiada") = frames.to_vec();
+        self.connection.event("stopped", json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }));
+
+        loop {
+            // a thread principal foi embora: como terminate
+            let control = self.controls.recv().unwrap_or(Control::Terminate);
+            if self.apply(control, line)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn describe(diagnostic: &Diagnostic) -> String {
+    match diagnostic.span.line {
+        Some(line) => format!("{}:{}: {}", diagnostic.span.file, line, diagnostic.message),
+        None => format!("{}: {}", diagnostic.span.file, diagnostic.message),
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn variable(name: &str, value: &Value) -> Json {
+    json!({ "name": name, "value": show_value(value), "type": value.type_().to_string(), "variablesReference": 0 })
+}
+
+const NOT_RUNNING: &str = "O programa não está em execução";
+
+struct Session {
+    connection: Arc<Connection>,
+    path: Option<PathBuf>,
+    // o programa do launch, até começar a rodar no configurationDone
+    launched: Option<Program>,
+    lines: BTreeSet<usize>,
+    breakpoints: BTreeSet<usize>,
+    stop_on_entry: bool,
+    configured: bool,
+    // a pilha na última parada
+    frames: Arc<Mutex<Vec<Frame>>>,
+    terminated: Arc<AtomicBool>,
+    controls: Option<Sender<Control>>,
+    // o controle de next, stepIn, stepOut e continue só segue depois da
+    // resposta: o DAP quer a resposta antes do stopped seguinte
+    pending: Option<Control>,
+    program_input: Option<Sender<Vec<u8>>>,
+}
+
+impl Session {
+    fn new(connection: Arc<Connection>) -> Self {
+        Self {
+            connection,
+            path: None,
+            launched: None,
+            lines: BTreeSet::new(),
+            breakpoints: BTreeSet::new(),
+            stop_on_entry: false,
+            configured: false,
+            frames: Arc::new(Mutex::new(Vec::new())),
+            terminated: Arc::new(AtomicBool::new(false)),
+            controls: None,
+            pending: None,
+            program_input: None,
+        }
+    }
+
+    fn frames(&self) -> MutexGuard<'_, Vec<Frame>> {
+        self.frames.lock().expect("a pilha só é copiada")
+    }
+
+    // false depois do disconnect
+    fn handle(&mut self, request: &Json) -> bool {
+        let command = request["command"].as_str().unwrap_or("");
+        let arguments = &request["arguments"];
+        let result = match command {
+            "initialize" => Ok(json!({ "supportsConfigurationDoneRequest": true, "supportsTerminateRequest": true })),
+            "launch" => self.launch(arguments),
+            "setBreakpoints" => self.set_breakpoints(arguments),
+            "configurationDone" => {
+                self.configured = true;
+                Ok(json!({}))
+            }
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "principal" }] })),
+            "stackTrace" => Ok(self.stack_trace()),
+            "scopes" => self.scopes(arguments),
+            "variables" => self.variables(arguments),
+            "next" => self.resume(Resume::Next),
+            "stepIn" => self.resume(|_| Resume::Step),
+            "stepOut" => self.resume(Resume::Finish),
+            "continue" => self.resume(|_| Resume::Continue).map(|_| json!({ "allThreadsContinued": true })),
+            "evaluate" => self.evaluate(arguments),
+            "terminate" | "disconnect" => {
+                self.terminate();
+                Ok(json!({}))
+            }
+            other => Err(format!("Pedido não suportado: {}", other)),
+        };
+
+        // os pontos de parada só fazem sentido com o programa carregado
+        let launched = command == "launch" && result.is_ok();
+        self.connection.respond(request, result);
+        if launched {
+            self.connection.event("initialized", json!({}));
+        }
+        if let Some(control) = self.pending.take()
+            && let Some(controls) = &self.controls
+        {
+            let _ = controls.send(control);
+        }
+        self.start();
+        command != "disconnect"
+    }
+
+    fn launch(&mut self, arguments: &Json) -> Result<Json, String> {
+        if self.path.is_some() {
+            return Err("O programa já foi carregado".to_string());
+        }
+        let path = PathBuf::from(arguments["program"].as_str().ok_or("launch precisa do caminho do programa em 'program'")?);
+        let source = fs::read_to_string(&path).map_err(|e| format!("Erro ao ler arquivo {}: {}", path.display(), e))?;
+        let program = analyze(&source, &path).map_err(|errors| errors.iter().map(describe).collect::<Vec<_>>().join("\n"))?;
+
+        self.lines = statement_lines(&program);
+        self.stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+        self.launched = Some(program);
+        self.path = Some(path);
+        Ok(json!({}))
+    }
+
+    // começa a execução quando há programa e a configuração terminou
+    fn start(&mut self) {
+        if !self.configured {
+            return;
+        }
+        let Some(program) = self.launched.take() else {
+            return;
+        };
+        let (controls, control_receiver) = channel();
+        let (program_input, input_receiver) = channel();
+        self.controls = Some(controls);
+        self.program_input = Some(program_input);
+
+        let mut stepper = Stepper::new();
+        stepper.breakpoints = self.breakpoints.clone();
+        if !self.stop_on_entry {
+            stepper.resume(Resume::Continue);
+        }
+        let mut adapter = Adapter {
+            stepper,
+            connection: self.connection.clone(),
+            frames: self.frames.clone(),
+            controls: control_receiver,
+            entry: self.stop_on_entry,
+        };
+        let connection = self.connection.clone();
+        let terminated = self.terminated.clone();
+
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let mut input = ChannelInput::new(input_receiver);
+                let mut output = EventOutput { connection: connection.clone(), pending: Vec::new() };
+                let result = Interpreter::new(&program, &mut input, &mut output).with_hook(&mut adapter).run(u64::MAX);
+                let _ = output.flush();
+
+                let exit_code = match result {
+                    Ok(()) => 0,
+                    Err(_) if terminated.load(Ordering::SeqCst) => 1,
+                    Err(e) => {
+                        connection.event("output", json!({ "category": "stderr", "output": format!("{}\n", e) }));
+                        1
+                    }
+                };
+                connection.event("exited", json!({ "exitCode": exit_code }));
+                connection.event("terminated", json!({}));
+            })
+            .expect("não foi possível criar a thread do interpretador");
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Json) -> Result<Json, String> {
+        let path = self.path.as_ref().ok_or("Nenhum programa carregado")?;
+        let requested: Vec<u64> = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| breakpoints.iter().filter_map(|breakpoint| breakpoint["line"].as_u64()).collect())
+            .unwrap_or_default();
+        let ours = arguments["source"]["path"].as_str().is_some_and(|source| same_file(Path::new(source), path));
+
+        let mut breakpoints = BTreeSet::new();
+        let mut results = Vec::new();
+        for line in requested {
+            match breakpoint_line(&self.lines, line as usize).filter(|_| ours) {
+                Some(actual) => {
+                    breakpoints.insert(actual);
+                    results.push(json!({ "verified": true, "line": actual }));
+                }
+                None => {
+                    let message = if ours { "Nenhum comando nesta linha ou depois dela" } else { "Pontos de parada só valem no programa depurado" };
+                    results.push(json!({ "verified": false, "line": line, "message": message }));
+                }
+            }
+        }
+        if ours {
+            if let Some(controls) = &self.controls {
+                let _ = controls.send(Control::Breakpoints(breakpoints.clone()));
+            }
+            self.breakpoints = breakpoints;
+        }
+        Ok(json!({ "breakpoints": results }))
+    }
+
+    // o quadro 0 é o do programa; o cliente recebe o mais interno primeiro.
+    // Sem comando global executado o quadro do programa está na linha 0, e as
+    // linhas do DAP começam em 1
+    fn stack_trace(&self) -> Json {
+        let frames = self.frames();
+        let source = self.path.as_ref().map(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            json!({ "name": name, "path": path.display().to_string() })
+        });
+        let stack: Vec<Json> = (0..frames.len())
+            .rev()
+            .map(|index| json!({ "id": index, "name": frames[index].function, "line": frames[index].line.max(1), "column": 1, "source": source }))
+            .collect();
+        json!({ "stackFrames": stack, "totalFrames": frames.len() })
+    }
+
+    fn scopes(&self, arguments: &Json) -> Result<Json, String> {
+        let frame = arguments["frameId"].as_u64().ok_or("scopes precisa de 'frameId'")? as usize;
+        Ok(json!({ "scopes": [
+            { "name": "Locais", "variablesReference": LOCALS + frame, "expensive": false },
+            { "name": "Globais", "variablesReference": GLOBALS, "expensive": false },
+        ] }))
+    }
+
+    fn variables(&self, arguments: &Json) -> Result<Json, String> {
+        let reference = arguments["variablesReference"].as_u64().ok_or("variables precisa de 'variablesReference'")? as usize;
+        let frames = self.frames();
+        let variables = match reference {
+            GLOBALS => global_variables(&frames),
+            _ if reference >= LOCALS && reference - LOCALS < frames.len() => local_variables(&frames, reference - LOCALS),
+            _ => return Err(format!("Referência de variáveis inválida: {}", reference)),
+        };
+        let variables: Vec<Json> = variables.iter().map(|(name, value)| variable(name, value)).collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    // next e stepOut partem da pilha da última parada
+    fn resume(&mut self, resume: impl FnOnce(usize) -> Resume) -> Result<Json, String> {
+        if self.controls.is_none() {
+            return Err(NOT_RUNNING.to_string());
+        }
+        let depth = self.frames().len();
+        self.pending = Some(Control::Resume(resume(depth)));
+        Ok(json!({}))
+    }
+
+    // no console (repl), a linha digitada é entrada para o `leia`; nos outros
+    // contextos (watch, hover) a expressão é o nome de uma variável
+    fn evaluate(&self, arguments: &Json) -> Result<Json, String> {
+        let expression = arguments["expression"].as_str().unwrap_or("").trim();
+        if arguments["context"] == "repl" {
+            let input = self.program_input.as_ref().ok_or(NOT_RUNNING)?;
+            input.send(format!("{}\n", expression).into_bytes()).map_err(|_| NOT_RUNNING.to_string())?;
+            return Ok(json!({ "result": "", "variablesReference": 0 }));
+        }
+
+        let frames = self.frames();
+        let index = arguments["frameId"].as_u64().map_or(frames.len().saturating_sub(1), |id| id as usize);
+        if index >= frames.len() {
+            return Err(NOT_RUNNING.to_string());
+        }
+        let locals = local_variables(&frames, index);
+        let found = locals.into_iter().chain(global_variables(&frames)).find(|(name, _)| *name == expression);
+        match found {
+            Some((_, value)) => Ok(json!({ "result": show_value(value), "type": value.type_().to_string(), "variablesReference": 0 })),
+            None => Err(format!("Nenhuma variável '{}' visível aqui", expression)),
+        }
+    }
+
+    // interrompe o programa; um `leia` esperando recebe o fim da entrada
+    fn terminate(&mut self) {
+        self.terminated.store(true, Ordering::SeqCst);
+        if let Some(controls) = self.controls.take() {
+            let _ = controls.send(Control::Terminate);
+        }
+        self.program_input = None;
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// a próxima mensagem; None no fim da entrada
+fn read_message(input: &mut dyn BufRead) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse().map_err(|_| invalid(format!("Content-Length inválido: {}", value.trim())))?);
+        } else if header.is_empty()
+            && let Some(length) = length
+        {
+            let mut body = vec![0; length];
+            input.read_exact(&mut body)?;
+            return serde_json::from_slice(&body).map(Some).map_err(|e| invalid(format!("Mensagem inválida: {}", e)));
+        }
+    }
+}
+
+// atende os pedidos que chegam por `input` até o disconnect ou o fim da
+// entrada; respostas e eventos vão para `output`
+pub fn serve(input: &mut dyn BufRead, output: Box<dyn Write + Send>) -> io::Result<()> {
+    let connection = Arc::new(Connection { writer: Mutex::new((output, 0)) });
+    let mut session = Session::new(connection);
+    while let Some(message) = read_message(input)? {
+        // respostas a pedidos do servidor, que ele não faz
+        if message["type"] != "request" {
+            continue;
+        }
+        if !session.handle(&message) {
+            return Ok(());
+        }
+    }
+    session.terminate();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a saída do servidor, em pedaços, para a thread do teste
+    struct Pipe(Sender<Vec<u8>>);
+
+    impl Write for Pipe {
+        fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+            let _ = self.0.send(buffer.to_vec());
+            Ok(buffer.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Client {
+        requests: Sender<Vec<u8>>,
+        output: ChannelInput,
+        seq: u64,
+        // mensagens lidas antes de alguém esperar por elas
+        received: Vec<Json>,
+        printed: String,
+    }
+
+    impl Client {
+        fn request(&mut self, command: &str, arguments: Json) -> Json {
+            self.seq += 1;
+            let body = json!({ "seq": self.seq, "type": "request", "command": command, "arguments": arguments }).to_string();
+            self.requests.send(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()).unwrap();
+            let seq = self.seq;
+            self.wait(|message| message["type"] == "response" && message["request_seq"] == seq)
+        }
+
+        fn wait(&mut self, wanted: impl Fn(&Json) -> bool) -> Json {
+            if let Some(index) = self.received.iter().position(&wanted) {
+                return self.received.remove(index);
+            }
+            loop {
+                let message = read_message(&mut self.output).unwrap().expect("o servidor encerrou antes");
+                if message["event"] == "output" {
+                    self.printed.push_str(message["body"]["output"].as_str().unwrap());
+                }
+                if wanted(&message) {
+                    return message;
+                }
+                self.received.push(message);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dap_session() {
+        let source = "funcao inteiro dobro(inteiro n) {
+    inteiro r = n * 2;
+    retorne r;
+}
+
+funcao principal() {
+    inteiro x;
+    leia(x);
+    escreva(dobro(x));
+    escreva(\"fim\");
+}
+";
+        let path = std::env::temp_dir().join(format!("symplia_dap_{}.sym", std::process::id()));
+        fs::write(&path, source).unwrap();
+        let (requests, request_receiver) = channel();
+        let (output, output_receiver) = channel();
+        let server = thread::spawn(move || serve(&mut ChannelInput::new(request_receiver), Box::new(Pipe(output))));
+        let mut client = Client { requests, output: ChannelInput::new(output_receiver), seq: 0, received: Vec::new(), printed: String::new() };
+
+        assert_eq!(client.request("initialize", json!({ "adapterID": "symplia" }))["body"]["supportsConfigurationDoneRequest"], true);
+        assert_eq!(client.request("launch", json!({ "program": "nao_existe.sym" }))["success"], false);
+        assert_eq!(client.request("launch", json!({ "program": path }))["success"], true);
+        client.wait(|message| message["event"] == "initialized");
+
+        // a linha 1 não tem comando: o ponto de parada vai para a 2
+        let response = client.request("setBreakpoints", json!({ "source": { "path": path }, "breakpoints": [{ "line": 1 }, { "line": 50 }] }));
+        assert_eq!(
+            response["body"]["breakpoints"],
+            json!([
+                { "verified": true, "line": 2 },
+                { "verified": false, "line": 50, "message": "Nenhum comando nesta linha ou depois dela" },
+            ])
+        );
+        client.request("configurationDone", json!({}));
+
+        // o leia espera a linha digitada no console
+        client.request("evaluate", json!({ "expression": "21", "context": "repl" }));
+        assert_eq!(client.wait(|message| message["event"] == "stopped")["body"]["reason"], "breakpoint");
+
+        let trace = client.request("stackTrace", json!({ "threadId": THREAD_ID }));
+        let frames = trace["body"]["stackFrames"].as_array().unwrap();
+        let names: Vec<(&str, u64)> = frames.iter().map(|frame| (frame["name"].as_str().unwrap(), frame["line"].as_u64().unwrap())).collect();
+        assert_eq!(names, [("dobro", 2), ("principal", 9), ("programa", 1)]);
+
+        let scopes = client.request("scopes", json!({ "frameId": frames[0]["id"] }));
+        let locals = scopes["body"]["scopes"][0]["variablesReference"].clone();
+        let variables = client.request("variables", json!({ "variablesReference": locals }));
+        assert_eq!(variables["body"]["variables"], json!([{ "name": "n", "value": "21", "type": "inteiro", "variablesReference": 0 }]));
+        let hover = client.request("evaluate", json!({ "expression": "x", "frameId": frames[1]["id"], "context": "hover" }));
+        assert_eq!(hover["body"]["result"], "21");
+
+        // a resposta chega antes do stopped
+        client.request("stepOut", json!({ "threadId": THREAD_ID }));
+        assert!(!client.received.iter().any(|message| message["event"] == "stopped"));
+        assert_eq!(client.wait(|message| message["event"] == "stopped")["body"]["reason"], "step");
+        assert_eq!(client.printed, "42\n");
+
+        client.request("continue", json!({ "threadId": THREAD_ID }));
+        assert_eq!(client.wait(|message| message["event"] == "exited")["body"]["exitCode"], 0);
+        client.wait(|message| message["event"] == "terminated");
+        assert_eq!(client.printed, "42\nfim\n");
+
+        client.request("disconnect", json!({}));
+        server.join().unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,506 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Read, Write};
+use crate::interpreter::{Frame, Hook, Interpreter, RuntimeError, Value};
+use crate::parser::ast::*;
+
+// Depurador do interpretador (`compiler debug`). O programa para antes do
+// primeiro comando e, depois, onde o comando de continuar pedir:
+//
+//     step       no próximo comando, entrando nas funções chamadas
+//     next       no próximo comando desta função (ou de quem a chamou)
+//     finish     quando a função atual retornar
+//     continue   no próximo ponto de parada
+//
+// Os pontos de parada são por linha; numa linha sem comando, valem para a
+// próxima que tem um. Um laço para na linha dele a cada volta. `print`
+// procura as variáveis do escopo mais interno da função para o mais
+// externo e, por último, nas globais.
+//
+// Os comandos do depurador e a entrada do programa (`leia`) vêm do mesmo
+// leitor, uma linha de cada vez, na ordem em que são pedidos.
+
+const PROMPT: &str = "(depurador) ";
+
+const HELP: &str = "Comandos:
+  s, step            próximo comando, entrando nas funções chamadas
+  n, next            próximo comando sem entrar nas chamadas
+  f, finish          continua até a função atual retornar
+  c, continue        continua até um ponto de parada
+  b, break [linha]   para na linha (sem linha, lista os pontos de parada)
+  d, delete [linha]  remove o ponto de parada da linha (sem linha, todos)
+  p, print [nome]    mostra a variável ou todas as visíveis
+  bt, backtrace      mostra a pilha de chamadas
+  l, list            mostra o código em volta da linha atual
+  q, quit            encerra o programa
+  h, help            mostra esta ajuda
+Uma linha vazia repete o último comando.";
+
+// como a execução segue depois de uma parada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    Step,
+    // para quando a pilha tiver este tamanho ou menos
+    Next(usize),
+    // para quando a pilha tiver menos quadros que este
+    Finish(usize),
+    Continue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Step,
+    Breakpoint,
+}
+
+// decide em que comando parar; serve ao depurador de terminal e ao DAP
+#[derive(Debug, Clone)]
+pub struct Stepper {
+    pub breakpoints: BTreeSet<usize>,
+    resume: Resume,
+}
+
+impl Default for Stepper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stepper {
+    // começa parando no primeiro comando
+    pub fn new() -> Self {
+        Self { breakpoints: BTreeSet::new(), resume: Resume::Step }
+    }
+
+    pub fn resume(&mut self, resume: Resume) {
+        self.resume = resume;
+    }
+
+    pub fn should_stop(&self, frames: &[Frame]) -> Option<StopReason> {
+        let line = frames.last().map_or(0, |frame| frame.line);
+        if self.breakpoints.contains(&line) {
+            return Some(StopReason::Breakpoint);
+        }
+        let stop = match self.resume {
+            Resume::Step => true,
+            Resume::Next(depth) => frames.len() <= depth,
+            Resume::Finish(depth) => frames.len() < depth,
+            Resume::Continue => false,
+        };
+        stop.then_some(StopReason::Step)
+    }
+}
+
+// linhas onde começa algum comando, as únicas em que o programa para
+pub fn statement_lines(program: &Program) -> BTreeSet<usize> {
+    fn collect(statements: &[Statement], lines: &mut BTreeSet<usize>) {
+        for statement in statements {
+            if statement.line() > 0 {
+                lines.insert(statement.line());
+            }
+            match statement {
+                Statement::IfStmt(stmt) => {
+                    collect(&stmt.then_branch.statements, lines);
+                    if let Some(else_branch) = &stmt.else_branch {
+                        collect(&else_branch.statements, lines);
+                    }
+                }
+                Statement::WhileStmt(stmt) => collect(&stmt.body.statements, lines),
+                Statement::ForStmt(stmt) => collect(&stmt.body.statements, lines),
+                _ => {}
+            }
+        }
+    }
+
+    let mut lines = BTreeSet::new();
+    collect(&program.statements, &mut lines);
+    for function in &program.functions {
+        collect(&function.body.statements, &mut lines);
+    }
+    lines
+}
+
+// a linha onde um ponto de parada pedido na linha dada vai valer
+pub fn breakpoint_line(lines: &BTreeSet<usize>, line: usize) -> Option<usize> {
+    lines.range(line..).next().copied()
+}
+
+// variáveis do quadro, do escopo mais interno para o mais externo, sem as
+// encobertas por outra de mesmo nome; no quadro 0 ficam de fora as globais
+pub fn local_variables(frames: &[Frame], index: usize) -> Vec<(&str, &Value)> {
+    let frame = &frames[index];
+    let skip = if index == 0 { 1 } else { 0 };
+    let mut variables: Vec<(&str, &Value)> = Vec::new();
+    for scope in frame.scopes.iter().skip(skip).rev() {
+        for (name, value) in scope {
+            if !variables.iter().any(|(seen, _)| seen == name) {
+                variables.push((name, value));
+            }
+        }
+    }
+    variables
+}
+
+pub fn global_variables(frames: &[Frame]) -> Vec<(&str, &Value)> {
+    frames
+        .first()
+        .map(|frame| frame.scopes[0].iter().map(|(name, value)| (name.as_str(), value)).collect())
+        .unwrap_or_default()
+}
+
+// o valor como aparece no depurador: textos entre aspas
+pub fn show_value(value: &Value) -> String {
+    match value {
+        Value::Texto(text) => format!("{:?}", text),
+        other => other.to_string(),
+    }
+}
+
+fn show_variable(name: &str, value: &Value) -> String {
+    format!("{}: {} = {}", name, value.type_(), show_value(value))
+}
+
+// o nome do quadro na pilha; o do programa só tem linha quando é o atual
+fn frame_label(frames: &[Frame], index: usize) -> String {
+    let frame = &frames[index];
+    if index == 0 && frames.len() > 1 {
+        frame.function.clone()
+    } else {
+        format!("{}, linha {}", frame.function, frame.line)
+    }
+}
+
+struct Debugger<'d, 'r> {
+    stepper: Stepper,
+    source: Vec<&'d str>,
+    lines: BTreeSet<usize>,
+    input: &'d RefCell<&'r mut dyn BufRead>,
+    output: &'d RefCell<&'r mut dyn Write>,
+    last_command: String,
+    quit: bool,
+}
+
+impl Debugger<'_, '_> {
+    // a saída do depurador é a do terminal; um erro de escrita nela não tem
+    // onde ser mostrado
+    fn say(&self, text: impl AsRef<str>) {
+        let mut output = self.output.borrow_mut();
+        let _ = writeln!(output, "{}", text.as_ref());
+    }
+
+    fn source_line(&self, line: usize, current: bool) -> String {
+        let marker = if current { "->" } else { "  " };
+        let text = line.checked_sub(1).and_then(|index| self.source.get(index)).copied().unwrap_or("");
+        format!("{} {:>4} | {}", marker, line, text).trim_end().to_string()
+    }
+
+    fn list(&self, line: usize) {
+        let first = line.saturating_sub(3).max(1);
+        let last = (line + 3).min(self.source.len());
+        for number in first..=last {
+            self.say(self.source_line(number, number == line));
+        }
+    }
+
+    fn set_breakpoint(&mut self, argument: Option<&str>) {
+        let Some(argument) = argument else {
+            if self.stepper.breakpoints.is_empty() {
+                return self.say("Nenhum ponto de parada");
+            }
+            let lines: Vec<String> = self.stepper.breakpoints.iter().map(|line| line.to_string()).collect();
+            return self.say(format!("Pontos de parada nas linhas {}", lines.join(", ")));
+        };
+        let Ok(requested) = argument.parse() else {
+            return self.say("Use: break <linha>");
+        };
+        match breakpoint_line(&self.lines, requested) {
+            Some(line) => {
+                self.stepper.breakpoints.insert(line);
+                self.say(format!("Ponto de parada na linha {}", line));
+            }
+            None => self.say(format!("Nenhum comando na linha {} ou depois dela", requested)),
+        }
+    }
+
+    fn delete_breakpoint(&mut self, argument: Option<&str>) {
+        match argument.map(str::parse::<usize>) {
+            None => {
+                self.stepper.breakpoints.clear();
+                self.say("Pontos de parada removidos");
+            }
+            Some(Ok(line)) if self.stepper.breakpoints.remove(&line) => {
+                self.say(format!("Ponto de parada da linha {} removido", line))
+            }
+            Some(Ok(line)) => self.say(format!("Não há ponto de parada na linha {}", line)),
+            Some(Err(_)) => self.say("Use: delete [linha]"),
+        }
+    }
+
+    fn print(&self, frames: &[Frame], name: Option<&str>) {
+        let current = frames.len() - 1;
+        let locals = local_variables(frames, current);
+        let globals = global_variables(frames);
+
+        if let Some(name) = name {
+            let found = locals.iter().chain(&globals).find(|(n, _)| *n == name);
+            return match found {
+                Some((name, value)) => self.say(show_variable(name, value)),
+                None => self.say(format!("Nenhuma variável '{}' visível aqui", name)),
+            };
+        }
+        if locals.is_empty() && globals.is_empty() {
+            return self.say("Nenhuma variável visível aqui");
+        }
+        for (name, value) in &locals {
+            self.say(format!("  {}", show_variable(name, value)));
+        }
+        if !globals.is_empty() {
+            self.say("globais:");
+            for (name, value) in &globals {
+                self.say(format!("  {}", show_variable(name, value)));
+            }
+        }
+    }
+
+    fn backtrace(&self, frames: &[Frame]) {
+        for (position, index) in (0..frames.len()).rev().enumerate() {
+            self.say(format!("#{} {}", position, frame_label(frames, index)));
+        }
+    }
+
+    fn read_command(&self) -> Option<String> {
+        {
+            let mut output = self.output.borrow_mut();
+            let _ = write!(output, "{}", PROMPT);
+            let _ = output.flush();
+        }
+        let mut command = String::new();
+        match self.input.borrow_mut().read_line(&mut command) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(command.trim().to_string()),
+        }
+    }
+}
+
+impl Hook for Debugger<'_, '_> {
+    fn before_statement(&mut self, frames: &[Frame]) -> Result<(), RuntimeError> {
+        let Some(reason) = self.stepper.should_stop(frames) else {
+            return Ok(());
+        };
+        let frame = frames.last().expect("sempre há o quadro do programa");
+        if reason == StopReason::Breakpoint {
+            self.say(format!("Ponto de parada na linha {}", frame.line));
+        }
+        self.say(format!("{}, linha {}", frame.function, frame.line));
+        self.say(self.source_line(frame.line, true));
+
+        loop {
+            let Some(mut command) = self.read_command() else {
+                // sem mais comandos, como quit
+                self.quit = true;
+                return Err(RuntimeError { message: "Execução interrompida".to_string(), line: frame.line });
+            };
+            if command.is_empty() {
+                command = self.last_command.clone();
+            }
+            self.last_command = command.clone();
+
+            let mut words = command.split_whitespace();
+            let name = words.next().unwrap_or("");
+            let argument = words.next();
+            let resume = match name {
+                "s" | "step" => Resume::Step,
+                "n" | "next" => Resume::Next(frames.len()),
+                "f" | "finish" => Resume::Finish(frames.len()),
+                "c" | "continue" => Resume::Continue,
+                "q" | "quit" => {
+                    self.quit = true;
+                    return Err(RuntimeError { message: "Execução interrompida".to_string(), line: frame.line });
+                }
+                "b" | "break" => {
+                    self.set_breakpoint(argument);
+                    continue;
+                }
+                "d" | "delete" => {
+                    self.delete_breakpoint(argument);
+                    continue;
+                }
+                "p" | "print" => {
+                    self.print(frames, argument);
+                    continue;
+                }
+                "bt" | "backtrace" => {
+                    self.backtrace(frames);
+                    continue;
+                }
+                "l" | "list" => {
+                    self.list(frame.line);
+                    continue;
+                }
+                "h" | "help" => {
+                    self.say(HELP);
+                    continue;
+                }
+                "" => continue,
+                other => {
+                    self.say(format!("Comando desconhecido: '{}' (help lista os comandos)", other));
+                    continue;
+                }
+            };
+            self.stepper.resume(resume);
+            return Ok(());
+        }
+    }
+}
+
+// o `leia` do programa lê linhas inteiras do leitor dos comandos
+struct SharedInput<'d, 'r> {
+    reader: &'d RefCell<&'r mut dyn BufRead>,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl Read for SharedInput<'_, '_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buffer.len());
+        buffer[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for SharedInput<'_, '_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position >= self.line.len() {
+            self.line.clear();
+            self.position = 0;
+            self.reader.borrow_mut().read_until(b'\n', &mut self.line)?;
+        }
+        Ok(&self.line[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position += amount;
+    }
+}
+
+struct SharedOutput<'d, 'r>(&'d RefCell<&'r mut dyn Write>);
+
+impl Write for SharedOutput<'_, '_> {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buffer)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+// executa o programa já analisado sob o depurador; comandos e entrada do
+// programa vêm de `input`, e o programa e o depurador escrevem em `output`.
+// Encerrar pelo depurador não é erro.
+pub fn debug<'r>(program: &Program, source: &str, input: &'r mut dyn BufRead, output: &'r mut dyn Write) -> Result<(), RuntimeError> {
+    let input = RefCell::new(input);
+    let output = RefCell::new(output);
+    let mut debugger = Debugger {
+        stepper: Stepper::new(),
+        source: source.lines().collect(),
+        lines: statement_lines(program),
+        input: &input,
+        output: &output,
+        last_command: String::new(),
+        quit: false,
+    };
+
+    let result = {
+        let mut program_input = SharedInput { reader: &input, line: Vec::new(), position: 0 };
+        let mut program_output = SharedOutput(&output);
+        Interpreter::new(program, &mut program_input, &mut program_output)
+            .with_hook(&mut debugger)
+            .run(u64::MAX)
+    };
+
+    match result {
+        _ if debugger.quit => {
+            debugger.say("Execução interrompida");
+            Ok(())
+        }
+        Ok(()) => {
+            debugger.say("Programa terminou");
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, SemanticAnalyzer};
+
+    #[test]
+    fn test_debug_session() {
+        let source = "funcao inteiro dobro(inteiro n) {
+    inteiro r = n * 2;
+    retorne r;
+}
+
+funcao principal() {
+    inteiro x;
+    leia(x);
+    escreva(dobro(x));
+    escreva(\"fim\");
+}
+";
+        let result = SemanticAnalyzer::new().analyze(Parser::parse_from_source(source).unwrap());
+        assert!(result.errors.is_empty());
+
+        // o 21 no meio dos comandos é a linha lida pelo `leia`
+        let mut input = "break 5\nbreak 2\ncontinue\n21\nprint\nbt\nfinish\np x\nnext\n".as_bytes();
+        let mut output = Vec::new();
+        debug(&result.annotated_ast, source, &mut input, &mut output).unwrap();
+
+        let expected = "principal, linha 7
+->    7 |     inteiro x;
+(depurador) Ponto de parada na linha 7
+(depurador) Ponto de parada na linha 2
+(depurador) Ponto de parada na linha 2
+dobro, linha 2
+->    2 |     inteiro r = n * 2;
+(depurador)   n: inteiro = 21
+(depurador) #0 dobro, linha 2
+#1 principal, linha 9
+#2 programa
+(depurador) 42
+principal, linha 10
+->   10 |     escreva(\"fim\");
+(depurador) x: inteiro = 21
+(depurador) fim
+Programa terminou
+";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_stepper() {
+        let frames = |depth: usize, line: usize| -> Vec<Frame> {
+            (0..depth).map(|i| Frame { function: format!("f{}", i), line, scopes: vec![Vec::new()] }).collect()
+        };
+        let mut stepper = Stepper::new();
+        assert_eq!(stepper.should_stop(&frames(1, 1)), Some(StopReason::Step));
+
+        stepper.resume(Resume::Next(2));
+        assert_eq!(stepper.should_stop(&frames(3, 4)), None);
+        assert_eq!(stepper.should_stop(&frames(2, 5)), Some(StopReason::Step));
+
+        stepper.resume(Resume::Finish(2));
+        assert_eq!(stepper.should_stop(&frames(2, 6)), None);
+        assert_eq!(stepper.should_stop(&frames(1, 7)), Some(StopReason::Step));
+
+        stepper.resume(Resume::Continue);
+        stepper.breakpoints.insert(9);
+        assert_eq!(stepper.should_stop(&frames(3, 8)), None);
+        assert_eq!(stepper.should_stop(&frames(3, 9)), Some(StopReason::Breakpoint));
+    }
+}
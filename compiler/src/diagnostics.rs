@@ -0,0 +1,201 @@
+use crate::backend::BackendError;
+use crate::ir::lowering::LoweringError;
+use crate::lexer::LexerError;
+use crate::lexer::error::ErrorSeverity;
+use crate::manifest::LevelError;
+use crate::modules::ModuleError;
+use crate::parser::ParserError;
+use crate::semantic::SemanticError;
+use crate::semantic::lints::LintWarning;
+use crate::interpreter::RuntimeError;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+// Diagnósticos estruturados para `--message-format json` (um objeto por
+// linha) e `--message-format sarif`. O formato humano continua sendo o
+// Display de cada erro; aqui todos viram o mesmo formato, com as mesmas
+// chaves sempre presentes (null ou [] quando não se aplicam).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub file: String,
+    // None quando a fase não sabe a posição
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Span {
+    // linha e coluna 0 são "desconhecida" nos erros das fases
+    pub fn new(file: &str, line: usize, column: usize) -> Self {
+        Self {
+            file: file.to_string(),
+            line: (line > 0).then_some(line),
+            column: (line > 0 && column > 0).then_some(column),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    // a fase ("lexico", "sintatico", ...) ou o nome do lint
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub suggestion: Option<String>,
+    // só erros sintáticos: o que o parser esperava e o que encontrou
+    pub expected: Vec<String>,
+    pub found: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(code: &str, severity: Severity, message: String, span: Span) -> Self {
+        Self {
+            code: code.to_string(),
+            severity,
+            message,
+            span,
+            suggestion: None,
+            expected: Vec::new(),
+            found: None,
+        }
+    }
+
+    pub fn lexer(error: &LexerError, file: &str) -> Self {
+        let severity = match error.severity {
+            ErrorSeverity::Warning => Severity::Warning,
+            ErrorSeverity::Error | ErrorSeverity::Fatal => Severity::Error,
+        };
+        Self {
+            suggestion: (!error.recovery_suggestion.is_empty()).then(|| error.recovery_suggestion.clone()),
+            ..Self::new("lexico", severity, error.message.clone(), Span::new(file, error.linha, error.coluna))
+        }
+    }
+
+    pub fn parser(error: &ParserError, file: &str) -> Self {
+        Self {
+            expected: error.expected.iter().map(|token| token.describe()).collect(),
+            found: Some(error.found.describe()),
+            ..Self::new("sintatico", Severity::Error, error.message.clone(), Span::new(file, error.linha, error.coluna))
+        }
+    }
+
+    pub fn module(error: &ModuleError) -> Self {
+        Self::new("modulo", Severity::Error, error.message.clone(), Span::new(&error.file, 0, 0))
+    }
+
+    pub fn level(error: &LevelError, file: &str) -> Self {
+        Self::new("nivel", Severity::Error, error.message.clone(), Span::new(file, 0, 0))
+    }
+
+    pub fn semantic(error: &SemanticError, file: &str) -> Self {
+        Self::new("semantico", Severity::Error, error.message.clone(), Span::new(file, error.line, error.column))
+    }
+
+    pub fn lowering(error: &LoweringError, file: &str) -> Self {
+        Self::new("geracao", Severity::Error, error.message.clone(), Span::new(file, error.line, 0))
+    }
+
+    pub fn backend(error: &BackendError, file: &str) -> Self {
+        Self::new("geracao", Severity::Error, error.message.clone(), Span::new(file, error.line, 0))
+    }
+
+    pub fn runtime(error: &RuntimeError, file: &str) -> Self {
+        Self::new("execucao", Severity::Error, error.message.clone(), Span::new(file, error.line, 0))
+    }
+
+    pub fn lint(warning: &LintWarning, file: &str) -> Self {
+        Self::new(warning.lint.name(), Severity::Warning, warning.message.clone(), Span::new(file, warning.line, 0))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("diagnóstico sempre serializa")
+    }
+}
+
+// Documento SARIF 2.1.0 com todos os diagnósticos de uma execução
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> Value {
+    let mut rules: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let results: Vec<Value> = diagnostics.iter().map(sarif_result).collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "symplia",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn sarif_result(diagnostic: &Diagnostic) -> Value {
+    let mut location = json!({ "artifactLocation": { "uri": diagnostic.span.file } });
+    if let Some(line) = diagnostic.span.line {
+        let mut region = json!({ "startLine": line });
+        if let Some(column) = diagnostic.span.column {
+            region["startColumn"] = json!(column);
+        }
+        location["region"] = region;
+    }
+
+    let level = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    json!({
+        "ruleId": diagnostic.code,
+        "level": level,
+        "message": { "text": diagnostic.message },
+        "locations": [{ "physicalLocation": location }],
+        "properties": {
+            "suggestion": diagnostic.suggestion,
+            "expected": diagnostic.expected,
+            "found": diagnostic.found,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_diagnostics() {
+        let error = Lexer::new("escreva(\"a\\q\");").tokenize().unwrap_err();
+        let diagnostic = Diagnostic::lexer(&error, "a.sym");
+        assert_eq!((diagnostic.code.as_str(), diagnostic.span.line, diagnostic.span.column), ("lexico", Some(1), Some(12)));
+
+        let errors = Parser::parse_from_source("inteiro x = ;").unwrap_err();
+        let diagnostic = Diagnostic::parser(&errors[0], "a.sym");
+        assert_eq!(diagnostic.found.as_deref(), Some("';'"));
+        assert!(diagnostic.expected.contains(&"identificador".to_string()));
+
+        let value: Value = serde_json::from_str(&diagnostic.to_json()).unwrap();
+        assert_eq!(value["span"], json!({ "file": "a.sym", "line": 1, "column": 13 }));
+        assert_eq!(value["suggestion"], Value::Null);
+
+        let semantic = SemanticError { message: "x".to_string(), line: 0, column: 0, function: None };
+        let sarif = to_sarif(&[diagnostic, Diagnostic::semantic(&semantic, "a.sym")]);
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["region"]["startLine"], 1);
+        assert!(results[1]["locations"][0]["physicalLocation"].get("region").is_none());
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+    }
+}
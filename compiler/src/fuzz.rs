@@ -0,0 +1,750 @@
+use std::panic::{self, AssertUnwindSafe};
+use crate::backend::riscv::{assemble, generate_riscv, Simulator};
+use crate::interpreter::run_program;
+use crate::ir::{verify, Lowering};
+use crate::parser::ast::*;
+use crate::parser::printer::format_program;
+use crate::{ConstantFolder, Lexer, Parser, SemanticAnalyzer};
+
+// Testes com programas aleatórios (`compiler fuzz`). O gerador segue a
+// gramática da linguagem (Docs/Entregas-Semanais/9.md) e só monta programas
+// bem tipados que terminam: os laços `para` têm limites pequenos, cada
+// `enquanto` tem um contador que o corpo não pode mudar e uma função só
+// chama as declaradas antes dela.
+//
+// Cada programa gerado precisa passar pelo léxico, pelo sintático e pelo
+// semântico sem erros, voltar igual do formatador, ir para a IR e dar o
+// mesmo resultado no interpretador, no interpretador depois do dobramento
+// de constantes e no simulador RISC-V. Depois, versões estragadas do mesmo
+// texto passam pelas fases de análise, que podem recusá-las mas não podem
+// entrar em pânico.
+//
+// A semente N gera o mesmo programa em qualquer máquina; `--seed N --runs 1`
+// repete um caso que falhou.
+
+// comandos executados pelo interpretador antes de desistir do programa
+pub const STEP_LIMIT: u64 = 100_000;
+// instruções do simulador; folga para os laços das rotinas de texto
+const SIMULATION_STEPS: u64 = 200_000_000;
+// versões estragadas de cada programa gerado
+pub const MUTATIONS: usize = 8;
+
+const MAX_BLOCK_DEPTH: usize = 3;
+const MAX_EXPR_DEPTH: usize = 3;
+const TYPES: [Type; 4] = [Type::Inteiro, Type::Decimal, Type::Texto, Type::Logico];
+const TEXTS: [&str; 12] = ["", "a", "Olá", "ação", "x y", "AbC", "12", "-7", "2.5", "verdadeiro", "falso", "\"aspas\"\n"];
+// pedaços inseridos pelas mutações, escolhidos para cair em casos de borda
+// dos autômatos e do parser
+const FRAGMENTS: [&str; 24] = [
+    "{", "}", "(", ")", ";", ",", "\"", "\\", "/*", "*/", "//", "\n", "0.", "1e", ".5",
+    "99999999999999999999", "ç", "\u{0}", "€", "se", "para", "funcao", "retorne", "&&",
+];
+
+// xorshift64*: pequeno, sem dependências e igual em toda plataforma
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // o estado nunca pode ser zero
+        Self { state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // de 0 a n - 1
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low + 1) as usize) as i64
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    name: String,
+    type_: Type,
+    // contadores de laço não podem ser lidos com `leia` nem reusados por
+    // outro `para`, senão o laço pode não terminar
+    assignable: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Signature {
+    name: String,
+    parameters: Vec<Type>,
+    return_type: Option<Type>,
+}
+
+pub struct ProgramGenerator {
+    rng: Rng,
+    scopes: Vec<Vec<Variable>>,
+    functions: Vec<Signature>,
+    // None fora das funções, onde não há `retorne`
+    return_type: Option<Option<Type>>,
+    block_depth: usize,
+    names: usize,
+}
+
+// um programa gerado e a entrada que os seus `leia` consomem
+#[derive(Debug, Clone)]
+pub struct Generated {
+    pub program: Program,
+    pub source: String,
+    pub input: String,
+}
+
+impl ProgramGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            scopes: vec![Vec::new()],
+            functions: Vec::new(),
+            return_type: None,
+            block_depth: 0,
+            names: 0,
+        }
+    }
+
+    pub fn generate(mut self) -> Generated {
+        let mut statements = Vec::new();
+        // as globais vêm primeiro para que já tenham valor quando uma
+        // função que as usa for chamada
+        for _ in 0..self.rng.below(3) {
+            self.declaration(&mut statements);
+        }
+
+        let mut functions = Vec::new();
+        for index in 0..self.rng.below(4) {
+            functions.push(self.function(format!("f{}", index + 1)));
+        }
+        for _ in 0..self.rng.below(3) {
+            self.statement(&mut statements);
+        }
+
+        self.return_type = Some(None);
+        let body = self.block(6);
+        functions.push(FunctionDecl { return_type: None, name: "principal".to_string(), parameters: Vec::new(), body, line: 0 });
+
+        let program = Program { imports: Vec::new(), functions, statements };
+        let source = format_program(&program);
+        // palavras que servem para qualquer tipo de variável
+        let words: Vec<String> = (0..24).map(|_| self.rng.range(-20, 99).to_string()).collect();
+        Generated { program, source, input: words.join(" ") + "\n" }
+    }
+
+    fn fresh_name(&mut self, prefix: &str) -> String {
+        self.names += 1;
+        format!("{}{}", prefix, self.names)
+    }
+
+    fn random_type(&mut self) -> Type {
+        self.rng.pick(&TYPES).clone()
+    }
+
+    fn declare(&mut self, name: &str, type_: Type, assignable: bool) {
+        let scope = self.scopes.last_mut().expect("sempre há o escopo global");
+        scope.push(Variable { name: name.to_string(), type_, assignable });
+    }
+
+    fn visible(&self, accepts: impl Fn(&Variable) -> bool) -> Vec<Variable> {
+        self.scopes.iter().flatten().filter(|v| accepts(v)).cloned().collect()
+    }
+
+    fn function(&mut self, name: String) -> FunctionDecl {
+        let parameters: Vec<Parameter> = (0..self.rng.below(4))
+            .map(|_| Parameter { param_type: self.random_type(), name: self.fresh_name("p") })
+            .collect();
+        let return_type = if self.rng.chance(70) { Some(self.random_type()) } else { None };
+
+        self.scopes.push(parameters.iter().map(|p| Variable { name: p.name.clone(), type_: p.param_type.clone(), assignable: true }).collect());
+        self.return_type = Some(return_type.clone());
+        let mut body = self.block(5);
+        if let Some(type_) = &return_type {
+            let value = self.expr(type_, MAX_EXPR_DEPTH);
+            body.statements.push(Statement::ReturnStmt(ReturnStmt { value: Some(value), line: 0 }));
+        }
+        self.return_type = None;
+        self.scopes.pop();
+
+        self.functions.push(Signature {
+            name: name.clone(),
+            parameters: parameters.iter().map(|p| p.param_type.clone()).collect(),
+            return_type: return_type.clone(),
+        });
+        FunctionDecl { return_type, name, parameters, body, line: 0 }
+    }
+
+    fn block(&mut self, max_statements: usize) -> Block {
+        self.scopes.push(Vec::new());
+        self.block_depth += 1;
+        let mut statements = Vec::new();
+        for _ in 0..=self.rng.below(max_statements) {
+            self.statement(&mut statements);
+        }
+        self.block_depth -= 1;
+        self.scopes.pop();
+        Block { statements }
+    }
+
+    fn statement(&mut self, out: &mut Vec<Statement>) {
+        let nested = self.block_depth < MAX_BLOCK_DEPTH;
+        match self.rng.below(16) {
+            0..=2 => self.declaration(out),
+            3..=5 => {
+                let arguments = (0..=self.rng.below(3)).map(|_| {
+                    let type_ = self.random_type();
+                    self.expr(&type_, MAX_EXPR_DEPTH)
+                }).collect();
+                out.push(Statement::WriteStmt(WriteStmt { arguments, line: 0 }));
+            }
+            6 | 7 if nested => {
+                let condition = self.expr(&Type::Logico, MAX_EXPR_DEPTH);
+                let then_branch = self.block(3);
+                let else_branch = if self.rng.chance(50) { Some(self.block(3)) } else { None };
+                out.push(Statement::IfStmt(IfStmt { condition, then_branch, else_branch, line: 0 }));
+            }
+            8 | 9 if nested => self.for_loop(out),
+            10 if nested => self.while_loop(out),
+            11 => {
+                let targets = self.visible(|v| v.assignable);
+                if targets.is_empty() {
+                    return self.declaration(out);
+                }
+                let target = self.rng.pick(&targets).name.clone();
+                out.push(Statement::ReadStmt(ReadStmt { target: Expr::Variable(target), line: 0 }));
+            }
+            12 | 13 if !self.functions.is_empty() => {
+                let index = self.rng.below(self.functions.len());
+                let call = self.call(index, MAX_EXPR_DEPTH);
+                out.push(Statement::ExprStmt(ExprStmt { expr: call, line: 0 }));
+            }
+            14 if self.return_type.is_some() => {
+                let return_type = self.return_type.clone().flatten();
+                let value = return_type.map(|type_| self.expr(&type_, MAX_EXPR_DEPTH));
+                out.push(Statement::ReturnStmt(ReturnStmt { value, line: 0 }));
+            }
+            _ => self.declaration(out),
+        }
+    }
+
+    fn declaration(&mut self, out: &mut Vec<Statement>) {
+        let var_type = self.random_type();
+        let initializer = if self.rng.chance(85) { Some(self.expr(&var_type, MAX_EXPR_DEPTH)) } else { None };
+        let name = self.fresh_name("v");
+        self.declare(&name, var_type.clone(), true);
+        out.push(Statement::VariableDecl(VariableDecl { var_type, name, initializer, line: 0 }));
+    }
+
+    // `para` com limites pequenos, numa variável nova ou reusando uma
+    // numérica que o corpo não vai poder mudar
+    fn for_loop(&mut self, out: &mut Vec<Statement>) {
+        let reusable = self.visible(|v| v.assignable && matches!(v.type_, Type::Inteiro | Type::Decimal));
+        let reused = if !reusable.is_empty() && self.rng.chance(30) { Some(self.rng.pick(&reusable).name.clone()) } else { None };
+
+        let first = self.rng.range(-1, 2);
+        let start = Expr::Literal(Literal::Inteiro(first));
+        let end = if self.rng.chance(20) {
+            // o resto por 4 mantém o limite entre -3 e 3
+            let value = self.expr(&Type::Inteiro, 1);
+            Expr::BinaryOp(BinaryOperator::Modulo, Box::new(value), Box::new(Expr::Literal(Literal::Inteiro(4))))
+        } else {
+            Expr::Literal(Literal::Inteiro(first + self.rng.range(-1, 3)))
+        };
+
+        let variable = match reused {
+            Some(name) => {
+                self.set_assignable(&name, false);
+                self.scopes.push(Vec::new());
+                name
+            }
+            None => {
+                let name = self.fresh_name("i");
+                self.scopes.push(vec![Variable { name: name.clone(), type_: Type::Inteiro, assignable: false }]);
+                name
+            }
+        };
+        let body = self.block(3);
+        self.scopes.pop();
+        self.set_assignable(&variable, true);
+
+        out.push(Statement::ForStmt(ForStmt { variable, start, end, body, line: 0 }));
+    }
+
+    // inteiro w = 0;
+    // enquanto (w < K && condição) { ...; para w de w + 1 ate w - 1 { } }
+    //
+    // sem atribuição na linguagem, o `para` vazio no fim do corpo é o que
+    // incrementa o contador: ele recebe o início e o laço não executa
+    fn while_loop(&mut self, out: &mut Vec<Statement>) {
+        let counter = self.fresh_name("w");
+        out.push(Statement::VariableDecl(VariableDecl {
+            var_type: Type::Inteiro,
+            name: counter.clone(),
+            initializer: Some(Expr::Literal(Literal::Inteiro(0))),
+            line: 0,
+        }));
+        self.declare(&counter, Type::Inteiro, false);
+
+        let variable = || Box::new(Expr::Variable(counter.clone()));
+        let limit = Box::new(Expr::Literal(Literal::Inteiro(self.rng.range(0, 3))));
+        let mut condition = Expr::BinaryOp(BinaryOperator::Less, variable(), limit);
+        if self.rng.chance(50) {
+            let extra = self.expr(&Type::Logico, 2);
+            condition = Expr::BinaryOp(BinaryOperator::And, Box::new(condition), Box::new(extra));
+        }
+
+        let mut body = self.block(3);
+        let one = || Box::new(Expr::Literal(Literal::Inteiro(1)));
+        body.statements.push(Statement::ForStmt(ForStmt {
+            variable: counter.clone(),
+            start: Expr::BinaryOp(BinaryOperator::Add, variable(), one()),
+            end: Expr::BinaryOp(BinaryOperator::Subtract, variable(), one()),
+            body: Block { statements: Vec::new() },
+            line: 0,
+        }));
+        out.push(Statement::WhileStmt(WhileStmt { condition, body, line: 0 }));
+    }
+
+    fn set_assignable(&mut self, name: &str, assignable: bool) {
+        if let Some(variable) = self.scopes.iter_mut().flatten().rev().find(|v| v.name == name) {
+            variable.assignable = assignable;
+        }
+    }
+
+    fn literal(&mut self, type_: &Type) -> Expr {
+        let literal = match type_ {
+            Type::Inteiro if self.rng.chance(5) => Literal::Inteiro(*self.rng.pick(&[i64::MAX, 4_294_967_296, 1_000_000_007])),
+            Type::Inteiro => Literal::Inteiro(self.rng.range(0, 20)),
+            // quartos são exatos em binário e aparecem iguais em todo backend
+            Type::Decimal => Literal::Decimal(self.rng.range(0, 40) as f64 / 4.0),
+            Type::Texto => Literal::Texto(self.rng.pick(&TEXTS).to_string()),
+            Type::Logico => Literal::Logico(self.rng.chance(50)),
+        };
+        Expr::Literal(literal)
+    }
+
+    fn leaf(&mut self, type_: &Type) -> Expr {
+        let candidates = self.visible(|v| v.type_ == *type_ || (*type_ == Type::Decimal && v.type_ == Type::Inteiro));
+        if !candidates.is_empty() && self.rng.chance(60) {
+            Expr::Variable(self.rng.pick(&candidates).name.clone())
+        } else {
+            self.literal(type_)
+        }
+    }
+
+    fn expr(&mut self, type_: &Type, depth: usize) -> Expr {
+        if depth == 0 || self.rng.chance(25) {
+            return self.leaf(type_);
+        }
+        let depth = depth - 1;
+
+        let callable: Vec<usize> = (0..self.functions.len())
+            .filter(|&i| self.functions[i].return_type.as_ref() == Some(type_))
+            .collect();
+        if !callable.is_empty() && self.rng.chance(15) {
+            let index = *self.rng.pick(&callable);
+            return self.call(index, depth);
+        }
+
+        match type_ {
+            Type::Inteiro => self.integer_expr(depth),
+            Type::Decimal => self.decimal_expr(depth),
+            Type::Texto => self.text_expr(depth),
+            Type::Logico => self.logical_expr(depth),
+        }
+    }
+
+    fn any_expr(&mut self, depth: usize) -> Expr {
+        let type_ = self.random_type();
+        self.expr(&type_, depth)
+    }
+
+    fn call(&mut self, index: usize, depth: usize) -> Expr {
+        let signature = self.functions[index].clone();
+        let arguments = signature.parameters.iter().map(|type_| self.expr(type_, depth)).collect();
+        Expr::Call(CallExpr { function: signature.name, arguments })
+    }
+
+    fn builtin(&mut self, name: &str, types: &[Type], depth: usize) -> Expr {
+        let arguments = types.iter().map(|type_| self.expr(type_, depth)).collect();
+        Expr::Call(CallExpr { function: name.to_string(), arguments })
+    }
+
+    fn binary(&mut self, op: BinaryOperator, left: &Type, right: &Type, depth: usize) -> Expr {
+        Expr::BinaryOp(op, Box::new(self.expr(left, depth)), Box::new(self.expr(right, depth)))
+    }
+
+    fn unary(&mut self, op: UnaryOperator, type_: &Type, depth: usize) -> Expr {
+        Expr::UnaryOp(op, Box::new(self.expr(type_, depth)))
+    }
+
+    fn integer_expr(&mut self, depth: usize) -> Expr {
+        use Type::*;
+        match self.rng.below(12) {
+            0 => self.binary(BinaryOperator::Add, &Inteiro, &Inteiro, depth),
+            1 => self.binary(BinaryOperator::Subtract, &Inteiro, &Inteiro, depth),
+            2 => self.binary(BinaryOperator::Multiply, &Inteiro, &Inteiro, depth),
+            3 => self.binary(BinaryOperator::Divide, &Inteiro, &Inteiro, depth),
+            4 => self.binary(BinaryOperator::Modulo, &Inteiro, &Inteiro, depth),
+            5 => {
+                let op = if self.rng.chance(70) { UnaryOperator::Negate } else { UnaryOperator::Plus };
+                self.unary(op, &Inteiro, depth)
+            }
+            6 => self.builtin("potencia", &[Inteiro, Inteiro], depth),
+            7 => {
+                let name = *self.rng.pick(&["abs", "arredondar", "piso", "teto"]);
+                self.builtin(name, &[Inteiro], depth)
+            }
+            8 => self.builtin("tamanho", &[Texto], depth),
+            9 => {
+                let from = self.random_type();
+                self.builtin("paraInteiro", &[from], depth)
+            }
+            _ => self.leaf(&Inteiro),
+        }
+    }
+
+    fn decimal_expr(&mut self, depth: usize) -> Expr {
+        use Type::*;
+        let operand = |rng: &mut Rng| if rng.chance(30) { Inteiro } else { Decimal };
+        match self.rng.below(11) {
+            0..=3 => {
+                let op = self.rng.pick(&[BinaryOperator::Add, BinaryOperator::Subtract, BinaryOperator::Multiply, BinaryOperator::Divide]).clone();
+                let (left, right) = (operand(&mut self.rng), operand(&mut self.rng));
+                let right = if left == Inteiro { Decimal } else { right };
+                self.binary(op, &left, &right, depth)
+            }
+            4 => self.unary(UnaryOperator::Negate, &Decimal, depth),
+            5 => self.builtin("raiz", &[Decimal], depth),
+            6 => {
+                let name = *self.rng.pick(&["abs", "arredondar", "piso", "teto"]);
+                self.builtin(name, &[Decimal], depth)
+            }
+            7 => {
+                // o rt.potencia do RISC-V usa quadrados sucessivos ou exp e ln
+                // e só acerta até as últimas casas; com base em quartos e
+                // expoente inteiro pequeno o resultado é exato
+                let base = self.literal(&Decimal);
+                let exponent = Expr::Literal(Literal::Decimal(self.rng.range(-2, 3) as f64));
+                Expr::Call(CallExpr { function: "potencia".to_string(), arguments: vec![base, exponent] })
+            }
+            8 => {
+                let from = self.random_type();
+                self.builtin("paraDecimal", &[from], depth)
+            }
+            9 => self.integer_expr(depth),
+            _ => self.leaf(&Decimal),
+        }
+    }
+
+    fn text_expr(&mut self, depth: usize) -> Expr {
+        use Type::*;
+        match self.rng.below(9) {
+            0 => self.binary(BinaryOperator::Add, &Texto, &Texto, depth),
+            1 => {
+                let other = self.any_expr(depth);
+                let text = self.expr(&Texto, depth);
+                let (left, right) = if self.rng.chance(50) { (text, other) } else { (other, text) };
+                Expr::BinaryOp(BinaryOperator::Add, Box::new(left), Box::new(right))
+            }
+            2 => {
+                let name = *self.rng.pick(&["maiusculo", "minusculo"]);
+                self.builtin(name, &[Texto], depth)
+            }
+            3 => self.builtin("subtexto", &[Texto, Inteiro, Inteiro], depth),
+            4 => self.builtin("substituir", &[Texto, Texto, Texto], depth),
+            5 | 6 => {
+                let from = self.random_type();
+                self.builtin("paraTexto", &[from], depth)
+            }
+            _ => self.leaf(&Texto),
+        }
+    }
+
+    fn logical_expr(&mut self, depth: usize) -> Expr {
+        use Type::*;
+        const COMPARISONS: [BinaryOperator; 6] = [
+            BinaryOperator::Equal,
+            BinaryOperator::NotEqual,
+            BinaryOperator::Less,
+            BinaryOperator::LessEqual,
+            BinaryOperator::Greater,
+            BinaryOperator::GreaterEqual,
+        ];
+        match self.rng.below(10) {
+            0 | 1 => {
+                let op = self.rng.pick(&COMPARISONS).clone();
+                let left = if self.rng.chance(50) { Inteiro } else { Decimal };
+                let right = if self.rng.chance(50) { Inteiro } else { Decimal };
+                self.binary(op, &left, &right, depth)
+            }
+            2 => {
+                let op = self.rng.pick(&COMPARISONS).clone();
+                self.binary(op, &Texto, &Texto, depth)
+            }
+            3 => {
+                let op = self.rng.pick(&COMPARISONS[..2]).clone();
+                self.binary(op, &Logico, &Logico, depth)
+            }
+            4 => self.binary(BinaryOperator::And, &Logico, &Logico, depth),
+            5 => self.binary(BinaryOperator::Or, &Logico, &Logico, depth),
+            6 => self.unary(UnaryOperator::Not, &Logico, depth),
+            7 => self.builtin("contem", &[Texto, Texto], depth),
+            8 => {
+                let from = self.rng.pick(&[Inteiro, Texto, Logico]).clone();
+                self.builtin("paraLogico", &[from], depth)
+            }
+            _ => self.leaf(&Logico),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // todos os caminhos deram o mesmo resultado
+    Agreed,
+    // o interpretador chegou ao STEP_LIMIT; o programa não foi comparado
+    Unfinished,
+}
+
+// o resultado de uma execução: saída e, se parou com erro, a mensagem
+type Run = (String, Option<String>);
+
+fn interpret(program: &Program, input: &str) -> Run {
+    let (output, result) = run_program(program, input, STEP_LIMIT);
+    (output, result.err().map(|e| e.message))
+}
+
+// o programa gerado passa por todas as fases e dá o mesmo resultado em
+// todos os caminhos de execução
+pub fn check_program(source: &str, input: &str) -> Result<Verdict, String> {
+    guard(|| {
+        Lexer::new(source).tokenize().map_err(|e| format!("erro léxico num programa gerado: {}", e))?;
+        let program = Parser::parse_from_source(source).map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            format!("erro sintático num programa gerado: {}", messages.join("; "))
+        })?;
+        let formatted = format_program(&program);
+        if formatted != source {
+            return Err(format!("o formatador não devolveu o mesmo texto:\n{}", formatted));
+        }
+
+        let result = SemanticAnalyzer::new().analyze(program);
+        if let Some(error) = result.errors.first() {
+            return Err(format!("erro semântico num programa gerado: {}", error));
+        }
+        let program = result.annotated_ast.clone();
+        let ir = Lowering::new().lower(&program).map_err(|e| format!("erro ao gerar a IR: {}", e))?;
+        if let Err(errors) = verify(&ir) {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            return Err(format!("IR inválida: {}", messages.join("; ")));
+        }
+
+        let reference = interpret(&program, input);
+        if reference.1.as_deref().is_some_and(|message| message.starts_with("Limite de")) {
+            return Ok(Verdict::Unfinished);
+        }
+
+        let folded = ConstantFolder::new().optimize(result).annotated_ast;
+        let optimized = interpret(&folded, input);
+        if optimized != reference {
+            return Err(format!("o dobramento de constantes mudou o resultado:\n{}", difference(&reference, &optimized)));
+        }
+
+        let simulated = simulate(&program, input)?;
+        agree_with_simulator(&reference, &simulated)?;
+        Ok(Verdict::Agreed)
+    })
+}
+
+// executa no simulador RISC-V; devolve a saída e o código de saída ou o
+// erro que parou a simulação
+fn simulate(program: &Program, input: &str) -> Result<(String, Result<i64, String>), String> {
+    let asm = generate_riscv(program).map_err(|e| format!("erro no backend RISC-V: {}", e))?;
+    let assembled = assemble(&asm).map_err(|e| format!("erro ao montar o RISC-V: {}", e))?;
+    let mut input = input.as_bytes();
+    let mut output = Vec::new();
+    let result = Simulator::new(&assembled, &mut input, &mut output).run(SIMULATION_STEPS);
+    Ok((String::from_utf8_lossy(&output).into_owned(), result.map_err(|e| e.to_string())))
+}
+
+// um erro do programa aparece no simulador de dois jeitos: o runtime
+// escreve "Erro: ..." e termina com código 1, ou o próprio simulador para
+// (a entrada acabou ou não é um número); as mensagens não precisam ser as
+// do interpretador
+fn agree_with_simulator(reference: &Run, (output, result): &(String, Result<i64, String>)) -> Result<(), String> {
+    let agrees = match (&reference.1, result) {
+        (None, Ok(code)) => *code == 0 && *output == reference.0,
+        (Some(_), Ok(code)) => *code != 0 && output.strip_prefix(reference.0.as_str()).is_some_and(|rest| rest.starts_with("Erro")),
+        (Some(_), Err(_)) => *output == reference.0,
+        (None, Err(_)) => false,
+    };
+    if agrees {
+        return Ok(());
+    }
+    let error = match result {
+        Ok(0) => None,
+        Ok(code) => Some(format!("código de saída {}", code)),
+        Err(e) => Some(e.clone()),
+    };
+    Err(format!("o simulador RISC-V discorda do interpretador:\n{}", difference(reference, &(output.clone(), error))))
+}
+
+fn difference(expected: &Run, actual: &Run) -> String {
+    let show = |(output, error): &Run| match error {
+        Some(error) => format!("{:?} e o erro \"{}\"", output, error),
+        None => format!("{:?}", output),
+    };
+    format!("  interpretador: {}\n  obtido:        {}", show(expected), show(actual))
+}
+
+// um texto qualquer passa pelas fases de análise sem pânico
+pub fn check_front_end(source: &str) -> Result<(), String> {
+    guard(|| {
+        let _ = Lexer::new(source).tokenize();
+        if let Ok(program) = Parser::parse_from_source(source) {
+            let _ = format_program(&program);
+            let result = SemanticAnalyzer::new().analyze(program);
+            if result.errors.is_empty() {
+                ConstantFolder::new().optimize(result);
+            }
+        }
+        Ok(())
+    })
+}
+
+// transforma um pânico em falha, com a mensagem dele
+fn guard<T>(check: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    match panic::catch_unwind(AssertUnwindSafe(check)) {
+        Ok(result) => result,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "sem mensagem".to_string());
+            Err(format!("pânico: {}", message))
+        }
+    }
+}
+
+// apaga, duplica, troca ou insere trechos em alguns pontos do texto
+pub fn mutate(source: &str, rng: &mut Rng) -> String {
+    let mut chars: Vec<char> = source.chars().collect();
+    for _ in 0..=rng.below(4) {
+        let at = rng.below(chars.len() + 1);
+        let end = (at + 1 + rng.below(8)).min(chars.len());
+        match rng.below(4) {
+            0 => {
+                chars.drain(at..end);
+            }
+            1 => {
+                let copy: Vec<char> = chars[at..end].to_vec();
+                chars.splice(at..at, copy);
+            }
+            2 if at < chars.len() => {
+                let fragment = rng.pick(&FRAGMENTS).chars().next().unwrap_or(' ');
+                chars[at] = fragment;
+            }
+            _ => {
+                let fragment = rng.pick(&FRAGMENTS);
+                chars.splice(at..at, fragment.chars());
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub seed: u64,
+    pub source: String,
+    pub input: String,
+    pub problem: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub runs: u64,
+    pub unfinished: u64,
+    pub failures: Vec<Failure>,
+}
+
+// gera e confere um programa com a semente dada e as suas mutações
+pub fn fuzz_seed(seed: u64, report: &mut Report) {
+    let generated = ProgramGenerator::new(seed).generate();
+    report.runs += 1;
+    let failure = |source: &str, problem: String| Failure {
+        seed,
+        source: source.to_string(),
+        input: generated.input.clone(),
+        problem,
+    };
+
+    match check_program(&generated.source, &generated.input) {
+        Ok(Verdict::Agreed) => {}
+        Ok(Verdict::Unfinished) => report.unfinished += 1,
+        Err(problem) => report.failures.push(failure(&generated.source, problem)),
+    }
+
+    let mut rng = Rng::new(!seed);
+    for _ in 0..MUTATIONS {
+        let mutated = mutate(&generated.source, &mut rng);
+        if let Err(problem) = check_front_end(&mutated) {
+            report.failures.push(failure(&mutated, problem));
+        }
+    }
+}
+
+// as sementes seed, seed + 1, ..., seed + runs - 1
+pub fn fuzz(seed: u64, runs: u64) -> Report {
+    let mut report = Report::default();
+    for offset in 0..runs {
+        fuzz_seed(seed.wrapping_add(offset), &mut report);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_is_deterministic() {
+        let first = ProgramGenerator::new(7).generate();
+        let second = ProgramGenerator::new(7).generate();
+        assert_eq!(first.source, second.source);
+        assert_eq!(first.input, second.input);
+        assert_ne!(first.source, ProgramGenerator::new(8).generate().source);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let report = fuzz(1, 150);
+        let failures: Vec<String> = report
+            .failures
+            .iter()
+            .map(|f| format!("semente {}: {}\n{}", f.seed, f.problem, f.source))
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+        assert!(report.unfinished < report.runs / 10, "{} de {} programas não terminaram", report.unfinished, report.runs);
+    }
+}
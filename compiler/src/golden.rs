@@ -0,0 +1,275 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::interpreter::run_program;
+use crate::{Lexer, ModuleLoader, Parser, Program, SemanticAnalyzer, SemanticError};
+
+// Testes de ponta a ponta sobre programas .sym (`compiler test`). Cada
+// programa declara o que espera em comentários de linha inteira:
+//
+//     // ENTRADA: 3 4          uma linha da entrada lida por `leia`
+//     // SAIDA: A soma é 7     uma linha da saída, na ordem
+//     // ERRO: linha 5 Token inesperado
+//
+// O programa é analisado como no `compiler check` e executa no
+// interpretador. A saída precisa ser exatamente a das linhas SAIDA (nenhuma
+// quer dizer saída vazia) e cada erro de compilação ou de execução precisa
+// de um ERRO com a mesma linha e um trecho da mensagem; ERRO sem "linha N"
+// vale para erros sem posição, como os de módulos.
+//
+// `--bless` apaga as linhas SAIDA e ERRO e grava as que descrevem o
+// resultado atual no fim do arquivo, onde não mudam as linhas do código.
+
+// comandos executados antes de considerar que o programa não termina
+pub const STEP_LIMIT: u64 = 10_000_000;
+
+const INPUT: &str = "// ENTRADA:";
+const OUTPUT: &str = "// SAIDA:";
+const ERROR: &str = "// ERRO:";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Expectations {
+    pub input: String,
+    pub output: Vec<String>,
+    pub errors: Vec<ExpectedError>,
+}
+
+impl Expectations {
+    pub fn parse(source: &str) -> Self {
+        let mut expectations = Expectations::default();
+        for line in source.lines() {
+            let line = line.trim_start();
+            if let Some(text) = annotation(line, INPUT) {
+                expectations.input.push_str(text);
+                expectations.input.push('\n');
+            } else if let Some(text) = annotation(line, OUTPUT) {
+                expectations.output.push(text.to_string());
+            } else if let Some(text) = annotation(line, ERROR) {
+                expectations.errors.push(ExpectedError::parse(text));
+            }
+        }
+        expectations
+    }
+}
+
+// o texto depois do marcador, sem o espaço que o separa
+fn annotation<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let text = line.strip_prefix(marker)?;
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+impl ExpectedError {
+    fn parse(text: &str) -> Self {
+        let located = text.strip_prefix("linha ").and_then(|rest| {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let line = rest[..digits].parse().ok()?;
+            Some((line, rest[digits..].trim_start()))
+        });
+        match located {
+            Some((line, message)) => Self { line: Some(line), message: message.to_string() },
+            None => Self { line: None, message: text.to_string() },
+        }
+    }
+
+    fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        self.line == diagnostic.span.line && describe(diagnostic).contains(&self.message)
+    }
+}
+
+impl std::fmt::Display for ExpectedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "linha {} {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// a mensagem numa linha só; erros sintáticos dizem também o que faltou
+fn describe(diagnostic: &Diagnostic) -> String {
+    let mut text = diagnostic.message.replace('\n', " ");
+    if let Some(found) = &diagnostic.found
+        && !diagnostic.expected.is_empty()
+    {
+        text.push_str(&format!(" (esperado {}, encontrado {})", diagnostic.expected.join(" ou "), found));
+    }
+    text
+}
+
+fn expected_error(diagnostic: &Diagnostic) -> ExpectedError {
+    ExpectedError { line: diagnostic.span.line, message: describe(diagnostic) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub output: String,
+    // erros da primeira fase que falhou ou o erro de execução
+    pub errors: Vec<Diagnostic>,
+}
+
+// léxico, sintático, módulos e análise semântica, as fases que `test` e o
+// depurador usam antes do interpretador; os erros são os da primeira que falhou
+pub fn analyze(source: &str, path: &Path) -> Result<Program, Vec<Diagnostic>> {
+    let file = path.display().to_string();
+    if let Err(e) = Lexer::new(source).tokenize() {
+        return Err(vec![Diagnostic::lexer(&e, &file)]);
+    }
+    let program = Parser::parse_from_source(source).map_err(|errors| errors.iter().map(|e| Diagnostic::parser(e, &file)).collect::<Vec<_>>())?;
+    let mut loader = ModuleLoader::new();
+    let program = loader.link(program, path).map_err(|errors| errors.iter().map(Diagnostic::module).collect::<Vec<_>>())?;
+    let result = SemanticAnalyzer::new().analyze(program);
+    if !result.errors.is_empty() {
+        // erros nas funções de um módulo apontam o arquivo do módulo
+        let file_of = |e: &SemanticError| e.function.as_deref().and_then(|f| loader.origin(f)).map_or(file.clone(), |p| p.display().to_string());
+        return Err(result.errors.iter().map(|e| Diagnostic::semantic(e, &file_of(e))).collect());
+    }
+    Ok(result.annotated_ast)
+}
+
+// compila e executa o programa com a entrada dada
+pub fn execute(source: &str, path: &Path, input: &str) -> Outcome {
+    let program = match analyze(source, path) {
+        Ok(program) => program,
+        Err(errors) => return Outcome { output: String::new(), errors },
+    };
+    let (output, result) = run_program(&program, input, STEP_LIMIT);
+    let file = path.display().to_string();
+    let errors = result.err().map(|e| Diagnostic::runtime(&e, &file)).into_iter().collect();
+    Outcome { output, errors }
+}
+
+// diferenças entre o que o programa declara e o que aconteceu; vazio se passou
+pub fn check(source: &str, path: &Path) -> Vec<String> {
+    let expectations = Expectations::parse(source);
+    let outcome = execute(source, path, &expectations.input);
+    let mut problems = Vec::new();
+
+    let mut unmatched: Vec<&Diagnostic> = outcome.errors.iter().filter(|d| d.severity == Severity::Error).collect();
+    for expected in &expectations.errors {
+        match unmatched.iter().position(|diagnostic| expected.matches(diagnostic)) {
+            Some(index) => {
+                unmatched.remove(index);
+            }
+            None => problems.push(format!("erro esperado não aconteceu: {}", expected)),
+        }
+    }
+    for diagnostic in unmatched {
+        problems.push(format!("erro inesperado: {}", expected_error(diagnostic)));
+    }
+
+    let actual: Vec<&str> = outcome.output.lines().collect();
+    let expected = &expectations.output;
+    if let Some(index) = (0..actual.len().max(expected.len())).find(|&i| actual.get(i).copied() != expected.get(i).map(String::as_str)) {
+        let show = |line: Option<&str>| line.map_or("(nada)".to_string(), |line| format!("{:?}", line));
+        problems.push(format!(
+            "saída diferente na linha {}: esperado {}, obtido {}",
+            index + 1,
+            show(expected.get(index).map(String::as_str)),
+            show(actual.get(index).copied())
+        ));
+    }
+    problems
+}
+
+// o programa com as linhas SAIDA e ERRO refeitas a partir do resultado atual
+pub fn bless(source: &str, path: &Path) -> String {
+    let mut lines: Vec<&str> = source
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !line.starts_with(OUTPUT) && !line.starts_with(ERROR)
+        })
+        .collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let code = lines.join("\n") + "\n";
+
+    let outcome = execute(&code, path, &Expectations::parse(&code).input);
+    let mut annotations: Vec<String> = outcome
+        .output
+        .lines()
+        .map(|line| if line.is_empty() { OUTPUT.to_string() } else { format!("{} {}", OUTPUT, line) })
+        .collect();
+    for diagnostic in outcome.errors.iter().filter(|d| d.severity == Severity::Error) {
+        annotations.push(format!("{} {}", ERROR, expected_error(diagnostic)));
+    }
+
+    if annotations.is_empty() {
+        code
+    } else {
+        format!("{}\n{}\n", code, annotations.join("\n"))
+    }
+}
+
+// os .sym dos caminhos dados, entrando nos diretórios, em ordem
+pub fn find_programs(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut programs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)?.map(|entry| entry.map(|e| e.path())).collect::<Result<_, _>>()?;
+            entries.sort();
+            let nested: Vec<PathBuf> = entries
+                .into_iter()
+                .filter(|entry| entry.is_dir() || entry.extension().is_some_and(|ext| ext == "sym"))
+                .collect();
+            programs.extend(find_programs(&nested)?);
+        } else {
+            programs.push(path.clone());
+        }
+    }
+    Ok(programs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expectations_and_bless() {
+        let source = "// ENTRADA: 2\n// SAIDA: 4\nfuncao principal() {\n    inteiro n;\n    leia(n);\n    escreva(n * 2);\n    escreva(n / 0);\n}\n// ERRO: linha 7 por zero\n";
+        let expectations = Expectations::parse(source);
+        assert_eq!(expectations.input, "2\n");
+        assert_eq!(expectations.errors, [ExpectedError { line: Some(7), message: "por zero".to_string() }]);
+        assert!(check(source, Path::new("t.sym")).is_empty());
+
+        let wrong = source.replace("SAIDA: 4", "SAIDA: 5").replace("linha 7", "linha 6");
+        assert_eq!(
+            check(&wrong, Path::new("t.sym")),
+            [
+                "erro esperado não aconteceu: linha 6 por zero",
+                "erro inesperado: linha 7 Divisão por zero",
+                "saída diferente na linha 1: esperado \"5\", obtido \"4\"",
+            ]
+        );
+
+        // as anotações vão para o fim; as linhas são as do código sem elas
+        let blessed = bless(&wrong, Path::new("t.sym"));
+        assert!(blessed.starts_with("// ENTRADA: 2\nfuncao principal() {\n"), "{}", blessed);
+        assert!(blessed.ends_with("}\n\n// SAIDA: 4\n// ERRO: linha 6 Divisão por zero\n"), "{}", blessed);
+        assert!(check(&blessed, Path::new("t.sym")).is_empty());
+
+        let syntax = bless("escreva(1)\nescreva(2);\n", Path::new("t.sym"));
+        assert_eq!(syntax, "escreva(1)\nescreva(2);\n\n// ERRO: linha 2 Token inesperado (esperado ';', encontrado 'escreva')\n");
+    }
+
+    #[test]
+    fn test_programas() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../programas");
+        let mut failures = Vec::new();
+        for path in find_programs(&[directory]).unwrap() {
+            let source = fs::read_to_string(&path).unwrap();
+            for problem in check(&source, &path) {
+                failures.push(format!("{}: {}", path.display(), problem));
+            }
+        }
+        assert!(failures.is_empty(), "{}\n(cargo run -- test --bless ../programas atualiza as anotações)", failures.join("\n"));
+    }
+}
@@ -0,0 +1,785 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+use crate::backend::{entry_point, format_decimal};
+use crate::parser::ast::*;
+use crate::semantic::builtins::{parse_decimal, parse_integer, parse_logical, Builtin};
+
+// Interpretador da AST, sem passar pela IR nem por um backend. Executa o
+// programa já analisado com as convenções de `backend` (comandos globais,
+// depois `principal()`; `escreva` com espaço entre os argumentos; decimais
+// com 6 casas) e a semântica dos backends nativos:
+//   - inteiros de 64 bits que dão a volta no transbordamento;
+//   - `&&` e `||` avaliam os dois lados;
+//   - divisão e resto de inteiros por zero, conversões de texto inválidas e
+//     entradas inválidas para `leia` são erros de execução.
+//
+// Cada chamada ganha um quadro com a linha do comando em execução e seus
+// escopos, do mais externo ao mais interno; o quadro 0 é o do programa
+// principal, e o primeiro escopo dele guarda as variáveis globais. Um
+// `Hook` (o depurador) vê a pilha de quadros antes de cada comando.
+
+// chamadas aninhadas antes de desistir; a recursão usa a pilha do Rust, e
+// 1000 chamadas pedem mais que os 2 MiB das threads de teste (ver `with_large_stack`)
+pub const MAX_CALL_DEPTH: usize = 1000;
+pub const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Inteiro(i64),
+    Decimal(f64),
+    Texto(String),
+    Logico(bool),
+}
+
+impl Value {
+    pub fn default_for(type_: &Type) -> Self {
+        match type_ {
+            Type::Inteiro => Value::Inteiro(0),
+            Type::Decimal => Value::Decimal(0.0),
+            Type::Texto => Value::Texto(String::new()),
+            Type::Logico => Value::Logico(false),
+        }
+    }
+
+    pub fn type_(&self) -> Type {
+        match self {
+            Value::Inteiro(_) => Type::Inteiro,
+            Value::Decimal(_) => Type::Decimal,
+            Value::Texto(_) => Type::Texto,
+            Value::Logico(_) => Type::Logico,
+        }
+    }
+
+    // promoção de inteiro para decimal onde um decimal é esperado
+    fn coerce(self, type_: &Type) -> Self {
+        match (self, type_) {
+            (Value::Inteiro(n), Type::Decimal) => Value::Decimal(n as f64),
+            (value, _) => value,
+        }
+    }
+}
+
+// como `escreva` mostra o valor
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Inteiro(n) => write!(f, "{}", n),
+            Value::Decimal(n) => write!(f, "{}", format_decimal(*n)),
+            Value::Texto(s) => write!(f, "{}", s),
+            Value::Logico(true) => write!(f, "verdadeiro"),
+            Value::Logico(false) => write!(f, "falso"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    // linha do comando que falhou (0 se desconhecida)
+    pub line: usize,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "ERRO DE EXECUÇÃO: {}", self.message)
+        } else {
+            write!(f, "ERRO DE EXECUÇÃO: Linha {} - {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    // "principal", o nome da função ou, no quadro 0, o do programa
+    pub function: String,
+    pub line: usize,
+    pub scopes: Vec<Vec<(String, Value)>>,
+}
+
+impl Frame {
+    fn new(function: &str, line: usize) -> Self {
+        Self { function: function.to_string(), line, scopes: vec![Vec::new()] }
+    }
+
+    fn find(&mut self, name: &str) -> Option<&mut Value> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|(n, _)| n == name).map(|(_, value)| value))
+    }
+}
+
+// Chamado antes de cada comando e a cada nova volta de um laço (com a linha
+// do laço), quando a linha do último quadro já é a do comando. Um erro
+// encerra a execução com ele.
+pub trait Hook {
+    fn before_statement(&mut self, frames: &[Frame]) -> Result<(), RuntimeError>;
+}
+
+// o que um comando faz com o restante do bloco
+enum Flow {
+    Next,
+    Return(Option<Value>),
+}
+
+pub struct Interpreter<'a, 'io> {
+    program: &'a Program,
+    functions: HashMap<&'a str, &'a FunctionDecl>,
+    frames: Vec<Frame>,
+    input: &'io mut dyn BufRead,
+    output: &'io mut dyn Write,
+    // palavras já lidas da linha atual, em ordem inversa
+    pending_input: Vec<String>,
+    hook: Option<&'io mut dyn Hook>,
+    max_steps: u64,
+    // comandos executados
+    pub steps: u64,
+}
+
+impl<'a, 'io> Interpreter<'a, 'io> {
+    pub fn new(program: &'a Program, input: &'io mut dyn BufRead, output: &'io mut dyn Write) -> Self {
+        Self {
+            program,
+            functions: program.functions.iter().map(|f| (f.name.as_str(), f)).collect(),
+            frames: Vec::new(),
+            input,
+            output,
+            pending_input: Vec::new(),
+            hook: None,
+            max_steps: u64::MAX,
+            steps: 0,
+        }
+    }
+
+    pub fn with_hook(mut self, hook: &'io mut dyn Hook) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    // executa até o fim ou até `max_steps` comandos
+    pub fn run(&mut self, max_steps: u64) -> Result<(), RuntimeError> {
+        self.max_steps = max_steps;
+        self.frames = vec![Frame::new("programa", 0)];
+
+        // as globais existem (com o valor padrão) desde o início, como nos backends
+        for statement in &self.program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                self.frames[0].scopes[0].push((decl.name.clone(), Value::default_for(&decl.var_type)));
+            }
+        }
+
+        let result = self.run_program();
+        let _ = self.output.flush();
+        result
+    }
+
+    // pilha de chamadas, do programa principal até a função atual
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    fn run_program(&mut self) -> Result<(), RuntimeError> {
+        let program = self.program;
+        for statement in &program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                self.step(decl.line)?;
+                if let Some(initializer) = &decl.initializer {
+                    let value = self.eval(initializer)?.coerce(&decl.var_type);
+                    self.assign(&decl.name, value)?;
+                }
+            } else if let Flow::Return(_) = self.execute(statement)? {
+                // `retorne` fora de funções encerra o programa
+                return Ok(());
+            }
+        }
+
+        if let Some(principal) = entry_point(program) {
+            self.call_function(principal, Vec::new())?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self, line: usize) -> Result<(), RuntimeError> {
+        self.steps += 1;
+        self.frame().line = line;
+        if self.steps > self.max_steps {
+            return Err(self.error(format!("Limite de {} comandos excedido", self.max_steps)));
+        }
+        match self.hook.as_deref_mut() {
+            Some(hook) => hook.before_statement(&self.frames),
+            None => Ok(()),
+        }
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("sempre há o quadro do programa")
+    }
+
+    fn error(&self, message: impl Into<String>) -> RuntimeError {
+        let line = self.frames.last().map_or(0, |frame| frame.line);
+        RuntimeError { message: message.into(), line }
+    }
+
+    // === VARIÁVEIS ===
+
+    fn declare(&mut self, name: &str, value: Value) {
+        let scope = self.frame().scopes.last_mut().expect("todo quadro tem um escopo");
+        scope.push((name.to_string(), value));
+    }
+
+    // escopos da função atual e, depois deles, as globais
+    fn lookup(&mut self, name: &str) -> Option<&mut Value> {
+        let (globals, rest) = self.frames.split_first_mut()?;
+        if let Some(frame) = rest.last_mut() {
+            if let Some(value) = frame.find(name) {
+                return Some(value);
+            }
+            return globals.scopes[0].iter_mut().rev().find(|(n, _)| n == name).map(|(_, value)| value);
+        }
+        globals.find(name)
+    }
+
+    fn assign(&mut self, name: &str, value: Value) -> Result<(), RuntimeError> {
+        match self.lookup(name) {
+            Some(slot) => {
+                let type_ = slot.type_();
+                *slot = value.coerce(&type_);
+                Ok(())
+            }
+            None => Err(self.error(format!("Variável '{}' não declarada", name))),
+        }
+    }
+
+    fn enter_scope(&mut self) {
+        self.frame().scopes.push(Vec::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.frame().scopes.pop();
+    }
+
+    // === COMANDOS ===
+
+    fn execute_block(&mut self, block: &Block) -> Result<Flow, RuntimeError> {
+        self.enter_scope();
+        let mut flow = Flow::Next;
+        for statement in &block.statements {
+            flow = self.execute(statement)?;
+            if let Flow::Return(_) = flow {
+                break;
+            }
+        }
+        self.exit_scope();
+        Ok(flow)
+    }
+
+    fn execute(&mut self, statement: &Statement) -> Result<Flow, RuntimeError> {
+        self.step(statement.line())?;
+
+        match statement {
+            Statement::VariableDecl(decl) => {
+                let value = match &decl.initializer {
+                    Some(initializer) => self.eval(initializer)?.coerce(&decl.var_type),
+                    None => Value::default_for(&decl.var_type),
+                };
+                self.declare(&decl.name, value);
+            }
+            Statement::ExprStmt(stmt) => {
+                self.eval_call_or_expr(&stmt.expr)?;
+            }
+            Statement::IfStmt(stmt) => {
+                if self.eval_condition(&stmt.condition)? {
+                    return self.execute_block(&stmt.then_branch);
+                } else if let Some(else_branch) = &stmt.else_branch {
+                    return self.execute_block(else_branch);
+                }
+            }
+            Statement::WhileStmt(stmt) => {
+                while self.eval_condition(&stmt.condition)? {
+                    if let Flow::Return(value) = self.execute_block(&stmt.body)? {
+                        return Ok(Flow::Return(value));
+                    }
+                    self.step(stmt.line)?;
+                }
+            }
+            Statement::ForStmt(stmt) => return self.execute_for(stmt),
+            Statement::ReturnStmt(stmt) => {
+                let value = match &stmt.value {
+                    Some(value) => Some(self.eval(value)?),
+                    None => None,
+                };
+                return Ok(Flow::Return(value));
+            }
+            // como nos backends nativos, cada argumento aparece assim que é
+            // avaliado: uma chamada que escreve ou um erro no meio da lista
+            // deixa na saída o que veio antes
+            Statement::WriteStmt(stmt) => {
+                for (i, argument) in stmt.arguments.iter().enumerate() {
+                    if i > 0 {
+                        self.write(" ", stmt.line)?;
+                    }
+                    let value = self.eval(argument)?;
+                    self.write(&value.to_string(), stmt.line)?;
+                }
+                self.write("\n", stmt.line)?;
+            }
+            Statement::ReadStmt(stmt) => {
+                let Expr::Variable(name) = &stmt.target else {
+                    return Err(self.error("Comando 'leia' só pode ser usado com variáveis"));
+                };
+                let type_ = match self.lookup(name) {
+                    Some(value) => value.type_(),
+                    None => return Err(self.error(format!("Variável '{}' não declarada", name))),
+                };
+                let value = self.read(&type_)?;
+                self.assign(name, value)?;
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    // `para v de a ate b`: v já declarada (inteiro ou decimal) é reaproveitada;
+    // senão, vira um inteiro novo visível só no laço. O limite é reavaliado a
+    // cada volta e incluído.
+    fn execute_for(&mut self, stmt: &ForStmt) -> Result<Flow, RuntimeError> {
+        self.enter_scope();
+        let existing = self.lookup(&stmt.variable).map(|value| value.type_());
+        let var_type = match existing {
+            Some(type_ @ (Type::Inteiro | Type::Decimal)) => type_,
+            _ => {
+                self.declare(&stmt.variable, Value::Inteiro(0));
+                Type::Inteiro
+            }
+        };
+
+        let result = self.run_for(stmt, &var_type);
+        self.exit_scope();
+        result
+    }
+
+    fn run_for(&mut self, stmt: &ForStmt, var_type: &Type) -> Result<Flow, RuntimeError> {
+        let start = self.eval(&stmt.start)?.coerce(var_type);
+        self.assign(&stmt.variable, start)?;
+
+        loop {
+            let current = self.lookup(&stmt.variable).cloned().unwrap_or(Value::Inteiro(0));
+            let end = self.eval(&stmt.end)?;
+            if !self.compare(&BinaryOperator::LessEqual, current.clone(), end)? {
+                return Ok(Flow::Next);
+            }
+            if let Flow::Return(value) = self.execute_block(&stmt.body)? {
+                return Ok(Flow::Return(value));
+            }
+            self.step(stmt.line)?;
+
+            let current = self.lookup(&stmt.variable).cloned().unwrap_or(current);
+            let next = match current {
+                Value::Decimal(n) => Value::Decimal(n + 1.0),
+                Value::Inteiro(n) => Value::Inteiro(n.wrapping_add(1)),
+                other => other,
+            };
+            self.assign(&stmt.variable, next)?;
+        }
+    }
+
+    fn write(&mut self, text: &str, line: usize) -> Result<(), RuntimeError> {
+        self.output
+            .write_all(text.as_bytes())
+            .map_err(|e| RuntimeError { message: format!("Erro de escrita: {}", e), line })
+    }
+
+    // lê uma palavra da entrada, como o `leia` dos backends nativos
+    fn read(&mut self, type_: &Type) -> Result<Value, RuntimeError> {
+        let word = self.next_word()?;
+        match type_ {
+            Type::Inteiro => word
+                .parse()
+                .map(Value::Inteiro)
+                .map_err(|_| self.error(format!("Entrada inválida para inteiro: '{}'", word))),
+            Type::Decimal => word
+                .parse()
+                .map(Value::Decimal)
+                .map_err(|_| self.error(format!("Entrada inválida para decimal: '{}'", word))),
+            Type::Texto => Ok(Value::Texto(word)),
+            Type::Logico => Ok(Value::Logico(word == "verdadeiro")),
+        }
+    }
+
+    // o fim da entrada é lido como texto vazio
+    fn next_word(&mut self) -> Result<String, RuntimeError> {
+        while self.pending_input.is_empty() {
+            let _ = self.output.flush();
+            let mut line = String::new();
+            let read = self.input
+                .read_line(&mut line)
+                .map_err(|e| self.error(format!("Erro de leitura: {}", e)))?;
+            if read == 0 {
+                return Ok(String::new());
+            }
+            self.pending_input = line.split_whitespace().rev().map(str::to_string).collect();
+        }
+        Ok(self.pending_input.pop().unwrap_or_default())
+    }
+
+    // === FUNÇÕES ===
+
+    fn call_function(&mut self, function: &FunctionDecl, arguments: Vec<Value>) -> Result<Option<Value>, RuntimeError> {
+        if self.frames.len() > MAX_CALL_DEPTH {
+            return Err(self.error(format!("Mais de {} chamadas aninhadas em '{}'", MAX_CALL_DEPTH, function.name)));
+        }
+
+        let mut frame = Frame::new(&function.name, function.line);
+        for (parameter, argument) in function.parameters.iter().zip(arguments) {
+            frame.scopes[0].push((parameter.name.clone(), argument.coerce(&parameter.param_type)));
+        }
+        self.frames.push(frame);
+        let flow = self.execute_block(&function.body);
+        self.frames.pop();
+
+        // sem `retorne`, a função devolve o valor padrão do tipo
+        let value = match (flow?, &function.return_type) {
+            (Flow::Return(Some(value)), Some(return_type)) => Some(value.coerce(return_type)),
+            (_, Some(return_type)) => Some(Value::default_for(return_type)),
+            (_, None) => None,
+        };
+        Ok(value)
+    }
+
+    fn call(&mut self, call: &CallExpr) -> Result<Option<Value>, RuntimeError> {
+        let mut arguments = Vec::new();
+        for argument in &call.arguments {
+            arguments.push(self.eval(argument)?);
+        }
+
+        if let Some(builtin) = Builtin::from_name(&call.function) {
+            return self.call_builtin(builtin, arguments).map(Some);
+        }
+        let Some(function) = self.functions.get(call.function.as_str()).copied() else {
+            return Err(self.error(format!("Função '{}' não declarada", call.function)));
+        };
+        if function.parameters.len() != arguments.len() {
+            return Err(self.error(format!(
+                "Função '{}' espera {} argumentos, mas {} foram fornecidos",
+                call.function, function.parameters.len(), arguments.len()
+            )));
+        }
+        self.call_function(function, arguments)
+    }
+
+    fn call_builtin(&mut self, builtin: Builtin, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        let types: Vec<Type> = arguments.iter().map(Value::type_).collect();
+        let overload = builtin.resolve(&types).map_err(|message| self.error(message))?;
+        let arguments: Vec<Value> = arguments
+            .into_iter()
+            .zip(overload.parameters)
+            .map(|(argument, type_)| argument.coerce(type_))
+            .collect();
+
+        let value = match (builtin, arguments.as_slice()) {
+            (Builtin::Raiz, [Value::Decimal(x)]) => Value::Decimal(x.sqrt()),
+            (Builtin::Potencia, [Value::Inteiro(base), Value::Inteiro(exponent)]) => Value::Inteiro(integer_power(*base, *exponent)),
+            (Builtin::Potencia, [Value::Decimal(base), Value::Decimal(exponent)]) => Value::Decimal(base.powf(*exponent)),
+            (Builtin::Abs, [Value::Inteiro(n)]) => Value::Inteiro(n.wrapping_abs()),
+            (Builtin::Abs, [Value::Decimal(x)]) => Value::Decimal(x.abs()),
+            (Builtin::Arredondar | Builtin::Piso | Builtin::Teto, [Value::Inteiro(n)]) => Value::Inteiro(*n),
+            (Builtin::Arredondar, [Value::Decimal(x)]) => Value::Decimal(x.round()),
+            (Builtin::Piso, [Value::Decimal(x)]) => Value::Decimal(x.floor()),
+            (Builtin::Teto, [Value::Decimal(x)]) => Value::Decimal(x.ceil()),
+            (Builtin::Tamanho, [Value::Texto(text)]) => Value::Inteiro(text.chars().count() as i64),
+            (Builtin::Maiusculo, [Value::Texto(text)]) => Value::Texto(text.chars().map(|c| swap_case(c, true)).collect()),
+            (Builtin::Minusculo, [Value::Texto(text)]) => Value::Texto(text.chars().map(|c| swap_case(c, false)).collect()),
+            (Builtin::Subtexto, [Value::Texto(text), Value::Inteiro(start), Value::Inteiro(count)]) => {
+                let skip = usize::try_from((*start).max(1) - 1).unwrap_or(usize::MAX);
+                let take = usize::try_from((*count).max(0)).unwrap_or(usize::MAX);
+                Value::Texto(text.chars().skip(skip).take(take).collect())
+            }
+            (Builtin::Contem, [Value::Texto(text), Value::Texto(part)]) => Value::Logico(text.contains(part.as_str())),
+            (Builtin::Substituir, [Value::Texto(text), Value::Texto(from), Value::Texto(to)]) => {
+                Value::Texto(if from.is_empty() { text.clone() } else { text.replace(from.as_str(), to) })
+            }
+            (Builtin::ParaInteiro | Builtin::ParaDecimal | Builtin::ParaTexto | Builtin::ParaLogico, [value]) => {
+                self.convert(value.clone(), &overload.return_type)?
+            }
+            _ => unreachable!("a sobrecarga escolhida fixa os tipos dos argumentos"),
+        };
+        Ok(value)
+    }
+
+    // conversões explícitas (paraInteiro, paraTexto...)
+    fn convert(&self, value: Value, to: &Type) -> Result<Value, RuntimeError> {
+        let invalid = |text: &str| self.error(format!("Conversão inválida para {}: \"{}\"", to, text));
+        let converted = match (value, to) {
+            (Value::Decimal(x), Type::Inteiro) => Value::Inteiro(x as i64), // satura; nan vira 0
+            (Value::Logico(b), Type::Inteiro) => Value::Inteiro(b as i64),
+            (Value::Texto(text), Type::Inteiro) => Value::Inteiro(parse_integer(&text).ok_or_else(|| invalid(&text))?),
+            (Value::Inteiro(n), Type::Decimal) => Value::Decimal(n as f64),
+            (Value::Logico(b), Type::Decimal) => Value::Decimal(b as i64 as f64),
+            (Value::Texto(text), Type::Decimal) => Value::Decimal(parse_decimal(&text).ok_or_else(|| invalid(&text))?),
+            (Value::Texto(text), Type::Logico) => Value::Logico(parse_logical(&text).ok_or_else(|| invalid(&text))?),
+            (Value::Inteiro(n), Type::Logico) => Value::Logico(n != 0),
+            (value, Type::Texto) => Value::Texto(value.to_string()),
+            (value, _) => value,
+        };
+        Ok(converted)
+    }
+
+    // === EXPRESSÕES ===
+
+    fn eval_call_or_expr(&mut self, expr: &Expr) -> Result<Option<Value>, RuntimeError> {
+        match expr {
+            Expr::Call(call) => self.call(call),
+            expr => self.eval(expr).map(Some),
+        }
+    }
+
+    fn eval_condition(&mut self, expr: &Expr) -> Result<bool, RuntimeError> {
+        match self.eval(expr)? {
+            Value::Logico(b) => Ok(b),
+            other => Err(self.error(format!("Condição deve ser logico, encontrado {}", other.type_()))),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
+        match expr {
+            Expr::Literal(literal) => Ok(match literal {
+                Literal::Inteiro(n) => Value::Inteiro(*n),
+                Literal::Decimal(n) => Value::Decimal(*n),
+                Literal::Texto(s) => Value::Texto(s.clone()),
+                Literal::Logico(b) => Value::Logico(*b),
+            }),
+            Expr::Variable(name) => match self.lookup(name) {
+                Some(value) => Ok(value.clone()),
+                None => Err(self.error(format!("Variável '{}' não declarada", name))),
+            },
+            Expr::Call(call) => self
+                .call(call)?
+                .ok_or_else(|| self.error(format!("Função '{}' não retorna valor", call.function))),
+            Expr::UnaryOp(op, operand) => match (op, self.eval(operand)?) {
+                (UnaryOperator::Plus, value) => Ok(value),
+                (UnaryOperator::Negate, Value::Inteiro(n)) => Ok(Value::Inteiro(n.wrapping_neg())),
+                (UnaryOperator::Negate, Value::Decimal(x)) => Ok(Value::Decimal(-x)),
+                (UnaryOperator::Not, Value::Logico(b)) => Ok(Value::Logico(!b)),
+                (op, value) => Err(self.error(format!("Operador '{}' não suportado para {}", op, value.type_()))),
+            },
+            Expr::BinaryOp(op, left, right) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                self.binary(op, left, right)
+            }
+        }
+    }
+
+    fn binary(&self, op: &BinaryOperator, left: Value, right: Value) -> Result<Value, RuntimeError> {
+        // concatenação: o outro lado vira texto como em paraTexto
+        if *op == BinaryOperator::Add && (left.type_() == Type::Texto || right.type_() == Type::Texto) {
+            return Ok(Value::Texto(format!("{}{}", left, right)));
+        }
+
+        match op {
+            BinaryOperator::Equal
+            | BinaryOperator::NotEqual
+            | BinaryOperator::Less
+            | BinaryOperator::LessEqual
+            | BinaryOperator::Greater
+            | BinaryOperator::GreaterEqual => self.compare(op, left, right).map(Value::Logico),
+            BinaryOperator::And | BinaryOperator::Or => match (left, right) {
+                (Value::Logico(a), Value::Logico(b)) => Ok(Value::Logico(if *op == BinaryOperator::And { a && b } else { a || b })),
+                _ => Err(self.error(format!("Operador '{}' requer operandos lógicos", op))),
+            },
+            _ => match (left, right) {
+                (Value::Inteiro(a), Value::Inteiro(b)) => self.integer_arithmetic(op, a, b),
+                (Value::Inteiro(a), Value::Decimal(b)) => self.decimal_arithmetic(op, a as f64, b),
+                (Value::Decimal(a), Value::Inteiro(b)) => self.decimal_arithmetic(op, a, b as f64),
+                (Value::Decimal(a), Value::Decimal(b)) => self.decimal_arithmetic(op, a, b),
+                (left, right) => Err(self.error(format!(
+                    "Operador '{}' não suportado para tipos {} e {}", op, left.type_(), right.type_()
+                ))),
+            },
+        }
+    }
+
+    fn integer_arithmetic(&self, op: &BinaryOperator, a: i64, b: i64) -> Result<Value, RuntimeError> {
+        if matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo) && b == 0 {
+            return Err(self.error("Divisão por zero"));
+        }
+        let result = match op {
+            BinaryOperator::Add => a.wrapping_add(b),
+            BinaryOperator::Subtract => a.wrapping_sub(b),
+            BinaryOperator::Multiply => a.wrapping_mul(b),
+            BinaryOperator::Divide => a.wrapping_div(b),
+            BinaryOperator::Modulo => a.wrapping_rem(b),
+            _ => return Err(self.error(format!("Operador '{}' não suportado para inteiros", op))),
+        };
+        Ok(Value::Inteiro(result))
+    }
+
+    fn decimal_arithmetic(&self, op: &BinaryOperator, a: f64, b: f64) -> Result<Value, RuntimeError> {
+        let result = match op {
+            BinaryOperator::Add => a + b,
+            BinaryOperator::Subtract => a - b,
+            BinaryOperator::Multiply => a * b,
+            BinaryOperator::Divide => a / b,
+            _ => return Err(self.error(format!("Operador '{}' não suportado para decimais", op))),
+        };
+        Ok(Value::Decimal(result))
+    }
+
+    // textos comparam byte a byte (a ordem do UTF-8), como nos backends
+    fn compare(&self, op: &BinaryOperator, left: Value, right: Value) -> Result<bool, RuntimeError> {
+        let ordering = match (&left, &right) {
+            (Value::Inteiro(a), Value::Inteiro(b)) => Some(a.cmp(b)),
+            (Value::Texto(a), Value::Texto(b)) => Some(a.as_bytes().cmp(b.as_bytes())),
+            (Value::Logico(a), Value::Logico(b)) if matches!(op, BinaryOperator::Equal | BinaryOperator::NotEqual) => {
+                Some(a.cmp(b))
+            }
+            (Value::Inteiro(_) | Value::Decimal(_), Value::Inteiro(_) | Value::Decimal(_)) => {
+                let as_decimal = |value: &Value| match value {
+                    Value::Inteiro(n) => *n as f64,
+                    Value::Decimal(x) => *x,
+                    _ => unreachable!(),
+                };
+                // nan não é igual, menor nem maior que nada
+                as_decimal(&left).partial_cmp(&as_decimal(&right))
+            }
+            _ => {
+                return Err(self.error(format!(
+                    "Operador '{}' não suportado para tipos {} e {}", op, left.type_(), right.type_()
+                )));
+            }
+        };
+
+        use std::cmp::Ordering::*;
+        Ok(match (op, ordering) {
+            (BinaryOperator::NotEqual, ordering) => ordering != Some(Equal),
+            (_, None) => false,
+            (BinaryOperator::Equal, Some(ordering)) => ordering == Equal,
+            (BinaryOperator::Less, Some(ordering)) => ordering == Less,
+            (BinaryOperator::LessEqual, Some(ordering)) => ordering != Greater,
+            (BinaryOperator::Greater, Some(ordering)) => ordering == Greater,
+            (_, Some(ordering)) => ordering != Less,
+        })
+    }
+}
+
+// executa com a entrada e a saída em memória; devolve o que foi escrito
+// até o fim ou até o erro
+pub fn run_program(program: &Program, input: &str, max_steps: u64) -> (String, Result<(), RuntimeError>) {
+    with_large_stack(|| {
+        let mut input = input.as_bytes();
+        let mut output = Vec::new();
+        let result = Interpreter::new(program, &mut input, &mut output).run(max_steps);
+        (String::from_utf8_lossy(&output).into_owned(), result)
+    })
+}
+
+// roda `run` numa thread com pilha de STACK_SIZE, onde cabem MAX_CALL_DEPTH
+// chamadas aninhadas
+pub fn with_large_stack<T: Send>(run: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, run)
+            .expect("não foi possível criar a thread do interpretador")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+// quadrados sucessivos com inteiros de 64 bits; com expoente negativo só as
+// bases 1 e -1 não resultam em 0
+fn integer_power(base: i64, exponent: i64) -> i64 {
+    if exponent < 0 {
+        return match base {
+            1 => 1,
+            -1 if exponent % 2 == 0 => 1,
+            -1 => -1,
+            _ => 0,
+        };
+    }
+    let (mut result, mut base, mut exponent) = (1i64, base, exponent);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+    result
+}
+
+// A-Z e as letras do Latin-1 (À-Þ e à-þ, sem × e ÷), como nos backends
+fn swap_case(c: char, upper: bool) -> char {
+    let from = if upper {
+        c.is_ascii_lowercase() || matches!(c, '\u{e0}'..='\u{fe}') && c != '\u{f7}'
+    } else {
+        c.is_ascii_uppercase() || matches!(c, '\u{c0}'..='\u{de}') && c != '\u{d7}'
+    };
+    if from { char::from_u32(c as u32 ^ 0x20).unwrap_or(c) } else { c }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, SemanticAnalyzer};
+
+    fn run(source: &str, input: &str) -> (String, Result<(), RuntimeError>) {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        run_program(&result.annotated_ast, input, 100_000)
+    }
+
+    #[test]
+    fn test_interpreter() {
+        let (output, result) = run(
+            "funcao inteiro fatorial(inteiro n) {
+                 se n <= 1 entao { retorne 1; } fimse
+                 retorne n * fatorial(n - 1);
+             }
+             funcao principal() {
+                 texto nome;
+                 inteiro i;
+                 inteiro limite = 3;
+                 leia(nome);
+                 para i de 1 ate limite faca { escreva(i, fatorial(i + 17), i / 2.0); } fimpara
+                 escreva(\"Olá, {maiusculo(nome)}!\", 7 % -3, potencia(2, 63), 1 < 2 && !falso);
+                 escreva(subtexto(\"ação\", 2, 10), paraInteiro(-2.9), paraTexto(verdadeiro) + 1);
+             }",
+            "ana\n",
+        );
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            output,
+            "1 6402373705728000 0.500000\n\
+             2 121645100408832000 1.000000\n\
+             3 2432902008176640000 1.500000\n\
+             Olá, ANA! 1 -9223372036854775808 verdadeiro\n\
+             ção -2 verdadeiro1\n"
+        );
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let (output, result) = run("funcao principal() {\n  escreva(1);\n  inteiro zero = 0;\n  escreva(1 / zero);\n}", "");
+        assert_eq!(output, "1\n");
+        assert_eq!(result, Err(RuntimeError { message: "Divisão por zero".to_string(), line: 4 }));
+
+        // o que veio antes do erro na lista do escreva já foi escrito
+        let (output, result) = run("funcao principal() {\n  inteiro zero = 0;\n  escreva(\"a\", 1 / zero);\n}", "");
+        assert_eq!(output, "a ");
+        assert_eq!(result.unwrap_err().line, 3);
+
+        let (_, result) = run("funcao principal() {\n  escreva(paraInteiro(\"abc\"));\n}", "");
+        assert_eq!(result.unwrap_err().to_string(), "ERRO DE EXECUÇÃO: Linha 2 - Conversão inválida para inteiro: \"abc\"");
+
+        let (_, result) = run("funcao inteiro f(inteiro n) { retorne f(n + 1); }\nescreva(f(0));", "");
+        assert!(result.unwrap_err().message.starts_with("Mais de 1000 chamadas aninhadas"));
+
+        let (_, result) = run("enquanto verdadeiro faca { } fimenquanto", "");
+        assert_eq!(result.unwrap_err().message, "Limite de 100000 comandos excedido");
+    }
+}
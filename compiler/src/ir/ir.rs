@@ -0,0 +1,157 @@
+use crate::parser::ast::{BinaryOperator, Literal, Type, UnaryOperator};
+
+// Representação intermediária de três endereços.
+//
+// Cada função é uma lista de blocos básicos; o bloco 0 é a entrada.
+// Temporários (%n) são atribuídos uma única vez e só valem dentro do bloco
+// em que foram definidos. Variáveis (locais e globais) vivem em memória e
+// são acessadas com load/store.
+
+// nome da função sintetizada que executa os comandos globais e chama `principal`
+pub const ENTRY_FUNCTION: &str = "__inicio__";
+
+pub type TempId = usize;
+pub type BlockId = usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Temp {
+    pub id: TempId,
+    pub ty: Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    Temp(TempId),
+    Const(Literal),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarRef {
+    Local(String),
+    Global(String),
+}
+
+impl VarRef {
+    pub fn name(&self) -> &str {
+        match self {
+            VarRef::Local(name) | VarRef::Global(name) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instr {
+    Load { dest: Temp, var: VarRef },
+    Store { var: VarRef, value: Operand },
+    Binary { dest: Temp, op: BinaryOperator, left: Operand, right: Operand },
+    Unary { dest: Temp, op: UnaryOperator, operand: Operand },
+    // conversão inteiro -> decimal
+    IntToDecimal { dest: Temp, value: Operand },
+    Call { dest: Option<Temp>, function: String, arguments: Vec<Operand> },
+    Write { arguments: Vec<Operand> },
+    Read { var: VarRef },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Terminator {
+    Jump(BlockId),
+    Branch { condition: Operand, then_block: BlockId, else_block: BlockId },
+    Return(Option<Operand>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasicBlock {
+    pub instructions: Vec<Instr>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrVariable {
+    pub name: String,
+    pub var_type: Type,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrFunction {
+    pub name: String,
+    pub return_type: Option<Type>,
+    pub parameters: Vec<IrVariable>,
+    pub locals: Vec<IrVariable>,
+    pub blocks: Vec<BasicBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IrProgram {
+    pub globals: Vec<IrVariable>,
+    pub functions: Vec<IrFunction>,
+}
+
+impl Instr {
+    pub fn dest(&self) -> Option<&Temp> {
+        match self {
+            Instr::Load { dest, .. }
+            | Instr::Binary { dest, .. }
+            | Instr::Unary { dest, .. }
+            | Instr::IntToDecimal { dest, .. } => Some(dest),
+            Instr::Call { dest, .. } => dest.as_ref(),
+            Instr::Store { .. } | Instr::Write { .. } | Instr::Read { .. } => None,
+        }
+    }
+
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Instr::Load { .. } | Instr::Read { .. } => vec![],
+            Instr::Store { value, .. } | Instr::IntToDecimal { value, .. } => vec![value],
+            Instr::Binary { left, right, .. } => vec![left, right],
+            Instr::Unary { operand, .. } => vec![operand],
+            Instr::Call { arguments, .. } | Instr::Write { arguments } => arguments.iter().collect(),
+        }
+    }
+}
+
+impl Terminator {
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Jump(target) => vec![*target],
+            Terminator::Branch { then_block, else_block, .. } => vec![*then_block, *else_block],
+            Terminator::Return(_) => vec![],
+        }
+    }
+}
+
+impl IrFunction {
+    pub fn lookup_variable(&self, name: &str) -> Option<&IrVariable> {
+        self.parameters
+            .iter()
+            .chain(self.locals.iter())
+            .find(|v| v.name == name)
+    }
+}
+
+impl IrProgram {
+    pub fn lookup_global(&self, name: &str) -> Option<&IrVariable> {
+        self.globals.iter().find(|v| v.name == name)
+    }
+
+    pub fn lookup_function(&self, name: &str) -> Option<&IrFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+}
+
+pub fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::Inteiro(_) => Type::Inteiro,
+        Literal::Decimal(_) => Type::Decimal,
+        Literal::Texto(_) => Type::Texto,
+        Literal::Logico(_) => Type::Logico,
+    }
+}
+
+pub fn default_value(ty: &Type) -> Literal {
+    match ty {
+        Type::Inteiro => Literal::Inteiro(0),
+        Type::Decimal => Literal::Decimal(0.0),
+        Type::Texto => Literal::Texto(String::new()),
+        Type::Logico => Literal::Logico(false),
+    }
+}
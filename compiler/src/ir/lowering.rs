@@ -0,0 +1,761 @@
+use std::collections::HashMap;
+use crate::parser::ast::*;
+use crate::ir::ir::*;
+use crate::semantic::builtins::Builtin;
+
+#[derive(Debug, Clone)]
+pub struct LoweringError {
+    pub message: String,
+    // linha do comando (ou da função) que falhou; 0 se desconhecida
+    pub line: usize,
+}
+
+impl LoweringError {
+    fn new(message: String) -> Self {
+        Self { message, line: 0 }
+    }
+
+    // como BackendError::at_line: fica a linha do comando mais interno
+    fn at_line(self, line: usize) -> Self {
+        if self.line == 0 { Self { line, ..self } } else { self }
+    }
+}
+
+impl std::fmt::Display for LoweringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "ERRO NA GERAÇÃO DE IR: {}", self.message)
+        } else {
+            write!(f, "ERRO NA GERAÇÃO DE IR: Linha {} - {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for LoweringError {}
+
+struct FunctionSignature {
+    parameters: Vec<Type>,
+    return_type: Option<Type>,
+}
+
+struct PendingBlock {
+    instructions: Vec<Instr>,
+    terminator: Option<Terminator>,
+}
+
+// Traduz a AST anotada para a IR de três endereços.
+// O laço `para` é reescrito como um `enquanto` e as promoções
+// inteiro -> decimal ficam explícitas (instrução itod).
+pub struct Lowering {
+    signatures: HashMap<String, FunctionSignature>,
+    globals: Vec<IrVariable>,
+
+    // estado da função sendo gerada
+    scopes: Vec<HashMap<String, (VarRef, Type)>>,
+    locals: Vec<IrVariable>,
+    name_counts: HashMap<String, usize>,
+    blocks: Vec<PendingBlock>,
+    current_block: BlockId,
+    next_temp: TempId,
+    current_return: Option<Type>,
+}
+
+impl Default for Lowering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lowering {
+    pub fn new() -> Self {
+        Self {
+            signatures: HashMap::new(),
+            globals: Vec::new(),
+            scopes: Vec::new(),
+            locals: Vec::new(),
+            name_counts: HashMap::new(),
+            blocks: Vec::new(),
+            current_block: 0,
+            next_temp: 0,
+            current_return: None,
+        }
+    }
+
+    pub fn lower(&mut self, program: &Program) -> Result<IrProgram, LoweringError> {
+        self.signatures.clear();
+        self.globals.clear();
+
+        for function in &program.functions {
+            if function.name == ENTRY_FUNCTION {
+                return Err(LoweringError::new(format!(
+                    "O nome '{}' é reservado", ENTRY_FUNCTION
+                )));
+            }
+            self.signatures.insert(function.name.clone(), FunctionSignature {
+                parameters: function.parameters.iter().map(|p| p.param_type.clone()).collect(),
+                return_type: function.return_type.clone(),
+            });
+        }
+
+        for statement in &program.statements {
+            if let Statement::VariableDecl(decl) = statement {
+                self.globals.push(IrVariable {
+                    name: decl.name.clone(),
+                    var_type: decl.var_type.clone(),
+                });
+            }
+        }
+
+        let mut functions = Vec::new();
+        for function in &program.functions {
+            functions.push(self.lower_function(function).map_err(|e| e.at_line(function.line))?);
+        }
+        functions.push(self.lower_entry(&program.statements)?);
+
+        Ok(IrProgram {
+            globals: self.globals.clone(),
+            functions,
+        })
+    }
+
+    // === FUNÇÕES ===
+
+    fn lower_function(&mut self, function: &FunctionDecl) -> Result<IrFunction, LoweringError> {
+        let parameters: Vec<IrVariable> = function.parameters
+            .iter()
+            .map(|p| IrVariable { name: p.name.clone(), var_type: p.param_type.clone() })
+            .collect();
+
+        self.begin_function(&parameters, function.return_type.clone());
+        self.lower_block(&function.body)?;
+
+        Ok(self.finish_function(function.name.clone(), function.return_type.clone(), parameters))
+    }
+
+    // executa os comandos globais e depois chama `principal`, se existir
+    fn lower_entry(&mut self, statements: &[Statement]) -> Result<IrFunction, LoweringError> {
+        self.begin_function(&[], None);
+
+        for statement in statements {
+            if self.is_terminated() {
+                break;
+            }
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    let value = self.lower_initializer(decl).map_err(|e| e.at_line(decl.line))?;
+                    self.emit(Instr::Store { var: VarRef::Global(decl.name.clone()), value });
+                }
+                other => self.lower_statement(other).map_err(|e| e.at_line(other.line()))?,
+            }
+        }
+
+        let calls_principal = self.signatures
+            .get("principal")
+            .is_some_and(|sig| sig.parameters.is_empty());
+
+        if calls_principal && !self.is_terminated() {
+            let return_type = self.signatures["principal"].return_type.clone();
+            let dest = return_type.map(|ty| self.new_temp(ty));
+            self.emit(Instr::Call {
+                dest,
+                function: "principal".to_string(),
+                arguments: vec![],
+            });
+        }
+
+        Ok(self.finish_function(ENTRY_FUNCTION.to_string(), None, vec![]))
+    }
+
+    fn begin_function(&mut self, parameters: &[IrVariable], return_type: Option<Type>) {
+        let global_scope = self.globals
+            .iter()
+            .map(|g| (g.name.clone(), (VarRef::Global(g.name.clone()), g.var_type.clone())))
+            .collect();
+        let param_scope = parameters
+            .iter()
+            .map(|p| (p.name.clone(), (VarRef::Local(p.name.clone()), p.var_type.clone())))
+            .collect();
+
+        self.scopes = vec![global_scope, param_scope];
+        self.locals.clear();
+        self.name_counts = parameters.iter().map(|p| (p.name.clone(), 1)).collect();
+        self.blocks = vec![PendingBlock { instructions: Vec::new(), terminator: None }];
+        self.current_block = 0;
+        self.next_temp = 0;
+        self.current_return = return_type;
+    }
+
+    fn finish_function(
+        &mut self,
+        name: String,
+        return_type: Option<Type>,
+        parameters: Vec<IrVariable>,
+    ) -> IrFunction {
+        // função que "cai" no fim do corpo retorna o valor padrão do tipo
+        let fallthrough = Terminator::Return(return_type.as_ref().map(|ty| Operand::Const(default_value(ty))));
+
+        let pending = std::mem::take(&mut self.blocks);
+        let blocks: Vec<BasicBlock> = pending
+            .into_iter()
+            .map(|b| BasicBlock {
+                instructions: b.instructions,
+                terminator: b.terminator.unwrap_or_else(|| fallthrough.clone()),
+            })
+            .collect();
+
+        IrFunction {
+            name,
+            return_type,
+            parameters,
+            locals: std::mem::take(&mut self.locals),
+            blocks: remove_unreachable_blocks(blocks),
+        }
+    }
+
+    // === STATEMENTS ===
+
+    fn lower_block(&mut self, block: &Block) -> Result<(), LoweringError> {
+        self.scopes.push(HashMap::new());
+
+        for statement in &block.statements {
+            // código após um retorno é inalcançável
+            if self.is_terminated() {
+                break;
+            }
+            self.lower_statement(statement).map_err(|e| e.at_line(statement.line()))?;
+        }
+
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn lower_statement(&mut self, statement: &Statement) -> Result<(), LoweringError> {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                let value = self.lower_initializer(decl)?;
+                let var = self.declare_local(&decl.name, decl.var_type.clone());
+                self.emit(Instr::Store { var, value });
+            }
+            Statement::ExprStmt(expr_stmt) => {
+                if let Expr::Call(call) = &expr_stmt.expr {
+                    self.lower_call(call)?;
+                } else {
+                    self.lower_expr(&expr_stmt.expr)?;
+                }
+            }
+            Statement::IfStmt(if_stmt) => self.lower_if(if_stmt)?,
+            Statement::WhileStmt(while_stmt) => self.lower_while(while_stmt)?,
+            Statement::ForStmt(for_stmt) => self.lower_for(for_stmt)?,
+            Statement::ReturnStmt(return_stmt) => {
+                let value = match (&return_stmt.value, self.current_return.clone()) {
+                    (Some(expr), Some(expected)) => {
+                        let (operand, ty) = self.lower_expr(expr)?;
+                        Some(self.coerce(operand, &ty, &expected)?)
+                    }
+                    (None, None) => None,
+                    (Some(_), None) => {
+                        return Err(LoweringError::new("Retorno com valor em função sem tipo de retorno".to_string()));
+                    }
+                    (None, Some(_)) => {
+                        return Err(LoweringError::new("Retorno sem valor em função com tipo de retorno".to_string()));
+                    }
+                };
+                self.terminate(Terminator::Return(value));
+            }
+            Statement::WriteStmt(write_stmt) => {
+                let mut arguments = Vec::new();
+                for arg in &write_stmt.arguments {
+                    arguments.push(self.lower_expr(arg)?.0);
+                }
+                self.emit(Instr::Write { arguments });
+            }
+            Statement::ReadStmt(read_stmt) => {
+                let Expr::Variable(name) = &read_stmt.target else {
+                    return Err(LoweringError::new("Comando 'leia' só pode ser usado com variáveis".to_string()));
+                };
+                let (var, _) = self.lookup(name)?;
+                self.emit(Instr::Read { var });
+            }
+        }
+        Ok(())
+    }
+
+    fn lower_initializer(&mut self, decl: &VariableDecl) -> Result<Operand, LoweringError> {
+        match &decl.initializer {
+            Some(expr) => {
+                let (operand, ty) = self.lower_expr(expr)?;
+                self.coerce(operand, &ty, &decl.var_type)
+            }
+            None => Ok(Operand::Const(default_value(&decl.var_type))),
+        }
+    }
+
+    fn lower_if(&mut self, if_stmt: &IfStmt) -> Result<(), LoweringError> {
+        let condition = self.lower_condition(&if_stmt.condition)?;
+
+        let then_block = self.new_block();
+        let else_block = if_stmt.else_branch.as_ref().map(|_| self.new_block());
+        let merge_block = self.new_block();
+
+        self.terminate(Terminator::Branch {
+            condition,
+            then_block,
+            else_block: else_block.unwrap_or(merge_block),
+        });
+
+        self.switch_to(then_block);
+        self.lower_block(&if_stmt.then_branch)?;
+        self.terminate(Terminator::Jump(merge_block));
+
+        if let (Some(else_branch), Some(else_block)) = (&if_stmt.else_branch, else_block) {
+            self.switch_to(else_block);
+            self.lower_block(else_branch)?;
+            self.terminate(Terminator::Jump(merge_block));
+        }
+
+        self.switch_to(merge_block);
+        Ok(())
+    }
+
+    fn lower_while(&mut self, while_stmt: &WhileStmt) -> Result<(), LoweringError> {
+        let cond_block = self.new_block();
+        let body_block = self.new_block();
+        let exit_block = self.new_block();
+
+        self.terminate(Terminator::Jump(cond_block));
+
+        self.switch_to(cond_block);
+        let condition = self.lower_condition(&while_stmt.condition)?;
+        self.terminate(Terminator::Branch { condition, then_block: body_block, else_block: exit_block });
+
+        self.switch_to(body_block);
+        self.lower_block(&while_stmt.body)?;
+        self.terminate(Terminator::Jump(cond_block));
+
+        self.switch_to(exit_block);
+        Ok(())
+    }
+
+    // para v de a ate b faca corpo fimpara
+    //   =>  v = a; enquanto v <= b faca corpo; v = v + 1 fimenquanto
+    // Se já existe uma variável numérica `v` visível ela é reutilizada,
+    // senão o laço declara uma nova variável inteira.
+    fn lower_for(&mut self, for_stmt: &ForStmt) -> Result<(), LoweringError> {
+        self.scopes.push(HashMap::new());
+
+        let (var, var_type) = match self.lookup(&for_stmt.variable) {
+            Ok((var, ty)) if matches!(ty, Type::Inteiro | Type::Decimal) => (var, ty),
+            _ => (self.declare_local(&for_stmt.variable, Type::Inteiro), Type::Inteiro),
+        };
+
+        let (start, start_type) = self.lower_expr(&for_stmt.start)?;
+        let start = self.coerce(start, &start_type, &var_type)?;
+        self.emit(Instr::Store { var: var.clone(), value: start });
+
+        let cond_block = self.new_block();
+        let body_block = self.new_block();
+        let exit_block = self.new_block();
+
+        self.terminate(Terminator::Jump(cond_block));
+
+        self.switch_to(cond_block);
+        let current = self.new_temp(var_type.clone());
+        self.emit(Instr::Load { dest: current.clone(), var: var.clone() });
+        let (end, end_type) = self.lower_expr(&for_stmt.end)?;
+        let compare_type = Self::numeric_result(&var_type, &end_type)?;
+        let current = self.coerce(Operand::Temp(current.id), &var_type, &compare_type)?;
+        let end = self.coerce(end, &end_type, &compare_type)?;
+        let condition = self.new_temp(Type::Logico);
+        self.emit(Instr::Binary {
+            dest: condition.clone(),
+            op: BinaryOperator::LessEqual,
+            left: current,
+            right: end,
+        });
+        self.terminate(Terminator::Branch {
+            condition: Operand::Temp(condition.id),
+            then_block: body_block,
+            else_block: exit_block,
+        });
+
+        self.switch_to(body_block);
+        self.lower_block(&for_stmt.body)?;
+        if !self.is_terminated() {
+            let current = self.new_temp(var_type.clone());
+            self.emit(Instr::Load { dest: current.clone(), var: var.clone() });
+            let one = match var_type {
+                Type::Decimal => Literal::Decimal(1.0),
+                _ => Literal::Inteiro(1),
+            };
+            let next = self.new_temp(var_type.clone());
+            self.emit(Instr::Binary {
+                dest: next.clone(),
+                op: BinaryOperator::Add,
+                left: Operand::Temp(current.id),
+                right: Operand::Const(one),
+            });
+            self.emit(Instr::Store { var, value: Operand::Temp(next.id) });
+            self.terminate(Terminator::Jump(cond_block));
+        }
+
+        self.switch_to(exit_block);
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn lower_condition(&mut self, condition: &Expr) -> Result<Operand, LoweringError> {
+        let (operand, ty) = self.lower_expr(condition)?;
+        if ty != Type::Logico {
+            return Err(LoweringError::new(format!("Condição deve ser do tipo lógico, encontrado {}", ty)));
+        }
+        Ok(operand)
+    }
+
+    // === EXPRESSÕES ===
+
+    fn lower_expr(&mut self, expr: &Expr) -> Result<(Operand, Type), LoweringError> {
+        match expr {
+            Expr::Literal(literal) => Ok((Operand::Const(literal.clone()), literal_type(literal))),
+            Expr::Variable(name) => {
+                let (var, ty) = self.lookup(name)?;
+                let dest = self.new_temp(ty.clone());
+                self.emit(Instr::Load { dest: dest.clone(), var });
+                Ok((Operand::Temp(dest.id), ty))
+            }
+            Expr::Call(call) => self.lower_call(call)?.ok_or_else(|| {
+                LoweringError::new(format!("Função '{}' não tem tipo de retorno", call.function))
+            }),
+            Expr::BinaryOp(op, left, right) => self.lower_binary(op, left, right),
+            Expr::UnaryOp(op, operand) => {
+                let (operand, ty) = self.lower_expr(operand)?;
+                match op {
+                    UnaryOperator::Plus if Self::is_numeric(&ty) => Ok((operand, ty)),
+                    UnaryOperator::Negate if Self::is_numeric(&ty) => {
+                        let dest = self.new_temp(ty.clone());
+                        self.emit(Instr::Unary { dest: dest.clone(), op: op.clone(), operand });
+                        Ok((Operand::Temp(dest.id), ty))
+                    }
+                    UnaryOperator::Not if ty == Type::Logico => {
+                        let dest = self.new_temp(Type::Logico);
+                        self.emit(Instr::Unary { dest: dest.clone(), op: op.clone(), operand });
+                        Ok((Operand::Temp(dest.id), Type::Logico))
+                    }
+                    _ => Err(LoweringError::new(format!("Operador '{}' não suportado para o tipo {}", op, ty))),
+                }
+            }
+        }
+    }
+
+    fn lower_binary(&mut self, op: &BinaryOperator, left: &Expr, right: &Expr) -> Result<(Operand, Type), LoweringError> {
+        let (left, left_type) = self.lower_expr(left)?;
+        let (right, right_type) = self.lower_expr(right)?;
+
+        let mismatch = || LoweringError::new(format!(
+            "Operador '{}' não suportado para tipos {} e {}", op, left_type, right_type
+        ));
+
+        // tipo dos operandos (após promoção) e tipo do resultado
+        let (operand_type, result_type) = match op {
+            BinaryOperator::Add if left_type == Type::Texto || right_type == Type::Texto => {
+                (Type::Texto, Type::Texto)
+            }
+            BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
+                let ty = Self::numeric_result(&left_type, &right_type).map_err(|_| mismatch())?;
+                (ty.clone(), ty)
+            }
+            BinaryOperator::Modulo => {
+                if left_type != Type::Inteiro || right_type != Type::Inteiro {
+                    return Err(mismatch());
+                }
+                (Type::Inteiro, Type::Inteiro)
+            }
+            BinaryOperator::Equal | BinaryOperator::NotEqual => {
+                if left_type == right_type {
+                    (left_type.clone(), Type::Logico)
+                } else {
+                    (Self::numeric_result(&left_type, &right_type).map_err(|_| mismatch())?, Type::Logico)
+                }
+            }
+            BinaryOperator::Less | BinaryOperator::LessEqual | BinaryOperator::Greater | BinaryOperator::GreaterEqual => {
+                if left_type == Type::Texto && right_type == Type::Texto {
+                    (Type::Texto, Type::Logico)
+                } else {
+                    (Self::numeric_result(&left_type, &right_type).map_err(|_| mismatch())?, Type::Logico)
+                }
+            }
+            BinaryOperator::And | BinaryOperator::Or => {
+                if left_type != Type::Logico || right_type != Type::Logico {
+                    return Err(mismatch());
+                }
+                (Type::Logico, Type::Logico)
+            }
+        };
+
+        let (left, right) = if operand_type == Type::Texto && *op == BinaryOperator::Add {
+            (self.text_operand(left, &left_type), self.text_operand(right, &right_type))
+        } else {
+            (self.coerce(left, &left_type, &operand_type)?, self.coerce(right, &right_type, &operand_type)?)
+        };
+
+        let dest = self.new_temp(result_type.clone());
+        self.emit(Instr::Binary { dest: dest.clone(), op: op.clone(), left, right });
+        Ok((Operand::Temp(dest.id), result_type))
+    }
+
+    fn lower_call(&mut self, call: &CallExpr) -> Result<Option<(Operand, Type)>, LoweringError> {
+        let mut lowered = Vec::new();
+        for arg in &call.arguments {
+            lowered.push(self.lower_expr(arg)?);
+        }
+
+        let (parameters, return_type) = match self.signatures.get(&call.function) {
+            Some(signature) => (signature.parameters.clone(), signature.return_type.clone()),
+            // a sobrecarga é escolhida pelos tipos dos argumentos
+            None => match Builtin::from_name(&call.function) {
+                Some(builtin) => {
+                    let argument_types: Vec<Type> = lowered.iter().map(|(_, ty)| ty.clone()).collect();
+                    let overload = builtin.resolve(&argument_types).map_err(LoweringError::new)?;
+                    (overload.parameters.to_vec(), Some(overload.return_type.clone()))
+                }
+                None => return Err(LoweringError::new(format!("Função '{}' não declarada", call.function))),
+            },
+        };
+
+        if parameters.len() != call.arguments.len() {
+            return Err(LoweringError::new(format!(
+                "Função '{}' espera {} argumentos, mas {} foram fornecidos",
+                call.function, parameters.len(), call.arguments.len()
+            )));
+        }
+
+        let mut arguments = Vec::new();
+        for ((operand, ty), param_type) in lowered.into_iter().zip(parameters.iter()) {
+            arguments.push(self.coerce(operand, &ty, param_type)?);
+        }
+
+        let dest = return_type.map(|ty| self.new_temp(ty));
+        self.emit(Instr::Call {
+            dest: dest.clone(),
+            function: call.function.clone(),
+            arguments,
+        });
+
+        Ok(dest.map(|d| (Operand::Temp(d.id), d.ty)))
+    }
+
+    // === AUXILIARES ===
+
+    fn is_numeric(ty: &Type) -> bool {
+        matches!(ty, Type::Inteiro | Type::Decimal)
+    }
+
+    fn numeric_result(left: &Type, right: &Type) -> Result<Type, LoweringError> {
+        match (left, right) {
+            (Type::Inteiro, Type::Inteiro) => Ok(Type::Inteiro),
+            (Type::Inteiro | Type::Decimal, Type::Inteiro | Type::Decimal) => Ok(Type::Decimal),
+            _ => Err(LoweringError::new(format!("Esperados operandos numéricos: {} e {}", left, right))),
+        }
+    }
+
+    // aplica a promoção inteiro -> decimal quando necessário
+    fn coerce(&mut self, operand: Operand, from: &Type, to: &Type) -> Result<Operand, LoweringError> {
+        match (from, to) {
+            _ if from == to => Ok(operand),
+            (Type::Inteiro, Type::Decimal) => match operand {
+                Operand::Const(Literal::Inteiro(n)) => Ok(Operand::Const(Literal::Decimal(n as f64))),
+                operand => {
+                    let dest = self.new_temp(Type::Decimal);
+                    self.emit(Instr::IntToDecimal { dest: dest.clone(), value: operand });
+                    Ok(Operand::Temp(dest.id))
+                }
+            },
+            _ => Err(LoweringError::new(format!("Tipo {} não pode ser convertido para {}", from, to))),
+        }
+    }
+
+    // na concatenação, o operando que não é texto passa por paraTexto
+    fn text_operand(&mut self, operand: Operand, ty: &Type) -> Operand {
+        if *ty == Type::Texto {
+            return operand;
+        }
+        let dest = self.new_temp(Type::Texto);
+        self.emit(Instr::Call {
+            dest: Some(dest.clone()),
+            function: Builtin::ParaTexto.name().to_string(),
+            arguments: vec![operand],
+        });
+        Operand::Temp(dest.id)
+    }
+
+    fn lookup(&self, name: &str) -> Result<(VarRef, Type), LoweringError> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).cloned())
+            .ok_or_else(|| LoweringError::new(format!("Variável '{}' não declarada", name)))
+    }
+
+    // variáveis com o mesmo nome em escopos diferentes recebem sufixos (x, x.1, x.2...)
+    fn declare_local(&mut self, name: &str, var_type: Type) -> VarRef {
+        let count = self.name_counts.entry(name.to_string()).or_insert(0);
+        let unique_name = if *count == 0 { name.to_string() } else { format!("{}.{}", name, count) };
+        *count += 1;
+
+        self.locals.push(IrVariable { name: unique_name.clone(), var_type: var_type.clone() });
+
+        let var = VarRef::Local(unique_name);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), (var.clone(), var_type));
+        }
+        var
+    }
+
+    fn new_temp(&mut self, ty: Type) -> Temp {
+        let id = self.next_temp;
+        self.next_temp += 1;
+        Temp { id, ty }
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push(PendingBlock { instructions: Vec::new(), terminator: None });
+        self.blocks.len() - 1
+    }
+
+    fn switch_to(&mut self, block: BlockId) {
+        self.current_block = block;
+    }
+
+    fn emit(&mut self, instr: Instr) {
+        self.blocks[self.current_block].instructions.push(instr);
+    }
+
+    fn is_terminated(&self) -> bool {
+        self.blocks[self.current_block].terminator.is_some()
+    }
+
+    // não sobrescreve um terminador já existente (ex.: `retorne` no fim de um ramo)
+    fn terminate(&mut self, terminator: Terminator) {
+        let block = &mut self.blocks[self.current_block];
+        if block.terminator.is_none() {
+            block.terminator = Some(terminator);
+        }
+    }
+}
+
+// Remove os blocos que não são alcançáveis a partir da entrada e renumera o resto.
+fn remove_unreachable_blocks(blocks: Vec<BasicBlock>) -> Vec<BasicBlock> {
+    let mut reachable = vec![false; blocks.len()];
+    let mut worklist = vec![0];
+
+    while let Some(id) = worklist.pop() {
+        if id >= blocks.len() || reachable[id] {
+            continue;
+        }
+        reachable[id] = true;
+        worklist.extend(blocks[id].terminator.successors());
+    }
+
+    let mut new_ids = vec![0; blocks.len()];
+    let mut next_id = 0;
+    for (id, is_reachable) in reachable.iter().enumerate() {
+        if *is_reachable {
+            new_ids[id] = next_id;
+            next_id += 1;
+        }
+    }
+
+    blocks
+        .into_iter()
+        .enumerate()
+        .filter(|(id, _)| reachable[*id])
+        .map(|(_, mut block)| {
+            block.terminator = match block.terminator {
+                Terminator::Jump(target) => Terminator::Jump(new_ids[target]),
+                Terminator::Branch { condition, then_block, else_block } => Terminator::Branch {
+                    condition,
+                    then_block: new_ids[then_block],
+                    else_block: new_ids[else_block],
+                },
+                ret => ret,
+            };
+            block
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{parse_ir, verify};
+    use crate::parser::Parser;
+
+    fn lower_source(source: &str) -> IrProgram {
+        let program = Parser::parse_from_source(source).unwrap();
+        Lowering::new().lower(&program).unwrap()
+    }
+
+    #[test]
+    fn test_lower_arithmetic_with_promotion() {
+        let ir = lower_source("funcao decimal media(inteiro a, decimal b) { retorne (a + b) / 2; }");
+        let function = ir.lookup_function("media").unwrap();
+
+        assert_eq!(function.blocks.len(), 1);
+        assert!(function.blocks[0].instructions.iter().any(|i| matches!(i, Instr::IntToDecimal { .. })));
+        assert!(verify(&ir).is_ok());
+    }
+
+    #[test]
+    fn test_for_is_desugared_to_while() {
+        let ir = lower_source(
+            "funcao principal() { inteiro soma = 0; para i de 1 ate 10 faca { escreva(i); } fimpara }",
+        );
+        let function = ir.lookup_function("principal").unwrap();
+
+        // entrada, condição, corpo e saída
+        assert_eq!(function.blocks.len(), 4);
+        assert!(function.lookup_variable("i").is_some());
+        assert!(verify(&ir).is_ok());
+    }
+
+    #[test]
+    fn test_shadowed_variables_are_renamed() {
+        let ir = lower_source(
+            "funcao principal() {
+                inteiro x = 1;
+                se x > 0 entao { decimal x = 2.5; escreva(x); } fimse
+            }",
+        );
+        let function = ir.lookup_function("principal").unwrap();
+        let names: Vec<&str> = function.locals.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["x", "x.1"]);
+    }
+
+    #[test]
+    fn test_entry_calls_principal_after_globals() {
+        let ir = lower_source("inteiro total = 3; funcao principal() { escreva(total); }");
+        let entry = ir.lookup_function(ENTRY_FUNCTION).unwrap();
+
+        assert_eq!(ir.globals.len(), 1);
+        assert!(matches!(entry.blocks[0].instructions[0], Instr::Store { var: VarRef::Global(_), .. }));
+        assert!(matches!(&entry.blocks[0].instructions[1], Instr::Call { function, .. } if function == "principal"));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let ir = lower_source(
+            "funcao inteiro fatorial(inteiro n) {
+                se n <= 1 entao { retorne 1; } fimse
+                retorne n * fatorial(n - 1);
+            }
+            funcao principal() {
+                texto msg = \"Resultado:\\n\";
+                escreva(msg, fatorial(5), verdadeiro, 2.0);
+            }",
+        );
+        let text = ir.to_string();
+        let parsed = parse_ir(&text).unwrap();
+
+        assert_eq!(parsed, ir);
+        assert_eq!(parsed.to_string(), text);
+    }
+}
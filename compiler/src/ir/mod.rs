@@ -0,0 +1,11 @@
+pub mod ir;
+pub mod lowering;
+pub mod printer;
+pub mod text_parser;
+pub mod verifier;
+
+// Re-export para facilitar o acesso
+pub use ir::*;
+pub use lowering::{Lowering, LoweringError};
+pub use text_parser::{parse_ir, IrParseError};
+pub use verifier::{verify, VerifierError};
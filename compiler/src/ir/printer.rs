@@ -0,0 +1,179 @@
+use std::fmt;
+use crate::parser::ast::{BinaryOperator, Literal, UnaryOperator};
+use crate::ir::ir::*;
+
+// Formato textual da IR. Exemplo:
+//
+//   global inteiro @total
+//
+//   funcao inteiro dobro(inteiro n) {
+//   bb0:
+//       %0: inteiro = load n
+//       %1: inteiro = mul %0, 2
+//       ret %1
+//   }
+//
+// O text_parser lê exatamente este formato de volta.
+
+pub fn binary_mnemonic(op: &BinaryOperator) -> &'static str {
+    match op {
+        BinaryOperator::Add => "add",
+        BinaryOperator::Subtract => "sub",
+        BinaryOperator::Multiply => "mul",
+        BinaryOperator::Divide => "div",
+        BinaryOperator::Modulo => "mod",
+        BinaryOperator::Equal => "eq",
+        BinaryOperator::NotEqual => "ne",
+        BinaryOperator::Less => "lt",
+        BinaryOperator::LessEqual => "le",
+        BinaryOperator::Greater => "gt",
+        BinaryOperator::GreaterEqual => "ge",
+        BinaryOperator::And => "and",
+        BinaryOperator::Or => "or",
+    }
+}
+
+pub fn unary_mnemonic(op: &UnaryOperator) -> &'static str {
+    match op {
+        UnaryOperator::Negate => "neg",
+        UnaryOperator::Plus => "plus",
+        UnaryOperator::Not => "not",
+    }
+}
+
+pub fn format_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Inteiro(n) => n.to_string(),
+        // {:?} sempre mantém o ponto decimal (1.0, não 1)
+        Literal::Decimal(n) => format!("{:?}", n),
+        Literal::Texto(s) => {
+            let mut escaped = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"' => escaped.push_str("\\\""),
+                    '\\' => escaped.push_str("\\\\"),
+                    '\n' => escaped.push_str("\\n"),
+                    '\t' => escaped.push_str("\\t"),
+                    '\r' => escaped.push_str("\\r"),
+                    c => escaped.push(c),
+                }
+            }
+            escaped.push('"');
+            escaped
+        }
+        Literal::Logico(true) => "verdadeiro".to_string(),
+        Literal::Logico(false) => "falso".to_string(),
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Temp(id) => write!(f, "%{}", id),
+            Operand::Const(literal) => write!(f, "{}", format_literal(literal)),
+        }
+    }
+}
+
+impl fmt::Display for VarRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarRef::Local(name) => write!(f, "{}", name),
+            VarRef::Global(name) => write!(f, "@{}", name),
+        }
+    }
+}
+
+impl fmt::Display for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "%{}: {}", self.id, self.ty)
+    }
+}
+
+fn join_operands(operands: &[Operand]) -> String {
+    operands.iter().map(|o| o.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instr::Load { dest, var } => write!(f, "{} = load {}", dest, var),
+            Instr::Store { var, value } => write!(f, "store {}, {}", var, value),
+            Instr::Binary { dest, op, left, right } => {
+                write!(f, "{} = {} {}, {}", dest, binary_mnemonic(op), left, right)
+            }
+            Instr::Unary { dest, op, operand } => {
+                write!(f, "{} = {} {}", dest, unary_mnemonic(op), operand)
+            }
+            Instr::IntToDecimal { dest, value } => write!(f, "{} = itod {}", dest, value),
+            Instr::Call { dest: Some(dest), function, arguments } => {
+                write!(f, "{} = call {}({})", dest, function, join_operands(arguments))
+            }
+            Instr::Call { dest: None, function, arguments } => {
+                write!(f, "call {}({})", function, join_operands(arguments))
+            }
+            Instr::Write { arguments } if arguments.is_empty() => write!(f, "write"),
+            Instr::Write { arguments } => write!(f, "write {}", join_operands(arguments)),
+            Instr::Read { var } => write!(f, "read {}", var),
+        }
+    }
+}
+
+impl fmt::Display for Terminator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Terminator::Jump(target) => write!(f, "jmp bb{}", target),
+            Terminator::Branch { condition, then_block, else_block } => {
+                write!(f, "br {}, bb{}, bb{}", condition, then_block, else_block)
+            }
+            Terminator::Return(Some(value)) => write!(f, "ret {}", value),
+            Terminator::Return(None) => write!(f, "ret"),
+        }
+    }
+}
+
+impl fmt::Display for IrFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parameters = self.parameters
+            .iter()
+            .map(|p| format!("{} {}", p.var_type, p.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match &self.return_type {
+            Some(ty) => writeln!(f, "funcao {} {}({}) {{", ty, self.name, parameters)?,
+            None => writeln!(f, "funcao {}({}) {{", self.name, parameters)?,
+        }
+
+        for local in &self.locals {
+            writeln!(f, "    local {} {}", local.var_type, local.name)?;
+        }
+
+        for (id, block) in self.blocks.iter().enumerate() {
+            writeln!(f, "bb{}:", id)?;
+            for instr in &block.instructions {
+                writeln!(f, "    {}", instr)?;
+            }
+            writeln!(f, "    {}", block.terminator)?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+impl fmt::Display for IrProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for global in &self.globals {
+            writeln!(f, "global {} @{}", global.var_type, global.name)?;
+        }
+
+        for (i, function) in self.functions.iter().enumerate() {
+            if i > 0 || !self.globals.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "{}", function)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,607 @@
+use std::collections::HashMap;
+use crate::parser::ast::{BinaryOperator, Literal, Type, UnaryOperator};
+use crate::ir::ir::*;
+
+#[derive(Debug, Clone)]
+pub struct IrParseError {
+    pub message: String,
+    pub linha: usize,
+}
+
+impl IrParseError {
+    fn new(message: impl Into<String>, linha: usize) -> Self {
+        Self { message: message.into(), linha }
+    }
+}
+
+impl std::fmt::Display for IrParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "ERRO NA IR: Linha {} - {}", self.linha, self.message)
+    }
+}
+
+impl std::error::Error for IrParseError {}
+
+// Lê o formato textual produzido pelo printer. A IR é orientada a linhas:
+// cada linha é uma declaração, um rótulo, uma instrução ou um terminador.
+// Comentários começam com ';' e vão até o fim da linha.
+pub fn parse_ir(text: &str) -> Result<IrProgram, IrParseError> {
+    let mut program = IrProgram::default();
+    let mut current: Option<PendingFunction> = None;
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let linha = index + 1;
+        let tokens = tokenize_line(raw_line, linha)?;
+        if tokens.is_empty() {
+            continue;
+        }
+        let mut line = Line { tokens, pos: 0, linha };
+
+        match current.as_mut() {
+            None => {
+                match line.next_ident()?.as_str() {
+                    "global" => {
+                        let var_type = line.parse_type()?;
+                        let name = match line.next()? {
+                            Tok::Global(name) => name,
+                            other => return Err(line.unexpected(&other, "nome global (@nome)")),
+                        };
+                        line.finish()?;
+                        program.globals.push(IrVariable { name, var_type });
+                    }
+                    "funcao" => current = Some(parse_function_header(&mut line)?),
+                    other => {
+                        return Err(IrParseError::new(format!("Esperado 'global' ou 'funcao', encontrado '{}'", other), linha));
+                    }
+                }
+            }
+            Some(function) => {
+                if line.peek_is(&Tok::Punct('}')) {
+                    line.next()?;
+                    line.finish()?;
+                    let function = current.take().expect("função em andamento");
+                    program.functions.push(function.resolve()?);
+                    continue;
+                }
+                function.parse_line(&mut line)?;
+            }
+        }
+    }
+
+    if let Some(function) = current {
+        return Err(IrParseError::new(
+            format!("Função '{}' não foi fechada com '}}'", function.name),
+            function.linha,
+        ));
+    }
+
+    Ok(program)
+}
+
+// === TOKENS ===
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Temp(TempId),
+    Global(String),
+    Int(i64),
+    Dec(f64),
+    Str(String),
+    Punct(char),
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+fn tokenize_line(line: &str, linha: usize) -> Result<Vec<Tok>, IrParseError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == ';' {
+            break;
+        } else if "(),:={}".contains(c) {
+            tokens.push(Tok::Punct(c));
+            i += 1;
+        } else if c == '%' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            let id = digits.parse().map_err(|_| IrParseError::new("Temporário inválido", linha))?;
+            tokens.push(Tok::Temp(id));
+        } else if c == '@' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Tok::Global(chars[start..i].iter().collect()));
+        } else if c == '"' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(IrParseError::new("Texto não foi fechado", linha)),
+                    Some('"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some('\\') => {
+                        let escaped = match chars.get(i + 1) {
+                            Some('n') => '\n',
+                            Some('t') => '\t',
+                            Some('r') => '\r',
+                            Some('"') => '"',
+                            Some('\\') => '\\',
+                            other => {
+                                return Err(IrParseError::new(format!("Sequência de escape inválida: {:?}", other), linha));
+                            }
+                        };
+                        value.push(escaped);
+                        i += 2;
+                    }
+                    Some(c) => {
+                        value.push(*c);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Tok::Str(value));
+        } else if c.is_ascii_digit() || (c == '-' && i + 1 < chars.len()) {
+            let start = i;
+            i += 1;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || chars[i] == '.'
+                    || ((chars[i] == '-' || chars[i] == '+') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let lexema: String = chars[start..i].iter().collect();
+            tokens.push(parse_number(&lexema, linha)?);
+        } else if is_name_char(c) {
+            let start = i;
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Tok::Ident(chars[start..i].iter().collect()));
+        } else {
+            return Err(IrParseError::new(format!("Caractere inválido: '{}'", c), linha));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_number(lexema: &str, linha: usize) -> Result<Tok, IrParseError> {
+    let invalid = || IrParseError::new(format!("Número inválido: '{}'", lexema), linha);
+
+    if lexema == "-inf" {
+        return Ok(Tok::Dec(f64::NEG_INFINITY));
+    }
+    if lexema.contains(['.', 'e', 'E']) {
+        lexema.parse().map(Tok::Dec).map_err(|_| invalid())
+    } else {
+        lexema.parse().map(Tok::Int).map_err(|_| invalid())
+    }
+}
+
+struct Line {
+    tokens: Vec<Tok>,
+    pos: usize,
+    linha: usize,
+}
+
+impl Line {
+    fn next(&mut self) -> Result<Tok, IrParseError> {
+        let tok = self.tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| IrParseError::new("Fim de linha inesperado", self.linha))?;
+        self.pos += 1;
+        Ok(tok)
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_is(&self, tok: &Tok) -> bool {
+        self.peek() == Some(tok)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn finish(&self) -> Result<(), IrParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(tok) => Err(self.unexpected(tok, "fim de linha")),
+        }
+    }
+
+    fn unexpected(&self, found: &Tok, expected: &str) -> IrParseError {
+        IrParseError::new(format!("Esperado {}, encontrado {:?}", expected, found), self.linha)
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), IrParseError> {
+        match self.next()? {
+            Tok::Punct(c) if c == punct => Ok(()),
+            other => Err(self.unexpected(&other, &format!("'{}'", punct))),
+        }
+    }
+
+    fn next_ident(&mut self) -> Result<String, IrParseError> {
+        match self.next()? {
+            Tok::Ident(name) => Ok(name),
+            other => Err(self.unexpected(&other, "identificador")),
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<Type, IrParseError> {
+        let name = self.next_ident()?;
+        parse_type_name(&name).ok_or_else(|| {
+            IrParseError::new(format!("Tipo desconhecido: '{}'", name), self.linha)
+        })
+    }
+
+    fn parse_var(&mut self) -> Result<VarRef, IrParseError> {
+        match self.next()? {
+            Tok::Ident(name) => Ok(VarRef::Local(name)),
+            Tok::Global(name) => Ok(VarRef::Global(name)),
+            other => Err(self.unexpected(&other, "variável")),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, IrParseError> {
+        match self.next()? {
+            Tok::Temp(id) => Ok(Operand::Temp(id)),
+            Tok::Int(n) => Ok(Operand::Const(Literal::Inteiro(n))),
+            Tok::Dec(n) => Ok(Operand::Const(Literal::Decimal(n))),
+            Tok::Str(s) => Ok(Operand::Const(Literal::Texto(s))),
+            Tok::Ident(name) => match name.as_str() {
+                "verdadeiro" => Ok(Operand::Const(Literal::Logico(true))),
+                "falso" => Ok(Operand::Const(Literal::Logico(false))),
+                "inf" => Ok(Operand::Const(Literal::Decimal(f64::INFINITY))),
+                "NaN" => Ok(Operand::Const(Literal::Decimal(f64::NAN))),
+                _ => Err(IrParseError::new(format!("Operando inválido: '{}'", name), self.linha)),
+            },
+            other => Err(self.unexpected(&other, "operando")),
+        }
+    }
+
+    // lista separada por vírgulas até o fim da linha ou até ')'
+    fn parse_operand_list(&mut self, until_paren: bool) -> Result<Vec<Operand>, IrParseError> {
+        let mut operands = Vec::new();
+        let done = |line: &Line| if until_paren { line.peek_is(&Tok::Punct(')')) } else { line.at_end() };
+
+        if !done(self) {
+            operands.push(self.parse_operand()?);
+            while self.peek_is(&Tok::Punct(',')) {
+                self.next()?;
+                operands.push(self.parse_operand()?);
+            }
+        }
+        Ok(operands)
+    }
+
+    fn parse_call(&mut self) -> Result<(String, Vec<Operand>), IrParseError> {
+        let function = self.next_ident()?;
+        self.expect('(')?;
+        let arguments = self.parse_operand_list(true)?;
+        self.expect(')')?;
+        Ok((function, arguments))
+    }
+}
+
+fn parse_type_name(name: &str) -> Option<Type> {
+    match name {
+        "inteiro" => Some(Type::Inteiro),
+        "decimal" => Some(Type::Decimal),
+        "texto" => Some(Type::Texto),
+        "logico" => Some(Type::Logico),
+        _ => None,
+    }
+}
+
+fn parse_binary_mnemonic(name: &str) -> Option<BinaryOperator> {
+    match name {
+        "add" => Some(BinaryOperator::Add),
+        "sub" => Some(BinaryOperator::Subtract),
+        "mul" => Some(BinaryOperator::Multiply),
+        "div" => Some(BinaryOperator::Divide),
+        "mod" => Some(BinaryOperator::Modulo),
+        "eq" => Some(BinaryOperator::Equal),
+        "ne" => Some(BinaryOperator::NotEqual),
+        "lt" => Some(BinaryOperator::Less),
+        "le" => Some(BinaryOperator::LessEqual),
+        "gt" => Some(BinaryOperator::Greater),
+        "ge" => Some(BinaryOperator::GreaterEqual),
+        "and" => Some(BinaryOperator::And),
+        "or" => Some(BinaryOperator::Or),
+        _ => None,
+    }
+}
+
+fn parse_unary_mnemonic(name: &str) -> Option<UnaryOperator> {
+    match name {
+        "neg" => Some(UnaryOperator::Negate),
+        "plus" => Some(UnaryOperator::Plus),
+        "not" => Some(UnaryOperator::Not),
+        _ => None,
+    }
+}
+
+// === FUNÇÕES ===
+
+enum RawTerminator {
+    Jump(String),
+    Branch(Operand, String, String),
+    Return(Option<Operand>),
+}
+
+struct PendingBlock {
+    label: String,
+    linha: usize,
+    instructions: Vec<Instr>,
+    terminator: Option<RawTerminator>,
+}
+
+struct PendingFunction {
+    name: String,
+    linha: usize,
+    return_type: Option<Type>,
+    parameters: Vec<IrVariable>,
+    locals: Vec<IrVariable>,
+    blocks: Vec<PendingBlock>,
+}
+
+fn parse_function_header(line: &mut Line) -> Result<PendingFunction, IrParseError> {
+    let first = line.next_ident()?;
+    let (return_type, name) = match parse_type_name(&first) {
+        Some(ty) => (Some(ty), line.next_ident()?),
+        None => (None, first),
+    };
+
+    line.expect('(')?;
+    let mut parameters = Vec::new();
+    if !line.peek_is(&Tok::Punct(')')) {
+        loop {
+            let var_type = line.parse_type()?;
+            let name = line.next_ident()?;
+            parameters.push(IrVariable { name, var_type });
+            if line.peek_is(&Tok::Punct(',')) {
+                line.next()?;
+            } else {
+                break;
+            }
+        }
+    }
+    line.expect(')')?;
+    line.expect('{')?;
+    line.finish()?;
+
+    Ok(PendingFunction {
+        name,
+        linha: line.linha,
+        return_type,
+        parameters,
+        locals: Vec::new(),
+        blocks: Vec::new(),
+    })
+}
+
+impl PendingFunction {
+    fn parse_line(&mut self, line: &mut Line) -> Result<(), IrParseError> {
+        // rótulo de bloco: "bb0:"
+        if let (Some(Tok::Ident(label)), Some(Tok::Punct(':'))) = (line.tokens.first(), line.tokens.get(1))
+            && line.tokens.len() == 2
+        {
+            self.blocks.push(PendingBlock {
+                label: label.clone(),
+                linha: line.linha,
+                instructions: Vec::new(),
+                terminator: None,
+            });
+            return Ok(());
+        }
+
+        if line.peek_is(&Tok::Ident("local".to_string())) {
+            if !self.blocks.is_empty() {
+                return Err(IrParseError::new("Declarações 'local' devem vir antes do primeiro bloco", line.linha));
+            }
+            line.next()?;
+            let var_type = line.parse_type()?;
+            let name = line.next_ident()?;
+            line.finish()?;
+            self.locals.push(IrVariable { name, var_type });
+            return Ok(());
+        }
+
+        let linha = line.linha;
+        let Some(block) = self.blocks.last_mut() else {
+            return Err(IrParseError::new("Instrução fora de um bloco (falta um rótulo)", linha));
+        };
+        if block.terminator.is_some() {
+            return Err(IrParseError::new(format!("Instrução após o terminador do bloco '{}'", block.label), linha));
+        }
+
+        if let Some(Tok::Temp(id)) = line.peek().cloned() {
+            line.next()?;
+            line.expect(':')?;
+            let ty = line.parse_type()?;
+            line.expect('=')?;
+            let dest = Temp { id, ty };
+            let instr = parse_definition(line, dest)?;
+            line.finish()?;
+            block.instructions.push(instr);
+            return Ok(());
+        }
+
+        let keyword = line.next_ident()?;
+        match keyword.as_str() {
+            "store" => {
+                let var = line.parse_var()?;
+                line.expect(',')?;
+                let value = line.parse_operand()?;
+                block.instructions.push(Instr::Store { var, value });
+            }
+            "call" => {
+                let (function, arguments) = line.parse_call()?;
+                block.instructions.push(Instr::Call { dest: None, function, arguments });
+            }
+            "write" => {
+                let arguments = line.parse_operand_list(false)?;
+                block.instructions.push(Instr::Write { arguments });
+            }
+            "read" => {
+                let var = line.parse_var()?;
+                block.instructions.push(Instr::Read { var });
+            }
+            "jmp" => block.terminator = Some(RawTerminator::Jump(line.next_ident()?)),
+            "br" => {
+                let condition = line.parse_operand()?;
+                line.expect(',')?;
+                let then_label = line.next_ident()?;
+                line.expect(',')?;
+                let else_label = line.next_ident()?;
+                block.terminator = Some(RawTerminator::Branch(condition, then_label, else_label));
+            }
+            "ret" => {
+                let value = if line.at_end() { None } else { Some(line.parse_operand()?) };
+                block.terminator = Some(RawTerminator::Return(value));
+            }
+            other => return Err(IrParseError::new(format!("Instrução desconhecida: '{}'", other), linha)),
+        }
+        line.finish()
+    }
+
+    fn resolve(self) -> Result<IrFunction, IrParseError> {
+        let mut labels = HashMap::new();
+        for (id, block) in self.blocks.iter().enumerate() {
+            if labels.insert(block.label.clone(), id).is_some() {
+                return Err(IrParseError::new(format!("Rótulo '{}' duplicado", block.label), block.linha));
+            }
+        }
+
+        let mut blocks = Vec::new();
+        for block in self.blocks {
+            let target = |label: &str| {
+                labels.get(label).copied().ok_or_else(|| {
+                    IrParseError::new(format!("Rótulo '{}' não definido", label), block.linha)
+                })
+            };
+
+            let terminator = match &block.terminator {
+                Some(RawTerminator::Jump(label)) => Terminator::Jump(target(label)?),
+                Some(RawTerminator::Branch(condition, then_label, else_label)) => Terminator::Branch {
+                    condition: condition.clone(),
+                    then_block: target(then_label)?,
+                    else_block: target(else_label)?,
+                },
+                Some(RawTerminator::Return(value)) => Terminator::Return(value.clone()),
+                None => {
+                    return Err(IrParseError::new(
+                        format!("Bloco '{}' não termina com jmp, br ou ret", block.label),
+                        block.linha,
+                    ));
+                }
+            };
+
+            blocks.push(BasicBlock { instructions: block.instructions, terminator });
+        }
+
+        Ok(IrFunction {
+            name: self.name,
+            return_type: self.return_type,
+            parameters: self.parameters,
+            locals: self.locals,
+            blocks,
+        })
+    }
+}
+
+fn parse_definition(line: &mut Line, dest: Temp) -> Result<Instr, IrParseError> {
+    let mnemonic = line.next_ident()?;
+
+    if let Some(op) = parse_binary_mnemonic(&mnemonic) {
+        let left = line.parse_operand()?;
+        line.expect(',')?;
+        let right = line.parse_operand()?;
+        return Ok(Instr::Binary { dest, op, left, right });
+    }
+    if let Some(op) = parse_unary_mnemonic(&mnemonic) {
+        let operand = line.parse_operand()?;
+        return Ok(Instr::Unary { dest, op, operand });
+    }
+
+    match mnemonic.as_str() {
+        "load" => Ok(Instr::Load { dest, var: line.parse_var()? }),
+        "itod" => Ok(Instr::IntToDecimal { dest, value: line.parse_operand()? }),
+        "call" => {
+            let (function, arguments) = line.parse_call()?;
+            Ok(Instr::Call { dest: Some(dest), function, arguments })
+        }
+        other => Err(IrParseError::new(format!("Instrução desconhecida: '{}'", other), line.linha)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_handwritten_ir() {
+        let text = "
+            global inteiro @contador
+
+            ; soma os números de 1 até n
+            funcao inteiro soma(inteiro n) {
+                local inteiro total
+            entrada:
+                store total, 0
+                jmp teste
+            teste:
+                %0: inteiro = load n
+                %1: logico = gt %0, 0
+                br %1, corpo, fim
+            corpo:
+                %2: inteiro = load total
+                %3: inteiro = load n
+                %4: inteiro = add %2, %3
+                store total, %4
+                %5: inteiro = sub %3, 1
+                store n, %5
+                jmp teste
+            fim:
+                %6: inteiro = load total
+                ret %6
+            }
+        ";
+        let program = parse_ir(text).unwrap();
+        let function = &program.functions[0];
+
+        assert_eq!(program.globals[0].name, "contador");
+        assert_eq!(function.blocks.len(), 4);
+        assert_eq!(function.blocks[0].terminator, Terminator::Jump(1));
+        assert!(crate::ir::verify(&program).is_ok());
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = parse_ir("funcao f() {\nbb0:\n    %0: inteiro = load x\n}\n").unwrap_err();
+        assert_eq!(err.linha, 2);
+
+        let err = parse_ir("funcao f() {\nbb0:\n    jmp bb7\n}\n").unwrap_err();
+        assert!(err.message.contains("bb7"));
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use crate::parser::ast::{BinaryOperator, Type, UnaryOperator};
+use crate::ir::ir::*;
+use crate::semantic::builtins::Builtin;
+
+#[derive(Debug, Clone)]
+pub struct VerifierError {
+    pub function: String,
+    pub block: Option<BlockId>,
+    pub message: String,
+}
+
+impl std::fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.block {
+            Some(block) => write!(f, "ERRO NA IR: função '{}', bb{} - {}", self.function, block, self.message),
+            None => write!(f, "ERRO NA IR: função '{}' - {}", self.function, self.message),
+        }
+    }
+}
+
+impl std::error::Error for VerifierError {}
+
+// Verifica a consistência de tipos e a boa formação do fluxo de controle.
+// Regras:
+//  - toda função tem ao menos um bloco e todo desvio aponta para um bloco existente;
+//  - cada temporário é definido uma única vez e só é usado depois da
+//    definição, dentro do mesmo bloco;
+//  - operandos, variáveis, chamadas e retornos têm os tipos esperados.
+pub fn verify(program: &IrProgram) -> Result<(), Vec<VerifierError>> {
+    let mut errors = Vec::new();
+
+    let mut seen = HashMap::new();
+    for function in &program.functions {
+        if seen.insert(function.name.as_str(), ()).is_some() {
+            errors.push(VerifierError {
+                function: function.name.clone(),
+                block: None,
+                message: "Função definida mais de uma vez".to_string(),
+            });
+        }
+        FunctionVerifier::new(program, function, &mut errors).verify();
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+struct FunctionVerifier<'a> {
+    program: &'a IrProgram,
+    function: &'a IrFunction,
+    errors: &'a mut Vec<VerifierError>,
+    defined: HashMap<TempId, BlockId>,
+    current_block: Option<BlockId>,
+}
+
+impl<'a> FunctionVerifier<'a> {
+    fn new(program: &'a IrProgram, function: &'a IrFunction, errors: &'a mut Vec<VerifierError>) -> Self {
+        Self {
+            program,
+            function,
+            errors,
+            defined: HashMap::new(),
+            current_block: None,
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors.push(VerifierError {
+            function: self.function.name.clone(),
+            block: self.current_block,
+            message,
+        });
+    }
+
+    fn verify(&mut self) {
+        if self.function.blocks.is_empty() {
+            self.error("Função sem blocos".to_string());
+            return;
+        }
+
+        let mut names = HashMap::new();
+        for var in self.function.parameters.iter().chain(self.function.locals.iter()) {
+            if names.insert(var.name.as_str(), ()).is_some() {
+                self.error(format!("Variável '{}' declarada mais de uma vez", var.name));
+            }
+        }
+
+        for (id, block) in self.function.blocks.iter().enumerate() {
+            self.current_block = Some(id);
+            // tipos dos temporários visíveis neste bloco
+            let mut temps: HashMap<TempId, Type> = HashMap::new();
+
+            for instr in &block.instructions {
+                for operand in instr.operands() {
+                    self.check_operand_defined(operand, &temps);
+                }
+                self.check_instr(instr, &temps);
+
+                if let Some(dest) = instr.dest() {
+                    if let Some(previous) = self.defined.insert(dest.id, id) {
+                        self.error(format!("Temporário %{} redefinido (definido antes em bb{})", dest.id, previous));
+                    }
+                    temps.insert(dest.id, dest.ty.clone());
+                }
+            }
+
+            self.check_terminator(&block.terminator, &temps);
+        }
+    }
+
+    fn check_operand_defined(&mut self, operand: &Operand, temps: &HashMap<TempId, Type>) {
+        if let Operand::Temp(id) = operand
+            && !temps.contains_key(id)
+        {
+            let message = match self.defined.get(id) {
+                Some(block) if Some(*block) != self.current_block => {
+                    format!("Temporário %{} usado fora do bloco onde foi definido (bb{})", id, block)
+                }
+                _ => format!("Temporário %{} usado antes de ser definido", id),
+            };
+            self.error(message);
+        }
+    }
+
+    fn operand_type(operand: &Operand, temps: &HashMap<TempId, Type>) -> Option<Type> {
+        match operand {
+            Operand::Temp(id) => temps.get(id).cloned(),
+            Operand::Const(literal) => Some(literal_type(literal)),
+        }
+    }
+
+    fn variable_type(&self, var: &VarRef) -> Option<Type> {
+        match var {
+            VarRef::Local(name) => self.function.lookup_variable(name).map(|v| v.var_type.clone()),
+            VarRef::Global(name) => self.program.lookup_global(name).map(|v| v.var_type.clone()),
+        }
+    }
+
+    fn check_variable(&mut self, var: &VarRef) -> Option<Type> {
+        let ty = self.variable_type(var);
+        if ty.is_none() {
+            self.error(format!("Variável '{}' não declarada", var));
+        }
+        ty
+    }
+
+    fn expect_type(&mut self, what: &str, found: Option<Type>, expected: &Type) {
+        if let Some(found) = found
+            && &found != expected
+        {
+            self.error(format!("{}: tipo esperado {}, encontrado {}", what, expected, found));
+        }
+    }
+
+    fn check_instr(&mut self, instr: &Instr, temps: &HashMap<TempId, Type>) {
+        match instr {
+            Instr::Load { dest, var } => {
+                if let Some(var_type) = self.check_variable(var) {
+                    self.expect_type(&format!("load {}", var), Some(dest.ty.clone()), &var_type);
+                }
+            }
+            Instr::Store { var, value } => {
+                if let Some(var_type) = self.check_variable(var) {
+                    let value_type = Self::operand_type(value, temps);
+                    self.expect_type(&format!("store {}", var), value_type, &var_type);
+                }
+            }
+            Instr::Binary { dest, op, left, right } => {
+                let left_type = Self::operand_type(left, temps);
+                let right_type = Self::operand_type(right, temps);
+                if let (Some(l), Some(r)) = (left_type, right_type) {
+                    match Self::binary_result(op, &l, &r) {
+                        Some(result) => self.expect_type(&format!("resultado de '{}'", op), Some(dest.ty.clone()), &result),
+                        None => self.error(format!("Operador '{}' não suportado para tipos {} e {}", op, l, r)),
+                    }
+                }
+            }
+            Instr::Unary { dest, op, operand } => {
+                if let Some(ty) = Self::operand_type(operand, temps) {
+                    let valid = match op {
+                        UnaryOperator::Negate | UnaryOperator::Plus => matches!(ty, Type::Inteiro | Type::Decimal),
+                        UnaryOperator::Not => ty == Type::Logico,
+                    };
+                    if valid {
+                        self.expect_type(&format!("resultado de '{}'", op), Some(dest.ty.clone()), &ty);
+                    } else {
+                        self.error(format!("Operador '{}' não suportado para o tipo {}", op, ty));
+                    }
+                }
+            }
+            Instr::IntToDecimal { dest, value } => {
+                self.expect_type("operando de itod", Self::operand_type(value, temps), &Type::Inteiro);
+                self.expect_type("resultado de itod", Some(dest.ty.clone()), &Type::Decimal);
+            }
+            Instr::Call { dest, function, arguments } => self.check_call(dest, function, arguments, temps),
+            Instr::Write { .. } => {}
+            Instr::Read { var } => {
+                self.check_variable(var);
+            }
+        }
+    }
+
+    fn check_call(&mut self, dest: &Option<Temp>, function: &str, arguments: &[Operand], temps: &HashMap<TempId, Type>) {
+        let Some(callee) = self.program.lookup_function(function) else {
+            match Builtin::from_name(function) {
+                Some(builtin) => self.check_builtin_call(builtin, dest, arguments, temps),
+                None => self.error(format!("Função '{}' não definida", function)),
+            }
+            return;
+        };
+
+        if callee.parameters.len() != arguments.len() {
+            self.error(format!(
+                "Função '{}' espera {} argumentos, mas {} foram fornecidos",
+                function, callee.parameters.len(), arguments.len()
+            ));
+            return;
+        }
+
+        for (i, (arg, param)) in arguments.iter().zip(callee.parameters.iter()).enumerate() {
+            let what = format!("argumento {} de '{}'", i + 1, function);
+            self.expect_type(&what, Self::operand_type(arg, temps), &param.var_type);
+        }
+
+        match (dest, &callee.return_type) {
+            (Some(dest), Some(return_type)) => {
+                self.expect_type(&format!("retorno de '{}'", function), Some(dest.ty.clone()), return_type);
+            }
+            (Some(_), None) => self.error(format!("Função '{}' não tem tipo de retorno", function)),
+            // descartar o valor de retorno é permitido
+            (None, _) => {}
+        }
+    }
+
+    // na IR os argumentos já chegam convertidos: a assinatura tem de ser exata
+    fn check_builtin_call(&mut self, builtin: Builtin, dest: &Option<Temp>, arguments: &[Operand], temps: &HashMap<TempId, Type>) {
+        let mut argument_types = Vec::new();
+        for arg in arguments {
+            match Self::operand_type(arg, temps) {
+                Some(ty) => argument_types.push(ty),
+                None => return,
+            }
+        }
+
+        let overload = builtin
+            .overloads()
+            .iter()
+            .find(|o| o.parameters == argument_types.as_slice());
+        match (overload, dest) {
+            (Some(overload), Some(dest)) => {
+                let what = format!("retorno de '{}'", builtin.name());
+                self.expect_type(&what, Some(dest.ty.clone()), &overload.return_type);
+            }
+            (Some(_), None) => {}
+            (None, _) => {
+                let found: Vec<String> = argument_types.iter().map(|t| t.to_string()).collect();
+                self.error(format!("Função '{}' não aceita argumentos ({})", builtin.name(), found.join(", ")));
+            }
+        }
+    }
+
+    fn check_terminator(&mut self, terminator: &Terminator, temps: &HashMap<TempId, Type>) {
+        for target in terminator.successors() {
+            if target >= self.function.blocks.len() {
+                self.error(format!("Desvio para bloco inexistente bb{}", target));
+            }
+        }
+
+        match terminator {
+            Terminator::Branch { condition, .. } => {
+                self.check_operand_defined(condition, temps);
+                self.expect_type("condição do br", Self::operand_type(condition, temps), &Type::Logico);
+            }
+            Terminator::Return(value) => match (value, self.function.return_type.clone()) {
+                (Some(value), Some(expected)) => {
+                    self.check_operand_defined(value, temps);
+                    self.expect_type("valor de retorno", Self::operand_type(value, temps), &expected);
+                }
+                (Some(_), None) => self.error("Retorno com valor em função sem tipo de retorno".to_string()),
+                (None, Some(_)) => self.error("Retorno sem valor em função com tipo de retorno".to_string()),
+                (None, None) => {}
+            },
+            Terminator::Jump(_) => {}
+        }
+    }
+
+    // na IR não há promoção implícita: operandos aritméticos têm o mesmo tipo
+    fn binary_result(op: &BinaryOperator, left: &Type, right: &Type) -> Option<Type> {
+        if left != right {
+            return None;
+        }
+        match op {
+            BinaryOperator::Add if *left == Type::Texto => Some(Type::Texto),
+            BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
+                matches!(left, Type::Inteiro | Type::Decimal).then(|| left.clone())
+            }
+            BinaryOperator::Modulo => (*left == Type::Inteiro).then_some(Type::Inteiro),
+            BinaryOperator::Equal | BinaryOperator::NotEqual => Some(Type::Logico),
+            BinaryOperator::Less | BinaryOperator::LessEqual | BinaryOperator::Greater | BinaryOperator::GreaterEqual => {
+                (*left != Type::Logico).then_some(Type::Logico)
+            }
+            BinaryOperator::And | BinaryOperator::Or => (*left == Type::Logico).then_some(Type::Logico),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::{parse_ir, verify};
+
+    fn verify_errors(text: &str) -> Vec<String> {
+        let program = parse_ir(text).unwrap();
+        verify(&program).err().unwrap_or_default().into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn test_rejects_type_errors() {
+        let errors = verify_errors(
+            "funcao inteiro f(decimal x) {
+            bb0:
+                %0: decimal = load x
+                %1: inteiro = add %0, 1
+                ret %1
+            }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("decimal e inteiro"));
+    }
+
+    #[test]
+    fn test_rejects_temp_used_across_blocks() {
+        let errors = verify_errors(
+            "funcao logico f(inteiro x) {
+            bb0:
+                %0: inteiro = load x
+                %1: logico = gt %0, 0
+                jmp bb1
+            bb1:
+                ret %1
+            }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("fora do bloco"));
+    }
+
+    #[test]
+    fn test_rejects_bad_calls_and_returns() {
+        let errors = verify_errors(
+            "funcao g(inteiro a) {
+            bb0:
+                ret 1
+            }
+
+            funcao f() {
+            bb0:
+                %0: inteiro = call g(\"x\")
+                call h()
+                ret
+            }",
+        );
+        assert_eq!(errors.len(), 4);
+    }
+}
@@ -1,6 +1,11 @@
-use crate::lexer::token::{Token, TokenType};
+use crate::lexer::token::{StringPart, Token, TokenType};
 use crate::lexer::error::LexerError;
+use crate::lexer::lexer::Lexer;
 
+// Textos entre "..." ou """...""". Os de três aspas podem ter aspas soltas
+// e a quebra de linha logo depois da abertura não entra no texto. Nos dois
+// casos `{expressão}` interpola um valor (\{ e \} escrevem as chaves) e
+// \u{...} escreve um caractere pelo código Unicode.
 pub fn try_consume_string(
     source: &[char],
     current_pos: &mut usize,
@@ -11,41 +16,52 @@ pub fn try_consume_string(
         return Ok(None);
     }
 
+    let start_pos = *current_pos;
     let start_line = *current_line;
     let start_column = *current_column;
+    let triple = source[*current_pos..].starts_with(&['"'; 3]);
+    let quotes = if triple { 3 } else { 1 };
+    let mut parts = Vec::new();
     let mut string_content = String::new();
-    let mut escape = false;
 
     // pula inicio de aspas
-    advance(source, current_pos, current_line, current_column);
+    for _ in 0..quotes {
+        advance(source, current_pos, current_line, current_column);
+    }
+    if triple && source.get(*current_pos) == Some(&'\n') {
+        advance(source, current_pos, current_line, current_column);
+    }
 
     while *current_pos < source.len() {
         let char = source[*current_pos];
 
-        if escape {
-            match char {
-                'n' => string_content.push('\n'),
-                't' => string_content.push('\t'),
-                'r' => string_content.push('\r'),
-                '"' => string_content.push('"'),
-                '\\' => string_content.push('\\'),
-                _ => return Err(LexerError::invalid_escape(char, *current_line, *current_column)),
-            }
-            escape = false;
+        if char == '\\' {
             advance(source, current_pos, current_line, current_column);
-        } else if char == '\\' {
-            escape = true;
-            advance(source, current_pos, current_line, current_column);
-        } else if char == '"' {
+            if *current_pos < source.len() {
+                string_content.push(consume_escape(source, current_pos, current_line, current_column)?);
+            }
+        } else if char == '"' && (!triple || source[*current_pos..].starts_with(&['"'; 3])) {
             // chega no fim da string
-            advance(source, current_pos, current_line, current_column);
-            let lexema = format!("\"{}\"", string_content);
-            return Ok(Some(Token::new(
-                TokenType::StringLiteral(string_content),
-                lexema,
-                start_line,
-                start_column,
-            )));
+            for _ in 0..quotes {
+                advance(source, current_pos, current_line, current_column);
+            }
+            let lexema: String = source[start_pos..*current_pos].iter().collect();
+            let token_type = if parts.is_empty() {
+                TokenType::StringLiteral(string_content)
+            } else {
+                if !string_content.is_empty() {
+                    parts.push(StringPart::Text(string_content));
+                }
+                TokenType::InterpolatedString(parts)
+            };
+            return Ok(Some(Token::new(token_type, lexema, start_line, start_column)));
+        } else if char == '{' {
+            if !string_content.is_empty() {
+                parts.push(StringPart::Text(std::mem::take(&mut string_content)));
+            }
+            parts.push(consume_interpolation(source, current_pos, current_line, current_column)?);
+        } else if char == '}' {
+            return Err(LexerError::unmatched_brace(*current_line, *current_column));
         } else {
             string_content.push(char);
             advance(source, current_pos, current_line, current_column);
@@ -55,6 +71,115 @@ pub fn try_consume_string(
     Err(LexerError::unterminated_string(start_line, start_column))
 }
 
+// caractere depois da barra invertida
+fn consume_escape(
+    source: &[char],
+    current_pos: &mut usize,
+    current_line: &mut usize,
+    current_column: &mut usize,
+) -> Result<char, LexerError> {
+    let char = source[*current_pos];
+    let (line, column) = (*current_line, *current_column);
+    advance(source, current_pos, current_line, current_column);
+
+    Ok(match char {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        '"' => '"',
+        '\\' => '\\',
+        '{' => '{',
+        '}' => '}',
+        'u' => consume_unicode_escape(source, current_pos, current_line, current_column, line, column)?,
+        _ => return Err(LexerError::invalid_escape(char, line, column)),
+    })
+}
+
+// \u{...}: de 1 a 6 dígitos hexadecimais, sem os substitutos do UTF-16
+fn consume_unicode_escape(
+    source: &[char],
+    current_pos: &mut usize,
+    current_line: &mut usize,
+    current_column: &mut usize,
+    line: usize,
+    column: usize,
+) -> Result<char, LexerError> {
+    let mut lexema = String::from("\\u");
+    if source.get(*current_pos) != Some(&'{') {
+        return Err(LexerError::invalid_unicode_escape(&lexema, line, column));
+    }
+    lexema.push('{');
+    advance(source, current_pos, current_line, current_column);
+
+    let mut digits = String::new();
+    while let Some(&char) = source.get(*current_pos) {
+        if !char.is_ascii_hexdigit() {
+            break;
+        }
+        digits.push(char);
+        advance(source, current_pos, current_line, current_column);
+    }
+    lexema.push_str(&digits);
+
+    if source.get(*current_pos) != Some(&'}') {
+        return Err(LexerError::invalid_unicode_escape(&lexema, line, column));
+    }
+    lexema.push('}');
+    advance(source, current_pos, current_line, current_column);
+
+    if digits.is_empty() || digits.len() > 6 {
+        return Err(LexerError::invalid_unicode_escape(&lexema, line, column));
+    }
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| LexerError::invalid_unicode_escape(&lexema, line, column))
+}
+
+// Guarda o código entre { e } para o parser; textos dentro da expressão
+// podem ter chaves próprias. O código já passa pelo léxico aqui, para que um
+// erro léxico dentro da interpolação seja informado como tal e na sua posição
+fn consume_interpolation(
+    source: &[char],
+    current_pos: &mut usize,
+    current_line: &mut usize,
+    current_column: &mut usize,
+) -> Result<StringPart, LexerError> {
+    let (open_line, open_column) = (*current_line, *current_column);
+    advance(source, current_pos, current_line, current_column);
+
+    let (linha, coluna) = (*current_line, *current_column);
+    let mut code = String::new();
+    let mut in_string = false;
+
+    while *current_pos < source.len() {
+        let char = source[*current_pos];
+
+        if !in_string && char == '}' {
+            advance(source, current_pos, current_line, current_column);
+            if code.trim().is_empty() {
+                return Err(LexerError::empty_interpolation(open_line, open_column));
+            }
+            Lexer::at(&code, linha, coluna).tokenize()?;
+            return Ok(StringPart::Code { source: code, linha, coluna });
+        }
+
+        if in_string && char == '\\' {
+            code.push(char);
+            advance(source, current_pos, current_line, current_column);
+            if *current_pos >= source.len() {
+                break;
+            }
+        } else if char == '"' {
+            in_string = !in_string;
+        }
+        code.push(source[*current_pos]);
+        advance(source, current_pos, current_line, current_column);
+    }
+
+    Err(LexerError::unclosed_interpolation(open_line, open_column))
+}
+
 fn advance(
     source: &[char],
     current_pos: &mut usize,
@@ -70,4 +195,4 @@ fn advance(
         }
         *current_pos += 1;
     }
-}
\ No newline at end of file
+}
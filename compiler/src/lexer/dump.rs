@@ -0,0 +1,164 @@
+use super::token::{Token, Trivia, TriviaKind};
+use serde::Serialize;
+
+// Saída de `compiler tokens`: uma linha por token (e, com `--trivia`, por
+// espaço ou comentário), em tabela alinhada, JSON ou CSV. As chaves do JSON
+// e o cabeçalho do CSV são as colunas da tabela, em minúsculas.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl TokenFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(TokenFormat::Table),
+            "json" => Some(TokenFormat::Json),
+            "csv" => Some(TokenFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenRow {
+    // nome da variante de TokenType, ou Espaco/Comentario
+    pub tipo: String,
+    pub lexema: String,
+    pub linha: usize,
+    pub coluna: usize,
+    pub comprimento: usize,
+}
+
+// tokens e trivia intercalados pela posição no arquivo
+pub fn token_rows(tokens: &[Token], trivia: &[Trivia]) -> Vec<TokenRow> {
+    let mut rows: Vec<TokenRow> = tokens
+        .iter()
+        .map(|token| TokenRow {
+            tipo: token.token_type.name(),
+            lexema: token.lexema.clone(),
+            linha: token.linha,
+            coluna: token.coluna,
+            comprimento: token.comprimento,
+        })
+        .chain(trivia.iter().map(|trivia| TokenRow {
+            tipo: match trivia.kind {
+                TriviaKind::Whitespace => "Espaco".to_string(),
+                TriviaKind::Comment => "Comentario".to_string(),
+            },
+            lexema: trivia.lexema.clone(),
+            linha: trivia.linha,
+            coluna: trivia.coluna,
+            comprimento: trivia.comprimento,
+        }))
+        .collect();
+    rows.sort_by_key(|row| (row.linha, row.coluna));
+    rows
+}
+
+pub fn format_tokens(rows: &[TokenRow], format: TokenFormat) -> String {
+    match format {
+        TokenFormat::Table => format_table(rows),
+        TokenFormat::Json => serde_json::to_string_pretty(rows).expect("tokens sempre serializam") + "\n",
+        TokenFormat::Csv => {
+            let mut out = String::from("tipo,lexema,linha,coluna,comprimento\n");
+            for row in rows {
+                out.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    row.tipo,
+                    csv_field(&row.lexema),
+                    row.linha,
+                    row.coluna,
+                    row.comprimento
+                ));
+            }
+            out
+        }
+    }
+}
+
+// lexemas entre aspas e com escapes, para espaços e quebras de linha ficarem
+// visíveis
+fn format_table(rows: &[TokenRow]) -> String {
+    let header = ["TIPO", "LEXEMA", "LINHA", "COLUNA", "COMPRIMENTO"];
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .map(|row| {
+            [
+                row.tipo.clone(),
+                format!("{:?}", row.lexema),
+                row.linha.to_string(),
+                row.coluna.to_string(),
+                row.comprimento.to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(|title| title.chars().count());
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut line = |cells: [&str; 5]| {
+        // texto à esquerda, números à direita
+        let text = format!("{:<w0$}  {:<w1$}", cells[0], cells[1], w0 = widths[0], w1 = widths[1]);
+        let numbers = format!(
+            "{:>w2$}  {:>w3$}  {:>w4$}",
+            cells[2], cells[3], cells[4], w2 = widths[2], w3 = widths[3], w4 = widths[4]
+        );
+        out.push_str(format!("{}  {}", text, numbers).trim_end());
+        out.push('\n');
+    };
+    line(header);
+    for row in &cells {
+        line([&row[0], &row[1], &row[2], &row[3], &row[4]]);
+    }
+    out
+}
+
+// entre aspas também com espaço nas pontas, que muitos leitores de CSV cortam
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    #[test]
+    fn test_format_tokens() {
+        let source = "inteiro x = 10; // dez\nescreva(\"a, \\\"b\\\"\");";
+        let mut lexer = Lexer::new(source).with_trivia();
+        let tokens = lexer.tokenize().unwrap();
+        let rows = token_rows(&tokens, lexer.trivia());
+
+        let kinds: Vec<&str> = rows.iter().take(8).map(|row| row.tipo.as_str()).collect();
+        assert_eq!(kinds, ["Inteiro", "Espaco", "Identificador", "Espaco", "Atribuicao", "Espaco", "InteiroLiteral", "PontoEVirgula"]);
+        assert_eq!(rows[9], TokenRow { tipo: "Comentario".to_string(), lexema: "// dez".to_string(), linha: 1, coluna: 17, comprimento: 6 });
+        assert_eq!(rows[10].lexema, "\n");
+
+        let table = format_tokens(&rows, TokenFormat::Table);
+        let mut lines = table.lines();
+        assert_eq!(lines.next(), Some("TIPO               LEXEMA              LINHA  COLUNA  COMPRIMENTO"));
+        assert_eq!(lines.next(), Some("Inteiro            \"inteiro\"               1       1            7"));
+        assert_eq!(lines.next(), Some("Espaco             \" \"                     1       8            1"));
+
+        let csv = format_tokens(&rows, TokenFormat::Csv);
+        assert!(csv.contains("\nEspaco,\" \",1,8,1\n"));
+        assert!(csv.contains("\nStringLiteral,\"\"\"a, \\\"\"b\\\"\"\"\"\",2,9,"));
+
+        let json: serde_json::Value = serde_json::from_str(&format_tokens(&token_rows(&tokens, &[]), TokenFormat::Json)).unwrap();
+        assert_eq!(json[1], serde_json::json!({ "tipo": "Identificador", "lexema": "x", "linha": 1, "coluna": 9, "comprimento": 1 }));
+    }
+}
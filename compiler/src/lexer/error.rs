@@ -41,6 +41,32 @@ impl LexerError {
         Self::new(format!("Sequência de escape inválida: '\\{}'", c), linha, coluna)
     }
 
+    pub fn invalid_unicode_escape(lexema: &str, linha: usize, coluna: usize) -> Self {
+        Self::with_recovery_suggestion(
+            format!("Escape Unicode inválido: '{}'", lexema),
+            linha,
+            coluna,
+            "Use \\u{...} com 1 a 6 dígitos hexadecimais, como \\u{e9}".to_string(),
+        )
+    }
+
+    pub fn unclosed_interpolation(linha: usize, coluna: usize) -> Self {
+        Self::new("Interpolação não foi fechada com '}'".to_string(), linha, coluna)
+    }
+
+    pub fn empty_interpolation(linha: usize, coluna: usize) -> Self {
+        Self::new("Interpolação vazia: '{}'".to_string(), linha, coluna)
+    }
+
+    pub fn unmatched_brace(linha: usize, coluna: usize) -> Self {
+        Self::with_recovery_suggestion(
+            "'}' sem '{' correspondente no texto".to_string(),
+            linha,
+            coluna,
+            "Use \\} para escrever uma chave".to_string(),
+        )
+    }
+
     pub fn unclosed_comment(linha: usize, coluna: usize) -> Self {
         Self::new("Comentário de bloco não foi fechado".to_string(), linha, coluna)
     }
@@ -1,5 +1,5 @@
 use std::collections::VecDeque;
-use super::token::{Token, TokenType};
+use super::token::{Token, TokenType, Trivia, TriviaKind};
 use super::error::LexerError;
 use super::afds;
 
@@ -8,22 +8,45 @@ pub struct Lexer {
     current_pos: usize,
     current_line: usize,
     current_column: usize,
+    first_line: usize,
+    first_column: usize,
     tokens: Vec<Token>,
     lookahead_buffer: VecDeque<Token>,
+    // espaços e comentários, só com `with_trivia`
+    trivia: Option<Vec<Trivia>>,
 }
 
 impl Lexer {
     pub fn new(source_code: &str) -> Self {
+        Self::at(source_code, 1, 1)
+    }
+
+    // trecho que começa na linha e coluna dadas de um arquivo maior, como a
+    // expressão de uma interpolação
+    pub fn at(source_code: &str, linha: usize, coluna: usize) -> Self {
         Self {
             source: source_code.chars().collect(),
             current_pos: 0,
-            current_line: 1,
-            current_column: 1,
+            current_line: linha,
+            current_column: coluna,
+            first_line: linha,
+            first_column: coluna,
             tokens: Vec::new(),
             lookahead_buffer: VecDeque::new(),
+            trivia: None,
         }
     }
 
+    pub fn with_trivia(mut self) -> Self {
+        self.trivia = Some(Vec::new());
+        self
+    }
+
+    // em ordem de posição; vazio sem `with_trivia`
+    pub fn trivia(&self) -> &[Trivia] {
+        self.trivia.as_deref().unwrap_or_default()
+    }
+
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
         let mut tokens = Vec::new();
         self.reset();
@@ -76,10 +99,13 @@ impl Lexer {
 
     pub fn reset(&mut self) {
         self.current_pos = 0;
-        self.current_line = 1;
-        self.current_column = 1;
+        self.current_line = self.first_line;
+        self.current_column = self.first_column;
         self.tokens.clear();
         self.lookahead_buffer.clear();
+        if let Some(trivia) = &mut self.trivia {
+            trivia.clear();
+        }
     }
 
     pub fn get_all_tokens(&mut self) -> Result<Vec<Token>, LexerError> {
@@ -97,6 +123,7 @@ impl Lexer {
         
         let start_line = self.current_line;
         let start_column = self.current_column;
+        let start_pos = self.current_pos;
         
         // aqui aplicamos princípio do match mais longo usando AFDs
         if let Some(_token) = afds::try_consume_comment(
@@ -106,7 +133,8 @@ impl Lexer {
             &mut self.current_column
         )? {
             // comentario são ignorados, chama recursivamente
-            return self.next_token_internal();
+            self.record_trivia(TriviaKind::Comment, start_pos, start_line, start_column);
+            self.next_token_internal()
         } else if let Some(token) = afds::try_consume_string(
             &self.source, 
             &mut self.current_pos, 
@@ -183,8 +211,25 @@ impl Lexer {
     }
 
     fn skip_whitespace(&mut self) {
+        let (start_pos, start_line, start_column) = (self.current_pos, self.current_line, self.current_column);
         while self.current_pos < self.source.len() && self.current_char().is_whitespace() {
             self.advance();
         }
+        if self.current_pos > start_pos {
+            self.record_trivia(TriviaKind::Whitespace, start_pos, start_line, start_column);
+        }
+    }
+
+    // o trecho de `start_pos` até a posição atual
+    fn record_trivia(&mut self, kind: TriviaKind, start_pos: usize, linha: usize, coluna: usize) {
+        if let Some(trivia) = &mut self.trivia {
+            trivia.push(Trivia {
+                kind,
+                lexema: self.source[start_pos..self.current_pos].iter().collect(),
+                linha,
+                coluna,
+                comprimento: self.current_pos - start_pos,
+            });
+        }
     }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 pub mod afds;
+pub mod dump;
 pub mod error;
 pub mod lexer;
 pub mod token;
@@ -6,4 +7,4 @@ pub mod token;
 // Re-export para facilitar o acesso
 pub use error::LexerError;
 pub use lexer::Lexer;
-pub use token::{Token, TokenType};
\ No newline at end of file
+pub use token::{StringPart, Token, TokenType, Trivia, TriviaKind};
\ No newline at end of file
@@ -23,12 +23,15 @@ pub enum TokenType {
     Falso,
     Escreva,
     Leia,
+    Importe,
     //Principal,
 
     Identificador(String),
     InteiroLiteral(i64),
     DecimalLiteral(f64),
     StringLiteral(String),
+    // texto com `{expressão}` dentro; partes na ordem em que aparecem
+    InterpolatedString(Vec<StringPart>),
 
     Mais,
     Menos,
@@ -60,6 +63,30 @@ pub enum TokenType {
     EOF,
 }
 
+// Espaços e comentários, que o parser não vê; o lexer só os guarda quando
+// pedido (`Lexer::with_trivia`), para `compiler tokens --trivia`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub lexema: String,
+    pub linha: usize,
+    pub coluna: usize,
+    pub comprimento: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    Text(String),
+    // código da expressão, ainda sem tokens, e onde ele começa no arquivo
+    Code { source: String, linha: usize, coluna: usize },
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
@@ -69,6 +96,82 @@ pub struct Token {
     pub comprimento: usize,
 }
 
+impl TokenType {
+    // Nome da variante, sem o valor (`Identificador`, `InteiroLiteral`, ...)
+    pub fn name(&self) -> String {
+        match self {
+            TokenType::Identificador(_) => "Identificador".to_string(),
+            TokenType::InteiroLiteral(_) => "InteiroLiteral".to_string(),
+            TokenType::DecimalLiteral(_) => "DecimalLiteral".to_string(),
+            TokenType::StringLiteral(_) => "StringLiteral".to_string(),
+            TokenType::InterpolatedString(_) => "InterpolatedString".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
+
+    // Nome para mensagens: o próprio símbolo ou palavra-chave, e o tipo para
+    // literais e identificadores (os `expected` do parser usam valores vazios)
+    pub fn describe(&self) -> String {
+        let symbol = match self {
+            TokenType::Se => "se",
+            TokenType::Entao => "entao",
+            TokenType::Senao => "senao",
+            TokenType::FimSe => "fimse",
+            TokenType::Enquanto => "enquanto",
+            TokenType::Faca => "faca",
+            TokenType::FimEnquanto => "fimenquanto",
+            TokenType::Para => "para",
+            TokenType::De => "de",
+            TokenType::Ate => "ate",
+            TokenType::FimPara => "fimpara",
+            TokenType::Funcao => "funcao",
+            TokenType::Retorne => "retorne",
+            TokenType::FimFuncao => "fimfuncao",
+            TokenType::Inteiro => "inteiro",
+            TokenType::Decimal => "decimal",
+            TokenType::Texto => "texto",
+            TokenType::Logico => "logico",
+            TokenType::Verdadeiro => "verdadeiro",
+            TokenType::Falso => "falso",
+            TokenType::Escreva => "escreva",
+            TokenType::Leia => "leia",
+            TokenType::Importe => "importe",
+            TokenType::Identificador(name) if name.is_empty() => return "identificador".to_string(),
+            TokenType::Identificador(name) => return format!("identificador '{}'", name),
+            TokenType::InteiroLiteral(_) => return "número inteiro".to_string(),
+            TokenType::DecimalLiteral(_) => return "número decimal".to_string(),
+            TokenType::StringLiteral(_) | TokenType::InterpolatedString(_) => return "texto entre aspas".to_string(),
+            TokenType::Mais => "+",
+            TokenType::Menos => "-",
+            TokenType::Multiplicacao => "*",
+            TokenType::Divisao => "/",
+            TokenType::Modulo => "%",
+            TokenType::Atribuicao => "=",
+            TokenType::Igual => "==",
+            TokenType::Diferente => "!=",
+            TokenType::Menor => "<",
+            TokenType::Maior => ">",
+            TokenType::MenorIgual => "<=",
+            TokenType::MaiorIgual => ">=",
+            TokenType::ELogico => "&&",
+            TokenType::OuLogico => "||",
+            TokenType::NaoLogico => "!",
+            TokenType::ParenteseEsquerdo => "(",
+            TokenType::ParenteseDireito => ")",
+            TokenType::ChaveEsquerda => "{",
+            TokenType::ChaveDireita => "}",
+            TokenType::ColcheteEsquerdo => "[",
+            TokenType::ColcheteDireito => "]",
+            TokenType::Ponto => ".",
+            TokenType::Virgula => ",",
+            TokenType::PontoEVirgula => ";",
+            TokenType::DoisPontos => ":",
+            TokenType::EOF => return "fim do arquivo".to_string(),
+        };
+        format!("'{}'", symbol)
+    }
+}
+
 impl std::fmt::Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
@@ -1,8 +1,29 @@
+#![allow(clippy::module_inception)]
+
 pub mod lexer;
 pub mod parser;
 pub mod semantic;
+pub mod optimizer;
+pub mod ir;
+pub mod backend;
 pub mod serialization;
+pub mod ast_schema;
+pub mod ast_binary;
+pub mod ast_graph;
+pub mod interpreter;
+pub mod golden;
+pub mod fuzz;
+pub mod debugger;
+pub mod dap;
+pub mod modules;
+pub mod manifest;
+pub mod diagnostics;
 
 pub use lexer::{Lexer, Token, TokenType, LexerError};
 pub use parser::{Parser, ParserError, Program, Expr, Statement, Literal, Type};
 pub use semantic::{SemanticAnalyzer, SemanticAnalysisResult, SemanticError};
+pub use optimizer::ConstantFolder;
+pub use backend::{BackendError, WatGenerator};
+pub use modules::{ModuleError, ModuleLoader};
+pub use manifest::{Manifest, ManifestError};
+pub use diagnostics::Diagnostic;
@@ -1,111 +1,929 @@
-use compiler::{Lexer, Parser, SemanticAnalyzer};
+use compiler::{ConstantFolder, Lexer, Manifest, ModuleLoader, Parser, SemanticAnalyzer, SemanticError};
+use compiler::backend::wat::generate_wat;
+use compiler::diagnostics::{to_sarif, Diagnostic, Severity, Span};
+use compiler::backend::x86_64::generate_x86_64;
+use compiler::backend::riscv::{assemble, generate_riscv, Simulator};
+use compiler::backend::javascript::generate_js;
+use compiler::backend::python::generate_python;
+use compiler::ir::{verify, Lowering};
+use compiler::parser::printer::format_file;
+use compiler::semantic::lints::lint;
 use compiler::serialization::save_semantic_result_to_json;
+use compiler::ast_schema;
+use compiler::ast_binary::{self, save_semantic_result_to_binary};
+use compiler::ast_graph::{to_dot, to_mermaid};
+use compiler::lexer::dump::{format_tokens, token_rows, TokenFormat};
+use compiler::golden;
+use compiler::fuzz;
+use compiler::debugger;
+use compiler::dap;
+use compiler::interpreter::with_large_stack;
+use std::cell::RefCell;
 use std::env;
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const EMIT_TARGETS: [&str; 5] = ["wat", "x86_64", "riscv", "js", "py"];
+// limite de instruções executadas pelo simulador RISC-V
+const SIMULATION_STEPS: u64 = 1_000_000_000;
+// programas gerados por `fuzz` sem --runs
+const FUZZ_RUNS: u64 = 100;
+
+// Códigos de saída, um por fase, para scripts saberem onde a compilação
+// parou. `run` devolve o código do próprio programa quando ele termina: os
+// programas Symplia só terminam com 0 ou 1 (erro de execução), mas um arquivo
+// .s pode sair com qualquer código, inclusive um de 10 a 15, e não há como
+// tirá-lo dessa faixa sem colidir com outro código que ele poderia usar.
+const EXIT_FAILURE: i32 = 1; // fmt --check encontrou diferenças, test ou fuzz falhou
+const EXIT_USAGE: i32 = 2;
+const EXIT_IO: i32 = 3; // leitura/escrita de arquivos e symplia.toml
+const EXIT_LEXICAL: i32 = 10;
+const EXIT_SYNTAX: i32 = 11;
+const EXIT_MODULES: i32 = 12;
+const EXIT_SEMANTIC: i32 = 13; // inclui o nível da linguagem do manifesto
+const EXIT_CODEGEN: i32 = 14; // otimização, IR e backends
+const EXIT_RUNTIME: i32 = 15; // montagem e simulação
+
+const USAGE: &str = "Uso: compiler [comando] [opções] [arquivo.sym | -]
+
+Comandos:
+  build    compila e grava as saídas de --emit (padrão: json)
+  check    só verifica o programa, sem gravar arquivos
+  run      compila e executa no simulador RISC-V (aceita também arquivos .s)
+  fmt      escreve o programa no formato padrão
+  tokens   lista os tokens do programa: tipo, lexema, linha, coluna e
+           comprimento
+  schema   imprime o JSON Schema dos arquivos .ast.json
+  dump     converte uma AST binária (.ast.bin) para JSON
+  test     executa os programas (arquivos ou diretórios) no interpretador e
+           confere as anotações // SAIDA:, // ERRO: e // ENTRADA:
+  fuzz     gera programas aleatórios e confere que o interpretador, o
+           dobramento de constantes e o simulador RISC-V concordam e que
+           versões estragadas deles não derrubam o compilador
+  debug    executa o programa no interpretador parando comando a comando;
+           os comandos do depurador (help lista todos) e a entrada do
+           programa vêm da entrada padrão
+  dap      servidor do Debug Adapter Protocol na entrada e saída padrão,
+           para depurar no editor; o programa vem do pedido launch
+
+Sem arquivo, usa a entrada do symplia.toml mais próximo; '-' lê o programa
+da entrada padrão.
+
+Opções:
+  --emit <saídas>      separadas por vírgula; tokens, ast, typed-ast, ast-dot,
+                       ast-mermaid e ir vão para a saída padrão, json,
+                       ast-bin, wat, x86_64, riscv, js e py para arquivos no
+                       diretório de saída; ast-dot e ast-mermaid mostram os
+                       tipos quando o programa passa pela análise semântica
+  -o, --out-dir <dir>  diretório de saída (padrão: o do manifesto ou ../build)
+  -O                   dobramento de constantes
+  -q, --quiet          mostra só os erros
+  -v, --verbose        mostra o andamento de cada fase
+  --message-format <f> erros e avisos como human (padrão), json (um objeto
+                       por linha) ou sarif, sempre na saída de erros; com
+                       json e sarif ela só tem os diagnósticos
+  --check              (fmt) falha se o arquivo não estiver formatado
+  --write              (fmt) reescreve o arquivo
+  --format <f>         (tokens) table (padrão), json ou csv
+  --trivia             (tokens) inclui espaços e comentários
+  --bless              (test) reescreve as anotações com o resultado atual
+  --seed <n>           (fuzz) primeira semente (padrão: o relógio)
+  --runs <n>           (fuzz) quantos programas gerar (padrão: 100)
+  --ir, --simular      o mesmo que --emit ir e o comando run
+
+Códigos de saída: 0 sucesso, 1 fmt --check com diferenças, test ou fuzz com falhas,
+2 uso, 3 arquivos,
+10 léxico, 11 sintático, 12 módulos, 13 semântico, 14 geração de código,
+15 execução. O run sai com o código do programa, que num arquivo .s pode ser
+também um desses.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Build,
+    Check,
+    Run,
+    Fmt,
+    Tokens,
+    Schema,
+    Dump,
+    Test,
+    Fuzz,
+    Debug,
+    Dap,
+}
+
+impl Command {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "build" => Some(Command::Build),
+            "check" => Some(Command::Check),
+            "run" => Some(Command::Run),
+            "fmt" => Some(Command::Fmt),
+            "tokens" => Some(Command::Tokens),
+            "schema" => Some(Command::Schema),
+            "dump" => Some(Command::Dump),
+            "test" => Some(Command::Test),
+            "fuzz" => Some(Command::Fuzz),
+            "debug" => Some(Command::Debug),
+            "dap" => Some(Command::Dap),
+            _ => None,
+        }
+    }
+}
+
+// Fases do compilador, na ordem em que rodam
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Lexing,
+    Parsing,
+    Analysis,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Emit {
+    Tokens,
+    Ast,
+    TypedAst,
+    // árvore em Graphviz e Mermaid (ver `ast_graph`)
+    AstDot,
+    AstMermaid,
+    Json,
+    // AST tipada no formato binário de `ast_binary`
+    AstBin,
+    Ir,
+    Code(&'static str),
+}
+
+impl Emit {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "tokens" => Some(Emit::Tokens),
+            "ast" => Some(Emit::Ast),
+            "typed-ast" => Some(Emit::TypedAst),
+            "ast-dot" => Some(Emit::AstDot),
+            "ast-mermaid" => Some(Emit::AstMermaid),
+            "json" => Some(Emit::Json),
+            "ast-bin" => Some(Emit::AstBin),
+            "ir" => Some(Emit::Ir),
+            _ => EMIT_TARGETS.iter().find(|target| **target == name).map(|target| Emit::Code(target)),
+        }
+    }
+
+    // última fase necessária para produzir a saída
+    fn phase(&self) -> Phase {
+        match self {
+            Emit::Tokens => Phase::Lexing,
+            Emit::Ast | Emit::AstDot | Emit::AstMermaid => Phase::Parsing,
+            _ => Phase::Analysis,
+        }
+    }
+
+    fn writes_file(&self) -> bool {
+        matches!(self, Emit::Json | Emit::AstBin | Emit::Code(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+// erro semântico com o arquivo do módulo onde está, quando não é o programa
+// principal; no formato human o arquivo vem antes da mensagem
+struct Located<'a> {
+    error: &'a SemanticError,
+    file: Option<String>,
+}
+
+impl<'a> Located<'a> {
+    fn all(errors: &'a [SemanticError], loader: &ModuleLoader) -> Vec<Self> {
+        let origin = |e: &SemanticError| e.function.as_deref().and_then(|f| loader.origin(f)).map(|p| p.display().to_string());
+        errors.iter().map(|error| Located { error, file: origin(error) }).collect()
+    }
+}
+
+impl fmt::Display for Located<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}: {}", file, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug)]
+struct Options {
+    command: Command,
+    // None usa o manifesto; "-" é a entrada padrão
+    input: Option<String>,
+    // programas e diretórios de `test`
+    paths: Vec<PathBuf>,
+    emit: Vec<Emit>,
+    out_dir: Option<PathBuf>,
+    optimize: bool,
+    verbosity: Verbosity,
+    message_format: MessageFormat,
+    check: bool,
+    write: bool,
+    // saída dos tokens; None é a tabela
+    token_format: Option<TokenFormat>,
+    trivia: bool,
+    bless: bool,
+    // primeira semente e quantidade de programas de `fuzz`
+    seed: Option<u64>,
+    runs: Option<u64>,
+    help: bool,
+    // no SARIF os diagnósticos saem juntos, num documento só, no fim
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut args = args.iter().peekable();
+        let command = match args.peek().and_then(|arg| Command::parse(arg)) {
+            Some(command) => {
+                args.next();
+                command
+            }
+            None => Command::Build,
+        };
+
+        let mut options = Options {
+            command,
+            input: None,
+            paths: Vec::new(),
+            emit: Vec::new(),
+            out_dir: None,
+            optimize: false,
+            verbosity: Verbosity::Normal,
+            message_format: MessageFormat::Human,
+            check: false,
+            write: false,
+            token_format: None,
+            trivia: false,
+            bless: false,
+            seed: None,
+            runs: None,
+            help: false,
+            diagnostics: RefCell::new(Vec::new()),
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-O" => options.optimize = true,
+                "--ir" => options.emit.push(Emit::Ir),
+                "--simular" if matches!(options.command, Command::Build | Command::Run) => options.command = Command::Run,
+                "-q" | "--quiet" => options.verbosity = Verbosity::Quiet,
+                "-v" | "--verbose" => options.verbosity = Verbosity::Verbose,
+                "--check" => options.check = true,
+                "--write" => options.write = true,
+                "--trivia" => options.trivia = true,
+                "--bless" => options.bless = true,
+                "-h" | "--help" => options.help = true,
+                "--emit" | "-o" | "--out-dir" | "--message-format" | "--format" | "--seed" | "--runs" => {
+                    let value = args.next().ok_or_else(|| format!("Faltou o valor depois de {}", arg))?;
+                    options.set(arg, value)?;
+                }
+                _ if arg.starts_with("--emit=") => options.set("--emit", &arg["--emit=".len()..])?,
+                _ if arg.starts_with("--message-format=") => {
+                    options.set("--message-format", &arg["--message-format=".len()..])?
+                }
+                _ if arg.starts_with("--format=") => options.set("--format", &arg["--format=".len()..])?,
+                _ if arg.starts_with('-') && arg != "-" => return Err(format!("Opção inválida: {}", arg)),
+                _ if options.command == Command::Test => options.paths.push(PathBuf::from(arg)),
+                _ if options.input.is_some() => return Err(format!("Mais de um arquivo de entrada: {}", arg)),
+                _ => options.input = Some(arg.clone()),
+            }
+        }
+
+        let lists_tokens = options.command == Command::Tokens || options.emits(Emit::Tokens);
+        if (options.token_format.is_some() || options.trivia) && !lists_tokens {
+            return Err("--format e --trivia só valem para tokens".to_string());
+        }
+        if (options.check || options.write) && options.command != Command::Fmt {
+            return Err("--check e --write só valem para o comando fmt".to_string());
+        }
+        if options.check && options.write {
+            return Err("Use --check ou --write, não os dois".to_string());
+        }
+        if options.write && options.input.as_deref() == Some("-") {
+            return Err("--write não pode reescrever a entrada padrão".to_string());
+        }
+        if options.bless && options.command != Command::Test {
+            return Err("--bless só vale para o comando test".to_string());
+        }
+        if (options.seed.is_some() || options.runs.is_some()) && options.command != Command::Fuzz {
+            return Err("--seed e --runs só valem para o comando fuzz".to_string());
+        }
+        if options.command == Command::Fuzz && (options.input.is_some() || !options.emit.is_empty()) {
+            return Err("fuzz não recebe arquivo nem --emit".to_string());
+        }
+        if options.command == Command::Dap && (options.input.is_some() || !options.emit.is_empty()) {
+            return Err("dap recebe o programa pelo pedido launch: não recebe arquivo nem --emit".to_string());
+        }
+        if options.command == Command::Debug && (options.input.as_deref() == Some("-") || !options.emit.is_empty()) {
+            return Err("debug lê os comandos da entrada padrão: recebe um arquivo .sym e não aceita --emit".to_string());
+        }
+        if options.command == Command::Test && (options.paths.is_empty() || !options.emit.is_empty()) {
+            return Err("test recebe programas .sym ou diretórios e não aceita --emit".to_string());
+        }
+        if options.command == Command::Schema && (options.input.is_some() || !options.emit.is_empty()) {
+            return Err("schema não recebe arquivo nem --emit".to_string());
+        }
+        if options.command == Command::Dump && (options.input.is_none() || !options.emit.is_empty()) {
+            return Err("dump recebe um arquivo .ast.bin (ou '-') e não aceita --emit".to_string());
+        }
+        if options.command == Command::Check && options.emit.iter().any(Emit::writes_file) {
+            return Err("check não grava arquivos; use build para --emit json ou um alvo".to_string());
+        }
+        if options.command == Command::Build && options.emit.is_empty() {
+            options.emit.push(Emit::Json);
+        }
+        Ok(options)
+    }
+
+    fn set(&mut self, option: &str, value: &str) -> Result<(), String> {
+        if option == "--format" {
+            let format = TokenFormat::parse(value)
+                .ok_or_else(|| format!("Formato de --format inválido: '{}'. Formatos: table, json, csv", value))?;
+            self.token_format = Some(format);
+        } else if option == "--message-format" {
+            self.message_format = match value {
+                "human" => MessageFormat::Human,
+                "json" => MessageFormat::Json,
+                "sarif" => MessageFormat::Sarif,
+                _ => return Err(format!("Formato de --message-format inválido: '{}'. Formatos: human, json, sarif", value)),
+            };
+        } else if option == "--seed" || option == "--runs" {
+            let number = value
+                .parse()
+                .map_err(|_| format!("Valor de {} inválido: '{}' (esperado um número)", option, value))?;
+            if option == "--seed" {
+                self.seed = Some(number);
+            } else {
+                self.runs = Some(number);
+            }
+        } else if option == "--emit" {
+            for name in value.split(',') {
+                let emit = Emit::parse(name).ok_or_else(|| {
+                    format!("Saída de --emit inválida: '{}'. Saídas: tokens, ast, typed-ast, ast-dot, ast-mermaid, json, ast-bin, ir, {}", name, EMIT_TARGETS.join(", "))
+                })?;
+                if !self.emit.contains(&emit) {
+                    self.emit.push(emit);
+                }
+            }
+        } else {
+            self.out_dir = Some(PathBuf::from(value));
+        }
+        Ok(())
+    }
+
+    fn emits(&self, emit: Emit) -> bool {
+        self.emit.contains(&emit)
+    }
+
+    // até onde o pipeline precisa ir
+    fn last_phase(&self) -> Phase {
+        match self.command {
+            Command::Build => self.emit.iter().map(Emit::phase).max().unwrap_or(Phase::Analysis),
+            Command::Check | Command::Run | Command::Debug => Phase::Analysis,
+            Command::Fmt => Phase::Parsing,
+            Command::Tokens | Command::Schema | Command::Dump | Command::Test | Command::Fuzz | Command::Dap => Phase::Lexing,
+        }
+    }
+
+    // andamento das fases, só com --verbose; com --message-format json ou
+    // sarif a saída de erros fica só com os diagnósticos, para ser lida por
+    // outro programa
+    fn info(&self, message: impl fmt::Display) {
+        if self.verbosity == Verbosity::Verbose && self.message_format == MessageFormat::Human {
+            eprintln!("{}", message);
+        }
+    }
+
+    // arquivos gerados, omitidos com --quiet e fora do formato humano
+    fn status(&self, message: impl fmt::Display) {
+        if self.verbosity != Verbosity::Quiet && self.message_format == MessageFormat::Human {
+            eprintln!("{}", message);
+        }
+    }
+
+    // erro sem posição no programa (arquivos, montagem, simulador), também no
+    // formato de --message-format
+    fn error(&self, code: &str, file: &str, message: impl fmt::Display) {
+        self.report(&[message.to_string()], |message| Diagnostic::new(code, Severity::Error, message.clone(), Span::new(file, 0, 0)));
+    }
+
+    // erros e avisos no formato de --message-format, sempre em stderr
+    fn report<E: fmt::Display>(&self, items: &[E], diagnostic: impl Fn(&E) -> Diagnostic) {
+        for item in items {
+            match self.message_format {
+                MessageFormat::Human => eprintln!("{}", item),
+                MessageFormat::Json => eprintln!("{}", diagnostic(item).to_json()),
+                MessageFormat::Sarif => self.diagnostics.borrow_mut().push(diagnostic(item)),
+            }
+        }
+    }
+
+    fn warn<E: fmt::Display>(&self, warnings: &[E], diagnostic: impl Fn(&E) -> Diagnostic) {
+        if self.verbosity != Verbosity::Quiet {
+            self.report(warnings, diagnostic);
+        }
+    }
+
+    // mostra os erros de uma fase e sai com o código dela
+    fn fail<E: fmt::Display>(&self, code: i32, errors: &[E], diagnostic: impl Fn(&E) -> Diagnostic) -> ! {
+        self.report(errors, diagnostic);
+        self.finish();
+        process::exit(code);
+    }
+
+    fn finish(&self) {
+        if self.message_format == MessageFormat::Sarif {
+            let sarif = to_sarif(&self.diagnostics.borrow());
+            eprintln!("{}", serde_json::to_string_pretty(&sarif).expect("SARIF sempre serializa"));
+        }
+    }
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    
-    if args.len() < 2 {
-        eprintln!("Uso: {} <arquivo.sym>", args[0]);
-        process::exit(1);
-    }
-    
-    let filename = &args[1];
-    
-    let source_code = match fs::read_to_string(filename) {
+    let options = match Options::parse(&args[1..]) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\nUse --help para ver os comandos e opções", message);
+            process::exit(EXIT_USAGE);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let code = compile(&options);
+    options.finish();
+    process::exit(code);
+}
+
+// roda o comando pedido e devolve o código de saída
+fn compile(options: &Options) -> i32 {
+    if options.command == Command::Schema {
+        println!("{}", serde_json::to_string_pretty(&ast_schema::schema()).expect("schema sempre serializa"));
+        return 0;
+    }
+    if options.command == Command::Dump {
+        return dump(options.input.as_deref().unwrap_or("-"));
+    }
+    if options.command == Command::Test {
+        return test_programs(options);
+    }
+    if options.command == Command::Fuzz {
+        return fuzz_programs(options);
+    }
+    if options.command == Command::Dap {
+        let stdin = io::stdin();
+        return match dap::serve(&mut stdin.lock(), Box::new(io::stdout())) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Erro na conexão com o editor: {}", e);
+                EXIT_IO
+            }
+        };
+    }
+
+    // sem arquivo, compila o projeto do symplia.toml mais próximo
+    let manifest = match options.input {
+        Some(_) => None,
+        None => Some(find_manifest(options)),
+    };
+    let filename = match (&options.input, &manifest) {
+        (Some(input), _) if input == "-" => "<stdin>".to_string(),
+        (Some(input), _) => input.clone(),
+        (None, Some(manifest)) => manifest.entry().display().to_string(),
+        (None, None) => unreachable!(),
+    };
+    let source_code = match read_source(options.input.as_deref().unwrap_or(&filename)) {
         Ok(content) => content,
         Err(e) => {
-            eprintln!("Erro ao ler arquivo {}: {}", filename, e);
-            process::exit(1);
+            options.error("arquivo", &filename, format!("Erro ao ler arquivo {}: {}", filename, e));
+            return EXIT_IO;
         }
     };
-    
-    println!("=== COMPILADOR SYMPLIA ===\n");
-    println!("Arquivo: {}", filename);
-    println!("Tamanho do código: {} caracteres\n", source_code.len());
-
-    println!("=== ANÁLISE LÉXICA ===");
-    let mut lexer = Lexer::new(&source_code);
-    let _tokens = match lexer.tokenize() {
-        Ok(tokens) => {
-            println!("✅ Tokens reconhecidos: {}", tokens.len());
-            if cfg!(debug_assertions) {
-                // Mostrar tokens apenas em modo debug
-                for (i, token) in tokens.iter().enumerate() {
-                    println!("  {}: {}", i, token);
-                }
-            }
-            tokens
+    let file = filename.as_str();
+
+    if let Some(manifest) = &manifest {
+        options.info(format!("Projeto: {}", manifest.project.nome));
+    }
+    options.info(format!("Arquivo: {} ({} caracteres)", filename, source_code.len()));
+
+    // um arquivo .s é montado e executado diretamente no simulador
+    if filename.ends_with(".s") {
+        if !matches!(options.command, Command::Build | Command::Run) {
+            options.error("uso", file, "Arquivos .s só podem ser executados (use run)");
+            return EXIT_USAGE;
         }
+        return run_riscv(&source_code, file, options);
+    }
+
+    // o fmt precisa dos comentários para mantê-los
+    let trivia = options.trivia || options.command == Command::Fmt;
+    let mut lexer = if trivia { Lexer::new(&source_code).with_trivia() } else { Lexer::new(&source_code) };
+    let tokens = match lexer.tokenize() {
+        Ok(tokens) => tokens,
+        Err(e) => options.fail(EXIT_LEXICAL, &[e], |e| Diagnostic::lexer(e, file)),
+    };
+    options.info(format!("Análise léxica: {} tokens", tokens.len()));
+    if options.command == Command::Tokens || options.emits(Emit::Tokens) {
+        let rows = token_rows(&tokens, lexer.trivia());
+        print!("{}", format_tokens(&rows, options.token_format.unwrap_or(TokenFormat::Table)));
+    }
+    if options.last_phase() == Phase::Lexing {
+        return 0;
+    }
+
+    let program = match Parser::parse_from_source(&source_code) {
+        Ok(program) => program,
+        Err(errors) => options.fail(EXIT_SYNTAX, &errors, |e| Diagnostic::parser(e, file)),
+    };
+    options.info("Análise sintática: ok");
+
+    if options.command == Command::Fmt {
+        return format_source(file, &source_code, &format_file(&program, &tokens, lexer.trivia()), options);
+    }
+    if options.emits(Emit::Ast) {
+        println!("{}", program);
+    }
+    if options.last_phase() == Phase::Parsing {
+        print_graphs(&program, false, options);
+        return 0;
+    }
+
+    if let Some(manifest) = &manifest {
+        let errors = manifest.project.nivel.check(&program);
+        if !errors.is_empty() {
+            options.fail(EXIT_SEMANTIC, &errors, |e| Diagnostic::level(e, file));
+        }
+    }
+
+    let imports = program.imports.len();
+    let search_paths = manifest.as_ref().map(Manifest::source_dirs).unwrap_or_default();
+    let mut loader = ModuleLoader::new().with_search_paths(search_paths);
+    let program = match loader.link(program, Path::new(&filename)) {
+        Ok(program) => program,
+        Err(errors) => options.fail(EXIT_MODULES, &errors, Diagnostic::module),
+    };
+    options.info(format!("Módulos: {} importações resolvidas", imports));
+
+    let mut semantic_analyzer = SemanticAnalyzer::new();
+    let mut semantic_result = semantic_analyzer.analyze(program);
+    if !semantic_result.errors.is_empty() {
+        options.fail(EXIT_SEMANTIC, &Located::all(&semantic_result.errors, &loader), |e| {
+            Diagnostic::semantic(e.error, e.file.as_deref().unwrap_or(file))
+        });
+    }
+    options.info("Análise semântica: ok");
+
+    if let Some(manifest) = &manifest {
+        let warnings = lint(&semantic_result.annotated_ast, &manifest.project.lints);
+        options.warn(&warnings, |w| Diagnostic::lint(w, file));
+    }
+
+    if options.optimize {
+        semantic_result = ConstantFolder::new().optimize(semantic_result);
+        if !semantic_result.errors.is_empty() {
+            options.fail(EXIT_CODEGEN, &Located::all(&semantic_result.errors, &loader), |e| Diagnostic {
+                code: "otimizacao".to_string(),
+                ..Diagnostic::semantic(e.error, e.file.as_deref().unwrap_or(file))
+            });
+        }
+        options.info("Dobramento de constantes: ok");
+    }
+
+    if options.command == Command::Debug {
+        let program = &semantic_result.annotated_ast;
+        let result = with_large_stack(|| {
+            let stdin = io::stdin();
+            let mut input = stdin.lock();
+            let mut output = io::stdout();
+            debugger::debug(program, &source_code, &mut input, &mut output)
+        });
+        if let Err(e) = result {
+            options.fail(EXIT_RUNTIME, &[e], |e| Diagnostic::runtime(e, file));
+        }
+        return 0;
+    }
+
+    if options.emits(Emit::TypedAst) {
+        println!("{}", semantic_result.annotated_ast);
+    }
+    print_graphs(&semantic_result.annotated_ast, true, options);
+
+    if options.emits(Emit::Ir) {
+        let ir_program = match Lowering::new().lower(&semantic_result.annotated_ast) {
+            Ok(ir_program) => ir_program,
+            Err(e) => options.fail(EXIT_CODEGEN, &[e], |e| Diagnostic::lowering(e, file)),
+        };
+        // os erros do verificador são da IR, que não guarda as linhas do programa
+        if let Err(errors) = verify(&ir_program) {
+            options.fail(EXIT_CODEGEN, &errors, |e| Diagnostic::new("geracao", Severity::Error, e.to_string(), Span::new(file, 0, 0)));
+        }
+        println!("{}", ir_program);
+    }
+
+    let out_dir = options.out_dir.clone().unwrap_or_else(|| match &manifest {
+        Some(manifest) => manifest.output_dir(),
+        None => PathBuf::from("../build"),
+    });
+    let file_stem = match options.input.as_deref() {
+        Some("-") => "programa",
+        _ => Path::new(&filename).file_stem().and_then(|s| s.to_str()).unwrap_or("programa"),
+    };
+    if options.emit.iter().any(Emit::writes_file)
+        && let Err(e) = fs::create_dir_all(&out_dir)
+    {
+        options.error("arquivo", file, format!("Erro ao criar {}: {}", out_dir.display(), e));
+        return EXIT_IO;
+    }
+
+    for target in options.emit.iter().filter_map(|emit| match emit {
+        Emit::Code(target) => Some(*target),
+        _ => None,
+    }) {
+        let (generated, extension) = match target {
+            "wat" => (generate_wat(&semantic_result.annotated_ast), "wat"),
+            "x86_64" => (generate_x86_64(&semantic_result.annotated_ast), "s"),
+            "riscv" => (generate_riscv(&semantic_result.annotated_ast), "rv64.s"),
+            "js" => (generate_js(&semantic_result.annotated_ast), "js"),
+            "py" => (generate_python(&semantic_result.annotated_ast), "py"),
+            _ => unreachable!(),
+        };
+        let code = match generated {
+            Ok(code) => code,
+            Err(e) => options.fail(EXIT_CODEGEN, &[e], |e| Diagnostic::backend(e, file)),
+        };
+
+        let output_filename = out_dir.join(format!("{}.{}", file_stem, extension));
+        if let Err(e) = fs::write(&output_filename, code) {
+            options.error("arquivo", file, format!("Erro ao salvar {}: {}", output_filename.display(), e));
+            return EXIT_IO;
+        }
+        options.status(format!("Gerado: {}", output_filename.display()));
+    }
+
+    if options.emits(Emit::Json) {
+        let json_filename = out_dir.join(format!("{}.ast.json", file_stem)).display().to_string();
+        if let Err(e) = save_semantic_result_to_json(&semantic_result, &json_filename) {
+            options.error("arquivo", file, format!("Erro ao serializar AST em {}: {}", json_filename, e));
+            return EXIT_IO;
+        }
+        options.status(format!("Gerado: {}", json_filename));
+    }
+
+    if options.emits(Emit::AstBin) {
+        let binary_filename = out_dir.join(format!("{}.ast.bin", file_stem)).display().to_string();
+        if let Err(e) = save_semantic_result_to_binary(&semantic_result, &binary_filename) {
+            options.error("arquivo", file, format!("Erro ao serializar AST em {}: {}", binary_filename, e));
+            return EXIT_IO;
+        }
+        options.status(format!("Gerado: {}", binary_filename));
+    }
+
+    if options.command == Command::Run {
+        let asm = match generate_riscv(&semantic_result.annotated_ast) {
+            Ok(asm) => asm,
+            Err(e) => options.fail(EXIT_CODEGEN, &[e], |e| Diagnostic::backend(e, file)),
+        };
+        return run_riscv(&asm, file, options);
+    }
+    0
+}
+
+// ast-dot e ast-mermaid; com `typed` depois da análise semântica
+fn print_graphs(program: &compiler::Program, typed: bool, options: &Options) {
+    if options.emits(Emit::AstDot) {
+        print!("{}", to_dot(program, typed));
+    }
+    if options.emits(Emit::AstMermaid) {
+        print!("{}", to_mermaid(program, typed));
+    }
+}
+
+// AST binária para o JSON de `--emit json`, na saída padrão
+fn dump(input: &str) -> i32 {
+    let read = if input == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+    } else {
+        fs::read(input)
+    };
+    let bytes = match read {
+        Ok(bytes) => bytes,
         Err(e) => {
-            eprintln!("❌ ERRO LÉXICO: {}", e);
-            process::exit(1);
+            eprintln!("Erro ao ler arquivo {}: {}", input, e);
+            return EXIT_IO;
         }
     };
-    
-    println!("\n=== ANÁLISE SINTÁTICA ===");
-    let program = match Parser::parse_from_source(&source_code) {
+    match ast_binary::decode(&bytes) {
         Ok(program) => {
-            println!("✅ Análise sintática concluída com sucesso!");
-            program
+            println!("{}", serde_json::to_string_pretty(&program).expect("AST sempre serializa"));
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {}", input, e);
+            EXIT_IO
+        }
+    }
+}
+
+// gera e confere programas a partir da semente (ver `fuzz`); cada falha
+// mostra a semente que a repete com --seed N --runs 1
+fn fuzz_programs(options: &Options) -> i32 {
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+    });
+    options.info(format!("Semente inicial: {}", seed));
+
+    let mut report = fuzz::Report::default();
+    for offset in 0..options.runs.unwrap_or(FUZZ_RUNS) {
+        let known = report.failures.len();
+        fuzz::fuzz_seed(seed.wrapping_add(offset), &mut report);
+        for failure in &report.failures[known..] {
+            println!("FALHOU semente {}", failure.seed);
+            for line in failure.problem.lines() {
+                println!("    {}", line);
+            }
+            println!("    entrada: {}", failure.input.trim_end());
+            println!("    programa:");
+            for line in failure.source.lines() {
+                println!("        {}", line);
+            }
+        }
+    }
+
+    let failed = report.failures.len();
+    options.status(format!(
+        "{} programas a partir da semente {}: {} sem terminar, {} falhas",
+        report.runs, seed, report.unfinished, failed
+    ));
+    if failed > 0 { EXIT_FAILURE } else { 0 }
+}
+
+// `test`: confere (ou, com --bless, reescreve) as anotações de cada programa
+fn test_programs(options: &Options) -> i32 {
+    let programs = match golden::find_programs(&options.paths) {
+        Ok(programs) => programs,
+        Err(e) => {
+            eprintln!("Erro ao listar programas: {}", e);
+            return EXIT_IO;
+        }
+    };
+
+    let mut failed = 0;
+    for path in &programs {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Erro ao ler arquivo {}: {}", path.display(), e);
+                return EXIT_IO;
+            }
+        };
+
+        if options.bless {
+            let blessed = golden::bless(&source, path);
+            if blessed != source {
+                if let Err(e) = fs::write(path, blessed) {
+                    eprintln!("Erro ao salvar {}: {}", path.display(), e);
+                    return EXIT_IO;
+                }
+                println!("atualizado {}", path.display());
+            }
+            continue;
         }
-        Err(errors) => {
-            eprintln!("❌ Foram encontrados {} erros sintáticos:", errors.len());
-            for (i, error) in errors.iter().enumerate() {
-                println!("  {}. {}", i + 1, error);
+
+        let problems = golden::check(&source, path);
+        if problems.is_empty() {
+            options.info(format!("ok {}", path.display()));
+        } else {
+            failed += 1;
+            println!("FALHOU {}", path.display());
+            for problem in problems {
+                println!("    {}", problem);
             }
-            process::exit(1);
         }
+    }
+
+    if !options.bless {
+        options.status(format!("{} programas: {} ok, {} com falhas", programs.len(), programs.len() - failed, failed));
+    }
+    if failed > 0 { EXIT_FAILURE } else { 0 }
+}
+
+fn read_source(input: &str) -> io::Result<String> {
+    if input == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        Ok(source)
+    } else {
+        fs::read_to_string(input)
+    }
+}
+
+fn find_manifest(options: &Options) -> Manifest {
+    let current_dir = env::current_dir().unwrap_or_else(|e| {
+        options.error("arquivo", ".", format!("Não foi possível obter o diretório atual: {}", e));
+        options.finish();
+        process::exit(EXIT_IO);
+    });
+    let Some(path) = Manifest::find(&current_dir) else {
+        let message = format!("Nenhum arquivo informado e nenhum symplia.toml em {} nem nos diretórios acima", current_dir.display());
+        if options.message_format == MessageFormat::Human {
+            eprintln!("{}\n\n{}", message, USAGE);
+        } else {
+            options.error("uso", &current_dir.display().to_string(), message);
+            options.finish();
+        }
+        process::exit(EXIT_USAGE);
     };
+    Manifest::load(&path).unwrap_or_else(|e| {
+        options.error("arquivo", &path.display().to_string(), e);
+        options.finish();
+        process::exit(EXIT_IO);
+    })
+}
 
-    println!("\n=== ANÁLISE SEMÂNTICA ===");
-    let mut semantic_analyzer = SemanticAnalyzer::new();
-    let semantic_result = semantic_analyzer.analyze(program);
-    
-    if !semantic_result.errors.is_empty() {
-        eprintln!("❌ Foram encontrados {} erros semânticos:", semantic_result.errors.len());
-        for (i, error) in semantic_result.errors.iter().enumerate() {
-            println!("  {}. Linha {}: {}", i + 1, error.line, error.message);
-        }
-        process::exit(1);
-    }
-    
-    println!("✅ Análise semântica concluída com sucesso!");
-
-    println!("\n=== SERIALIZAÇÃO DA AST ===");
-    
-    let file_stem = Path::new(filename)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("programa");
-    
-    let json_filename = format!("../build/{}.ast.json", file_stem);
-    
-    match save_semantic_result_to_json(&semantic_result, &json_filename) {
-        Ok(()) => {
-            println!("✅ AST serializada salva em: {}", json_filename);
-            println!("\n🎉 Compilação concluída com sucesso!");
-            println!("📁 Arquivo JSON gerado: {}", json_filename);
+// `fmt`: mostra o programa formatado, confere (--check) ou reescreve (--write)
+fn format_source(filename: &str, source: &str, formatted: &str, options: &Options) -> i32 {
+    if options.check {
+        if formatted != source {
+            options.error("formato", filename, format!("{} não está formatado", filename));
+            return EXIT_FAILURE;
         }
-        Err(e) => {
-            eprintln!("❌ Erro ao serializar AST: {}", e);
-            process::exit(1);
+    } else if options.write {
+        if formatted != source {
+            if let Err(e) = fs::write(filename, formatted) {
+                options.error("arquivo", filename, format!("Erro ao salvar {}: {}", filename, e));
+                return EXIT_IO;
+            }
+            options.status(format!("Formatado: {}", filename));
         }
+    } else {
+        print!("{}", formatted);
     }
+    0
 }
 
+// monta e executa um programa RV64 lendo de stdin e escrevendo em stdout
+fn run_riscv(asm: &str, file: &str, options: &Options) -> i32 {
+    let program = match assemble(asm) {
+        Ok(program) => program,
+        Err(e) => {
+            options.error("montagem", file, e);
+            return EXIT_RUNTIME;
+        }
+    };
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout();
+    let mut simulator = Simulator::new(&program, &mut input, &mut output);
+    let result = simulator.run(SIMULATION_STEPS);
+    let steps = simulator.steps;
+    let _ = output.flush();
+
+    match result {
+        Ok(code) => {
+            options.info(format!("Programa terminou com código {} ({} instruções)", code, steps));
+            code as i32
+        }
+        Err(e) => {
+            options.error("execucao", file, e);
+            EXIT_RUNTIME
+        }
+    }
+}
 
 //testes unitários para o main
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn test_args_validation() {
         // Teste simulado de validação de argumentos
-        let args = vec!["compiler".to_string(), "test.sym".to_string()];
+        let args = ["compiler".to_string(), "test.sym".to_string()];
         assert_eq!(args.len(), 2);
         assert!(args[1].ends_with(".sym"));
     }
@@ -113,23 +931,61 @@ mod tests {
     #[test]
     fn test_json_filename_generation() {
         use std::path::Path;
-        
+
         let filename = "programas/exemplo.sym";
         let file_stem = Path::new(filename)
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("programa");
-        
+
         let json_filename = format!("../build/{}.ast.json", file_stem);
         assert_eq!(json_filename, "../build/exemplo.ast.json");
-        
+
         let filename_without_path = "teste.sym";
         let file_stem2 = Path::new(filename_without_path)
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("programa");
-        
+
         let json_filename2 = format!("../build/{}.ast.json", file_stem2);
         assert_eq!(json_filename2, "../build/teste.ast.json");
     }
+
+    #[test]
+    fn test_parse_options() {
+        let parse = |line: &str| Options::parse(&line.split_whitespace().map(String::from).collect::<Vec<_>>());
+
+        // sem comando é build, que grava o JSON se nada mais for pedido
+        let options = parse("-O a.sym").unwrap();
+        assert_eq!((options.command, options.input.as_deref()), (Command::Build, Some("a.sym")));
+        assert_eq!(options.emit, [Emit::Json]);
+
+        let options = parse("build --emit=ast,js -o saida --emit ir -q").unwrap();
+        assert_eq!(options.emit, [Emit::Ast, Emit::Code("js"), Emit::Ir]);
+        assert_eq!((options.input.as_deref(), options.out_dir.as_deref()), (None, Some(Path::new("saida"))));
+        assert_eq!((options.verbosity, options.last_phase()), (Verbosity::Quiet, Phase::Analysis));
+        assert_eq!(parse("--emit tokens -").unwrap().last_phase(), Phase::Lexing);
+        assert_eq!(parse("a.sym --simular").unwrap().command, Command::Run);
+
+        let options = parse("test a.sym testes --bless").unwrap();
+        assert_eq!(options.paths, [PathBuf::from("a.sym"), PathBuf::from("testes")]);
+        assert!(options.bless);
+
+        let options = parse("fuzz --seed 42 --runs 10").unwrap();
+        assert_eq!((options.command, options.seed, options.runs), (Command::Fuzz, Some(42), Some(10)));
+        assert_eq!(parse("debug a.sym").unwrap().last_phase(), Phase::Analysis);
+
+        for invalid in ["--emit", "--emit c a.sym", "a.sym b.sym", "--x a.sym", "check --emit js", "run --check", "fmt --write -", "schema a.sym", "dump", "test", "a.sym --bless", "a.sym --trivia", "fuzz a.sym", "fuzz --runs x", "debug -", "debug a.sym --emit ir", "dap a.sym", "a.sym --seed 1"] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_run_exit_codes() {
+        let options = Options::parse(&["run".to_string(), "a.s".to_string(), "-q".to_string()]).unwrap();
+        // o código do programa passa adiante mesmo quando coincide com o de uma fase
+        assert_eq!(run_riscv("_start:\n    li a0, 13\n    li a7, 93\n    ecall\n", "a.s", &options), EXIT_SEMANTIC);
+        assert_eq!(run_riscv("_start:\n    li a0, 0\n    li a7, 93\n    ecall\n", "a.s", &options), 0);
+        assert_eq!(run_riscv("_start:\n    j nenhum\n", "a.s", &options), EXIT_RUNTIME);
+    }
 }
@@ -0,0 +1,178 @@
+use crate::parser::ast::Program;
+use crate::semantic::lints::Lint;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Manifesto de um projeto Symplia (`symplia.toml`). `compiler build` procura
+// o arquivo a partir do diretório atual, subindo até a raiz; os caminhos do
+// manifesto são relativos ao diretório dele.
+//
+//     [projeto]
+//     nome = "calculadora"
+//     entrada = "src/principal.sym"
+//     fontes = ["src", "lib"]      # onde `importe` também procura módulos
+//     saida = "build"
+//     nivel = "intermediario"      # basico, intermediario ou completo
+//     lints = ["variavel_nao_usada", "funcao_nao_usada"]
+
+pub const MANIFEST_FILE: &str = "symplia.toml";
+
+#[derive(Debug, Clone)]
+pub struct ManifestError {
+    pub file: String,
+    pub message: String,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ERRO NO MANIFESTO: {} - {}", self.file, self.message)
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+// recurso que o nível da linguagem do projeto não permite
+#[derive(Debug, Clone)]
+pub struct LevelError {
+    pub message: String,
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ERRO DE NÍVEL: {}", self.message)
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    projeto: Project,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Project {
+    pub nome: String,
+    pub entrada: PathBuf,
+    #[serde(default)]
+    pub fontes: Vec<PathBuf>,
+    #[serde(default = "default_output")]
+    pub saida: PathBuf,
+    #[serde(default)]
+    pub nivel: LanguageLevel,
+    #[serde(default)]
+    pub lints: Vec<Lint>,
+}
+
+fn default_output() -> PathBuf {
+    PathBuf::from("build")
+}
+
+// Níveis para turmas que ainda não viram toda a linguagem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum LanguageLevel {
+    // só a função `principal`, sem módulos
+    #[serde(rename = "basico")]
+    Basic,
+    // funções próprias, sem módulos
+    #[serde(rename = "intermediario")]
+    Intermediate,
+    #[default]
+    #[serde(rename = "completo")]
+    Complete,
+}
+
+impl LanguageLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LanguageLevel::Basic => "básico",
+            LanguageLevel::Intermediate => "intermediário",
+            LanguageLevel::Complete => "completo",
+        }
+    }
+
+    // recursos do programa (ainda sem os módulos) que o nível não permite
+    pub fn check(&self, program: &Program) -> Vec<LevelError> {
+        let mut messages = Vec::new();
+        if *self != LanguageLevel::Complete && !program.imports.is_empty() {
+            messages.push(format!("'importe' não está disponível no nível {}", self.name()));
+        }
+        if *self == LanguageLevel::Basic {
+            for function in program.functions.iter().filter(|f| f.name != "principal") {
+                messages.push(format!(
+                    "Função '{}': no nível {} o programa tem só a função 'principal'",
+                    function.name,
+                    self.name()
+                ));
+            }
+        }
+        messages.into_iter().map(|message| LevelError { message }).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    // diretório do symplia.toml
+    pub root: PathBuf,
+    pub project: Project,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self, ManifestError> {
+        let error = |message: String| ManifestError { file: path.display().to_string(), message };
+        let source = fs::read_to_string(path).map_err(|e| error(format!("Não foi possível ler: {}", e)))?;
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        Self::parse(&source, root).map_err(error)
+    }
+
+    pub fn parse(source: &str, root: PathBuf) -> Result<Self, String> {
+        let file: ManifestFile = toml::from_str(source).map_err(|e| e.message().to_string())?;
+        Ok(Self { root, project: file.projeto })
+    }
+
+    // primeiro symplia.toml em `start` ou num diretório acima
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start.ancestors().map(|dir| dir.join(MANIFEST_FILE)).find(|path| path.is_file())
+    }
+
+    pub fn entry(&self) -> PathBuf {
+        self.root.join(&self.project.entrada)
+    }
+
+    pub fn output_dir(&self) -> PathBuf {
+        self.root.join(&self.project.saida)
+    }
+
+    pub fn source_dirs(&self) -> Vec<PathBuf> {
+        self.project.fontes.iter().map(|dir| self.root.join(dir)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = Manifest::parse(
+            "[projeto]\nnome = \"aula\"\nentrada = \"src/principal.sym\"\nfontes = [\"lib\"]\nnivel = \"basico\"\nlints = [\"funcao_nao_usada\"]\n",
+            PathBuf::from("turma"),
+        )
+        .unwrap();
+        assert_eq!(manifest.entry(), Path::new("turma/src/principal.sym"));
+        assert_eq!(manifest.output_dir(), Path::new("turma/build"));
+        assert_eq!(manifest.source_dirs(), [Path::new("turma/lib")]);
+        assert_eq!(manifest.project.lints, [Lint::UnusedFunction]);
+
+        let program = Parser::parse_from_source("funcao f() { } funcao principal() { f(); }").unwrap();
+        assert_eq!(manifest.project.nivel.check(&program).len(), 1);
+
+        let error = Manifest::parse("[projeto]\nnome = \"x\"\nentrada = \"a.sym\"\nlints = [\"tudo\"]\n", PathBuf::new());
+        assert!(error.unwrap_err().contains("tudo"));
+    }
+}
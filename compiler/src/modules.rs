@@ -0,0 +1,542 @@
+use crate::lexer::afds::classify_keyword;
+use crate::parser::ast::*;
+use crate::parser::Parser;
+use crate::semantic::Builtin;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// Programas em vários arquivos. `importe "caminho.sym";` carrega outro
+// arquivo, com o caminho relativo ao arquivo que importa, e o nome do
+// arquivo sem a extensão é o nome do módulo. Módulos importados só declaram
+// funções, que no programa final se chamam `modulo.funcao`; assim dois
+// módulos podem ter funções com o mesmo nome. Dentro de um módulo, uma
+// chamada sem qualificação vale para as funções dele e, se não houver, para
+// as dos módulos que ele importa diretamente (desde que só um a tenha);
+// `modulo.funcao(...)` escolhe o módulo explicitamente. Um caminho que não
+// existe ao lado do arquivo é procurado nos diretórios de fontes do projeto.
+
+#[derive(Debug, Clone)]
+pub struct ModuleError {
+    pub file: String,
+    pub message: String,
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ERRO DE MÓDULO: {} - {}", self.file, self.message)
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+type Reader = Box<dyn Fn(&Path) -> io::Result<String>>;
+
+struct Module {
+    // None para o programa principal, cujas funções mantêm o nome
+    name: Option<String>,
+    path: PathBuf,
+    program: Program,
+    // índices em `modules` dos módulos importados diretamente
+    imports: Vec<usize>,
+    functions: HashSet<String>,
+}
+
+pub struct ModuleLoader {
+    read: Reader,
+    search_paths: Vec<PathBuf>,
+    modules: Vec<Module>,
+    indices: HashMap<PathBuf, usize>,
+    names: HashMap<String, PathBuf>,
+    // caminho de importações sendo carregadas, para achar ciclos
+    loading: Vec<PathBuf>,
+    errors: Vec<ModuleError>,
+}
+
+impl Default for ModuleLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModuleLoader {
+    pub fn new() -> Self {
+        Self::with_reader(|path| fs::read_to_string(path))
+    }
+
+    // os testes leem os módulos da memória
+    pub fn with_reader(read: impl Fn(&Path) -> io::Result<String> + 'static) -> Self {
+        Self {
+            read: Box::new(read),
+            search_paths: Vec::new(),
+            modules: Vec::new(),
+            indices: HashMap::new(),
+            names: HashMap::new(),
+            loading: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_search_paths(mut self, search_paths: Vec<PathBuf>) -> Self {
+        self.search_paths = search_paths;
+        self
+    }
+
+    // Carrega as dependências do programa principal (já analisado, lido de
+    // `path`) e devolve um programa único, sem importações
+    pub fn link(&mut self, program: Program, path: &Path) -> Result<Program, Vec<ModuleError>> {
+        let path = normalize(path);
+        if let Some(name) = module_name(&path) {
+            self.names.insert(name, path.clone());
+        }
+
+        self.loading.push(path.clone());
+        let imports = self.load_imports(&program.imports, &path);
+        self.loading.pop();
+
+        let functions = program.functions.iter().map(|f| f.name.clone()).collect();
+        let root = Module { name: None, path, program, imports, functions };
+
+        let mut merged = Program { imports: Vec::new(), functions: Vec::new(), statements: Vec::new() };
+        let mut errors = Vec::new();
+        for module in self.modules.iter().chain([&root]) {
+            let (functions, module_errors) = resolve_functions(&self.modules, module);
+            merged.functions.extend(functions);
+            errors.push((module.path.clone(), module_errors));
+        }
+
+        let mut resolver = NameResolver::new(&self.modules, &root, None);
+        merged.statements = root.program.statements.clone();
+        resolver.statements(&mut merged.statements);
+        errors.push((root.path.clone(), resolver.errors));
+
+        for (path, messages) in errors {
+            self.report_all(&path, messages);
+        }
+
+        if self.errors.is_empty() {
+            Ok(merged)
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    // arquivo onde foi declarada uma função do programa ligado; None para as
+    // do programa principal, que mantêm o nome
+    pub fn origin(&self, function: &str) -> Option<&Path> {
+        let (module, _) = function.split_once('.')?;
+        self.names.get(module).map(PathBuf::as_path)
+    }
+
+    fn load_imports(&mut self, imports: &[Import], from: &Path) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for import in imports {
+            if let Some(index) = self.load(&import.path, from)
+                && !indices.contains(&index)
+            {
+                indices.push(index);
+            }
+        }
+        indices
+    }
+
+    fn load(&mut self, relative: &str, from: &Path) -> Option<usize> {
+        let directory = from.parent().unwrap_or(Path::new(""));
+        let candidates: Vec<PathBuf> = [directory]
+            .into_iter()
+            .chain(self.search_paths.iter().map(PathBuf::as_path))
+            .map(|dir| normalize(&dir.join(relative)))
+            .collect();
+
+        // o primeiro candidato já carregado ou que pode ser lido
+        let mut source = None;
+        let mut read_error = None;
+        for candidate in &candidates {
+            if self.loading.contains(candidate) || self.indices.contains_key(candidate) {
+                break;
+            }
+            match (self.read)(candidate) {
+                Ok(text) => {
+                    source = Some((candidate.clone(), text));
+                    break;
+                }
+                Err(e) => {
+                    read_error.get_or_insert(e);
+                }
+            }
+        }
+        let path = match &source {
+            Some((path, _)) => path.clone(),
+            None => candidates
+                .iter()
+                .find(|c| self.loading.contains(c) || self.indices.contains_key(*c))
+                .unwrap_or(&candidates[0])
+                .clone(),
+        };
+
+        if let Some(start) = self.loading.iter().position(|p| *p == path) {
+            let cycle: Vec<String> = self.loading[start..]
+                .iter()
+                .chain([&path])
+                .map(|p| p.display().to_string())
+                .collect();
+            self.report(from, format!("Importação circular: {}", cycle.join(" -> ")));
+            return None;
+        }
+        if let Some(&index) = self.indices.get(&path) {
+            return Some(index);
+        }
+
+        let Some(name) = module_name(&path) else {
+            self.report(from, format!(
+                "Nome de módulo inválido em '{}': o nome do arquivo precisa ser um identificador",
+                relative
+            ));
+            return None;
+        };
+        if let Some(other) = self.names.get(&name) {
+            self.report(from, format!(
+                "Os módulos '{}' e '{}' têm o mesmo nome '{}'",
+                other.display(), path.display(), name
+            ));
+            return None;
+        }
+
+        let Some((_, source)) = source else {
+            let reason = read_error.map(|e| e.to_string()).unwrap_or_default();
+            self.report(from, format!("Não foi possível ler o módulo '{}': {}", relative, reason));
+            return None;
+        };
+        let program = match Parser::parse_from_source(&source) {
+            Ok(program) => program,
+            Err(errors) => {
+                let messages = errors.iter().map(|e| e.to_string()).collect();
+                self.report_all(&path, messages);
+                return None;
+            }
+        };
+        if !program.statements.is_empty() {
+            self.report(&path, "Módulos importados só podem declarar funções e importar outros módulos".to_string());
+        }
+        for function in &program.functions {
+            if Builtin::from_name(&function.name).is_some() {
+                self.report(&path, format!("Função '{}' já existe na biblioteca padrão", function.name));
+            }
+        }
+
+        self.names.insert(name.clone(), path.clone());
+        self.loading.push(path.clone());
+        let imports = self.load_imports(&program.imports, &path);
+        self.loading.pop();
+
+        // as dependências entram antes, para que o programa final tenha
+        // cada função depois das que ela pode chamar
+        let index = self.modules.len();
+        let functions = program.functions.iter().map(|f| f.name.clone()).collect();
+        self.modules.push(Module { name: Some(name), path: path.clone(), program, imports, functions });
+        self.indices.insert(path, index);
+        Some(index)
+    }
+
+    fn report(&mut self, file: &Path, message: String) {
+        self.errors.push(ModuleError { file: file.display().to_string(), message });
+    }
+
+    fn report_all(&mut self, file: &Path, messages: Vec<String>) {
+        for message in messages {
+            self.report(file, message);
+        }
+    }
+}
+
+fn resolve_functions(modules: &[Module], module: &Module) -> (Vec<FunctionDecl>, Vec<String>) {
+    let mut functions = module.program.functions.clone();
+    let mut errors = Vec::new();
+    for function in &mut functions {
+        let mut resolver = NameResolver::new(modules, module, Some(&function.parameters));
+        resolver.block(&mut function.body);
+        errors.extend(resolver.errors.into_iter().map(|e| format!("Na função '{}': {}", function.name, e)));
+        function.name = qualified_name(module, &function.name);
+    }
+    (functions, errors)
+}
+
+// Troca os nomes das chamadas pelos do programa final. Nas funções de
+// módulos importados também confere se toda variável é declarada ali
+// mesmo: as globais do programa principal não são visíveis nos módulos.
+struct NameResolver<'a> {
+    modules: &'a [Module],
+    module: &'a Module,
+    scopes: Option<Vec<HashSet<String>>>,
+    errors: Vec<String>,
+}
+
+impl<'a> NameResolver<'a> {
+    fn new(modules: &'a [Module], module: &'a Module, parameters: Option<&[Parameter]>) -> Self {
+        let scopes = match (&module.name, parameters) {
+            (Some(_), Some(parameters)) => Some(vec![parameters.iter().map(|p| p.name.clone()).collect()]),
+            _ => None,
+        };
+        Self { modules, module, scopes, errors: Vec::new() }
+    }
+
+    fn statements(&mut self, statements: &mut [Statement]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn block(&mut self, block: &mut Block) {
+        if let Some(scopes) = &mut self.scopes {
+            scopes.push(HashSet::new());
+        }
+        self.statements(&mut block.statements);
+        if let Some(scopes) = &mut self.scopes {
+            scopes.pop();
+        }
+    }
+
+    fn statement(&mut self, statement: &mut Statement) {
+        match statement {
+            Statement::VariableDecl(decl) => {
+                if let Some(initializer) = &mut decl.initializer {
+                    self.expr(initializer);
+                }
+                if let Some(scope) = self.scopes.as_mut().and_then(|scopes| scopes.last_mut()) {
+                    scope.insert(decl.name.clone());
+                }
+            }
+            Statement::ExprStmt(stmt) => self.expr(&mut stmt.expr),
+            Statement::IfStmt(stmt) => {
+                self.expr(&mut stmt.condition);
+                self.block(&mut stmt.then_branch);
+                if let Some(else_branch) = &mut stmt.else_branch {
+                    self.block(else_branch);
+                }
+            }
+            Statement::WhileStmt(stmt) => {
+                self.expr(&mut stmt.condition);
+                self.block(&mut stmt.body);
+            }
+            // como na análise semântica, o `para` declara a variável que não
+            // existe, num escopo que só vale no laço
+            Statement::ForStmt(stmt) => {
+                if let Some(scopes) = &mut self.scopes {
+                    scopes.push(HashSet::from([stmt.variable.clone()]));
+                }
+                self.expr(&mut stmt.start);
+                self.expr(&mut stmt.end);
+                self.block(&mut stmt.body);
+                if let Some(scopes) = &mut self.scopes {
+                    scopes.pop();
+                }
+            }
+            Statement::ReturnStmt(stmt) => {
+                if let Some(value) = &mut stmt.value {
+                    self.expr(value);
+                }
+            }
+            Statement::WriteStmt(stmt) => {
+                for argument in &mut stmt.arguments {
+                    self.expr(argument);
+                }
+            }
+            Statement::ReadStmt(stmt) => self.expr(&mut stmt.target),
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Variable(name) => self.variable(name),
+            Expr::Call(call) => {
+                match self.resolve_call(&call.function) {
+                    Ok(name) => call.function = name,
+                    Err(e) => self.errors.push(e),
+                }
+                for argument in &mut call.arguments {
+                    self.expr(argument);
+                }
+            }
+            Expr::BinaryOp(_, left, right) => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::UnaryOp(_, operand) => self.expr(operand),
+        }
+    }
+
+    fn variable(&mut self, name: &str) {
+        if let Some(scopes) = &self.scopes
+            && !scopes.iter().any(|scope| scope.contains(name))
+        {
+            self.errors.push(format!("Variável '{}' não declarada no módulo", name));
+        }
+    }
+
+    fn resolve_call(&self, function: &str) -> Result<String, String> {
+        if let Some((qualifier, name)) = function.split_once('.') {
+            let module = if self.module.name.as_deref() == Some(qualifier) {
+                self.module
+            } else {
+                self.imported()
+                    .find(|m| m.name.as_deref() == Some(qualifier))
+                    .ok_or_else(|| format!("Módulo '{}' não foi importado", qualifier))?
+            };
+            if !module.functions.contains(name) {
+                return Err(format!("O módulo '{}' não tem a função '{}'", qualifier, name));
+            }
+            return Ok(qualified_name(module, name));
+        }
+
+        if self.module.functions.contains(function) || Builtin::from_name(function).is_some() {
+            return Ok(qualified_name(self.module, function));
+        }
+        let candidates: Vec<&Module> = self.imported().filter(|m| m.functions.contains(function)).collect();
+        match candidates.as_slice() {
+            // não existe em lugar nenhum: a análise semântica avisa
+            [] => Ok(function.to_string()),
+            [module] => Ok(qualified_name(module, function)),
+            _ => {
+                let names: Vec<String> = candidates
+                    .iter()
+                    .filter_map(|m| m.name.as_ref().map(|name| format!("{}.{}", name, function)))
+                    .collect();
+                Err(format!("Chamada ambígua a '{}': use {}", function, names.join(" ou ")))
+            }
+        }
+    }
+
+    fn imported(&self) -> impl Iterator<Item = &'a Module> + '_ {
+        self.module.imports.iter().map(|&index| &self.modules[index])
+    }
+}
+
+// funções da biblioteca padrão nunca são qualificadas (nenhum módulo pode
+// declarar uma com o mesmo nome)
+fn qualified_name(module: &Module, function: &str) -> String {
+    match &module.name {
+        Some(name) if Builtin::from_name(function).is_none() => format!("{}.{}", name, function),
+        _ => function.to_string(),
+    }
+}
+
+fn module_name(path: &Path) -> Option<String> {
+    let name = path.file_stem()?.to_str()?;
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    let valid = (first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && classify_keyword(name).is_none();
+    valid.then(|| name.to_string())
+}
+
+// junta `.` e `..` sem consultar o sistema de arquivos
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(out.components().next_back(), Some(Component::Normal(_))) => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(files: &[(&str, &str)]) -> Result<Program, Vec<ModuleError>> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+            .collect();
+        let root = Parser::parse_from_source(&files[Path::new("principal.sym")]).unwrap();
+        ModuleLoader::with_reader(move |path| {
+            files.get(path).cloned().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        })
+        .link(root, Path::new("principal.sym"))
+    }
+
+    fn calls(function: &FunctionDecl) -> Vec<String> {
+        function.body.statements.iter().filter_map(|statement| match statement {
+            Statement::ExprStmt(ExprStmt { expr: Expr::Call(call), .. }) => Some(call.function.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn test_link_modules() {
+        let program = link(&[
+            ("principal.sym", "importe \"lib/texto_util.sym\"; importe \"lib/numeros.sym\";
+                funcao aviso() { }
+                funcao principal() { aviso(); moldura(\"x\"); numeros.aviso(); tamanho(\"a\"); }"),
+            ("lib/texto_util.sym", "importe \"numeros.sym\";
+                funcao texto moldura(texto s) { aviso(); retorne \"[\" + s + \"]\"; }
+                funcao aviso() { dobro(2); }"),
+            ("lib/numeros.sym", "funcao inteiro dobro(inteiro n) { retorne n * 2; } funcao aviso() { }"),
+        ])
+        .unwrap();
+
+        let names: Vec<&str> = program.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["numeros.dobro", "numeros.aviso", "texto_util.moldura", "texto_util.aviso", "aviso", "principal"]);
+        assert_eq!(calls(&program.functions[3]), ["numeros.dobro"]);
+        assert_eq!(calls(&program.functions[5]), ["aviso", "texto_util.moldura", "numeros.aviso", "tamanho"]);
+
+        // a variável do `para` vale no laço, e só nele
+        let errors = link(&[
+            ("principal.sym", "importe \"laco.sym\"; funcao principal() { }"),
+            ("laco.sym", "funcao f(inteiro n) { para i de 1 ate n faca { escreva(i); } fimpara escreva(i); }"),
+        ])
+        .unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["Na função 'f': Variável 'i' não declarada no módulo"]);
+    }
+
+    #[test]
+    fn test_module_errors() {
+        let errors = link(&[
+            ("principal.sym", "importe \"a.sym\"; funcao principal() { b.f(); }"),
+            ("a.sym", "importe \"sub/../b.sym\"; funcao f() { }"),
+            ("b.sym", "importe \"a.sym\"; funcao g(inteiro n) { escreva(n, global); }"),
+        ])
+        .unwrap_err();
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, [
+            "Importação circular: a.sym -> b.sym -> a.sym",
+            "Na função 'g': Variável 'global' não declarada no módulo",
+            "Na função 'principal': Módulo 'b' não foi importado",
+        ]);
+
+        let errors = link(&[
+            ("principal.sym", "importe \"a.sym\"; importe \"b.sym\"; importe \"c.sym\"; funcao principal() { f(); }"),
+            ("a.sym", "funcao f() { }"),
+            ("b.sym", "funcao f() { }"),
+        ])
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.starts_with("Não foi possível ler o módulo 'c.sym'"));
+        assert_eq!(errors[1].message, "Na função 'principal': Chamada ambígua a 'f': use a.f ou b.f");
+    }
+
+    #[test]
+    fn test_origin_of_semantic_errors() {
+        let mut loader = ModuleLoader::with_reader(|path| match path.to_str() {
+            Some("lib/util.sym") => Ok("funcao inteiro f() {\n    retorne \"x\";\n}\n".to_string()),
+            _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+        });
+        let root = Parser::parse_from_source("importe \"lib/util.sym\";\nfuncao principal() {\n    escreva(f() + nada);\n}\n").unwrap();
+        let program = loader.link(root, Path::new("principal.sym")).unwrap();
+
+        let errors = crate::semantic::SemanticAnalyzer::new().analyze(program).errors;
+        let origins: Vec<(usize, Option<&Path>)> =
+            errors.iter().map(|e| (e.line, e.function.as_deref().and_then(|f| loader.origin(f)))).collect();
+        assert_eq!(origins, [(2, Some(Path::new("lib/util.sym"))), (3, None)]);
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::HashSet;
+use crate::parser::ast::*;
+use crate::semantic::builtins::Builtin;
+use crate::semantic::semantic::{SemanticAnalysisResult, SemanticError};
+
+// Passo de otimização sobre a AST anotada: dobra subexpressões literais,
+// aplica simplificações algébricas e remove ramos com condição constante.
+// Deve rodar depois da análise semântica, pois assume um programa bem tipado.
+pub struct ConstantFolder {
+    errors: Vec<SemanticError>,
+    // nomes declarados como texto em algum ponto do programa e funções que
+    // devolvem texto: `x + 0` só vira `x` quando x com certeza não é texto
+    text_variables: HashSet<String>,
+    text_functions: HashSet<String>,
+    // linha do comando e função sendo dobrados, para os erros
+    current_line: usize,
+    current_function: Option<String>,
+}
+
+impl Default for ConstantFolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstantFolder {
+    pub fn new() -> Self {
+        Self {
+            errors: Vec::new(),
+            text_variables: HashSet::new(),
+            text_functions: HashSet::new(),
+            current_line: 0,
+            current_function: None,
+        }
+    }
+
+    pub fn optimize(&mut self, result: SemanticAnalysisResult) -> SemanticAnalysisResult {
+        let SemanticAnalysisResult { annotated_ast, symbol_table, mut errors } = result;
+
+        let optimized_ast = self.fold_program(annotated_ast);
+        errors.append(&mut self.errors);
+
+        SemanticAnalysisResult {
+            annotated_ast: optimized_ast,
+            symbol_table,
+            errors,
+        }
+    }
+
+    pub fn fold_program(&mut self, program: Program) -> Program {
+        for function in &program.functions {
+            if function.return_type == Some(Type::Texto) {
+                self.text_functions.insert(function.name.clone());
+            }
+            for parameter in &function.parameters {
+                if parameter.param_type == Type::Texto {
+                    self.text_variables.insert(parameter.name.clone());
+                }
+            }
+            self.collect_text_variables(&function.body.statements);
+        }
+        self.collect_text_variables(&program.statements);
+
+        let functions = program.functions
+            .into_iter()
+            .map(|mut function| {
+                self.current_function = Some(function.name.clone());
+                function.body = self.fold_block(function.body);
+                function
+            })
+            .collect();
+        self.current_function = None;
+
+        let statements = self.fold_statements(program.statements);
+
+        Program { imports: program.imports, functions, statements }
+    }
+
+    fn collect_text_variables(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            match statement {
+                Statement::VariableDecl(decl) if decl.var_type == Type::Texto => {
+                    self.text_variables.insert(decl.name.clone());
+                }
+                Statement::IfStmt(if_stmt) => {
+                    self.collect_text_variables(&if_stmt.then_branch.statements);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        self.collect_text_variables(&else_branch.statements);
+                    }
+                }
+                Statement::WhileStmt(while_stmt) => self.collect_text_variables(&while_stmt.body.statements),
+                Statement::ForStmt(for_stmt) => self.collect_text_variables(&for_stmt.body.statements),
+                _ => {}
+            }
+        }
+    }
+
+    // === STATEMENTS ===
+
+    fn fold_block(&mut self, block: Block) -> Block {
+        Block {
+            statements: self.fold_statements(block.statements),
+        }
+    }
+
+    fn fold_statements(&mut self, statements: Vec<Statement>) -> Vec<Statement> {
+        statements
+            .into_iter()
+            .flat_map(|statement| self.fold_statement(statement))
+            .collect()
+    }
+
+    // um statement pode sumir (ramo morto) ou virar vários (ramo vivo inlinado)
+    fn fold_statement(&mut self, statement: Statement) -> Vec<Statement> {
+        self.current_line = statement.line();
+        match statement {
+            Statement::VariableDecl(decl) => vec![Statement::VariableDecl(VariableDecl {
+                var_type: decl.var_type,
+                name: decl.name,
+                initializer: decl.initializer.map(|e| self.fold_expr(e)),
+                line: decl.line,
+            })],
+            Statement::ExprStmt(expr_stmt) => vec![Statement::ExprStmt(ExprStmt {
+                expr: self.fold_expr(expr_stmt.expr),
+                line: expr_stmt.line,
+            })],
+            Statement::IfStmt(if_stmt) => {
+                let condition = self.fold_expr(if_stmt.condition);
+                let then_branch = self.fold_block(if_stmt.then_branch);
+                let else_branch = if_stmt.else_branch.map(|b| self.fold_block(b));
+
+                match condition {
+                    Expr::Literal(Literal::Logico(true)) => Self::inline_block(then_branch, if_stmt.line),
+                    Expr::Literal(Literal::Logico(false)) => {
+                        else_branch.map(|b| Self::inline_block(b, if_stmt.line)).unwrap_or_default()
+                    }
+                    condition => vec![Statement::IfStmt(IfStmt {
+                        condition,
+                        then_branch,
+                        else_branch,
+                        line: if_stmt.line,
+                    })],
+                }
+            }
+            Statement::WhileStmt(while_stmt) => {
+                let condition = self.fold_expr(while_stmt.condition);
+
+                if condition == Expr::Literal(Literal::Logico(false)) {
+                    return Vec::new();
+                }
+
+                vec![Statement::WhileStmt(WhileStmt {
+                    condition,
+                    body: self.fold_block(while_stmt.body),
+                    line: while_stmt.line,
+                })]
+            }
+            Statement::ForStmt(for_stmt) => vec![Statement::ForStmt(ForStmt {
+                variable: for_stmt.variable,
+                start: self.fold_expr(for_stmt.start),
+                end: self.fold_expr(for_stmt.end),
+                body: self.fold_block(for_stmt.body),
+                line: for_stmt.line,
+            })],
+            Statement::ReturnStmt(return_stmt) => vec![Statement::ReturnStmt(ReturnStmt {
+                value: return_stmt.value.map(|e| self.fold_expr(e)),
+                line: return_stmt.line,
+            })],
+            Statement::WriteStmt(write_stmt) => vec![Statement::WriteStmt(WriteStmt {
+                arguments: write_stmt.arguments.into_iter().map(|e| self.fold_expr(e)).collect(),
+                line: write_stmt.line,
+            })],
+            Statement::ReadStmt(read_stmt) => vec![Statement::ReadStmt(read_stmt)],
+        }
+    }
+
+    // Ramo sempre executado. Se ele declara variáveis, mantemos o bloco
+    // (com condição verdadeira) para não vazar as declarações para o escopo de fora.
+    fn inline_block(block: Block, line: usize) -> Vec<Statement> {
+        let declares_variables = block.statements
+            .iter()
+            .any(|s| matches!(s, Statement::VariableDecl(_)));
+
+        if declares_variables {
+            vec![Statement::IfStmt(IfStmt {
+                condition: Expr::Literal(Literal::Logico(true)),
+                then_branch: block,
+                else_branch: None,
+                line,
+            })]
+        } else {
+            block.statements
+        }
+    }
+
+    // === EXPRESSÕES ===
+
+    pub fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::BinaryOp(op, left, right) => {
+                let left = self.fold_expr(*left);
+                let right = self.fold_expr(*right);
+                self.fold_binary(op, left, right)
+            }
+            Expr::UnaryOp(op, operand) => {
+                let operand = self.fold_expr(*operand);
+                Self::fold_unary(op, operand)
+            }
+            Expr::Call(call) => Expr::Call(CallExpr {
+                function: call.function,
+                arguments: call.arguments.into_iter().map(|e| self.fold_expr(e)).collect(),
+            }),
+            other => other,
+        }
+    }
+
+    fn fold_binary(&mut self, op: BinaryOperator, left: Expr, right: Expr) -> Expr {
+        // só a divisão inteira por zero é erro; a decimal dá infinito ou NaN,
+        // como em tempo de execução, e é dobrada como qualquer outra
+        if matches!(op, BinaryOperator::Divide | BinaryOperator::Modulo)
+            && matches!(left, Expr::Literal(Literal::Inteiro(_)))
+            && right == Expr::Literal(Literal::Inteiro(0))
+        {
+            let message = if op == BinaryOperator::Divide {
+                "Divisão por zero em expressão constante"
+            } else {
+                "Módulo por zero em expressão constante"
+            };
+            self.report_error(message);
+            return Expr::BinaryOp(op, Box::new(left), Box::new(right));
+        }
+
+        if let (Expr::Literal(l), Expr::Literal(r)) = (&left, &right)
+            && let Some(folded) = Self::eval_binary(&op, l, r)
+        {
+            return Expr::Literal(folded);
+        }
+
+        // identidades algébricas: x + 0, x - 0, x * 1, x / 1, verdadeiro && x, falso || x
+        // (com literais inteiros o tipo do resultado é sempre o tipo de x,
+        // exceto em `texto + 0`, que concatena)
+        let int_lit = |e: &Expr, v: i64| *e == Expr::Literal(Literal::Inteiro(v));
+        let bool_lit = |e: &Expr, v: bool| *e == Expr::Literal(Literal::Logico(v));
+
+        match op {
+            BinaryOperator::Add if int_lit(&right, 0) && !self.may_be_text(&left) => left,
+            BinaryOperator::Add if int_lit(&left, 0) && !self.may_be_text(&right) => right,
+            BinaryOperator::Subtract if int_lit(&right, 0) => left,
+            BinaryOperator::Multiply if int_lit(&right, 1) => left,
+            BinaryOperator::Multiply if int_lit(&left, 1) => right,
+            BinaryOperator::Divide if int_lit(&right, 1) => left,
+            BinaryOperator::And if bool_lit(&right, true) => left,
+            BinaryOperator::And if bool_lit(&left, true) => right,
+            BinaryOperator::Or if bool_lit(&right, false) => left,
+            BinaryOperator::Or if bool_lit(&left, false) => right,
+            _ => Expr::BinaryOp(op, Box::new(left), Box::new(right)),
+        }
+    }
+
+    fn may_be_text(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(literal) => matches!(literal, Literal::Texto(_)),
+            Expr::Variable(name) => self.text_variables.contains(name),
+            Expr::Call(call) => {
+                self.text_functions.contains(&call.function)
+                    || Builtin::from_name(&call.function)
+                        .is_some_and(|b| b.overloads().iter().any(|o| o.return_type == Type::Texto))
+            }
+            Expr::BinaryOp(BinaryOperator::Add, left, right) => self.may_be_text(left) || self.may_be_text(right),
+            Expr::BinaryOp(..) | Expr::UnaryOp(..) => false,
+        }
+    }
+
+    fn fold_unary(op: UnaryOperator, operand: Expr) -> Expr {
+        match (op, operand) {
+            (UnaryOperator::Negate, Expr::Literal(Literal::Inteiro(n))) if n != i64::MIN => {
+                Expr::Literal(Literal::Inteiro(-n))
+            }
+            (UnaryOperator::Negate, Expr::Literal(Literal::Decimal(n))) => {
+                Expr::Literal(Literal::Decimal(-n))
+            }
+            (UnaryOperator::Plus, operand) => operand,
+            (UnaryOperator::Not, Expr::Literal(Literal::Logico(b))) => {
+                Expr::Literal(Literal::Logico(!b))
+            }
+            // !!b => b
+            (UnaryOperator::Not, Expr::UnaryOp(UnaryOperator::Not, inner)) => *inner,
+            (op, operand) => Expr::UnaryOp(op, Box::new(operand)),
+        }
+    }
+
+    // Avalia um operador binário sobre dois literais. Retorna None quando o
+    // resultado não pode ser calculado em tempo de compilação (ex.: overflow).
+    fn eval_binary(op: &BinaryOperator, left: &Literal, right: &Literal) -> Option<Literal> {
+        use BinaryOperator::*;
+
+        match (left, right) {
+            (Literal::Inteiro(a), Literal::Inteiro(b)) => {
+                let (a, b) = (*a, *b);
+                match op {
+                    Add => a.checked_add(b).map(Literal::Inteiro),
+                    Subtract => a.checked_sub(b).map(Literal::Inteiro),
+                    Multiply => a.checked_mul(b).map(Literal::Inteiro),
+                    Divide => a.checked_div(b).map(Literal::Inteiro),
+                    Modulo => a.checked_rem(b).map(Literal::Inteiro),
+                    Equal => Some(Literal::Logico(a == b)),
+                    NotEqual => Some(Literal::Logico(a != b)),
+                    Less => Some(Literal::Logico(a < b)),
+                    LessEqual => Some(Literal::Logico(a <= b)),
+                    Greater => Some(Literal::Logico(a > b)),
+                    GreaterEqual => Some(Literal::Logico(a >= b)),
+                    And | Or => None,
+                }
+            }
+            (Literal::Inteiro(_) | Literal::Decimal(_), Literal::Inteiro(_) | Literal::Decimal(_)) => {
+                let a = Self::as_decimal(left)?;
+                let b = Self::as_decimal(right)?;
+                match op {
+                    Add => Some(Literal::Decimal(a + b)),
+                    Subtract => Some(Literal::Decimal(a - b)),
+                    Multiply => Some(Literal::Decimal(a * b)),
+                    Divide => Some(Literal::Decimal(a / b)),
+                    Less => Some(Literal::Logico(a < b)),
+                    LessEqual => Some(Literal::Logico(a <= b)),
+                    Greater => Some(Literal::Logico(a > b)),
+                    GreaterEqual => Some(Literal::Logico(a >= b)),
+                    // igualdade só é definida entre decimais (mesmo tipo)
+                    Equal if matches!(left, Literal::Decimal(_)) => Some(Literal::Logico(a == b)),
+                    NotEqual if matches!(left, Literal::Decimal(_)) => Some(Literal::Logico(a != b)),
+                    _ => None,
+                }
+            }
+            (Literal::Texto(a), Literal::Texto(b)) => match op {
+                Add => Some(Literal::Texto(format!("{}{}", a, b))),
+                Equal => Some(Literal::Logico(a == b)),
+                NotEqual => Some(Literal::Logico(a != b)),
+                Less => Some(Literal::Logico(a < b)),
+                LessEqual => Some(Literal::Logico(a <= b)),
+                Greater => Some(Literal::Logico(a > b)),
+                GreaterEqual => Some(Literal::Logico(a >= b)),
+                _ => None,
+            },
+            (Literal::Logico(a), Literal::Logico(b)) => match op {
+                Equal => Some(Literal::Logico(a == b)),
+                NotEqual => Some(Literal::Logico(a != b)),
+                And => Some(Literal::Logico(*a && *b)),
+                Or => Some(Literal::Logico(*a || *b)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn as_decimal(literal: &Literal) -> Option<f64> {
+        match literal {
+            Literal::Inteiro(n) => Some(*n as f64),
+            Literal::Decimal(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn report_error(&mut self, message: &str) {
+        self.errors.push(SemanticError {
+            message: message.to_string(),
+            line: self.current_line,
+            column: 0,
+            function: self.current_function.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::semantic::SemanticAnalyzer;
+
+    fn optimize_source(source: &str) -> SemanticAnalysisResult {
+        let program = Parser::parse_from_source(source).unwrap();
+        let result = SemanticAnalyzer::new().analyze(program);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        ConstantFolder::new().optimize(result)
+    }
+
+    fn first_statement(source: &str) -> Statement {
+        let result = optimize_source(source);
+        result.annotated_ast.functions[0].body.statements[0].clone()
+    }
+
+    #[test]
+    fn test_fold_arithmetic() {
+        let stmt = first_statement("funcao principal() { inteiro x = 2 * 3 + 1; }");
+        match stmt {
+            Statement::VariableDecl(decl) => {
+                assert_eq!(decl.initializer, Some(Expr::Literal(Literal::Inteiro(7))));
+            }
+            other => panic!("esperado VariableDecl, encontrado {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_algebraic_identities() {
+        let stmt = first_statement(
+            "funcao inteiro f(inteiro x, logico b) { retorne (x * 1 + 0) / 1; }",
+        );
+        assert_eq!(
+            stmt,
+            Statement::ReturnStmt(ReturnStmt { value: Some(Expr::Variable("x".to_string())), line: 1 })
+        );
+
+        let stmt = first_statement("funcao logico g(logico b) { retorne !!b; }");
+        assert_eq!(
+            stmt,
+            Statement::ReturnStmt(ReturnStmt { value: Some(Expr::Variable("b".to_string())), line: 1 })
+        );
+    }
+
+    #[test]
+    fn test_text_concatenation() {
+        let stmt = first_statement("funcao texto f() { retorne \"a\" + \"b\"; }");
+        assert_eq!(
+            stmt,
+            Statement::ReturnStmt(ReturnStmt { value: Some(Expr::Literal(Literal::Texto("ab".to_string()))), line: 1 })
+        );
+
+        // t + 0 junta "0" ao texto, então não é identidade
+        let stmt = first_statement("funcao texto g(texto t) { retorne t + 0; }");
+        assert!(matches!(stmt, Statement::ReturnStmt(ReturnStmt { value: Some(Expr::BinaryOp(..)), .. })));
+    }
+
+    #[test]
+    fn test_prune_constant_branches() {
+        let result = optimize_source(
+            "funcao principal() {
+                se verdadeiro entao { escreva(1); } senao { escreva(2); } fimse
+                se 1 > 2 entao { escreva(3); } fimse
+                enquanto falso faca { escreva(4); } fimenquanto
+            }",
+        );
+        let body = &result.annotated_ast.functions[0].body.statements;
+        assert_eq!(body.len(), 1);
+        assert_eq!(
+            body[0],
+            Statement::WriteStmt(WriteStmt { arguments: vec![Expr::Literal(Literal::Inteiro(1))], line: 2 })
+        );
+    }
+
+    #[test]
+    fn test_live_branch_with_declarations_keeps_scope() {
+        let stmt = first_statement(
+            "funcao principal() { se verdadeiro entao { inteiro a = 1; } fimse }",
+        );
+        assert!(matches!(stmt, Statement::IfStmt(IfStmt { else_branch: None, .. })));
+    }
+
+    #[test]
+    fn test_constant_division_by_zero() {
+        let result = optimize_source(
+            "funcao principal() {\n  inteiro x = 10 / (2 - 2);\n  se x > 0 entao {\n    escreva(7 % 0);\n  } fimse\n}",
+        );
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors[0].message.contains("Divisão por zero"));
+        assert!(result.errors[1].message.contains("Módulo por zero"));
+        assert_eq!((result.errors[0].line, result.errors[1].line), (2, 4));
+    }
+
+    #[test]
+    fn test_decimal_division_by_zero_matches_unoptimized() {
+        use crate::interpreter::run_program;
+
+        let source = "funcao principal() {\n  decimal v = 1.0 / 0.0;\n  escreva(v, 0.0 / 0.0, -1.0 / 0.0, 5 / 0.0);\n}";
+        let plain = SemanticAnalyzer::new().analyze(Parser::parse_from_source(source).unwrap());
+        let optimized = optimize_source(source);
+        assert!(optimized.errors.is_empty(), "{:?}", optimized.errors);
+        assert!(matches!(
+            &optimized.annotated_ast.functions[0].body.statements[0],
+            Statement::VariableDecl(VariableDecl { initializer: Some(Expr::Literal(Literal::Decimal(v))), .. }) if v.is_infinite()
+        ));
+
+        let expected = ("inf nan -inf inf\n".to_string(), Ok(()));
+        assert_eq!(run_program(&plain.annotated_ast, "", 1000), expected);
+        assert_eq!(run_program(&optimized.annotated_ast, "", 1000), expected);
+    }
+}
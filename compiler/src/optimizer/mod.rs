@@ -0,0 +1,3 @@
+pub mod constant_folding;
+
+pub use constant_folding::ConstantFolder;
@@ -72,11 +72,17 @@ pub struct VariableDecl {
     pub var_type: Type,
     pub name: String,
     pub initializer: Option<Expr>,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExprStmt {
     pub expr: Expr,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -84,12 +90,18 @@ pub struct IfStmt {
     pub condition: Expr,
     pub then_branch: Block,
     pub else_branch: Option<Block>,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhileStmt {
     pub condition: Expr,
     pub body: Block,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -98,21 +110,33 @@ pub struct ForStmt {
     pub start: Expr,
     pub end: Expr,
     pub body: Block,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReturnStmt {
     pub value: Option<Expr>,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WriteStmt {
     pub arguments: Vec<Expr>,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReadStmt {
     pub target: Expr,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -127,16 +151,43 @@ pub enum Statement {
     ReadStmt(ReadStmt),
 }
 
+impl Statement {
+    pub fn line(&self) -> usize {
+        match self {
+            Statement::VariableDecl(decl) => decl.line,
+            Statement::ExprStmt(stmt) => stmt.line,
+            Statement::IfStmt(stmt) => stmt.line,
+            Statement::WhileStmt(stmt) => stmt.line,
+            Statement::ForStmt(stmt) => stmt.line,
+            Statement::ReturnStmt(stmt) => stmt.line,
+            Statement::WriteStmt(stmt) => stmt.line,
+            Statement::ReadStmt(stmt) => stmt.line,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionDecl {
     pub return_type: Option<Type>,
     pub name: String,
     pub parameters: Vec<Parameter>,
     pub body: Block,
+    // linha onde começa no arquivo (0 se desconhecida)
+    #[serde(default)]
+    pub line: usize,
+}
+
+// `importe "caminho.sym";`, com o caminho relativo ao arquivo que importa
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Import {
+    pub path: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
+    // vazio depois que os módulos são resolvidos (ver `modules`)
+    #[serde(default)]
+    pub imports: Vec<Import>,
     pub functions: Vec<FunctionDecl>,
     pub statements: Vec<Statement>,
 }
@@ -1,5 +1,6 @@
 pub mod ast;
 pub mod parser;
+pub mod printer;
 
 pub use ast::*;
 pub use parser::{Parser, ParserError};
\ No newline at end of file
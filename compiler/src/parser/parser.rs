@@ -1,4 +1,4 @@
-use crate::lexer::{Lexer, Token, TokenType};
+use crate::lexer::{Lexer, StringPart, Token, TokenType};
 use crate::parser::ast::*;
 use std::collections::VecDeque;
 
@@ -46,9 +46,9 @@ impl Parser {
     pub fn new(mut lexer: Lexer) -> Result<Self, ParserError> {
         let current_token = lexer.next_token_for_parser()
             .map_err(|e| ParserError::new(
-                e.to_string(), 
-                0, 0, 
-                vec![], 
+                e.to_string(),
+                e.linha, e.coluna,
+                vec![],
                 TokenType::EOF
             ))?;
         
@@ -83,15 +83,14 @@ impl Parser {
             self.advance()?;
             Ok(())
         } else {
-            let error = ParserError::new(
-                format!("Token inesperado"),
+            // quem captura o erro (na recuperação) é quem o registra
+            Err(ParserError::new(
+                "Token inesperado".to_string(),
                 self.current_token.linha,
                 self.current_token.coluna,
                 vec![expected.clone()],
                 self.current_token.token_type.clone()
-            );
-            self.errors.push(error.clone());
-            Err(error)
+            ))
         }
     }
 
@@ -143,7 +142,7 @@ impl Parser {
             && !self.check(&TokenType::EOF) 
             && recovery_count < max_recovery_attempts {
             
-            if let Err(_) = self.advance() {
+            if self.advance().is_err() {
                 break;
             }
             recovery_count += 1;
@@ -151,7 +150,7 @@ impl Parser {
         
         if recovery_count >= max_recovery_attempts {
             while !self.check(&TokenType::EOF) {
-                if let Err(_) = self.advance() {
+                if self.advance().is_err() {
                     break;
                 }
             }
@@ -161,11 +160,20 @@ impl Parser {
     // === MÉTODOS DE PARSING PRINCIPAIS 
 
     pub fn parse_program(&mut self) -> Result<Program, Vec<ParserError>> {
+        let mut imports = Vec::new();
         let mut functions = Vec::new();
         let mut statements = Vec::new();
 
         while !self.check(&TokenType::EOF) {
-            if self.check(&TokenType::Funcao) {
+            if self.check(&TokenType::Importe) {
+                match self.parse_import() {
+                    Ok(import) => imports.push(import),
+                    Err(e) => {
+                        self.errors.push(e);
+                        self.sync_recovery(&[TokenType::Importe, TokenType::Funcao, TokenType::EOF]);
+                    }
+                }
+            } else if self.check(&TokenType::Funcao) {
                 match self.parse_function_decl() {
                     Ok(func) => functions.push(func),
                     Err(e) => {
@@ -203,11 +211,35 @@ impl Parser {
         if !self.errors.is_empty() {
             Err(std::mem::take(&mut self.errors))
         } else {
-            Ok(Program { functions, statements })
+            Ok(Program { imports, functions, statements })
         }
     }
 
+    // importacao ::= "importe" texto ";"
+    fn parse_import(&mut self) -> Result<Import, ParserError> {
+        self.consume(TokenType::Importe)?;
+
+        let path = if let TokenType::StringLiteral(path) = &self.current_token.token_type {
+            let path = path.clone();
+            self.advance()?;
+            path
+        } else {
+            return Err(ParserError::new(
+                "Esperado caminho do módulo entre aspas".to_string(),
+                self.current_token.linha,
+                self.current_token.coluna,
+                vec![TokenType::StringLiteral("".to_string())],
+                self.current_token.token_type.clone()
+            ));
+        };
+
+        self.consume(TokenType::PontoEVirgula)?;
+
+        Ok(Import { path })
+    }
+
     fn parse_function_decl(&mut self) -> Result<FunctionDecl, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Funcao)?;
 
         let return_type = if self.check_any(&[TokenType::Inteiro, TokenType::Decimal, 
@@ -245,6 +277,7 @@ impl Parser {
             name,
             parameters,
             body,
+            line,
         })
     }
 
@@ -335,6 +368,7 @@ impl Parser {
 
     // declaracao_variavel ::= tipo identificador ("=" expressao)? ";"
     fn parse_variable_decl(&mut self) -> Result<VariableDecl, ParserError> {
+        let line = self.current_token.linha;
         let var_type = self.parse_type()?;
         
         let name = if let TokenType::Identificador(name) = &self.current_token.token_type {
@@ -364,18 +398,21 @@ impl Parser {
             var_type,
             name,
             initializer,
+            line,
         })
     }
 
     // expressao_comando ::= expressao ";"
     fn parse_expr_stmt(&mut self) -> Result<ExprStmt, ParserError> {
+        let line = self.current_token.linha;
         let expr = self.parse_expression()?;
         self.consume(TokenType::PontoEVirgula)?;
-        Ok(ExprStmt { expr })
+        Ok(ExprStmt { expr, line })
     }
 
     // condicional ::= "se" expressao "entao" bloco ("senao" bloco)? "fimse"
     fn parse_if_stmt(&mut self) -> Result<IfStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Se)?;
         let condition = self.parse_expression()?;
         self.consume(TokenType::Entao)?;
@@ -394,22 +431,25 @@ impl Parser {
             condition,
             then_branch,
             else_branch,
+            line,
         })
     }
 
     // loop_enquanto ::= "enquanto" expressao "faca" bloco "fimenquanto"
     fn parse_while_stmt(&mut self) -> Result<WhileStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Enquanto)?;
         let condition = self.parse_expression()?;
         self.consume(TokenType::Faca)?;
         let body = self.parse_block()?;
         self.consume(TokenType::FimEnquanto)?;
 
-        Ok(WhileStmt { condition, body })
+        Ok(WhileStmt { condition, body, line })
     }
 
     // loop_para ::= "para" identificador "de" expressao "ate" expressao "faca" bloco "fimpara"
     fn parse_for_stmt(&mut self) -> Result<ForStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Para)?;
 
         let variable = if let TokenType::Identificador(name) = &self.current_token.token_type {
@@ -439,11 +479,13 @@ impl Parser {
             start,
             end,
             body,
+            line,
         })
     }
 
     // comando_retorno ::= "retorne" expressao? ";"
     fn parse_return_stmt(&mut self) -> Result<ReturnStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Retorne)?;
 
         let value = if !self.check(&TokenType::PontoEVirgula) {
@@ -454,29 +496,31 @@ impl Parser {
 
         self.consume(TokenType::PontoEVirgula)?;
 
-        Ok(ReturnStmt { value })
+        Ok(ReturnStmt { value, line })
     }
 
     // comando_escreva ::= "escreva" "(" argumentos ")" ";"
     fn parse_write_stmt(&mut self) -> Result<WriteStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Escreva)?;
         self.consume(TokenType::ParenteseEsquerdo)?;
         let arguments = self.parse_arguments()?;
         self.consume(TokenType::ParenteseDireito)?;
         self.consume(TokenType::PontoEVirgula)?;
 
-        Ok(WriteStmt { arguments })
+        Ok(WriteStmt { arguments, line })
     }
 
     // comando_leia ::= "leia" "(" expressao ")" ";"
     fn parse_read_stmt(&mut self) -> Result<ReadStmt, ParserError> {
+        let line = self.current_token.linha;
         self.consume(TokenType::Leia)?;
         self.consume(TokenType::ParenteseEsquerdo)?;
         let target = self.parse_expression()?;
         self.consume(TokenType::ParenteseDireito)?;
         self.consume(TokenType::PontoEVirgula)?;
 
-        Ok(ReadStmt { target })
+        Ok(ReadStmt { target, line })
     }
 
     // === EXPRESSÕES (com precedência) ===
@@ -622,6 +666,11 @@ impl Parser {
                 self.advance()?;
                 Ok(Expr::Literal(Literal::Texto(value)))
             }
+            TokenType::InterpolatedString(parts) => {
+                let parts = parts.clone();
+                self.advance()?;
+                Self::interpolation(parts)
+            }
             TokenType::Verdadeiro => {
                 self.advance()?;
                 Ok(Expr::Literal(Literal::Logico(true)))
@@ -631,9 +680,34 @@ impl Parser {
                 Ok(Expr::Literal(Literal::Logico(false)))
             }
             TokenType::Identificador(name) => {
-                let name = name.clone();
+                let mut name = name.clone();
                 self.advance()?;
 
+                // modulo.funcao(...): chamada qualificada a um módulo importado
+                if self.check(&TokenType::Ponto) {
+                    self.advance()?;
+                    let TokenType::Identificador(function) = &self.current_token.token_type else {
+                        return Err(ParserError::new(
+                            format!("Esperado nome de função depois de '{}.'", name),
+                            self.current_token.linha,
+                            self.current_token.coluna,
+                            vec![TokenType::Identificador("".to_string())],
+                            self.current_token.token_type.clone()
+                        ));
+                    };
+                    name = format!("{}.{}", name, function);
+                    self.advance()?;
+                    if !self.check(&TokenType::ParenteseEsquerdo) {
+                        return Err(ParserError::new(
+                            format!("'{}' só pode ser usado como chamada de função", name),
+                            self.current_token.linha,
+                            self.current_token.coluna,
+                            vec![TokenType::ParenteseEsquerdo],
+                            self.current_token.token_type.clone()
+                        ));
+                    }
+                }
+
                 // Verifica se é chamada de função
                 if self.check(&TokenType::ParenteseEsquerdo) {
                     self.consume(TokenType::ParenteseEsquerdo)?;
@@ -668,6 +742,48 @@ impl Parser {
         }
     }
 
+    // a expressão que parse_primary monta para um token de texto; o `fmt` a
+    // usa para reconhecer na AST os textos e voltar a escrevê-los como estavam
+    pub(crate) fn string_expr(token_type: &TokenType) -> Option<Expr> {
+        match token_type {
+            TokenType::StringLiteral(value) => Some(Expr::Literal(Literal::Texto(value.clone()))),
+            TokenType::InterpolatedString(parts) => Self::interpolation(parts.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    // "Olá, {nome}!" vira "Olá, " + nome + "!"; a soma sempre começa por um
+    // texto, então cada valor é convertido como na concatenação
+    fn interpolation(parts: Vec<StringPart>) -> Result<Expr, ParserError> {
+        let mut result = Expr::Literal(Literal::Texto(String::new()));
+        for (index, part) in parts.into_iter().enumerate() {
+            let expr = match part {
+                StringPart::Text(text) => Expr::Literal(Literal::Texto(text)),
+                StringPart::Code { source, linha, coluna } => Self::parse_embedded(&source, linha, coluna)?,
+            };
+            result = match expr {
+                Expr::Literal(Literal::Texto(_)) if index == 0 => expr,
+                expr => Expr::BinaryOp(BinaryOperator::Add, Box::new(result), Box::new(expr)),
+            };
+        }
+        Ok(result)
+    }
+
+    fn parse_embedded(source: &str, linha: usize, coluna: usize) -> Result<Expr, ParserError> {
+        let mut parser = Parser::new(Lexer::at(source, linha, coluna))?;
+        let expr = parser.parse_expression()?;
+        if !parser.current_token.is_eof() {
+            return Err(ParserError::new(
+                "Esperado '}' depois da expressão interpolada".to_string(),
+                parser.current_token.linha,
+                parser.current_token.coluna,
+                vec![],
+                parser.current_token.token_type.clone(),
+            ));
+        }
+        Ok(expr)
+    }
+
     // argumentos ::= expressao ("," expressao)*
     fn parse_arguments(&mut self) -> Result<Vec<Expr>, ParserError> {
         let mut arguments = Vec::new();
@@ -0,0 +1,551 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem::discriminant;
+use crate::lexer::{Lexer, Token, TokenType, Trivia, TriviaKind};
+use crate::parser::ast::*;
+use crate::parser::Parser;
+
+// Reconstrói código Symplia a partir da AST. Usado pelos transpiladores para
+// comentar cada comando gerado com o trecho original correspondente e pelo
+// `compiler fmt`.
+
+// Precedência dos operadores binários, do parser (menor = liga mais fraco)
+pub fn binary_precedence(op: &BinaryOperator) -> u8 {
+    match op {
+        BinaryOperator::Or => 1,
+        BinaryOperator::And => 2,
+        BinaryOperator::Equal | BinaryOperator::NotEqual => 3,
+        BinaryOperator::Less | BinaryOperator::LessEqual | BinaryOperator::Greater | BinaryOperator::GreaterEqual => 4,
+        BinaryOperator::Add | BinaryOperator::Subtract => 5,
+        BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => 6,
+    }
+}
+
+const INDENT: &str = "    ";
+const UNARY_PRECEDENCE: u8 = 7;
+const PRIMARY_PRECEDENCE: u8 = 8;
+
+pub fn format_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Inteiro(n) => n.to_string(),
+        // sempre com o ponto decimal ("2.0") e sem expoente ("1e20" não é um
+        // literal de Symplia)
+        Literal::Decimal(n) if n.is_finite() => {
+            let text = n.to_string();
+            if text.contains('.') { text } else { format!("{}.0", text) }
+        }
+        Literal::Decimal(n) => format!("{:?}", n),
+        Literal::Texto(s) => format!("\"{}\"", escape_text(s)),
+        Literal::Logico(true) => "verdadeiro".to_string(),
+        Literal::Logico(false) => "falso".to_string(),
+    }
+}
+
+fn escape_text(value: &str) -> String {
+    let mut out = String::new();
+    for c in value.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            // sem o escape as chaves voltariam como interpolação
+            '{' => out.push_str("\\{"),
+            '}' => out.push_str("\\}"),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn format_expr(expr: &Expr) -> String {
+    Printer::default().expr(expr, 0)
+}
+
+fn expr_precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::BinaryOp(op, _, _) => binary_precedence(op),
+        Expr::UnaryOp(_, _) => UNARY_PRECEDENCE,
+        // literais negativos só aparecem depois do dobramento de constantes
+        Expr::Literal(Literal::Inteiro(n)) if *n < 0 => UNARY_PRECEDENCE,
+        Expr::Literal(Literal::Decimal(n)) if n.is_sign_negative() => UNARY_PRECEDENCE,
+        _ => PRIMARY_PRECEDENCE,
+    }
+}
+
+// Uma linha de código Symplia para o comando; comandos compostos aparecem
+// só com o cabeçalho (`se ... entao`, `para ... faca`)
+pub fn statement_summary(statement: &Statement) -> String {
+    Printer::default().summary(statement)
+}
+
+pub fn function_signature(function: &FunctionDecl) -> String {
+    let parameters: Vec<String> = function.parameters
+        .iter()
+        .map(|p| format!("{} {}", p.param_type, p.name))
+        .collect();
+    match &function.return_type {
+        Some(return_type) => format!("funcao {} {}({})", return_type, function.name, parameters.join(", ")),
+        None => format!("funcao {}({})", function.name, parameters.join(", ")),
+    }
+}
+
+// Programa inteiro no formato padrão: importações, variáveis globais e
+// funções na ordem do arquivo, separadas por uma linha em branco. A AST não
+// guarda a forma original dos textos, então interpolações voltam como
+// concatenações e textos de três aspas como textos comuns; format_file
+// mantém os dois e também os comentários.
+pub fn format_program(program: &Program) -> String {
+    Printer::default().program(program, &[])
+}
+
+// O `compiler fmt`: como format_program, mas os textos voltam como foram
+// escritos e os comentários continuam no lugar. `tokens` e `trivia` vêm do
+// léxico do mesmo arquivo, com `with_trivia`.
+pub fn format_file(program: &Program, tokens: &[Token], trivia: &[Trivia]) -> String {
+    let strings = tokens
+        .iter()
+        .filter_map(|token| Some((Parser::string_expr(&token.token_type)?, token.lexema.as_str())))
+        .collect();
+    // as importações não guardam a linha na AST
+    let import_lines: Vec<usize> = tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::Importe)
+        .map(|token| token.linha)
+        .collect();
+    let formatted = Printer { strings }.program(program, &import_lines);
+
+    let comments: Vec<&Trivia> = trivia.iter().filter(|t| t.kind == TriviaKind::Comment).collect();
+    if comments.is_empty() {
+        return formatted;
+    }
+    attach_comments(&formatted, tokens, &comments)
+}
+
+enum Item<'p> {
+    Import(&'p Import),
+    Global(&'p Statement),
+    Function(&'p FunctionDecl),
+}
+
+#[derive(Default)]
+struct Printer<'t> {
+    // textos do arquivo ainda não escritos, em ordem, com a expressão que cada
+    // um gerou no parser e o lexema original
+    strings: VecDeque<(Expr, &'t str)>,
+}
+
+impl Printer<'_> {
+    // parênteses só onde a precedência (ou a associatividade à esquerda) exige
+    fn expr(&mut self, expr: &Expr, minimum: u8) -> String {
+        // a AST é percorrida na ordem do arquivo, então o próximo texto só
+        // pode ter gerado esta expressão ou uma que ainda vem
+        if self.strings.front().is_some_and(|(original, _)| original == expr) {
+            return self.strings.pop_front().map(|(_, lexeme)| lexeme.to_string()).unwrap_or_default();
+        }
+
+        let text = match expr {
+            Expr::Literal(literal) => format_literal(literal),
+            Expr::Variable(name) => name.clone(),
+            Expr::Call(call) => {
+                let arguments: Vec<String> = call.arguments.iter().map(|argument| self.expr(argument, 0)).collect();
+                format!("{}({})", call.function, arguments.join(", "))
+            }
+            // `-(-x)`, e não `--x`
+            Expr::UnaryOp(op, operand) => format!("{}{}", op, self.expr(operand, PRIMARY_PRECEDENCE)),
+            Expr::BinaryOp(op, left, right) => {
+                let precedence = binary_precedence(op);
+                let left = self.expr(left, precedence);
+                let right = self.expr(right, precedence + 1);
+                format!("{} {} {}", left, op, right)
+            }
+        };
+
+        if expr_precedence(expr) < minimum {
+            format!("({})", text)
+        } else {
+            text
+        }
+    }
+
+    fn summary(&mut self, statement: &Statement) -> String {
+        match statement {
+            Statement::VariableDecl(decl) => match &decl.initializer {
+                Some(initializer) => format!("{} {} = {};", decl.var_type, decl.name, self.expr(initializer, 0)),
+                None => format!("{} {};", decl.var_type, decl.name),
+            },
+            Statement::ExprStmt(expr_stmt) => format!("{};", self.expr(&expr_stmt.expr, 0)),
+            Statement::IfStmt(if_stmt) => format!("se {} entao", self.expr(&if_stmt.condition, 0)),
+            Statement::WhileStmt(while_stmt) => format!("enquanto {} faca", self.expr(&while_stmt.condition, 0)),
+            Statement::ForStmt(for_stmt) => {
+                let start = self.expr(&for_stmt.start, 0);
+                let end = self.expr(&for_stmt.end, 0);
+                format!("para {} de {} ate {} faca", for_stmt.variable, start, end)
+            }
+            Statement::ReturnStmt(return_stmt) => match &return_stmt.value {
+                Some(value) => format!("retorne {};", self.expr(value, 0)),
+                None => "retorne;".to_string(),
+            },
+            Statement::WriteStmt(write_stmt) => {
+                let arguments: Vec<String> = write_stmt.arguments.iter().map(|argument| self.expr(argument, 0)).collect();
+                format!("escreva({});", arguments.join(", "))
+            }
+            Statement::ReadStmt(read_stmt) => format!("leia({});", self.expr(&read_stmt.target, 0)),
+        }
+    }
+
+    // a AST separa importações, globais e funções; a linha de cada um refaz a
+    // ordem do arquivo (sem linhas, como nos programas gerados, fica essa)
+    fn program(&mut self, program: &Program, import_lines: &[usize]) -> String {
+        let mut items: Vec<(usize, Item)> = program.imports
+            .iter()
+            .enumerate()
+            .map(|(index, import)| (import_lines.get(index).copied().unwrap_or(0), Item::Import(import)))
+            .collect();
+        items.extend(program.statements.iter().map(|statement| (statement.line(), Item::Global(statement))));
+        items.extend(program.functions.iter().map(|function| (function.line, Item::Function(function))));
+        items.sort_by_key(|(line, _)| *line);
+
+        // importações e globais seguidas ficam juntas, cada função à parte
+        let mut sections: Vec<String> = Vec::new();
+        let mut last_group = None;
+        for (_, item) in items {
+            let (group, text) = match item {
+                Item::Import(import) => {
+                    let path = self.expr(&Expr::Literal(Literal::Texto(import.path.clone())), 0);
+                    (Some("importe"), format!("importe {};\n", path))
+                }
+                Item::Global(statement) => {
+                    let mut text = String::new();
+                    self.statements(&mut text, std::slice::from_ref(statement), 0);
+                    (Some("global"), text)
+                }
+                Item::Function(function) => {
+                    let mut text = format!("{} ", function_signature(function));
+                    self.block(&mut text, &function.body, 0);
+                    text.push('\n');
+                    (None, text)
+                }
+            };
+            match sections.last_mut() {
+                Some(section) if group.is_some() && group == last_group => section.push_str(&text),
+                _ => sections.push(text),
+            }
+            last_group = group;
+        }
+        sections.join("\n")
+    }
+
+    fn statements(&mut self, out: &mut String, statements: &[Statement], level: usize) {
+        for statement in statements {
+            out.push_str(&INDENT.repeat(level));
+            out.push_str(&self.summary(statement));
+            match statement {
+                Statement::IfStmt(if_stmt) => {
+                    out.push(' ');
+                    self.block(out, &if_stmt.then_branch, level);
+                    if let Some(else_branch) = &if_stmt.else_branch {
+                        out.push_str(" senao ");
+                        self.block(out, else_branch, level);
+                    }
+                    out.push_str(" fimse");
+                }
+                Statement::WhileStmt(while_stmt) => {
+                    out.push(' ');
+                    self.block(out, &while_stmt.body, level);
+                    out.push_str(" fimenquanto");
+                }
+                Statement::ForStmt(for_stmt) => {
+                    out.push(' ');
+                    self.block(out, &for_stmt.body, level);
+                    out.push_str(" fimpara");
+                }
+                _ => {}
+            }
+            out.push('\n');
+        }
+    }
+
+    fn block(&mut self, out: &mut String, block: &Block, level: usize) {
+        if block.statements.is_empty() {
+            out.push_str("{ }");
+            return;
+        }
+        out.push_str("{\n");
+        self.statements(out, &block.statements, level + 1);
+        out.push_str(&INDENT.repeat(level));
+        out.push('}');
+    }
+}
+
+// === COMENTÁRIOS ===
+//
+// O texto formatado tem os mesmos tokens do arquivo e na mesma ordem, exceto
+// por parênteses tirados ou postos. Cada comentário se prende ao token
+// anterior, se divide a linha com ele (e então fica no fim da linha desse
+// token), ou ao seguinte (e então fica numa linha própria antes dele, com a
+// mesma indentação); linhas em branco em volta de um comentário ficam.
+
+struct Comment<'t> {
+    text: &'t str,
+    blank_before: bool,
+    blank_after: bool,
+    // antes de um `}`: fica indentado como o corpo do bloco
+    inside_block: bool,
+}
+
+fn attach_comments(formatted: &str, tokens: &[Token], comments: &[&Trivia]) -> String {
+    let output_tokens = Lexer::new(formatted).tokenize().expect("o texto formatado é um programa válido");
+    let matches = align(tokens, &output_tokens);
+
+    // textos de três aspas ocupam várias linhas: não se põe um comentário
+    // antes de uma linha que começa dentro de um texto nem no fim de uma
+    // linha em que um texto começa
+    let mut started_at = HashMap::new();
+    let mut ends_at = HashMap::new();
+    for token in &output_tokens {
+        let last = end_line(token.linha, &token.lexema);
+        if last > token.linha {
+            ends_at.insert(token.linha, last);
+            for line in token.linha + 1..=last {
+                started_at.insert(line, token.linha);
+            }
+        }
+    }
+
+    let mut before: HashMap<usize, Vec<Comment>> = HashMap::new();
+    let mut after: HashMap<usize, Vec<&str>> = HashMap::new();
+    let mut at_end = Vec::new();
+    for (index, comment) in comments.iter().enumerate() {
+        let position = (comment.linha, comment.coluna);
+        let next = tokens.partition_point(|token| (token.linha, token.coluna) < position);
+        let last_line = end_line(comment.linha, &comment.lexema);
+
+        let previous_token = next.checked_sub(1).map(|i| end_line(tokens[i].linha, &tokens[i].lexema));
+        let previous_comment = index.checked_sub(1).map(|i| end_line(comments[i].linha, &comments[i].lexema));
+        let blank_before = previous_token.max(previous_comment).is_some_and(|line| comment.linha > line + 1);
+        // só conta a linha em branco até o código, não até outro comentário
+        let next_comment = comments.get(index + 1).map(|c| (c.linha, c.coluna));
+        let blank_after = tokens.get(next).is_some_and(|token| {
+            !token.is_eof() && next_comment.is_none_or(|c| c > (token.linha, token.coluna)) && token.linha > last_line + 1
+        });
+
+        if previous_token == Some(comment.linha)
+            && let Some(output) = (0..next).rev().find_map(|i| matches[i])
+        {
+            let mut line = end_line(output_tokens[output].linha, &output_tokens[output].lexema);
+            while let Some(&last) = ends_at.get(&line) {
+                line = last;
+            }
+            after.entry(line).or_default().push(&comment.lexema);
+            continue;
+        }
+
+        let output = (next..tokens.len()).find_map(|i| matches[i]).unwrap_or(output_tokens.len() - 1);
+        let token = &output_tokens[output];
+        let mut comment = Comment { text: &comment.lexema, blank_before, blank_after, inside_block: false };
+        if token.is_eof() {
+            comment.blank_after = false;
+            at_end.push(comment);
+            continue;
+        }
+        let mut line = token.linha;
+        while let Some(&start) = started_at.get(&line) {
+            line = start;
+        }
+        let first_on_line = output.checked_sub(1).is_none_or(|i| end_line(output_tokens[i].linha, &output_tokens[i].lexema) < line);
+        if line == token.linha && first_on_line && token.token_type == TokenType::ChaveDireita {
+            comment.inside_block = true;
+            comment.blank_after = false;
+        }
+        before.entry(line).or_default().push(comment);
+    }
+
+    let mut out = String::new();
+    let lines: Vec<&str> = formatted.lines().collect();
+    for (index, text) in lines.iter().enumerate() {
+        let line = index + 1;
+        let indentation = &text[..text.len() - text.trim_start().len()];
+        for comment in before.get(&line).into_iter().flatten() {
+            let extra = if comment.inside_block { INDENT } else { "" };
+            push_comment(&mut out, comment, &format!("{}{}", indentation, extra));
+        }
+        out.push_str(text);
+        for comment in after.get(&line).into_iter().flatten() {
+            out.push(' ');
+            out.push_str(comment);
+        }
+        out.push('\n');
+    }
+    for comment in &at_end {
+        push_comment(&mut out, comment, "");
+    }
+    out
+}
+
+fn push_comment(out: &mut String, comment: &Comment, indentation: &str) {
+    // no começo de um bloco ou depois de outra linha em branco não se repete
+    if comment.blank_before && !out.is_empty() && !out.ends_with("\n\n") && !out.ends_with("{\n") {
+        out.push('\n');
+    }
+    out.push_str(indentation);
+    out.push_str(comment.text);
+    out.push('\n');
+    if comment.blank_after {
+        out.push('\n');
+    }
+}
+
+// para cada token do arquivo, o mesmo token no texto formatado, se houver
+fn align(original: &[Token], formatted: &[Token]) -> Vec<Option<usize>> {
+    let is_parenthesis = |token: &Token| matches!(token.token_type, TokenType::ParenteseEsquerdo | TokenType::ParenteseDireito);
+    let same_kind = |a: &Token, b: &Token| discriminant(&a.token_type) == discriminant(&b.token_type);
+    let mut matches = vec![None; original.len()];
+    let mut next = 0;
+    for (index, token) in original.iter().enumerate() {
+        while next < formatted.len() && !same_kind(token, &formatted[next]) && is_parenthesis(&formatted[next]) {
+            next += 1;
+        }
+        if next < formatted.len() && same_kind(token, &formatted[next]) {
+            matches[index] = Some(next);
+            next += 1;
+        }
+    }
+    matches
+}
+
+fn end_line(linha: usize, lexema: &str) -> usize {
+    linha + lexema.matches('\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_printed_expressions_parse_back() {
+        let sources = [
+            "(a + b) * c - -d",
+            "a - (b - c)",
+            "!(x && y) || z == (w == -(-v))",
+            "f(1, \"a\\\"b\\n\", 2.0) % 3",
+            "\"\\{x\\} \\u{1b}\" + x + \"!\"",
+            "100000000000000000000.0 * 0.000001",
+        ];
+        for source in sources {
+            let program = Parser::parse_from_source(&format!("escreva({});", source)).unwrap();
+            let Statement::WriteStmt(write) = &program.statements[0] else { panic!() };
+            assert_eq!(format_expr(&write.arguments[0]), source);
+        }
+    }
+
+    #[test]
+    fn test_interpolation_becomes_concatenation() {
+        let cases = [
+            (r#""Olá, {nome}! {a + 1}""#, r#""Olá, " + nome + "! " + (a + 1)"#),
+            (r#""{x}{f("\}")}""#, r#""" + x + f("\}")"#),
+            (r#""\{x\} \u{e9}""#, r#""\{x\} é""#),
+            ("\"\"\"\nlinha \"1\"\n{x}\"\"\"", r#""linha \"1\"\n" + x"#),
+        ];
+        for (source, expected) in cases {
+            let program = Parser::parse_from_source(&format!("escreva({});", source)).unwrap();
+            let Statement::WriteStmt(write) = &program.statements[0] else { panic!() };
+            assert_eq!(format_expr(&write.arguments[0]), expected);
+        }
+
+        for source in [r#""{}""#, r#""}""#, r#""{a b}""#, r#""\u{d800}""#, r#""\u{}""#] {
+            assert!(Parser::parse_from_source(&format!("escreva({});", source)).is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_format_program() {
+        let source = "importe \"lib/util.sym\"; inteiro total;
+            funcao inteiro dobro(inteiro x) { retorne x*2; }
+            funcao principal() { inteiro i; para i de 1 ate 3 faca { se i%2==0 entao { escreva(i); } senao { } fimse } fimpara
+            enquanto falso faca { leia(total); } fimenquanto }";
+        let expected = "importe \"lib/util.sym\";
+
+inteiro total;
+
+funcao inteiro dobro(inteiro x) {
+    retorne x * 2;
+}
+
+funcao principal() {
+    inteiro i;
+    para i de 1 ate 3 faca {
+        se i % 2 == 0 entao {
+            escreva(i);
+        } senao { } fimse
+    } fimpara
+    enquanto falso faca {
+        leia(total);
+    } fimenquanto
+}
+";
+        let formatted = format_program(&Parser::parse_from_source(source).unwrap());
+        assert_eq!(formatted, expected);
+        assert_eq!(format_program(&Parser::parse_from_source(&formatted).unwrap()), expected);
+    }
+
+    #[test]
+    fn test_format_file_keeps_comments_and_strings() {
+        let source = "// cabeçalho
+
+importe \"lib/util.sym\"; // biblioteca
+funcao inteiro dobro(inteiro x) { retorne (x)*2; } /* dobra */
+inteiro total;
+funcao principal() {
+  texto nome = \"Ana\";   // nome
+  escreva(\"Olá, {nome}! {dobro(2) + 1}\", \"\\{\", \"\"\"
+linha \"1\"
+{nome}\"\"\", // fim
+  \"!\");
+  se total > 0 entao { // positivo
+     escreva(1);
+     // fim do então
+  } fimse
+
+  // lê
+  leia(total);
+}
+// SAIDA: 1
+";
+        let expected = "// cabeçalho
+
+importe \"lib/util.sym\"; // biblioteca
+
+funcao inteiro dobro(inteiro x) {
+    retorne x * 2;
+} /* dobra */
+
+inteiro total;
+
+funcao principal() {
+    texto nome = \"Ana\"; // nome
+    escreva(\"Olá, {nome}! {dobro(2) + 1}\", \"\\{\", \"\"\"
+linha \"1\"
+{nome}\"\"\", \"!\"); // fim
+    se total > 0 entao { // positivo
+        escreva(1);
+        // fim do então
+    } fimse
+
+    // lê
+    leia(total);
+}
+// SAIDA: 1
+";
+        let format = |source: &str| {
+            let mut lexer = Lexer::new(source).with_trivia();
+            let tokens = lexer.tokenize().unwrap();
+            format_file(&Parser::parse_from_source(source).unwrap(), &tokens, lexer.trivia())
+        };
+        let formatted = format(source);
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted), expected);
+        assert_eq!(format("/* só um comentário */\n"), "/* só um comentário */\n");
+    }
+}
@@ -0,0 +1,299 @@
+use crate::parser::ast::Type;
+
+// Biblioteca padrão: funções embutidas que todo programa enxerga sem
+// declarar. Cada uma tem uma ou mais assinaturas (sobrecargas); a chamada
+// escolhe a primeira cuja lista de parâmetros aceita os argumentos, com
+// promoção de inteiro para decimal quando não há uma assinatura exata.
+//
+// Funções matemáticas:
+//   raiz(x)          raiz quadrada (decimal)
+//   potencia(b, e)   inteiro com inteiros; com expoente negativo resulta em
+//                    0, exceto para as bases 1 e -1
+//   abs(x)           valor absoluto, no tipo do argumento
+//   arredondar(x)    metade se afasta do zero (2.5 -> 3.0, -2.5 -> -3.0)
+//   piso(x), teto(x) arredondam para baixo / para cima
+// arredondar, piso e teto devolvem o próprio argumento quando ele é inteiro.
+//
+// Funções de texto (contam caracteres Unicode, não bytes):
+//   tamanho(t)                 número de caracteres: tamanho("ação") == 4
+//   maiusculo(t), minusculo(t) trocam a caixa das letras de A a Z e das
+//                              acentuadas do Latin-1 (À-Þ, à-þ); as demais
+//                              ficam como estão (inclusive ß e ÿ)
+//   subtexto(t, inicio, n)     até n caracteres a partir da posição inicio,
+//                              contada de 1; inicio menor que 1 conta como 1
+//                              e o que passar do fim do texto é ignorado
+//   contem(t, trecho)          se trecho aparece em t (o texto vazio sempre aparece)
+//   substituir(t, de, por)     troca cada ocorrência de `de`, da esquerda para
+//                              a direita e sem sobreposição; `de` vazio não
+//                              troca nada
+//
+// Conversões (o inteiro já vira decimal sozinho onde um decimal é esperado):
+//   paraInteiro(x)   decimal: trunca em direção a zero; nan vira 0 e o que
+//                    passar da faixa fica no maior/menor inteiro
+//                    texto: sinal opcional e dígitos ("-42"); qualquer outra
+//                    coisa, ou um número fora da faixa, é erro de execução
+//                    logico: 1 ou 0
+//   paraDecimal(x)   texto: sinal, dígitos, fração e expoente opcionais
+//                    ("2.5", "-1e3"); fora desse formato é erro de execução
+//                    logico: 1.0 ou 0.0
+//   paraTexto(x)     como `escreva` mostraria o valor (decimais com 6 casas)
+//   paraLogico(x)    inteiro: diferente de zero; texto: "verdadeiro" ou
+//                    "falso", e qualquer outro é erro de execução
+// Com o argumento já no tipo de destino, a conversão não faz nada.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    Raiz,
+    Potencia,
+    Abs,
+    Arredondar,
+    Piso,
+    Teto,
+    Tamanho,
+    Maiusculo,
+    Minusculo,
+    Subtexto,
+    Contem,
+    Substituir,
+    ParaInteiro,
+    ParaDecimal,
+    ParaTexto,
+    ParaLogico,
+}
+
+#[derive(Debug)]
+pub struct Overload {
+    pub parameters: &'static [Type],
+    pub return_type: Type,
+}
+
+pub const BUILTINS: [Builtin; 16] = [
+    Builtin::Raiz,
+    Builtin::Potencia,
+    Builtin::Abs,
+    Builtin::Arredondar,
+    Builtin::Piso,
+    Builtin::Teto,
+    Builtin::Tamanho,
+    Builtin::Maiusculo,
+    Builtin::Minusculo,
+    Builtin::Subtexto,
+    Builtin::Contem,
+    Builtin::Substituir,
+    Builtin::ParaInteiro,
+    Builtin::ParaDecimal,
+    Builtin::ParaTexto,
+    Builtin::ParaLogico,
+];
+
+const INTEGER_UNARY: Overload = Overload { parameters: &[Type::Inteiro], return_type: Type::Inteiro };
+const DECIMAL_UNARY: Overload = Overload { parameters: &[Type::Decimal], return_type: Type::Decimal };
+
+const fn conversion(from: Type, to: Type) -> Overload {
+    let parameters: &'static [Type] = match from {
+        Type::Inteiro => &[Type::Inteiro],
+        Type::Decimal => &[Type::Decimal],
+        Type::Texto => &[Type::Texto],
+        Type::Logico => &[Type::Logico],
+    };
+    Overload { parameters, return_type: to }
+}
+
+const TO_INTEGER: [Overload; 4] = [
+    conversion(Type::Inteiro, Type::Inteiro),
+    conversion(Type::Decimal, Type::Inteiro),
+    conversion(Type::Texto, Type::Inteiro),
+    conversion(Type::Logico, Type::Inteiro),
+];
+const TO_DECIMAL: [Overload; 4] = [
+    conversion(Type::Inteiro, Type::Decimal),
+    conversion(Type::Decimal, Type::Decimal),
+    conversion(Type::Texto, Type::Decimal),
+    conversion(Type::Logico, Type::Decimal),
+];
+const TO_TEXT: [Overload; 4] = [
+    conversion(Type::Inteiro, Type::Texto),
+    conversion(Type::Decimal, Type::Texto),
+    conversion(Type::Texto, Type::Texto),
+    conversion(Type::Logico, Type::Texto),
+];
+const TO_LOGICAL: [Overload; 3] = [
+    conversion(Type::Inteiro, Type::Logico),
+    conversion(Type::Texto, Type::Logico),
+    conversion(Type::Logico, Type::Logico),
+];
+
+impl Builtin {
+    pub fn from_name(name: &str) -> Option<Builtin> {
+        BUILTINS.iter().copied().find(|b| b.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Raiz => "raiz",
+            Builtin::Potencia => "potencia",
+            Builtin::Abs => "abs",
+            Builtin::Arredondar => "arredondar",
+            Builtin::Piso => "piso",
+            Builtin::Teto => "teto",
+            Builtin::Tamanho => "tamanho",
+            Builtin::Maiusculo => "maiusculo",
+            Builtin::Minusculo => "minusculo",
+            Builtin::Subtexto => "subtexto",
+            Builtin::Contem => "contem",
+            Builtin::Substituir => "substituir",
+            Builtin::ParaInteiro => "paraInteiro",
+            Builtin::ParaDecimal => "paraDecimal",
+            Builtin::ParaTexto => "paraTexto",
+            Builtin::ParaLogico => "paraLogico",
+        }
+    }
+
+    pub fn overloads(&self) -> &'static [Overload] {
+        match self {
+            Builtin::Raiz => &[DECIMAL_UNARY],
+            Builtin::Potencia => &[
+                Overload { parameters: &[Type::Inteiro, Type::Inteiro], return_type: Type::Inteiro },
+                Overload { parameters: &[Type::Decimal, Type::Decimal], return_type: Type::Decimal },
+            ],
+            Builtin::Abs | Builtin::Arredondar | Builtin::Piso | Builtin::Teto => &[INTEGER_UNARY, DECIMAL_UNARY],
+            Builtin::Tamanho => &[Overload { parameters: &[Type::Texto], return_type: Type::Inteiro }],
+            Builtin::Maiusculo | Builtin::Minusculo => &[Overload { parameters: &[Type::Texto], return_type: Type::Texto }],
+            Builtin::Subtexto => &[Overload {
+                parameters: &[Type::Texto, Type::Inteiro, Type::Inteiro],
+                return_type: Type::Texto,
+            }],
+            Builtin::Contem => &[Overload { parameters: &[Type::Texto, Type::Texto], return_type: Type::Logico }],
+            Builtin::Substituir => &[Overload {
+                parameters: &[Type::Texto, Type::Texto, Type::Texto],
+                return_type: Type::Texto,
+            }],
+            Builtin::ParaInteiro => &TO_INTEGER,
+            Builtin::ParaDecimal => &TO_DECIMAL,
+            Builtin::ParaTexto => &TO_TEXT,
+            Builtin::ParaLogico => &TO_LOGICAL,
+        }
+    }
+
+    pub fn resolve(&self, arguments: &[Type]) -> Result<&'static Overload, String> {
+        let overloads = self.overloads();
+        let accepts = |overload: &Overload, promote: bool| {
+            overload.parameters.len() == arguments.len()
+                && overload.parameters.iter().zip(arguments).all(|(param, arg)| {
+                    param == arg || (promote && *param == Type::Decimal && *arg == Type::Inteiro)
+                })
+        };
+
+        if let Some(overload) = overloads.iter().find(|o| accepts(o, false)) {
+            return Ok(overload);
+        }
+        if let Some(overload) = overloads.iter().find(|o| accepts(o, true)) {
+            return Ok(overload);
+        }
+
+        let found: Vec<String> = arguments.iter().map(|t| t.to_string()).collect();
+        let signatures: Vec<String> = overloads.iter().map(|o| self.signature(o)).collect();
+        Err(format!(
+            "Função '{}' não aceita argumentos ({}); assinaturas: {}",
+            self.name(),
+            found.join(", "),
+            signatures.join(", ")
+        ))
+    }
+
+    pub fn signature(&self, overload: &Overload) -> String {
+        let parameters: Vec<String> = overload.parameters.iter().map(|t| t.to_string()).collect();
+        format!("{} {}({})", overload.return_type, self.name(), parameters.join(", "))
+    }
+}
+
+// Conversões de texto de referência; os backends aceitam exatamente os
+// mesmos formatos
+
+pub fn parse_integer(text: &str) -> Option<i64> {
+    // o from_str do Rust já aceita só sinal opcional e dígitos
+    text.parse().ok()
+}
+
+pub fn parse_decimal(text: &str) -> Option<f64> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while *i < bytes.len() && bytes[*i].is_ascii_digit() {
+            *i += 1;
+        }
+        *i > start
+    };
+
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        i += 1;
+    }
+    if !digits(&mut i) {
+        return None;
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if !digits(&mut i) {
+            return None;
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !digits(&mut i) {
+            return None;
+        }
+    }
+    if i != bytes.len() {
+        return None;
+    }
+    text.parse().ok()
+}
+
+pub fn parse_logical(text: &str) -> Option<bool> {
+    match text {
+        "verdadeiro" => Some(true),
+        "falso" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overload_resolution() {
+        let potencia = Builtin::from_name("potencia").unwrap();
+        assert_eq!(potencia.resolve(&[Type::Inteiro, Type::Inteiro]).unwrap().return_type, Type::Inteiro);
+        assert_eq!(potencia.resolve(&[Type::Inteiro, Type::Decimal]).unwrap().return_type, Type::Decimal);
+        assert_eq!(Builtin::Raiz.resolve(&[Type::Inteiro]).unwrap().parameters, &[Type::Decimal]);
+
+        let error = Builtin::Abs.resolve(&[Type::Texto]).unwrap_err();
+        assert!(error.contains("inteiro abs(inteiro), decimal abs(decimal)"));
+        assert!(Builtin::Piso.resolve(&[Type::Decimal, Type::Decimal]).is_err());
+        assert_eq!(Builtin::from_name("principal"), None);
+    }
+
+    #[test]
+    fn test_text_conversions() {
+        assert_eq!(parse_integer("-42"), Some(-42));
+        assert_eq!(parse_integer("+7"), Some(7));
+        assert_eq!(parse_integer("-9223372036854775808"), Some(i64::MIN));
+        for invalid in ["", "-", " 1", "1_000", "9223372036854775808", "2.0"] {
+            assert_eq!(parse_integer(invalid), None, "{:?}", invalid);
+        }
+
+        assert_eq!(parse_decimal("2.5"), Some(2.5));
+        assert_eq!(parse_decimal("-1e3"), Some(-1000.0));
+        assert_eq!(parse_decimal("1E+400"), Some(f64::INFINITY));
+        for invalid in ["", ".5", "5.", "1e", "nan", "inf", "0x10", "1.5 "] {
+            assert_eq!(parse_decimal(invalid), None, "{:?}", invalid);
+        }
+        assert_eq!(parse_logical("falso"), Some(false));
+        assert_eq!(parse_logical("Verdadeiro"), None);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::parser::ast::*;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+
+// Avisos opcionais, ligados no manifesto (`lints = [...]`). Não impedem a
+// compilação e rodam sobre o programa já analisado, com os módulos juntos.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Lint {
+    // variável declarada que nunca é lida (`leia` só escreve nela)
+    #[serde(rename = "variavel_nao_usada")]
+    UnusedVariable,
+    // função do programa que nunca é chamada, fora `principal`; funções de
+    // módulos importados ficam de fora, já que uma biblioteca não precisa
+    // ser usada inteira
+    #[serde(rename = "funcao_nao_usada")]
+    UnusedFunction,
+}
+
+impl Lint {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "variavel_nao_usada",
+            Lint::UnusedFunction => "funcao_nao_usada",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LintWarning {
+    pub lint: Lint,
+    pub message: String,
+    pub line: usize,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "aviso[{}]: {}", self.lint.name(), self.message)
+    }
+}
+
+pub fn lint(program: &Program, enabled: &[Lint]) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    if enabled.contains(&Lint::UnusedVariable) {
+        let mut checker = UnusedVariables::default();
+        checker.program(program);
+        warnings.extend(checker.warnings.into_iter().map(|(line, message)| LintWarning { lint: Lint::UnusedVariable, message, line }));
+    }
+    if enabled.contains(&Lint::UnusedFunction) {
+        warnings.extend(unused_functions(program).into_iter().map(|(line, message)| LintWarning { lint: Lint::UnusedFunction, message, line }));
+    }
+    warnings
+}
+
+#[derive(Default)]
+struct UnusedVariables {
+    // (nome, linha, lida) por escopo, na ordem de declaração
+    scopes: Vec<Vec<(String, usize, bool)>>,
+    function: Option<String>,
+    warnings: Vec<(usize, String)>,
+}
+
+impl UnusedVariables {
+    fn program(&mut self, program: &Program) {
+        self.scopes.push(Vec::new());
+        self.statements(&program.statements);
+        for function in &program.functions {
+            self.function = Some(function.name.clone());
+            // parâmetros não geram aviso
+            self.scopes.push(function.parameters.iter().map(|p| (p.name.clone(), function.line, true)).collect());
+            self.block(&function.body);
+            self.scopes.pop();
+        }
+        self.function = None;
+        self.exit_scope();
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.scopes.push(Vec::new());
+        self.statements(&block.statements);
+        self.exit_scope();
+    }
+
+    fn exit_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+        for (name, line, _) in scope.into_iter().filter(|(_, _, used)| !used) {
+            let message = match &self.function {
+                Some(function) => format!("Variável '{}' declarada mas nunca usada na função '{}'", name, function),
+                None => format!("Variável global '{}' declarada mas nunca usada", name),
+            };
+            self.warnings.push((line, message));
+        }
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            match statement {
+                Statement::VariableDecl(decl) => {
+                    if let Some(initializer) = &decl.initializer {
+                        self.expr(initializer);
+                    }
+                    if let Some(scope) = self.scopes.last_mut() {
+                        scope.push((decl.name.clone(), decl.line, false));
+                    }
+                }
+                Statement::ExprStmt(stmt) => self.expr(&stmt.expr),
+                Statement::IfStmt(stmt) => {
+                    self.expr(&stmt.condition);
+                    self.block(&stmt.then_branch);
+                    if let Some(else_branch) = &stmt.else_branch {
+                        self.block(else_branch);
+                    }
+                }
+                Statement::WhileStmt(stmt) => {
+                    self.expr(&stmt.condition);
+                    self.block(&stmt.body);
+                }
+                // a variável do `para` é usada pelo próprio laço
+                Statement::ForStmt(stmt) => {
+                    self.use_variable(&stmt.variable);
+                    self.expr(&stmt.start);
+                    self.expr(&stmt.end);
+                    self.block(&stmt.body);
+                }
+                Statement::ReturnStmt(stmt) => {
+                    if let Some(value) = &stmt.value {
+                        self.expr(value);
+                    }
+                }
+                Statement::WriteStmt(stmt) => stmt.arguments.iter().for_each(|argument| self.expr(argument)),
+                Statement::ReadStmt(stmt) => {
+                    if !matches!(stmt.target, Expr::Variable(_)) {
+                        self.expr(&stmt.target);
+                    }
+                }
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(_) => {}
+            Expr::Variable(name) => self.use_variable(name),
+            Expr::Call(call) => call.arguments.iter().for_each(|argument| self.expr(argument)),
+            Expr::BinaryOp(_, left, right) => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::UnaryOp(_, operand) => self.expr(operand),
+        }
+    }
+
+    fn use_variable(&mut self, name: &str) {
+        let declared = self.scopes.iter_mut().rev().find_map(|scope| scope.iter_mut().rev().find(|(n, _, _)| n == name));
+        if let Some((_, _, used)) = declared {
+            *used = true;
+        }
+    }
+}
+
+fn unused_functions(program: &Program) -> Vec<(usize, String)> {
+    let mut called = HashSet::new();
+    collect_calls(&program.statements, None, &mut called);
+    for function in &program.functions {
+        collect_calls(&function.body.statements, Some(&function.name), &mut called);
+    }
+
+    program.functions
+        .iter()
+        .filter(|f| f.name != "principal" && !f.name.contains('.') && !called.contains(f.name.as_str()))
+        .map(|f| (f.line, format!("Função '{}' nunca é chamada", f.name)))
+        .collect()
+}
+
+// chamadas de uma função a ela mesma não contam
+fn collect_calls<'a>(statements: &'a [Statement], caller: Option<&str>, called: &mut HashSet<&'a str>) {
+    for statement in statements {
+        match statement {
+            Statement::VariableDecl(decl) => decl.initializer.iter().for_each(|e| collect_expr_calls(e, caller, called)),
+            Statement::ExprStmt(stmt) => collect_expr_calls(&stmt.expr, caller, called),
+            Statement::IfStmt(stmt) => {
+                collect_expr_calls(&stmt.condition, caller, called);
+                collect_calls(&stmt.then_branch.statements, caller, called);
+                if let Some(else_branch) = &stmt.else_branch {
+                    collect_calls(&else_branch.statements, caller, called);
+                }
+            }
+            Statement::WhileStmt(stmt) => {
+                collect_expr_calls(&stmt.condition, caller, called);
+                collect_calls(&stmt.body.statements, caller, called);
+            }
+            Statement::ForStmt(stmt) => {
+                collect_expr_calls(&stmt.start, caller, called);
+                collect_expr_calls(&stmt.end, caller, called);
+                collect_calls(&stmt.body.statements, caller, called);
+            }
+            Statement::ReturnStmt(stmt) => stmt.value.iter().for_each(|e| collect_expr_calls(e, caller, called)),
+            Statement::WriteStmt(stmt) => stmt.arguments.iter().for_each(|e| collect_expr_calls(e, caller, called)),
+            Statement::ReadStmt(stmt) => collect_expr_calls(&stmt.target, caller, called),
+        }
+    }
+}
+
+fn collect_expr_calls<'a>(expr: &'a Expr, caller: Option<&str>, called: &mut HashSet<&'a str>) {
+    match expr {
+        Expr::Literal(_) | Expr::Variable(_) => {}
+        Expr::Call(call) => {
+            if caller != Some(call.function.as_str()) {
+                called.insert(&call.function);
+            }
+            call.arguments.iter().for_each(|argument| collect_expr_calls(argument, caller, called));
+        }
+        Expr::BinaryOp(_, left, right) => {
+            collect_expr_calls(left, caller, called);
+            collect_expr_calls(right, caller, called);
+        }
+        Expr::UnaryOp(_, operand) => collect_expr_calls(operand, caller, called),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_lints() {
+        let program = Parser::parse_from_source(
+            "funcao inteiro f(inteiro n) { retorne f(n - 1); }
+             funcao principal() { inteiro a = 1; inteiro b = 0; leia(b); escreva(a); }",
+        )
+        .unwrap();
+        let warnings: Vec<String> = lint(&program, &[Lint::UnusedVariable, Lint::UnusedFunction])
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                "aviso[variavel_nao_usada]: Variável 'b' declarada mas nunca usada na função 'principal'",
+                "aviso[funcao_nao_usada]: Função 'f' nunca é chamada",
+            ]
+        );
+        assert!(lint(&program, &[]).is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod symbol_table;
 pub mod type_checker;
 pub mod semantic;
+pub mod builtins;
+pub mod lints;
 
 pub use semantic::{SemanticAnalyzer, SemanticAnalysisResult, SemanticError};
-pub use symbol_table::SymbolTable;
\ No newline at end of file
+pub use symbol_table::SymbolTable;
+pub use builtins::Builtin;
\ No newline at end of file
@@ -1,6 +1,7 @@
 use crate::parser::ast::*;
 use crate::semantic::symbol_table::{SymbolTable, Symbol};
 use crate::semantic::type_checker::TypeChecker;
+use crate::semantic::builtins::Builtin;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,12 +28,35 @@ pub struct SemanticError {
     pub message: String,
     pub line: usize,
     pub column: usize,
+    // função do programa ligado onde está o erro (`modulo.funcao` nas dos
+    // módulos importados), para achar o arquivo; None fora de funções
+    #[serde(default)]
+    pub function: Option<String>,
+}
+
+impl std::fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // erros sem posição conhecida ficam com a linha 0
+        if self.line == 0 {
+            write!(f, "ERRO SEMÂNTICO: {}", self.message)
+        } else {
+            write!(f, "ERRO SEMÂNTICO: Linha {} - {}", self.line, self.message)
+        }
+    }
 }
 
 pub struct SemanticAnalyzer {
     symbol_table: SymbolTable,
     errors: Vec<SemanticError>,
     current_function_return: Option<Type>,
+    current_function: Option<String>,
+    current_line: usize,
+}
+
+impl Default for SemanticAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SemanticAnalyzer {
@@ -41,6 +65,8 @@ impl SemanticAnalyzer {
             symbol_table: SymbolTable::new(),
             errors: Vec::new(),
             current_function_return: None,
+            current_function: None,
+            current_line: 0,
         }
     }
 
@@ -60,28 +86,26 @@ impl SemanticAnalyzer {
     fn collect_declarations(&mut self, program: &Program) {
 
         for function in &program.functions {
+            self.current_line = function.line;
+            self.current_function = Some(function.name.clone());
+            if Builtin::from_name(&function.name).is_some() {
+                self.report_error(
+                    &format!("Função '{}' já existe na biblioteca padrão", function.name));
+                continue;
+            }
+
             let symbol = Symbol::Function {
                 declaration: function.clone(),
             };
             
             if let Err(e) = self.symbol_table.insert_symbol(function.name.clone(), symbol) {
-                self.report_error(&e, 0, 0);
-            }
-        }
-
-        for statement in &program.statements {
-            if let Statement::VariableDecl(decl) = statement {
-                let symbol = Symbol::Variable {
-                    name: decl.name.clone(),
-                    type_: decl.var_type.clone(),
-                    defined: decl.initializer.is_some(),
-                };
-                
-                if let Err(e) = self.symbol_table.insert_symbol(decl.name.clone(), symbol) {
-                    self.report_error(&e, 0, 0);
-                }
+                self.report_error(&e);
             }
         }
+        self.current_function = None;
+        // as variáveis globais entram na tabela quando a declaração é
+        // verificada; como os comandos globais vêm antes das funções, todas
+        // já estão lá quando os corpos das funções são verificados
     }
 
     fn check_and_annotate_program(&mut self, program: Program) -> Program {
@@ -105,12 +129,15 @@ impl SemanticAnalyzer {
         }
 
         Program {
+            imports: program.imports,
             functions: annotated_functions,
             statements: annotated_global_statements,
         }
     }
 
     fn check_and_annotate_function(&mut self, mut function: FunctionDecl) -> Result<FunctionDecl, ()> {
+        self.current_line = function.line;
+        self.current_function = Some(function.name.clone());
         self.symbol_table.enter_scope();
         
         for param in &function.parameters {
@@ -121,7 +148,7 @@ impl SemanticAnalyzer {
             };
             
             if let Err(e) = self.symbol_table.insert_symbol(param.name.clone(), symbol) {
-                self.report_error(&e, 0, 0);
+                self.report_error(&e);
                 self.symbol_table.exit_scope();
                 return Err(());
             }
@@ -159,6 +186,13 @@ impl SemanticAnalyzer {
     }
 
     fn check_and_annotate_statement(&mut self, statement: Statement) -> Result<AnnotatedStatement, ()> {
+        let outer_line = std::mem::replace(&mut self.current_line, statement.line());
+        let result = self.check_statement_kind(statement);
+        self.current_line = outer_line;
+        result
+    }
+
+    fn check_statement_kind(&mut self, statement: Statement) -> Result<AnnotatedStatement, ()> {
         let mut expr_annotations = Vec::new();
         let annotated_statement = match statement {
             Statement::VariableDecl(decl) => {
@@ -206,21 +240,19 @@ impl SemanticAnalyzer {
         };
         
         if let Err(e) = self.symbol_table.insert_symbol(decl.name.clone(), symbol) {
-            self.report_error(&e, 0, 0);
+            self.report_error(&e);
             return Err(());
         }
 
         if let Some(initializer) = decl.initializer {
             let (annotated_expr, expr_type) = self.check_and_annotate_expression(initializer)?;
             
-            if expr_type != decl.var_type {
+            if !TypeChecker::can_assign(&expr_type, &decl.var_type) {
                 self.report_error(
                     &format!(
                         "Tipo do inicializador ({}) não corresponde ao tipo da variável ({})",
                         expr_type, decl.var_type
-                    ),
-                    0, 0
-                );
+                    ));
                 return Err(());
             }
             
@@ -235,6 +267,7 @@ impl SemanticAnalyzer {
                 var_type: decl.var_type,
                 name: decl.name,
                 initializer: Some(annotated_expr.expr),
+                line: decl.line,
             }))
         } else {
             Ok(Statement::VariableDecl(decl))
@@ -246,6 +279,18 @@ impl SemanticAnalyzer {
         expr_stmt: ExprStmt,
         annotations: &mut Vec<AnnotatedExpr>,
     ) -> Result<Statement, ()> {
+        // a chamada de uma função sem retorno só vale aqui, como comando
+        if let Expr::Call(call) = &expr_stmt.expr {
+            match TypeChecker::check_call(call, &self.symbol_table) {
+                Ok(Some(_)) => {}
+                Ok(None) => return Ok(Statement::ExprStmt(expr_stmt)),
+                Err(e) => {
+                    self.report_error(&e);
+                    return Err(());
+                }
+            }
+        }
+
         let (annotated_expr, _) = self.check_and_annotate_expression(expr_stmt.expr)?;
         
         let new_annotated_expr = AnnotatedExpr {
@@ -257,6 +302,7 @@ impl SemanticAnalyzer {
         
         Ok(Statement::ExprStmt(ExprStmt {
             expr: annotated_expr.expr,
+            line: expr_stmt.line,
         }))
     }
 
@@ -268,7 +314,7 @@ impl SemanticAnalyzer {
         let (annotated_condition, condition_type) = self.check_and_annotate_expression(if_stmt.condition)?;
         
         if condition_type != Type::Logico {
-            self.report_error("Condição do if deve ser do tipo lógico", 0, 0);
+            self.report_error("Condição do if deve ser do tipo lógico");
             return Err(());
         }
         
@@ -286,6 +332,7 @@ impl SemanticAnalyzer {
             condition: annotated_condition.expr,
             then_branch,
             else_branch,
+            line: if_stmt.line,
         }))
     }
 
@@ -301,11 +348,9 @@ impl SemanticAnalyzer {
             (Some(value), Some(expected_type)) => {
                 let (annotated_value, actual_type) = self.check_and_annotate_expression(value)?;
                 
-                if actual_type != expected_type {
+                if !TypeChecker::can_assign(&actual_type, &expected_type) {
                     self.report_error(
-                        &format!("Tipo de retorno esperado: {}, encontrado: {}", expected_type, actual_type),
-                        0, 0
-                    );
+                        &format!("Tipo de retorno esperado: {}, encontrado: {}", expected_type, actual_type));
                     return Err(());
                 }
                 
@@ -318,17 +363,18 @@ impl SemanticAnalyzer {
                 
                 Ok(Statement::ReturnStmt(ReturnStmt {
                     value: Some(annotated_value.expr),
+                    line: return_stmt.line,
                 }))
             }
             (Some(_), None) => {
-                self.report_error("Retorno com valor em função sem tipo de retorno", 0, 0);
+                self.report_error("Retorno com valor em função sem tipo de retorno");
                 Err(())
             }
             (None, Some(_)) => {
-                self.report_error("Retorno sem valor em função com tipo de retorno", 0, 0);
+                self.report_error("Retorno sem valor em função com tipo de retorno");
                 Err(())
             }
-            (None, None) => Ok(Statement::ReturnStmt(ReturnStmt { value: None })),
+            (None, None) => Ok(Statement::ReturnStmt(ReturnStmt { value: None, line: return_stmt.line })),
         }
     }
 
@@ -339,17 +385,20 @@ impl SemanticAnalyzer {
                 Ok((annotated_expr, type_))
             }
             Err(e) => {
-                self.report_error(&e, 0, 0);
+                self.report_error(&e);
                 Err(())
             }
         }
     }
 
-    fn report_error(&mut self, message: &str, line: usize, column: usize) {
+    // a linha é a do comando (ou função) sendo verificado; colunas ainda não
+    // chegam à AST
+    fn report_error(&mut self, message: &str) {
         self.errors.push(SemanticError {
             message: message.to_string(),
-            line,
-            column,
+            line: self.current_line,
+            column: 0,
+            function: self.current_function.clone(),
         });
     }
 
@@ -362,7 +411,7 @@ impl SemanticAnalyzer {
         let (annotated_condition, condition_type) = self.check_and_annotate_expression(while_stmt.condition)?;
         
         if condition_type != Type::Logico {
-            self.report_error("Condição do while deve ser do tipo lógico", 0, 0);
+            self.report_error("Condição do while deve ser do tipo lógico");
             return Err(());
         }
         
@@ -377,13 +426,61 @@ impl SemanticAnalyzer {
         Ok(Statement::WhileStmt(WhileStmt {
             condition: annotated_condition.expr,
             body,
+            line: while_stmt.line,
         }))
     }
 
     fn check_for_statement(&mut self, for_stmt: ForStmt, annotations: &mut Vec<AnnotatedExpr>) -> Result<Statement, ()> {
+        // como nos backends, o `para` usa a variável inteira ou decimal que já
+        // existe e, se não houver, declara uma inteira que só vale no laço
+        self.symbol_table.enter_scope();
+        let result = self.check_for_loop(for_stmt, annotations);
+        self.symbol_table.exit_scope();
+        result
+    }
 
-        let (annotated_start, _) = self.check_and_annotate_expression(for_stmt.start.clone())?;
-        let (annotated_end, _) = self.check_and_annotate_expression(for_stmt.end.clone())?;
+    fn check_for_loop(&mut self, for_stmt: ForStmt, annotations: &mut Vec<AnnotatedExpr>) -> Result<Statement, ()> {
+        let variable_type = match self.symbol_table.lookup(&for_stmt.variable) {
+            Some(Symbol::Variable { type_: type_ @ (Type::Inteiro | Type::Decimal), .. }) => Some(type_.clone()),
+            None => None,
+            // o laço não esconde uma variável de outro tipo nem uma função
+            Some(Symbol::Variable { type_, .. }) => {
+                let message = format!("A variável '{}' do 'para' precisa ser inteira ou decimal, encontrado {}", for_stmt.variable, type_);
+                self.report_error(&message);
+                return Err(());
+            }
+            Some(Symbol::Function { .. }) => {
+                self.report_error(&format!("'{}' é uma função e não pode ser a variável do 'para'", for_stmt.variable));
+                return Err(());
+            }
+        };
+        if variable_type.is_none() {
+            let symbol = Symbol::Variable { name: for_stmt.variable.clone(), type_: Type::Inteiro, defined: true };
+            if let Err(e) = self.symbol_table.insert_symbol(for_stmt.variable.clone(), symbol) {
+                self.report_error(&e);
+                return Err(());
+            }
+        }
+
+        let (annotated_start, start_type) = self.check_and_annotate_expression(for_stmt.start.clone())?;
+        let (annotated_end, end_type) = self.check_and_annotate_expression(for_stmt.end.clone())?;
+        for type_ in [&start_type, &end_type] {
+            if !TypeChecker::can_assign(type_, &Type::Decimal) {
+                self.report_error(&format!("Os limites do 'para' devem ser numéricos, encontrado {}", type_));
+                return Err(());
+            }
+        }
+        // a variável inteira (ou a declarada pelo laço) não recebe limite decimal
+        let variable_type = variable_type.unwrap_or(Type::Inteiro);
+        for type_ in [&start_type, &end_type] {
+            if !TypeChecker::can_assign(type_, &variable_type) {
+                self.report_error(&format!(
+                    "Os limites do 'para' com a variável inteira '{}' devem ser inteiros, encontrado {}",
+                    for_stmt.variable, type_
+                ));
+                return Err(());
+            }
+        }
         
         let new_annotated_start = AnnotatedExpr {
             expr: annotated_start.expr.clone(),
@@ -403,6 +500,7 @@ impl SemanticAnalyzer {
             start: for_stmt.start,
             end: for_stmt.end,
             body,
+            line: for_stmt.line,
         }))
     }
 
@@ -426,6 +524,7 @@ impl SemanticAnalyzer {
         
         Ok(Statement::WriteStmt(WriteStmt {
             arguments: checked_arguments,
+            line: write_stmt.line,
         }))
     }
 
@@ -436,7 +535,7 @@ impl SemanticAnalyzer {
             Expr::Variable(_) => {
             }
             _ => {
-                self.report_error("Comando 'leia' só pode ser usado com variáveis", 0, 0);
+                self.report_error("Comando 'leia' só pode ser usado com variáveis");
                 return Err(());
             }
         }
@@ -449,6 +548,7 @@ impl SemanticAnalyzer {
         
         Ok(Statement::ReadStmt(ReadStmt {
             target: annotated_target.expr,
+            line: read_stmt.line,
         }))
     }
 }
\ No newline at end of file
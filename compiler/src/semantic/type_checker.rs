@@ -1,5 +1,6 @@
 use crate::parser::ast::{Expr, Type, Literal, BinaryOperator, UnaryOperator};
 use crate::semantic::symbol_table::SymbolTable;
+use crate::semantic::builtins::Builtin;
 
 #[derive(Debug)]
 pub struct TypeChecker;
@@ -50,6 +51,9 @@ impl TypeChecker {
         let right_type = Self::infer_expression_type(right, symbol_table)?;
 
         match op {
+            // com um texto de um dos lados, `+` concatena (o outro lado é
+            // escrito como paraTexto o escreveria)
+            BinaryOperator::Add if left_type == Type::Texto || right_type == Type::Texto => Ok(Type::Texto),
             BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply | BinaryOperator::Divide => {
                 Self::check_arithmetic_operation(&left_type, &right_type, op)
             }
@@ -61,7 +65,7 @@ impl TypeChecker {
                 }
             }
             BinaryOperator::Equal | BinaryOperator::NotEqual => {
-                if left_type == right_type {
+                if left_type == right_type || Self::check_arithmetic_operation(&left_type, &right_type, op).is_ok() {
                     Ok(Type::Logico)
                 } else {
                     Err(format!("Operador '{}' requer operandos do mesmo tipo: {} != {}", op, left_type, right_type))
@@ -95,13 +99,28 @@ impl TypeChecker {
                 if operand_type == Type::Logico {
                     Ok(Type::Logico)
                 } else {
-                    Err(format!("Operador '!' requer operando lógico"))
+                    Err("Operador '!' requer operando lógico".to_string())
                 }
             }
         }
     }
 
     fn infer_call_type(call_expr: &crate::parser::ast::CallExpr, symbol_table: &SymbolTable) -> Result<Type, String> {
+        Self::check_call(call_expr, symbol_table)?
+            .ok_or_else(|| format!("Função '{}' não tem tipo de retorno", call_expr.function))
+    }
+
+    // confere os argumentos de uma chamada; o tipo de retorno é None para
+    // funções sem retorno, que só podem ser chamadas como comando
+    pub fn check_call(call_expr: &crate::parser::ast::CallExpr, symbol_table: &SymbolTable) -> Result<Option<Type>, String> {
+        if let Some(builtin) = Builtin::from_name(&call_expr.function) {
+            let argument_types = call_expr.arguments
+                .iter()
+                .map(|arg| Self::infer_expression_type(arg, symbol_table))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(Some(builtin.resolve(&argument_types)?.return_type.clone()));
+        }
+
         let func_info = Self::get_function_info(&call_expr.function, symbol_table)?;
 
         if call_expr.arguments.len() != func_info.parameters.len() {
@@ -115,7 +134,7 @@ impl TypeChecker {
 
         for (i, (arg, param)) in call_expr.arguments.iter().zip(func_info.parameters.iter()).enumerate() {
             let arg_type = Self::infer_expression_type(arg, symbol_table)?;
-            if arg_type != param.param_type {
+            if !Self::can_assign(&arg_type, &param.param_type) {
                 return Err(format!(
                     "Argumento {} da função '{}': tipo esperado {}, encontrado {}",
                     i + 1,
@@ -126,9 +145,7 @@ impl TypeChecker {
             }
         }
 
-        func_info.return_type
-            .clone()
-            .ok_or_else(|| format!("Função '{}' não tem tipo de retorno", call_expr.function))
+        Ok(func_info.return_type)
     }
 
     fn get_function_info(function_name: &str, symbol_table: &SymbolTable) -> Result<FunctionInfo, String> {
@@ -147,11 +164,21 @@ impl TypeChecker {
         }
     }
 
+    // regra de coerção usada por declarações, argumentos e retornos (Symplia não
+    // tem atribuição fora da declaração): só o inteiro vira decimal sozinho;
+    // qualquer outra troca de tipo passa por paraInteiro, paraTexto etc.
+    pub fn can_assign(from: &Type, to: &Type) -> bool {
+        from == to || (*from == Type::Inteiro && *to == Type::Decimal)
+    }
+
     fn check_arithmetic_operation(left: &Type, right: &Type, op: &BinaryOperator) -> Result<Type, String> {
         match (left, right) {
             (Type::Inteiro, Type::Inteiro) => Ok(Type::Inteiro),
             (Type::Decimal, Type::Decimal) => Ok(Type::Decimal),
             (Type::Inteiro, Type::Decimal) | (Type::Decimal, Type::Inteiro) => Ok(Type::Decimal),
+            (Type::Texto, _) | (_, Type::Texto) => {
+                Err(format!("Operador '{}' não suportado para texto (só '+' junta textos)", op))
+            }
             _ => Err(format!("Operador '{}' não suportado para tipos {} e {}", op, left, right)),
         }
     }
@@ -1,22 +1,84 @@
 use serde::{Serialize, Deserialize};
 use crate::parser::ast::*;
 use crate::semantic::semantic::{SemanticAnalysisResult, AnnotatedExpr, AnnotatedStatement};
+use crate::ast_schema::{self, AST_FORMAT_VERSION};
 use std::fs;
 use chrono::Utc;
+use std::fmt;
 
 // ==================== ESTRUTURAS DE METADADOS ====================
 
 #[derive(Serialize, Deserialize)]
 pub struct ProgramMetadata {
+    // versão do formato do arquivo (ver `ast_schema`), não do compilador
+    pub format_version: u64,
     pub source_file: String,
     pub timestamp: String,
     pub version: String,
     pub entry_point: Option<String>,
 }
 
+// ==================== ERROS ====================
+
+// JSON bem formado que não descreve um programa válido; ferramentas externas
+// podem gerar ou transformar a AST, então a volta para `Program` não confia
+// no conteúdo
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerializationError {
+    pub message: String,
+}
+
+impl SerializationError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ERRO DE SERIALIZAÇÃO: {}", self.message)
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+// Aninhamento máximo (objetos e listas) de um arquivo .ast.json, conferido ao
+// gravar e ao ler: o limite de 128 do serde_json já recusava
+// `escreva(1 + 1 + ...)` com pouco mais de cem termos, e sem limite nenhum um
+// arquivo montado à mão estouraria a pilha
+pub const MAX_JSON_DEPTH: usize = 1000;
+
+fn check_json_depth(json: &str) -> Result<(), SerializationError> {
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    for byte in json.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth > MAX_JSON_DEPTH {
+            return Err(SerializationError::new(format!(
+                "a AST passa de {} níveis de aninhamento; simplifique as expressões mais longas",
+                MAX_JSON_DEPTH
+            )));
+        }
+    }
+    Ok(())
+}
+
 // ==================== ESTRUTURAS DE TIPOS ====================
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SerializableType {
     Inteiro,
     Decimal,
@@ -42,16 +104,83 @@ impl From<Type> for SerializableType {
     }
 }
 
+impl TryFrom<&SerializableType> for Type {
+    type Error = SerializationError;
+
+    fn try_from(ty: &SerializableType) -> Result<Self, Self::Error> {
+        match ty {
+            SerializableType::Inteiro => Ok(Type::Inteiro),
+            SerializableType::Decimal => Ok(Type::Decimal),
+            SerializableType::Texto => Ok(Type::Texto),
+            SerializableType::Logico => Ok(Type::Logico),
+            SerializableType::Void => Err(SerializationError::new("o tipo 'Void' só é válido como retorno de função")),
+        }
+    }
+}
+
 // ==================== ESTRUTURAS DE EXPRESSÕES ====================
 
 #[derive(Serialize, Deserialize)]
 pub enum SerializableLiteral {
     Inteiro(i64),
-    Decimal(f64),
+    Decimal(#[serde(with = "decimal")] f64),
     Texto(String),
     Logico(bool),
 }
 
+// O JSON não tem infinito nem NaN, e o serde_json gravaria `null`; o
+// dobramento de constantes produz esses valores a partir de programas válidos,
+// então eles vão como as strings "inf", "-inf" e "nan"
+mod decimal {
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        match *value {
+            n if n.is_finite() => serializer.serialize_f64(n),
+            n if n.is_nan() => serializer.serialize_str("nan"),
+            n if n > 0.0 => serializer.serialize_str("inf"),
+            _ => serializer.serialize_str("-inf"),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+
+    struct DecimalVisitor;
+
+    impl Visitor<'_> for DecimalVisitor {
+        type Value = f64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "um número ou \"inf\", \"-inf\" ou \"nan\"")
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<f64, E> {
+            match value {
+                "inf" => Ok(f64::INFINITY),
+                "-inf" => Ok(f64::NEG_INFINITY),
+                "nan" => Ok(f64::NAN),
+                _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+            }
+        }
+    }
+}
+
 impl From<&Literal> for SerializableLiteral {
     fn from(literal: &Literal) -> Self {
         match literal {
@@ -63,7 +192,18 @@ impl From<&Literal> for SerializableLiteral {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+impl From<&SerializableLiteral> for Literal {
+    fn from(literal: &SerializableLiteral) -> Self {
+        match literal {
+            SerializableLiteral::Inteiro(n) => Literal::Inteiro(*n),
+            SerializableLiteral::Decimal(n) => Literal::Decimal(*n),
+            SerializableLiteral::Texto(s) => Literal::Texto(s.clone()),
+            SerializableLiteral::Logico(b) => Literal::Logico(*b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SerializableBinaryOperator {
     Add,
     Subtract,
@@ -100,7 +240,27 @@ impl From<&BinaryOperator> for SerializableBinaryOperator {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+impl From<&SerializableBinaryOperator> for BinaryOperator {
+    fn from(op: &SerializableBinaryOperator) -> Self {
+        match op {
+            SerializableBinaryOperator::Add => BinaryOperator::Add,
+            SerializableBinaryOperator::Subtract => BinaryOperator::Subtract,
+            SerializableBinaryOperator::Multiply => BinaryOperator::Multiply,
+            SerializableBinaryOperator::Divide => BinaryOperator::Divide,
+            SerializableBinaryOperator::Modulo => BinaryOperator::Modulo,
+            SerializableBinaryOperator::Equal => BinaryOperator::Equal,
+            SerializableBinaryOperator::NotEqual => BinaryOperator::NotEqual,
+            SerializableBinaryOperator::Less => BinaryOperator::Less,
+            SerializableBinaryOperator::LessEqual => BinaryOperator::LessEqual,
+            SerializableBinaryOperator::Greater => BinaryOperator::Greater,
+            SerializableBinaryOperator::GreaterEqual => BinaryOperator::GreaterEqual,
+            SerializableBinaryOperator::And => BinaryOperator::And,
+            SerializableBinaryOperator::Or => BinaryOperator::Or,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SerializableUnaryOperator {
     Negate,
     Plus,
@@ -117,6 +277,16 @@ impl From<&UnaryOperator> for SerializableUnaryOperator {
     }
 }
 
+impl From<&SerializableUnaryOperator> for UnaryOperator {
+    fn from(op: &SerializableUnaryOperator) -> Self {
+        match op {
+            SerializableUnaryOperator::Negate => UnaryOperator::Negate,
+            SerializableUnaryOperator::Plus => UnaryOperator::Plus,
+            SerializableUnaryOperator::Not => UnaryOperator::Not,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableCallExpr {
     pub function: String,
@@ -127,7 +297,16 @@ impl From<&CallExpr> for SerializableCallExpr {
     fn from(call: &CallExpr) -> Self {
         SerializableCallExpr {
             function: call.function.clone(),
-            arguments: call.arguments.iter().map(|expr| SerializableExpr::from_expr(expr)).collect(),
+            arguments: call.arguments.iter().map(SerializableExpr::from_expr).collect(),
+        }
+    }
+}
+
+impl From<&SerializableCallExpr> for CallExpr {
+    fn from(call: &SerializableCallExpr) -> Self {
+        CallExpr {
+            function: call.function.clone(),
+            arguments: call.arguments.iter().map(Expr::from).collect(),
         }
     }
 }
@@ -227,6 +406,23 @@ impl From<&AnnotatedExpr> for SerializableExpr {
     }
 }
 
+// `expr_type` é só anotação da análise semântica e não faz parte da AST
+impl From<&SerializableExpr> for Expr {
+    fn from(expr: &SerializableExpr) -> Self {
+        match expr {
+            SerializableExpr::Literal { value, .. } => Expr::Literal(Literal::from(value)),
+            SerializableExpr::Variable { name, .. } => Expr::Variable(name.clone()),
+            SerializableExpr::Call { call, .. } => Expr::Call(CallExpr::from(call)),
+            SerializableExpr::BinaryOp { op, left, right, .. } => {
+                Expr::BinaryOp(BinaryOperator::from(op), Box::new(Expr::from(left.as_ref())), Box::new(Expr::from(right.as_ref())))
+            }
+            SerializableExpr::UnaryOp { op, operand, .. } => {
+                Expr::UnaryOp(UnaryOperator::from(op), Box::new(Expr::from(operand.as_ref())))
+            }
+        }
+    }
+}
+
 // ==================== ESTRUTURAS DE PARÂMETROS ====================
 
 #[derive(Serialize, Deserialize)]
@@ -244,6 +440,16 @@ impl From<&Parameter> for SerializableParameter {
     }
 }
 
+impl TryFrom<&SerializableParameter> for Parameter {
+    type Error = SerializationError;
+
+    fn try_from(param: &SerializableParameter) -> Result<Self, Self::Error> {
+        let param_type = Type::try_from(&param.param_type)
+            .map_err(|e| SerializationError::new(format!("parâmetro '{}': {}", param.name, e.message)))?;
+        Ok(Parameter { param_type, name: param.name.clone() })
+    }
+}
+
 // ==================== ESTRUTURAS DE BLOCO ====================
 
 #[derive(Serialize, Deserialize)]
@@ -259,13 +465,31 @@ impl From<&Block> for SerializableBlock {
     }
 }
 
+impl TryFrom<&SerializableBlock> for Block {
+    type Error = SerializationError;
+
+    fn try_from(block: &SerializableBlock) -> Result<Self, Self::Error> {
+        let statements = block.statements.iter().map(Statement::try_from).collect::<Result<_, _>>()?;
+        Ok(Block { statements })
+    }
+}
+
 // ==================== ESTRUTURAS DE STATEMENTS ====================
 
+// As versões anotadas recebem as expressões tipadas da análise semântica na
+// ordem em que aparecem no statement; sem anotação, usam a expressão da AST.
+
+fn annotated_or(annotations: &[AnnotatedExpr], index: usize, expr: &Expr) -> SerializableExpr {
+    annotations.get(index).map_or_else(|| SerializableExpr::from_expr(expr), SerializableExpr::from)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableVariableDecl {
     pub var_type: SerializableType,
     pub name: String,
     pub initializer: Option<SerializableExpr>,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&VariableDecl> for SerializableVariableDecl {
@@ -274,33 +498,40 @@ impl From<&VariableDecl> for SerializableVariableDecl {
             var_type: SerializableType::from(&decl.var_type),
             name: decl.name.clone(),
             initializer: decl.initializer.as_ref().map(SerializableExpr::from_expr),
+            line: decl.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableVariableDecl {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::VariableDecl(decl) = &annotated.statement {
-            let initializer = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                Some(SerializableExpr::from(expr_annotation))
-            } else {
-                decl.initializer.as_ref().map(SerializableExpr::from_expr)
-            };
-            
-            SerializableVariableDecl {
-                var_type: SerializableType::from(&decl.var_type),
-                name: decl.name.clone(),
-                initializer,
-            }
-        } else {
-            panic!("Expected VariableDecl statement");
+impl SerializableVariableDecl {
+    fn annotated(decl: &VariableDecl, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableVariableDecl {
+            initializer: decl.initializer.as_ref().map(|expr| annotated_or(annotations, 0, expr)),
+            ..Self::from(decl)
         }
     }
 }
 
+impl TryFrom<&SerializableVariableDecl> for VariableDecl {
+    type Error = SerializationError;
+
+    fn try_from(decl: &SerializableVariableDecl) -> Result<Self, Self::Error> {
+        let var_type = Type::try_from(&decl.var_type)
+            .map_err(|e| SerializationError::new(format!("variável '{}': {}", decl.name, e.message)))?;
+        Ok(VariableDecl {
+            var_type,
+            name: decl.name.clone(),
+            initializer: decl.initializer.as_ref().map(Expr::from),
+            line: decl.line,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableExprStmt {
     pub expr: SerializableExpr,
+    #[serde(default)]
+    pub line: usize,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -308,6 +539,8 @@ pub struct SerializableIfStmt {
     pub condition: SerializableExpr,
     pub then_branch: SerializableBlock,
     pub else_branch: Option<SerializableBlock>,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&IfStmt> for SerializableIfStmt {
@@ -316,34 +549,39 @@ impl From<&IfStmt> for SerializableIfStmt {
             condition: SerializableExpr::from_expr(&stmt.condition),
             then_branch: SerializableBlock::from(&stmt.then_branch),
             else_branch: stmt.else_branch.as_ref().map(SerializableBlock::from),
+            line: stmt.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableIfStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::IfStmt(if_stmt) = &annotated.statement {
-            let condition = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                SerializableExpr::from(expr_annotation)
-            } else {
-                SerializableExpr::from_expr(&if_stmt.condition)
-            };
-            
-            SerializableIfStmt {
-                condition,
-                then_branch: SerializableBlock::from(&if_stmt.then_branch),
-                else_branch: if_stmt.else_branch.as_ref().map(SerializableBlock::from),
-            }
-        } else {
-            panic!("Expected IfStmt statement");
+impl SerializableIfStmt {
+    fn annotated(stmt: &IfStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableIfStmt {
+            condition: annotated_or(annotations, 0, &stmt.condition),
+            ..Self::from(stmt)
         }
     }
 }
 
+impl TryFrom<&SerializableIfStmt> for IfStmt {
+    type Error = SerializationError;
+
+    fn try_from(stmt: &SerializableIfStmt) -> Result<Self, Self::Error> {
+        Ok(IfStmt {
+            condition: Expr::from(&stmt.condition),
+            then_branch: Block::try_from(&stmt.then_branch)?,
+            else_branch: stmt.else_branch.as_ref().map(Block::try_from).transpose()?,
+            line: stmt.line,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableWhileStmt {
     pub condition: SerializableExpr,
     pub body: SerializableBlock,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&WhileStmt> for SerializableWhileStmt {
@@ -351,35 +589,40 @@ impl From<&WhileStmt> for SerializableWhileStmt {
         SerializableWhileStmt {
             condition: SerializableExpr::from_expr(&stmt.condition),
             body: SerializableBlock::from(&stmt.body),
+            line: stmt.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableWhileStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::WhileStmt(while_stmt) = &annotated.statement {
-            let condition = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                SerializableExpr::from(expr_annotation)
-            } else {
-                SerializableExpr::from_expr(&while_stmt.condition)
-            };
-            
-            SerializableWhileStmt {
-                condition,
-                body: SerializableBlock::from(&while_stmt.body),
-            }
-        } else {
-            panic!("Expected WhileStmt statement");
+impl SerializableWhileStmt {
+    fn annotated(stmt: &WhileStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableWhileStmt {
+            condition: annotated_or(annotations, 0, &stmt.condition),
+            ..Self::from(stmt)
         }
     }
 }
 
+impl TryFrom<&SerializableWhileStmt> for WhileStmt {
+    type Error = SerializationError;
+
+    fn try_from(stmt: &SerializableWhileStmt) -> Result<Self, Self::Error> {
+        Ok(WhileStmt {
+            condition: Expr::from(&stmt.condition),
+            body: Block::try_from(&stmt.body)?,
+            line: stmt.line,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableForStmt {
     pub variable: String,
     pub start: SerializableExpr,
     pub end: SerializableExpr,
     pub body: SerializableBlock,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&ForStmt> for SerializableForStmt {
@@ -389,65 +632,56 @@ impl From<&ForStmt> for SerializableForStmt {
             start: SerializableExpr::from_expr(&stmt.start),
             end: SerializableExpr::from_expr(&stmt.end),
             body: SerializableBlock::from(&stmt.body),
+            line: stmt.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableForStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::ForStmt(for_stmt) = &annotated.statement {
-            let start = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                SerializableExpr::from(expr_annotation)
-            } else {
-                SerializableExpr::from_expr(&for_stmt.start)
-            };
-            
-            let end = if let Some(expr_annotation) = annotated.expr_annotations.get(1) {
-                SerializableExpr::from(expr_annotation)
-            } else {
-                SerializableExpr::from_expr(&for_stmt.end)
-            };
-            
-            SerializableForStmt {
-                variable: for_stmt.variable.clone(),
-                start,
-                end,
-                body: SerializableBlock::from(&for_stmt.body),
-            }
-        } else {
-            panic!("Expected ForStmt statement");
+impl SerializableForStmt {
+    fn annotated(stmt: &ForStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableForStmt {
+            start: annotated_or(annotations, 0, &stmt.start),
+            end: annotated_or(annotations, 1, &stmt.end),
+            ..Self::from(stmt)
         }
     }
 }
 
+impl TryFrom<&SerializableForStmt> for ForStmt {
+    type Error = SerializationError;
+
+    fn try_from(stmt: &SerializableForStmt) -> Result<Self, Self::Error> {
+        Ok(ForStmt {
+            variable: stmt.variable.clone(),
+            start: Expr::from(&stmt.start),
+            end: Expr::from(&stmt.end),
+            body: Block::try_from(&stmt.body)?,
+            line: stmt.line,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct SerializableReturnStmt {
     pub value: Option<SerializableExpr>,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&ReturnStmt> for SerializableReturnStmt {
     fn from(stmt: &ReturnStmt) -> Self {
         SerializableReturnStmt {
             value: stmt.value.as_ref().map(SerializableExpr::from_expr),
+            line: stmt.line,
         }
     }
 }
 
-// CORREÇÃO: Implementação simplificada para ReturnStmt anotado
-impl From<&AnnotatedStatement> for SerializableReturnStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::ReturnStmt(return_stmt) = &annotated.statement {
-            let value = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                Some(SerializableExpr::from(expr_annotation))
-            } else {
-                return_stmt.value.as_ref().map(SerializableExpr::from_expr)
-            };
-            
-            SerializableReturnStmt {
-                value,
-            }
-        } else {
-            panic!("Expected ReturnStmt statement");
+impl SerializableReturnStmt {
+    fn annotated(stmt: &ReturnStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableReturnStmt {
+            value: stmt.value.as_ref().map(|expr| annotated_or(annotations, 0, expr)),
+            line: stmt.line,
         }
     }
 }
@@ -455,30 +689,24 @@ impl From<&AnnotatedStatement> for SerializableReturnStmt {
 #[derive(Serialize, Deserialize)]
 pub struct SerializableWriteStmt {
     pub arguments: Vec<SerializableExpr>,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&WriteStmt> for SerializableWriteStmt {
     fn from(stmt: &WriteStmt) -> Self {
         SerializableWriteStmt {
             arguments: stmt.arguments.iter().map(SerializableExpr::from_expr).collect(),
+            line: stmt.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableWriteStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::WriteStmt(write_stmt) = &annotated.statement {
-            let arguments = if !annotated.expr_annotations.is_empty() {
-                annotated.expr_annotations.iter().map(SerializableExpr::from).collect()
-            } else {
-                write_stmt.arguments.iter().map(SerializableExpr::from_expr).collect()
-            };
-            
-            SerializableWriteStmt {
-                arguments,
-            }
-        } else {
-            panic!("Expected WriteStmt statement");
+impl SerializableWriteStmt {
+    fn annotated(stmt: &WriteStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableWriteStmt {
+            arguments: stmt.arguments.iter().enumerate().map(|(i, expr)| annotated_or(annotations, i, expr)).collect(),
+            line: stmt.line,
         }
     }
 }
@@ -486,30 +714,24 @@ impl From<&AnnotatedStatement> for SerializableWriteStmt {
 #[derive(Serialize, Deserialize)]
 pub struct SerializableReadStmt {
     pub target: SerializableExpr,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&ReadStmt> for SerializableReadStmt {
     fn from(stmt: &ReadStmt) -> Self {
         SerializableReadStmt {
             target: SerializableExpr::from_expr(&stmt.target),
+            line: stmt.line,
         }
     }
 }
 
-impl From<&AnnotatedStatement> for SerializableReadStmt {
-    fn from(annotated: &AnnotatedStatement) -> Self {
-        if let Statement::ReadStmt(read_stmt) = &annotated.statement {
-            let target = if let Some(expr_annotation) = annotated.expr_annotations.get(0) {
-                SerializableExpr::from(expr_annotation)
-            } else {
-                SerializableExpr::from_expr(&read_stmt.target)
-            };
-            
-            SerializableReadStmt {
-                target,
-            }
-        } else {
-            panic!("Expected ReadStmt statement");
+impl SerializableReadStmt {
+    fn annotated(stmt: &ReadStmt, annotations: &[AnnotatedExpr]) -> Self {
+        SerializableReadStmt {
+            target: annotated_or(annotations, 0, &stmt.target),
+            line: stmt.line,
         }
     }
 }
@@ -529,32 +751,37 @@ pub enum SerializableStatement {
 
 impl SerializableStatement {
     pub fn from_stmt(stmt: &Statement) -> Self {
+        Self::annotated(stmt, &[])
+    }
+
+    fn annotated(stmt: &Statement, annotations: &[AnnotatedExpr]) -> Self {
         match stmt {
             Statement::VariableDecl(decl) => {
-                SerializableStatement::VariableDecl(SerializableVariableDecl::from(decl))
+                SerializableStatement::VariableDecl(SerializableVariableDecl::annotated(decl, annotations))
             }
             Statement::ExprStmt(expr_stmt) => {
                 SerializableStatement::ExprStmt(SerializableExprStmt {
-                    expr: SerializableExpr::from_expr(&expr_stmt.expr),
+                    expr: annotated_or(annotations, 0, &expr_stmt.expr),
+                    line: expr_stmt.line,
                 })
             }
             Statement::IfStmt(if_stmt) => {
-                SerializableStatement::IfStmt(SerializableIfStmt::from(if_stmt))
+                SerializableStatement::IfStmt(SerializableIfStmt::annotated(if_stmt, annotations))
             }
             Statement::WhileStmt(while_stmt) => {
-                SerializableStatement::WhileStmt(SerializableWhileStmt::from(while_stmt))
+                SerializableStatement::WhileStmt(SerializableWhileStmt::annotated(while_stmt, annotations))
             }
             Statement::ForStmt(for_stmt) => {
-                SerializableStatement::ForStmt(SerializableForStmt::from(for_stmt))
+                SerializableStatement::ForStmt(SerializableForStmt::annotated(for_stmt, annotations))
             }
             Statement::ReturnStmt(return_stmt) => {
-                SerializableStatement::ReturnStmt(SerializableReturnStmt::from(return_stmt))
+                SerializableStatement::ReturnStmt(SerializableReturnStmt::annotated(return_stmt, annotations))
             }
             Statement::WriteStmt(write_stmt) => {
-                SerializableStatement::WriteStmt(SerializableWriteStmt::from(write_stmt))
+                SerializableStatement::WriteStmt(SerializableWriteStmt::annotated(write_stmt, annotations))
             }
             Statement::ReadStmt(read_stmt) => {
-                SerializableStatement::ReadStmt(SerializableReadStmt::from(read_stmt))
+                SerializableStatement::ReadStmt(SerializableReadStmt::annotated(read_stmt, annotations))
             }
         }
     }
@@ -566,41 +793,34 @@ impl From<&Statement> for SerializableStatement {
     }
 }
 
-// CORREÇÃO: Implementação simplificada para AnnotatedStatement
 impl From<&AnnotatedStatement> for SerializableStatement {
     fn from(annotated: &AnnotatedStatement) -> Self {
-        match &annotated.statement {
-            Statement::VariableDecl(_) => {
-                SerializableStatement::VariableDecl(SerializableVariableDecl::from(annotated))
-            }
-            Statement::ExprStmt(_) => {
-                if let Some(annotated_expr) = annotated.expr_annotations.get(0) {
-                    SerializableStatement::ExprStmt(SerializableExprStmt {
-                        expr: SerializableExpr::from(annotated_expr),
-                    })
-                } else {
-                    Self::from_stmt(&annotated.statement)
-                }
-            }
-            Statement::IfStmt(_) => {
-                SerializableStatement::IfStmt(SerializableIfStmt::from(annotated))
-            }
-            Statement::WhileStmt(_) => {
-                SerializableStatement::WhileStmt(SerializableWhileStmt::from(annotated))
-            }
-            Statement::ForStmt(_) => {
-                SerializableStatement::ForStmt(SerializableForStmt::from(annotated))
+        SerializableStatement::annotated(&annotated.statement, &annotated.expr_annotations)
+    }
+}
+
+impl TryFrom<&SerializableStatement> for Statement {
+    type Error = SerializationError;
+
+    fn try_from(stmt: &SerializableStatement) -> Result<Self, Self::Error> {
+        Ok(match stmt {
+            SerializableStatement::VariableDecl(decl) => Statement::VariableDecl(VariableDecl::try_from(decl)?),
+            SerializableStatement::ExprStmt(stmt) => {
+                Statement::ExprStmt(ExprStmt { expr: Expr::from(&stmt.expr), line: stmt.line })
             }
-            Statement::ReturnStmt(_) => {
-                SerializableStatement::ReturnStmt(SerializableReturnStmt::from(annotated))
+            SerializableStatement::IfStmt(stmt) => Statement::IfStmt(IfStmt::try_from(stmt)?),
+            SerializableStatement::WhileStmt(stmt) => Statement::WhileStmt(WhileStmt::try_from(stmt)?),
+            SerializableStatement::ForStmt(stmt) => Statement::ForStmt(ForStmt::try_from(stmt)?),
+            SerializableStatement::ReturnStmt(stmt) => {
+                Statement::ReturnStmt(ReturnStmt { value: stmt.value.as_ref().map(Expr::from), line: stmt.line })
             }
-            Statement::WriteStmt(_) => {
-                SerializableStatement::WriteStmt(SerializableWriteStmt::from(annotated))
+            SerializableStatement::WriteStmt(stmt) => {
+                Statement::WriteStmt(WriteStmt { arguments: stmt.arguments.iter().map(Expr::from).collect(), line: stmt.line })
             }
-            Statement::ReadStmt(_) => {
-                SerializableStatement::ReadStmt(SerializableReadStmt::from(annotated))
+            SerializableStatement::ReadStmt(stmt) => {
+                Statement::ReadStmt(ReadStmt { target: Expr::from(&stmt.target), line: stmt.line })
             }
-        }
+        })
     }
 }
 
@@ -612,6 +832,8 @@ pub struct SerializableFunction {
     pub return_type: Option<SerializableType>,
     pub parameters: Vec<SerializableParameter>,
     pub body: SerializableBlock,
+    #[serde(default)]
+    pub line: usize,
 }
 
 impl From<&FunctionDecl> for SerializableFunction {
@@ -621,14 +843,38 @@ impl From<&FunctionDecl> for SerializableFunction {
             return_type: func.return_type.as_ref().map(SerializableType::from),
             parameters: func.parameters.iter().map(SerializableParameter::from).collect(),
             body: SerializableBlock::from(&func.body),
+            line: func.line,
         }
     }
 }
 
+impl TryFrom<&SerializableFunction> for FunctionDecl {
+    type Error = SerializationError;
+
+    fn try_from(func: &SerializableFunction) -> Result<Self, Self::Error> {
+        let in_function = |e: SerializationError| SerializationError::new(format!("função '{}': {}", func.name, e.message));
+        // `Void` como retorno é o mesmo que função sem tipo de retorno
+        let return_type = match &func.return_type {
+            None | Some(SerializableType::Void) => None,
+            Some(ty) => Some(Type::try_from(ty).map_err(in_function)?),
+        };
+        Ok(FunctionDecl {
+            return_type,
+            name: func.name.clone(),
+            parameters: func.parameters.iter().map(Parameter::try_from).collect::<Result<_, _>>().map_err(in_function)?,
+            body: Block::try_from(&func.body).map_err(in_function)?,
+            line: func.line,
+        })
+    }
+}
+
 // ==================== ESTRUTURA PRINCIPAL DO PROGRAMA ====================
 
 #[derive(Serialize, Deserialize)]
 pub struct SerializableProgram {
+    // caminhos dos `importe` ainda não resolvidos
+    #[serde(default)]
+    pub imports: Vec<String>,
     pub functions: Vec<SerializableFunction>,
     pub global_statements: Vec<SerializableStatement>,
     pub metadata: ProgramMetadata,
@@ -637,9 +883,11 @@ pub struct SerializableProgram {
 impl From<&Program> for SerializableProgram {
     fn from(program: &Program) -> Self {
         SerializableProgram {
+            imports: program.imports.iter().map(|import| import.path.clone()).collect(),
             functions: program.functions.iter().map(SerializableFunction::from).collect(),
             global_statements: program.statements.iter().map(SerializableStatement::from_stmt).collect(),
             metadata: ProgramMetadata {
+                format_version: AST_FORMAT_VERSION,
                 source_file: "unknown".to_string(),
                 timestamp: Utc::now().to_rfc3339(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -652,10 +900,12 @@ impl From<&Program> for SerializableProgram {
 impl From<&SemanticAnalysisResult> for SerializableProgram {
     fn from(result: &SemanticAnalysisResult) -> Self {
         SerializableProgram {
+            imports: result.annotated_ast.imports.iter().map(|import| import.path.clone()).collect(),
             functions: result.annotated_ast.functions.iter().map(SerializableFunction::from).collect(),
             global_statements: result.annotated_ast.statements.iter().map(SerializableStatement::from).collect(),
             metadata: ProgramMetadata {
-                source_file: "unknown".to_string(), 
+                format_version: AST_FORMAT_VERSION,
+                source_file: "unknown".to_string(),
                 timestamp: Utc::now().to_rfc3339(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
                 entry_point: Some("principal".to_string()),
@@ -664,11 +914,25 @@ impl From<&SemanticAnalysisResult> for SerializableProgram {
     }
 }
 
+// `metadata` não faz parte do programa e é descartado na volta
+impl TryFrom<&SerializableProgram> for Program {
+    type Error = SerializationError;
+
+    fn try_from(program: &SerializableProgram) -> Result<Self, Self::Error> {
+        Ok(Program {
+            imports: program.imports.iter().map(|path| Import { path: path.clone() }).collect(),
+            functions: program.functions.iter().map(FunctionDecl::try_from).collect::<Result<_, _>>()?,
+            statements: program.global_statements.iter().map(Statement::try_from).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 // ==================== FUNÇÕES PÚBLICAS ====================
 
 pub fn save_program_to_json(program: &Program, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
     let serializable_program = SerializableProgram::from(program);
     let json = serde_json::to_string_pretty(&serializable_program)?;
+    check_json_depth(&json)?;
     fs::write(filename, json)?;
     Ok(())
 }
@@ -679,14 +943,28 @@ pub fn save_semantic_result_to_json(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let serializable_program = SerializableProgram::from(result);
     let json = serde_json::to_string_pretty(&serializable_program)?;
+    check_json_depth(&json)?;
     fs::write(filename, json)?;
     Ok(())
 }
 
-pub fn load_program_from_json(filename: &str) -> Result<SerializableProgram, Box<dyn std::error::Error>> {
+// Arquivos de versões anteriores do formato são migrados antes de validados
+pub fn program_from_json(json: &str) -> Result<Program, Box<dyn std::error::Error>> {
+    // o limite é o de MAX_JSON_DEPTH, não o do serde_json
+    check_json_depth(json)?;
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    deserializer.disable_recursion_limit();
+    let value = serde_json::Value::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    let value = ast_schema::migrate(value)?;
+    ast_schema::validate(&value)?;
+    let program: SerializableProgram = serde_json::from_value(value)?;
+    Ok(Program::try_from(&program)?)
+}
+
+pub fn load_program_from_json(filename: &str) -> Result<Program, Box<dyn std::error::Error>> {
     let json = fs::read_to_string(filename)?;
-    let program: SerializableProgram = serde_json::from_str(&json)?;
-    Ok(program)
+    program_from_json(&json)
 }
 
 // ==================== TESTES ====================
@@ -718,6 +996,7 @@ mod tests {
     #[test]
     fn test_serialize_empty_program() {
         let program = Program {
+            imports: vec![],
             functions: vec![],
             statements: vec![],
         };
@@ -733,6 +1012,7 @@ mod tests {
     #[test]
     fn test_save_and_load_json() {
         let program = Program {
+            imports: vec![],
             functions: vec![],
             statements: vec![],
         };
@@ -741,10 +1021,193 @@ mod tests {
         save_program_to_json(&program, test_file).unwrap();
         
         let loaded = load_program_from_json(test_file).unwrap();
-        assert!(loaded.functions.is_empty());
-        assert!(loaded.global_statements.is_empty());
+        assert_eq!(loaded, program);
         
         // Limpar
         let _ = fs::remove_file(test_file);
     }
-}
\ No newline at end of file
+
+    fn round_trip(program: &Program) -> Program {
+        let json = serde_json::to_string(&SerializableProgram::from(program)).unwrap();
+        program_from_json(&json).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_examples() {
+        use crate::parser::Parser;
+        use crate::semantic::semantic::SemanticAnalyzer;
+
+        let sources = [
+            include_str!("../../programas/exemplo3.sym"),
+            include_str!("../../programas/exemplo4.sym"),
+            include_str!("../../programas/exemplo5.sym"),
+            include_str!("../../programas/teste_simples.sym"),
+            "importe \"util.sym\"; funcao logico f(decimal x) { retorne -x <= 2.5 || !verdadeiro; }",
+        ];
+        for source in sources {
+            let program = Parser::parse_from_source(source).unwrap();
+            assert_eq!(round_trip(&program), program);
+
+            // a saída anotada da análise volta para a mesma AST
+            let result = SemanticAnalyzer::new().analyze(program);
+            let json = serde_json::to_string(&SerializableProgram::from(&result)).unwrap();
+            assert_eq!(program_from_json(&json).unwrap(), result.annotated_ast);
+        }
+    }
+
+    // gerador pseudoaleatório simples (LCG) para não depender de crates de teste
+    struct Gen(u64);
+
+    impl Gen {
+        fn next(&mut self, n: u64) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (self.0 >> 33) % n
+        }
+
+        fn name(&mut self) -> String {
+            ["a", "b", "soma", "mod.f", "ação"][self.next(5) as usize].to_string()
+        }
+
+        fn ty(&mut self) -> Type {
+            [Type::Inteiro, Type::Decimal, Type::Texto, Type::Logico][self.next(4) as usize].clone()
+        }
+
+        fn expr(&mut self, depth: u32) -> Expr {
+            match if depth == 0 { self.next(2) } else { self.next(5) } {
+                0 => Expr::Literal(match self.next(4) {
+                    0 => Literal::Inteiro(self.next(u64::MAX) as i64),
+                    1 => Literal::Decimal(self.next(1 << 20) as f64 / 7.0 - 1000.0),
+                    2 => Literal::Texto(["", "olá \"mundo\"\n", "\u{1F600}"][self.next(3) as usize].to_string()),
+                    _ => Literal::Logico(self.next(2) == 0),
+                }),
+                1 => Expr::Variable(self.name()),
+                2 => Expr::Call(CallExpr { function: self.name(), arguments: (0..self.next(3)).map(|_| self.expr(depth - 1)).collect() }),
+                3 => Expr::BinaryOp(BinaryOperator::from(&BINARY[self.next(13) as usize]), Box::new(self.expr(depth - 1)), Box::new(self.expr(depth - 1))),
+                _ => Expr::UnaryOp([UnaryOperator::Negate, UnaryOperator::Plus, UnaryOperator::Not][self.next(3) as usize].clone(), Box::new(self.expr(depth - 1))),
+            }
+        }
+
+        fn block(&mut self, depth: u32) -> Block {
+            Block { statements: (0..self.next(4)).map(|_| self.statement(depth)).collect() }
+        }
+
+        fn statement(&mut self, depth: u32) -> Statement {
+            let line = self.next(500) as usize;
+            match if depth == 0 { self.next(5) } else { self.next(8) } {
+                0 => Statement::VariableDecl(VariableDecl { var_type: self.ty(), name: self.name(), initializer: (self.next(2) == 0).then(|| self.expr(2)), line }),
+                1 => Statement::ExprStmt(ExprStmt { expr: self.expr(3), line }),
+                2 => Statement::ReturnStmt(ReturnStmt { value: (self.next(2) == 0).then(|| self.expr(2)), line }),
+                3 => Statement::WriteStmt(WriteStmt { arguments: (0..self.next(3)).map(|_| self.expr(2)).collect(), line }),
+                4 => Statement::ReadStmt(ReadStmt { target: Expr::Variable(self.name()), line }),
+                5 => Statement::IfStmt(IfStmt {
+                    condition: self.expr(2),
+                    then_branch: self.block(depth - 1),
+                    else_branch: (self.next(2) == 0).then(|| self.block(depth - 1)),
+                    line,
+                }),
+                6 => Statement::WhileStmt(WhileStmt { condition: self.expr(2), body: self.block(depth - 1), line }),
+                _ => Statement::ForStmt(ForStmt { variable: self.name(), start: self.expr(1), end: self.expr(1), body: self.block(depth - 1), line }),
+            }
+        }
+
+        fn program(&mut self) -> Program {
+            Program {
+                imports: (0..self.next(2)).map(|_| Import { path: format!("{}.sym", self.name()) }).collect(),
+                functions: (0..self.next(3))
+                    .map(|_| FunctionDecl {
+                        return_type: (self.next(2) == 0).then(|| self.ty()),
+                        name: self.name(),
+                        parameters: (0..self.next(3)).map(|_| Parameter { param_type: self.ty(), name: self.name() }).collect(),
+                        body: self.block(2),
+                        line: self.next(500) as usize,
+                    })
+                    .collect(),
+                statements: (0..self.next(3)).map(|_| self.statement(1)).collect(),
+            }
+        }
+    }
+
+    const BINARY: [SerializableBinaryOperator; 13] = [
+        SerializableBinaryOperator::Add, SerializableBinaryOperator::Subtract, SerializableBinaryOperator::Multiply,
+        SerializableBinaryOperator::Divide, SerializableBinaryOperator::Modulo, SerializableBinaryOperator::Equal,
+        SerializableBinaryOperator::NotEqual, SerializableBinaryOperator::Less, SerializableBinaryOperator::LessEqual,
+        SerializableBinaryOperator::Greater, SerializableBinaryOperator::GreaterEqual, SerializableBinaryOperator::And,
+        SerializableBinaryOperator::Or,
+    ];
+
+    #[test]
+    fn test_round_trip_generated_programs() {
+        for seed in 0..300 {
+            let program = Gen(seed).program();
+            assert_eq!(round_trip(&program), program, "semente {}", seed);
+
+            let bytes = crate::ast_binary::encode(&SerializableProgram::from(&program));
+            assert_eq!(crate::ast_binary::program_from_binary(&bytes).unwrap(), program, "semente {} (binário)", seed);
+        }
+    }
+
+    #[test]
+    fn test_invalid_json_is_an_error() {
+        let json = r#"{"imports": [], "functions": [{"name": "f", "return_type": "Void", "parameters": [{"param_type": "Void", "name": "x"}], "body": {"statements": []}, "line": 1}],
+                       "global_statements": [], "metadata": {"format_version": 2, "source_file": "", "timestamp": "", "version": "", "entry_point": null}}"#;
+        let serializable: SerializableProgram = serde_json::from_str(json).unwrap();
+        let error = Program::try_from(&serializable).unwrap_err();
+        assert_eq!(error.to_string(), "ERRO DE SERIALIZAÇÃO: função 'f': parâmetro 'x': o tipo 'Void' só é válido como retorno de função");
+
+        // o schema já recusa na carga, antes da conversão
+        let error = program_from_json(json).unwrap_err();
+        assert!(error.to_string().starts_with("ERRO DE SERIALIZAÇÃO: $.functions[0].parameters[0].param_type:"));
+        assert!(program_from_json(r#"{"functions": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_non_finite_decimals() {
+        use crate::parser::Parser;
+
+        // `-O` dobra `1.0 / 0.0` e `0.0 / 0.0` em literais
+        let mut program = Parser::parse_from_source("escreva(1.0, 2.0, 3.0);").unwrap();
+        let Statement::WriteStmt(write) = &mut program.statements[0] else { unreachable!() };
+        for (argument, value) in write.arguments.iter_mut().zip([f64::INFINITY, f64::NEG_INFINITY, f64::NAN]) {
+            *argument = Expr::Literal(Literal::Decimal(value));
+        }
+        let json = serde_json::to_string(&SerializableProgram::from(&program)).unwrap();
+        assert!(json.contains(r#"{"Decimal":"inf"}"#) && json.contains(r#"{"Decimal":"-inf"}"#) && json.contains(r#"{"Decimal":"nan"}"#));
+
+        let loaded = program_from_json(&json).unwrap();
+        let Statement::WriteStmt(write) = &loaded.statements[0] else { unreachable!() };
+        let values: Vec<f64> = write
+            .arguments
+            .iter()
+            .map(|argument| match argument {
+                Expr::Literal(Literal::Decimal(value)) => *value,
+                other => panic!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(values[..2], [f64::INFINITY, f64::NEG_INFINITY]);
+        assert!(values[2].is_nan());
+
+        assert!(program_from_json(&json.replace("\"nan\"", "\"infinito\"")).is_err());
+    }
+
+    #[test]
+    fn test_deep_expressions() {
+        use crate::parser::Parser;
+        use crate::interpreter::with_large_stack;
+
+        // cada termo da soma é um nível a mais no JSON; como as outras fases,
+        // a carga usa mais pilha que os 2 MiB das threads de teste
+        let terms = vec!["1"; MAX_JSON_DEPTH - 10].join(" + ");
+        let program = Parser::parse_from_source(&format!("escreva({});", terms)).unwrap();
+        with_large_stack(|| assert_eq!(round_trip(&program), program));
+
+        let terms = vec!["1"; MAX_JSON_DEPTH].join(" + ");
+        let program = Parser::parse_from_source(&format!("escreva({});", terms)).unwrap();
+        let error = save_program_to_json(&program, "test_deep.ast.json").unwrap_err();
+        assert!(error.to_string().contains("níveis de aninhamento"), "{}", error);
+        assert!(fs::metadata("test_deep.ast.json").is_err());
+
+        // um arquivo montado à mão não estoura a pilha
+        let error = program_from_json(&"[".repeat(1_000_000)).unwrap_err();
+        assert!(error.to_string().contains("níveis de aninhamento"), "{}", error);
+    }
+}